@@ -0,0 +1,13 @@
+//! The kernel of Quantum Error Analyzer: Pauli strings, Clifford circuits,
+//! error propagation, tableaux and the step-through simulator.
+//!
+//! This crate keeps its dependencies minimal and its API semver-stable, so
+//! embedded and WASM users can depend on it alone. File formats, codes,
+//! noise, analyses and decoders live in `quantum-error-analyzer`, which
+//! re-exports everything here under the same paths.
+
+pub mod physics;
+pub mod memory;
+pub mod perf;
+
+pub use physics::*;
@@ -0,0 +1,174 @@
+//! Approximate memory accounting and limits.
+//!
+//! Sizes are estimates: the inline size of each value plus its heap
+//! payload, without allocator slack or spare `Vec` capacity. They are
+//! meant to catch analyses that would run out of memory (or crash a
+//! browser tab) before they start, not to measure usage precisely.
+//! `MemoryLimits::default()` is unlimited. Estimates for DEMs and shot
+//! records live in `quantum-error-analyzer`.
+
+use crate::physics::circuit::{Circuit, Gate, GateNoise, Measure, NoiseOverride};
+use crate::physics::pauli::{PauliString, SinglePauli};
+use crate::physics::simulator::{Simulator, Snapshot};
+use std::mem::size_of;
+
+pub trait ApproximateSize {
+    fn approximate_bytes(&self) -> usize;
+}
+
+/// Heap bytes of the two bit vectors of an `n`-qubit Pauli string.
+pub fn pauli_heap_bytes(num_qubits: usize) -> usize {
+    2 * num_qubits.div_ceil(usize::BITS as usize) * size_of::<usize>()
+}
+
+impl ApproximateSize for PauliString {
+    fn approximate_bytes(&self) -> usize {
+        size_of::<PauliString>() + pauli_heap_bytes(self.num_qubits())
+    }
+}
+
+impl ApproximateSize for Snapshot {
+    fn approximate_bytes(&self) -> usize {
+        snapshot_bytes(self.error_pattern.num_qubits(), self.classical_bits.len() + self.measurement_flips.len())
+    }
+}
+
+impl ApproximateSize for Circuit {
+    fn approximate_bytes(&self) -> usize {
+        let regions: usize = self
+            .regions
+            .iter()
+            .map(|r| size_of_val(r) + r.name.len())
+            .sum();
+        let registers: usize = self
+            .registers
+            .iter()
+            .map(|r| size_of_val(r) + r.name.len())
+            .sum();
+        let noise_overrides: usize = self
+            .noise_overrides
+            .iter()
+            .map(|entry| {
+                let terms = match &entry.noise {
+                    GateNoise::Depolarizing(_) => 0,
+                    GateNoise::Pauli(terms) => terms
+                        .iter()
+                        .map(|(paulis, _)| size_of::<(Vec<SinglePauli>, f64)>() + paulis.len() * size_of::<SinglePauli>())
+                        .sum(),
+                };
+                size_of::<NoiseOverride>() + terms
+            })
+            .sum();
+        size_of::<Circuit>()
+            + self.gates.len() * size_of::<Gate>()
+            + (self.moments.len() + self.barriers.len()) * size_of::<usize>()
+            + self.measurements.len() * size_of::<Measure>()
+            + regions
+            + registers
+            + noise_overrides
+    }
+}
+
+impl ApproximateSize for Simulator {
+    fn approximate_bytes(&self) -> usize {
+        size_of::<Simulator>()
+            + self.circuit().approximate_bytes()
+            + self.error_pattern().approximate_bytes()
+            + self.timeline().approximate_bytes()
+    }
+}
+
+impl<T: ApproximateSize> ApproximateSize for [T] {
+    fn approximate_bytes(&self) -> usize {
+        self.iter().map(T::approximate_bytes).sum()
+    }
+}
+
+fn snapshot_bytes(num_qubits: usize, classical_bits: usize) -> usize {
+    size_of::<Snapshot>() + pauli_heap_bytes(num_qubits) + classical_bits
+}
+
+/// Projected size of a timeline of `snapshots` snapshots.
+pub fn timeline_bytes(num_qubits: usize, snapshots: usize, classical_bits: usize) -> usize {
+    snapshots * snapshot_bytes(num_qubits, classical_bits)
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryLimits {
+    /// Largest allocation one analysis may make; `None` is unlimited
+    pub max_bytes: Option<usize>,
+}
+
+impl MemoryLimits {
+    pub fn new(max_bytes: usize) -> Self {
+        Self { max_bytes: Some(max_bytes) }
+    }
+
+    /// Fails when `what` would need more than the limit.
+    pub fn check(&self, what: &str, bytes: usize) -> Result<(), String> {
+        match self.max_bytes {
+            Some(max) if bytes > max => Err(format!(
+                "{} would need about {}, over the {} memory limit",
+                what,
+                format_bytes(bytes),
+                format_bytes(max)
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Human-readable byte count, e.g. `12.3 MiB`.
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{MeasurementBasis, SingleGate};
+
+    #[test]
+    fn test_timeline_estimate_matches_simulator() {
+        let mut circuit = Circuit::new(100);
+        for qubit in 0..50 {
+            circuit.add_gate(Gate::Single { qubit, gate: SingleGate::H }).unwrap();
+        }
+        let mut simulator = Simulator::new(circuit);
+        simulator.run();
+        assert_eq!(simulator.timeline().approximate_bytes(), timeline_bytes(100, 51, 0));
+        assert!(simulator.approximate_bytes() > simulator.timeline().approximate_bytes());
+
+        let limits = MemoryLimits::new(1024);
+        assert!(MemoryLimits::default().check("timeline", usize::MAX).is_ok());
+        let message = limits.check("timeline", 3 * 1024 * 1024).unwrap_err();
+        assert!(message.contains("3.0 MiB") && message.contains("1.0 KiB"), "{}", message);
+    }
+
+    #[test]
+    fn test_circuit_estimate_counts_annotations() {
+        let mut circuit = Circuit::new(0);
+        circuit.add_register("data", 2).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        let bare = circuit.approximate_bytes();
+        circuit.add_barrier();
+        circuit.add_measurement(0, MeasurementBasis::Z).unwrap();
+        circuit.moments = vec![0];
+        circuit.set_noise_override(0, GateNoise::Pauli(vec![(vec![SinglePauli::X], 0.1)])).unwrap();
+        let annotated = circuit.approximate_bytes();
+        assert!(annotated >= bare + 2 * size_of::<usize>() + size_of::<Measure>() + size_of::<NoiseOverride>());
+        circuit.registers.clear();
+        assert!(circuit.approximate_bytes() < annotated);
+    }
+}
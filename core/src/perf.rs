@@ -0,0 +1,148 @@
+//! Runtime performance counters and a propagation micro-benchmark.
+//!
+//! With the `perf` feature enabled, hot paths bump global atomic counters:
+//! Pauli gate applications, simulator snapshots, tableau gate applications,
+//! and snapshot copies avoided by `Simulator::fork`. Without the feature
+//! the counting calls compile to nothing and `counters()` reads zero.
+//! Counters are process-wide, so concurrent analyses share them.
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::physics::{
+    circuit::{Circuit, Gate, SingleGate, TwoGate},
+    pauli::{PauliString, SinglePauli},
+    propagation::apply_gate,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Counter {
+    GatesApplied,
+    SnapshotsCreated,
+    TableauApplications,
+    /// Snapshots a fork did not copy
+    SnapshotCopiesAvoided,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PerfCounters {
+    pub gates_applied: u64,
+    pub snapshots_created: u64,
+    pub tableau_applications: u64,
+    pub snapshot_copies_avoided: u64,
+}
+
+static COUNTERS: [AtomicU64; 4] = [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)];
+
+/// Whether this build counts anything.
+pub const ENABLED: bool = cfg!(feature = "perf");
+
+#[doc(hidden)]
+#[inline(always)]
+pub fn count(counter: Counter, amount: u64) {
+    if ENABLED {
+        COUNTERS[counter as usize].fetch_add(amount, Ordering::Relaxed);
+    }
+}
+
+pub fn counters() -> PerfCounters {
+    let read = |counter: Counter| COUNTERS[counter as usize].load(Ordering::Relaxed);
+    PerfCounters {
+        gates_applied: read(Counter::GatesApplied),
+        snapshots_created: read(Counter::SnapshotsCreated),
+        tableau_applications: read(Counter::TableauApplications),
+        snapshot_copies_avoided: read(Counter::SnapshotCopiesAvoided),
+    }
+}
+
+pub fn reset_counters() {
+    for counter in &COUNTERS {
+        counter.store(0, Ordering::Relaxed);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PropagationBenchmark {
+    pub num_qubits: usize,
+    pub gates: usize,
+    pub iterations: usize,
+    pub seconds: f64,
+    pub gates_per_second: f64,
+}
+
+/// Times Pauli propagation through a fixed mix of single- and two-qubit
+/// gates on a dense error, `iterations` passes over `gates` gates. Needs a
+/// platform clock, so it is not available in the browser build.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn bench_propagation(num_qubits: usize, gates: usize, iterations: usize) -> Result<PropagationBenchmark, String> {
+    if num_qubits < 2 {
+        return Err("The propagation benchmark needs at least 2 qubits".to_string());
+    }
+    let circuit = benchmark_circuit(num_qubits, gates)?;
+    let mut error = PauliString::new(num_qubits);
+    for qubit in 0..num_qubits {
+        let pauli = [SinglePauli::X, SinglePauli::Y, SinglePauli::Z][qubit % 3];
+        error.set_pauli(qubit, pauli);
+    }
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        for gate in &circuit.gates {
+            apply_gate(&mut error, gate);
+        }
+    }
+    let seconds = start.elapsed().as_secs_f64();
+    std::hint::black_box(&error);
+    let applied = (gates * iterations) as f64;
+    Ok(PropagationBenchmark {
+        num_qubits,
+        gates,
+        iterations,
+        seconds,
+        gates_per_second: if seconds > 0.0 { applied / seconds } else { f64::INFINITY },
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn benchmark_circuit(num_qubits: usize, gates: usize) -> Result<Circuit, String> {
+    let mut circuit = Circuit::new(num_qubits);
+    for i in 0..gates {
+        let a = i % num_qubits;
+        let b = (i * 7 + 1) % num_qubits;
+        let b = if a == b { (b + 1) % num_qubits } else { b };
+        let gate = match i % 6 {
+            0 => Gate::Single { qubit: a, gate: SingleGate::H },
+            1 => Gate::Single { qubit: a, gate: SingleGate::S },
+            2 => Gate::Two(TwoGate::CNOT { control: a, target: b }),
+            3 => Gate::Two(TwoGate::CZ { control: a, target: b }),
+            4 => Gate::Single { qubit: a, gate: SingleGate::Sdg },
+            _ => Gate::Two(TwoGate::SWAP { qubit1: a, qubit2: b }),
+        };
+        circuit.add_gate(gate)?;
+    }
+    Ok(circuit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_benchmark_and_counters() {
+        let before = counters();
+        let result = bench_propagation(8, 60, 10).unwrap();
+        assert_eq!(result.gates * result.iterations, 600);
+        assert!(result.gates_per_second > 0.0);
+        let after = counters();
+        // Other tests may run concurrently, so only monotonicity is checked
+        if ENABLED {
+            assert!(after.gates_applied >= before.gates_applied + 600);
+        } else {
+            assert_eq!(after, PerfCounters::default());
+        }
+        assert!(bench_propagation(1, 10, 1).is_err());
+    }
+}
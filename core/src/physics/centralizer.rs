@@ -0,0 +1,90 @@
+//! Centralizers of sets of Pauli strings.
+//!
+//! A Pauli P commutes with G exactly when the symplectic product of their
+//! (x | z) vectors vanishes, i.e. when P's vector is orthogonal to G's with
+//! its halves swapped, (z | x). The centralizer of a generating set is
+//! therefore the GF(2) null space of the swapped generator matrix. For
+//! stabilizer generators it holds the stabilizers and the logical operators.
+
+use crate::physics::pauli::PauliString;
+use bitvec::prelude::*;
+
+/// Whether `pauli` commutes with every generator.
+pub fn commutes_with_all(pauli: &PauliString, generators: &[PauliString]) -> bool {
+    generators.iter().all(|generator| pauli.commutes_with(generator))
+}
+
+/// A basis (phase +1) of the Paulis on `num_qubits` qubits commuting with
+/// every generator. It has 2n - r elements, r being the GF(2) rank of the
+/// generators.
+pub fn centralizer(generators: &[PauliString], num_qubits: usize) -> Result<Vec<PauliString>, String> {
+    if let Some(generator) = generators.iter().find(|g| g.num_qubits() != num_qubits) {
+        return Err(format!(
+            "Generator has {} qubits, expected {}",
+            generator.num_qubits(),
+            num_qubits
+        ));
+    }
+
+    // Reduced row echelon form of the swapped rows (z | x)
+    let mut reduced: Vec<(usize, BitVec)> = Vec::new();
+    for generator in generators {
+        let mut row = generator.z_bits().clone();
+        row.extend_from_bitslice(generator.x_bits());
+        for (pivot, other) in &reduced {
+            if row[*pivot] {
+                row ^= other;
+            }
+        }
+        let Some(pivot) = row.first_one() else {
+            continue;
+        };
+        for (_, other) in reduced.iter_mut() {
+            if other[pivot] {
+                *other ^= &row;
+            }
+        }
+        reduced.push((pivot, row));
+    }
+
+    // One basis vector per free column: set it, then fix the pivots
+    let mut is_pivot = bitvec![0; 2 * num_qubits];
+    for (pivot, _) in &reduced {
+        is_pivot.set(*pivot, true);
+    }
+    is_pivot
+        .iter_zeros()
+        .map(|free| {
+            let mut vector = bitvec![0; 2 * num_qubits];
+            vector.set(free, true);
+            for (pivot, row) in &reduced {
+                vector.set(*pivot, row[free]);
+            }
+            PauliString::from_symplectic(&vector)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_centralizer_of_repetition_checks() {
+        let generators: Vec<PauliString> = ["ZZI", "IZZ", "ZIZ"]
+            .iter()
+            .map(|s| PauliString::from_str(s, 3).unwrap())
+            .collect();
+        let basis = centralizer(&generators, 3).unwrap();
+        // Rank 2, so 6 - 2 elements: the checks plus logical X and Z
+        assert_eq!(basis.len(), 4);
+        assert!(basis.iter().all(|p| commutes_with_all(p, &generators)));
+
+        let logical_x = PauliString::from_str("XXX", 3).unwrap();
+        assert!(commutes_with_all(&logical_x, &generators));
+        assert!(!commutes_with_all(&PauliString::from_str("XII", 3).unwrap(), &generators));
+
+        assert_eq!(centralizer(&[], 2).unwrap().len(), 4);
+        assert!(centralizer(&generators, 4).is_err());
+    }
+}
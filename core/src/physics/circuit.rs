@@ -0,0 +1,1510 @@
+use crate::physics::pauli::{PauliString, SinglePauli};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SingleGate {
+    X,
+    Y,
+    Z,
+    H,
+    S,
+    Sdg,
+    I,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TwoGate {
+    CNOT { control: usize, target: usize },
+    CZ { control: usize, target: usize },
+    SWAP { qubit1: usize, qubit2: usize },
+}
+
+/// Gate type without operands, e.g. for allow-lists and per-type statistics.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GateKind {
+    Single(SingleGate),
+    CNOT,
+    CZ,
+    SWAP,
+}
+
+impl fmt::Display for GateKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GateKind::Single(gate) => write!(f, "{:?}", gate),
+            GateKind::CNOT => write!(f, "CNOT"),
+            GateKind::CZ => write!(f, "CZ"),
+            GateKind::SWAP => write!(f, "SWAP"),
+        }
+    }
+}
+
+/// How a gate acts on one of its qubits, for commutation checks.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum QubitAction {
+    Identity,
+    /// Diagonal in the Z basis (Z, S, S†, CNOT control, CZ)
+    Diagonal,
+    /// Diagonal in the X basis (X, CNOT target)
+    BitFlip,
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Gate {
+    Single {
+        qubit: usize,
+        gate: SingleGate,
+    },
+    Two(TwoGate),
+}
+
+impl Gate {
+    pub fn qubits(&self) -> Vec<usize> {
+        match self {
+            Gate::Single { qubit, .. } => vec![*qubit],
+            Gate::Two(two_gate) => match two_gate {
+                TwoGate::CNOT { control, target } | TwoGate::CZ { control, target } => {
+                    vec![*control, *target]
+                }
+                TwoGate::SWAP { qubit1, qubit2 } => vec![*qubit1, *qubit2],
+            },
+        }
+    }
+
+    /// Whether the two gates commute as operators. Uses sufficient rules
+    /// (disjoint support, or matching diagonal / bit-flip action on every
+    /// shared qubit), so `false` means "not known to commute".
+    pub fn commutes_with(&self, other: &Gate) -> bool {
+        if self == other {
+            return true;
+        }
+        self.qubits().iter().all(|&qubit| {
+            matches!(
+                (self.action_on(qubit), other.action_on(qubit)),
+                (QubitAction::Identity, _)
+                    | (_, QubitAction::Identity)
+                    | (QubitAction::Diagonal, QubitAction::Diagonal)
+                    | (QubitAction::BitFlip, QubitAction::BitFlip)
+            )
+        })
+    }
+
+    fn action_on(&self, qubit: usize) -> QubitAction {
+        match self {
+            Gate::Single { qubit: q, .. } if *q != qubit => QubitAction::Identity,
+            Gate::Single { gate, .. } => match gate {
+                SingleGate::I => QubitAction::Identity,
+                SingleGate::Z | SingleGate::S | SingleGate::Sdg => QubitAction::Diagonal,
+                SingleGate::X => QubitAction::BitFlip,
+                SingleGate::Y | SingleGate::H => QubitAction::Other,
+            },
+            Gate::Two(TwoGate::CNOT { control, target }) => {
+                if qubit == *control {
+                    QubitAction::Diagonal
+                } else if qubit == *target {
+                    QubitAction::BitFlip
+                } else {
+                    QubitAction::Identity
+                }
+            }
+            Gate::Two(TwoGate::CZ { control, target }) => {
+                if qubit == *control || qubit == *target {
+                    QubitAction::Diagonal
+                } else {
+                    QubitAction::Identity
+                }
+            }
+            Gate::Two(TwoGate::SWAP { qubit1, qubit2 }) => {
+                if qubit == *qubit1 || qubit == *qubit2 {
+                    QubitAction::Other
+                } else {
+                    QubitAction::Identity
+                }
+            }
+        }
+    }
+
+    /// The same gate with every operand relabelled through `map`.
+    pub fn map_qubits<F: Fn(usize) -> usize>(&self, map: F) -> Gate {
+        match self {
+            Gate::Single { qubit, gate } => Gate::Single {
+                qubit: map(*qubit),
+                gate: *gate,
+            },
+            Gate::Two(TwoGate::CNOT { control, target }) => Gate::Two(TwoGate::CNOT {
+                control: map(*control),
+                target: map(*target),
+            }),
+            Gate::Two(TwoGate::CZ { control, target }) => Gate::Two(TwoGate::CZ {
+                control: map(*control),
+                target: map(*target),
+            }),
+            Gate::Two(TwoGate::SWAP { qubit1, qubit2 }) => Gate::Two(TwoGate::SWAP {
+                qubit1: map(*qubit1),
+                qubit2: map(*qubit2),
+            }),
+        }
+    }
+
+    pub fn kind(&self) -> GateKind {
+        match self {
+            Gate::Single { gate, .. } => GateKind::Single(*gate),
+            Gate::Two(TwoGate::CNOT { .. }) => GateKind::CNOT,
+            Gate::Two(TwoGate::CZ { .. }) => GateKind::CZ,
+            Gate::Two(TwoGate::SWAP { .. }) => GateKind::SWAP,
+        }
+    }
+
+    /// The gate undoing this one: S and S† swap, every other gate is its
+    /// own inverse.
+    pub fn inverse(&self) -> Gate {
+        match self {
+            Gate::Single { qubit, gate: SingleGate::S } => Gate::Single { qubit: *qubit, gate: SingleGate::Sdg },
+            Gate::Single { qubit, gate: SingleGate::Sdg } => Gate::Single { qubit: *qubit, gate: SingleGate::S },
+            other => other.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Gate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Gate::Single { qubit, gate } => write!(f, "{:?}({})", gate, qubit),
+            Gate::Two(TwoGate::CNOT { control, target }) => {
+                write!(f, "CNOT({}, {})", control, target)
+            }
+            Gate::Two(TwoGate::CZ { control, target }) => {
+                write!(f, "CZ({}, {})", control, target)
+            }
+            Gate::Two(TwoGate::SWAP { qubit1, qubit2 }) => {
+                write!(f, "SWAP({}, {})", qubit1, qubit2)
+            }
+        }
+    }
+}
+
+/// One-line summary; the alternate form `{:#}` is `to_pretty_string`.
+impl fmt::Display for Circuit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", self.to_pretty_string().trim_end());
+        }
+        write!(
+            f,
+            "Circuit({} qubits, {} gates, {} two-qubit",
+            self.num_qubits,
+            self.gates.len(),
+            self.two_qubit_gate_count()
+        )?;
+        if !self.registers.is_empty() {
+            let names: Vec<String> = self
+                .registers
+                .iter()
+                .map(|register| format!("{}[{}]", register.name, register.size))
+                .collect();
+            write!(f, ", registers: {}", names.join(", "))?;
+        }
+        if !self.regions.is_empty() {
+            let names: Vec<&str> = self.regions.iter().map(|region| region.name.as_str()).collect();
+            write!(f, ", regions: {}", names.join(", "))?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Labelled range of gate indices `[start, end)`, e.g. an encoder or a
+/// syndrome-extraction round.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Region {
+    pub name: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Named range of qubits `[start, start + size)`, e.g. "data" or "ancilla".
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QubitRegister {
+    pub name: String,
+    pub start: usize,
+    pub size: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MeasurementBasis {
+    X,
+    Z,
+}
+
+impl MeasurementBasis {
+    /// Whether `error` flips a readout of `qubit` in this basis: Z readouts
+    /// see X errors and X readouts see Z errors.
+    pub fn flipped_by(self, error: &PauliString, qubit: usize) -> bool {
+        match self {
+            MeasurementBasis::Z => error.x_bits()[qubit],
+            MeasurementBasis::X => error.z_bits()[qubit],
+        }
+    }
+}
+
+/// Readout of `qubit` in `basis` once the first `position` gates have run.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Measure {
+    pub position: usize,
+    pub qubit: usize,
+    pub basis: MeasurementBasis,
+}
+
+/// Noise replacing the noise model's gate-tied channel right after one
+/// gate instance, e.g. a known-bad coupler. Schedules do not scale it.
+/// Probabilities compare bit for bit, so equality is total even for NaN.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum GateNoise {
+    /// Each non-identity Pauli on the gate's k qubits with p / (4^k - 1)
+    Depolarizing(f64),
+    /// Each listed Pauli on the gate's qubits, in `Gate::qubits` order,
+    /// with its probability
+    Pauli(Vec<(Vec<SinglePauli>, f64)>),
+}
+
+impl PartialEq for GateNoise {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (GateNoise::Depolarizing(a), GateNoise::Depolarizing(b)) => a.to_bits() == b.to_bits(),
+            (GateNoise::Pauli(a), GateNoise::Pauli(b)) => {
+                a.len() == b.len()
+                    && a.iter().zip(b).all(|((pa, a), (pb, b))| pa == pb && a.to_bits() == b.to_bits())
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for GateNoise {}
+
+impl GateNoise {
+    /// The Paulis on a gate of `arity` qubits with their probabilities;
+    /// identity terms are left out.
+    pub fn terms(&self, arity: usize) -> Vec<(Vec<SinglePauli>, f64)> {
+        const PAULIS: [SinglePauli; 4] = [SinglePauli::I, SinglePauli::X, SinglePauli::Y, SinglePauli::Z];
+        match self {
+            GateNoise::Depolarizing(p) => {
+                let count = 1usize << (2 * arity);
+                (1..count)
+                    .map(|index| {
+                        let paulis = (0..arity).rev().map(|digit| PAULIS[(index >> (2 * digit)) % 4]).collect();
+                        (paulis, p / (count - 1) as f64)
+                    })
+                    .collect()
+            }
+            GateNoise::Pauli(terms) => terms
+                .iter()
+                .filter(|(paulis, _)| paulis.iter().any(|&pauli| pauli != SinglePauli::I))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn validate(&self, arity: usize) -> Result<(), String> {
+        let probabilities: Vec<f64> = match self {
+            GateNoise::Depolarizing(p) => vec![*p],
+            GateNoise::Pauli(terms) => {
+                if let Some((paulis, _)) = terms.iter().find(|(paulis, _)| paulis.len() != arity) {
+                    return Err(format!("Noise term has {} Paulis for a {}-qubit gate", paulis.len(), arity));
+                }
+                terms.iter().map(|(_, p)| *p).collect()
+            }
+        };
+        if let Some(p) = probabilities.iter().find(|p| !(0.0..=1.0).contains(*p)) {
+            return Err(format!("Gate noise probability must be in [0, 1], got {}", p));
+        }
+        if probabilities.iter().sum::<f64>() > 1.0 + 1e-12 {
+            return Err("Gate noise probabilities sum to more than 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// `noise` applies after gate `gate` instead of the model's channel.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoiseOverride {
+    pub gate: usize,
+    pub noise: GateNoise,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Circuit {
+    pub num_qubits: usize,
+    pub gates: Vec<Gate>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<Region>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub noise_overrides: Vec<NoiseOverride>,
+    /// First gate of each moment (time step); the gates of a moment act on
+    /// disjoint qubits. Empty means every gate is its own moment.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub moments: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub registers: Vec<QubitRegister>,
+    /// Gate indices with a barrier just before them, increasing; a barrier
+    /// at `gates.len()` closes the circuit. Scheduling never moves a gate
+    /// across a barrier.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub barriers: Vec<usize>,
+    /// Mid-circuit and final readouts by increasing position. Like
+    /// barriers, scheduling never moves a gate across one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub measurements: Vec<Measure>,
+}
+
+impl Circuit {
+    pub fn new(num_qubits: usize) -> Self {
+        Self {
+            num_qubits,
+            gates: Vec::new(),
+            regions: Vec::new(),
+            noise_overrides: Vec::new(),
+            moments: Vec::new(),
+            registers: Vec::new(),
+            barriers: Vec::new(),
+            measurements: Vec::new(),
+        }
+    }
+
+    /// Appends `size` fresh qubits named `name`; returns the first one.
+    pub fn add_register(&mut self, name: &str, size: usize) -> Result<usize, String> {
+        if self.register(name).is_some() {
+            return Err(format!("Register '{}' already exists", name));
+        }
+        let start = self.num_qubits;
+        self.registers.push(QubitRegister {
+            name: name.to_string(),
+            start,
+            size,
+        });
+        self.num_qubits += size;
+        Ok(start)
+    }
+
+    pub fn register(&self, name: &str) -> Option<&QubitRegister> {
+        self.registers.iter().find(|register| register.name == name)
+    }
+
+    /// The global index of qubit `index` of register `name`.
+    pub fn qubit(&self, name: &str, index: usize) -> Result<usize, String> {
+        let register = self.register(name).ok_or_else(|| format!("No register '{}'", name))?;
+        if index >= register.size {
+            return Err(format!("Register '{}' has {} qubits, no qubit {}", name, register.size, index));
+        }
+        Ok(register.start + index)
+    }
+
+    /// The register holding global qubit `qubit` and its index there.
+    pub fn register_of(&self, qubit: usize) -> Option<(&str, usize)> {
+        self.registers
+            .iter()
+            .find(|register| (register.start..register.start + register.size).contains(&qubit))
+            .map(|register| (register.name.as_str(), qubit - register.start))
+    }
+
+    /// Registers must lie inside the circuit, not overlap and have distinct
+    /// names.
+    pub fn validate_registers(&self) -> Result<(), String> {
+        let mut owner: Vec<Option<&str>> = vec![None; self.num_qubits];
+        for (i, register) in self.registers.iter().enumerate() {
+            if self.registers[..i].iter().any(|other| other.name == register.name) {
+                return Err(format!("Register '{}' is defined twice", register.name));
+            }
+            for qubit in register.start..register.start + register.size {
+                match owner.get_mut(qubit) {
+                    None => return Err(format!("Register '{}' reaches past qubit {}", register.name, self.num_qubits)),
+                    Some(Some(other)) => return Err(format!("Registers '{}' and '{}' share qubit {}", other, register.name, qubit)),
+                    Some(slot) => *slot = Some(&register.name),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends `gate` in a moment of its own.
+    pub fn add_gate(&mut self, gate: Gate) -> Result<(), String> {
+        self.check_qubits(&gate)?;
+        if !self.moments.is_empty() {
+            self.moments.push(self.gates.len());
+        }
+        self.gates.push(gate);
+        Ok(())
+    }
+
+    /// Puts a barrier after the gates added so far.
+    pub fn add_barrier(&mut self) {
+        if self.barriers.last() != Some(&self.gates.len()) {
+            self.barriers.push(self.gates.len());
+        }
+    }
+
+    /// Whether a barrier sits just before gate `gate`.
+    pub fn has_barrier_before(&self, gate: usize) -> bool {
+        self.barriers.binary_search(&gate).is_ok()
+    }
+
+    /// Barriers must increase strictly, stay within the circuit and fall
+    /// between moments.
+    pub fn validate_barriers(&self) -> Result<(), String> {
+        if let Some(pair) = self.barriers.windows(2).find(|pair| pair[0] >= pair[1]) {
+            return Err(format!("Barriers at {} and {} are not increasing", pair[0], pair[1]));
+        }
+        for &barrier in &self.barriers {
+            if barrier > self.gates.len() {
+                return Err(format!("Barrier at gate {} but the circuit has {} gates", barrier, self.gates.len()));
+            }
+            if self.splits_moment(barrier) {
+                return Err(format!("Barrier at gate {} splits a moment", barrier));
+            }
+        }
+        Ok(())
+    }
+
+    /// Measures `qubit` in `basis` after the gates added so far.
+    pub fn add_measurement(&mut self, qubit: usize, basis: MeasurementBasis) -> Result<(), String> {
+        if qubit >= self.num_qubits {
+            return Err(format!("Measurement of qubit {} but circuit has only {} qubits", qubit, self.num_qubits));
+        }
+        self.measurements.push(Measure {
+            position: self.gates.len(),
+            qubit,
+            basis,
+        });
+        Ok(())
+    }
+
+    /// For each qubit, the position of the measurement after which no gate
+    /// acts on it, or `usize::MAX`. An error landing on the qubit after gate
+    /// `g` with `g >= position` cannot change any readout.
+    pub fn retirement_positions(&self) -> Vec<usize> {
+        let mut last_use = vec![0; self.num_qubits];
+        for (index, gate) in self.gates.iter().enumerate() {
+            for qubit in gate.qubits() {
+                last_use[qubit] = index + 1;
+            }
+        }
+        let mut retired = vec![usize::MAX; self.num_qubits];
+        for m in &self.measurements {
+            if m.position >= last_use[m.qubit] {
+                retired[m.qubit] = retired[m.qubit].min(m.position);
+            }
+        }
+        retired
+    }
+
+    /// The measurements made once the first `position` gates have run.
+    pub fn measurements_at(&self, position: usize) -> &[Measure] {
+        let start = self.measurements.partition_point(|m| m.position < position);
+        let end = self.measurements.partition_point(|m| m.position <= position);
+        &self.measurements[start..end]
+    }
+
+    /// Measurements must be in order of position, on existing qubits and
+    /// fall between moments.
+    pub fn validate_measurements(&self) -> Result<(), String> {
+        if let Some(pair) = self.measurements.windows(2).find(|pair| pair[0].position > pair[1].position) {
+            return Err(format!("Measurements at {} and {} are out of order", pair[0].position, pair[1].position));
+        }
+        for m in &self.measurements {
+            if m.qubit >= self.num_qubits {
+                return Err(format!("Measurement of qubit {} but circuit has only {} qubits", m.qubit, self.num_qubits));
+            }
+            if m.position > self.gates.len() {
+                return Err(format!("Measurement at gate {} but the circuit has {} gates", m.position, self.gates.len()));
+            }
+            if self.splits_moment(m.position) {
+                return Err(format!("Measurement at gate {} splits a moment", m.position));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether `position` falls inside a moment rather than at its start.
+    fn splits_moment(&self, position: usize) -> bool {
+        let moment = self.moment_of(position).and_then(|time| self.moment_range(time));
+        moment.is_some_and(|range| range.start != position)
+    }
+
+    /// Adds `gate` to moment `time`, or to a new last moment when `time` is
+    /// `depth()`. The gate must not share a qubit with the moment's gates.
+    /// Later gates, regions, noise overrides, barriers and measurements
+    /// shift up by one index.
+    pub fn add_gate_at(&mut self, time: usize, gate: Gate) -> Result<(), String> {
+        self.check_qubits(&gate)?;
+        if time > self.depth() {
+            return Err(format!("Time {} is past the end of a circuit of depth {}", time, self.depth()));
+        }
+        if time == self.depth() {
+            self.moments = self.moment_starts();
+            self.moments.push(self.gates.len());
+            self.gates.push(gate);
+            return Ok(());
+        }
+        let qubits = gate.qubits();
+        if let Some(busy) = self.gates_at_time(time).iter().find(|other| other.qubits().iter().any(|q| qubits.contains(q))) {
+            return Err(format!("{} shares a qubit with {} at time {}", gate, busy, time));
+        }
+
+        let position = self.moment_range(time).expect("time checked").end;
+        self.moments = self.moment_starts();
+        for start in &mut self.moments[time + 1..] {
+            *start += 1;
+        }
+        // Regions ending with this moment take the gate, ones starting after do not
+        for region in &mut self.regions {
+            if region.start >= position {
+                region.start += 1;
+            }
+            if region.end >= position {
+                region.end += 1;
+            }
+        }
+        for entry in &mut self.noise_overrides {
+            if entry.gate >= position {
+                entry.gate += 1;
+            }
+        }
+        for barrier in &mut self.barriers {
+            if *barrier >= position {
+                *barrier += 1;
+            }
+        }
+        for m in &mut self.measurements {
+            if m.position >= position {
+                m.position += 1;
+            }
+        }
+        self.gates.insert(position, gate);
+        Ok(())
+    }
+
+    /// Appends `other`, whose qubit q is qubit q here.
+    pub fn append(&mut self, other: &Circuit) -> Result<(), String> {
+        let identity: Vec<usize> = (0..other.num_qubits).collect();
+        self.compose(other, &identity)
+    }
+
+    /// Appends `other` with its qubit q on qubit `qubit_map[q]` here. Its
+    /// regions, noise overrides, moments, barriers and measurements come
+    /// along, shifted past the existing gates; region names must not clash.
+    /// Its registers do not, as the map need not keep them contiguous.
+    pub fn compose(&mut self, other: &Circuit, qubit_map: &[usize]) -> Result<(), String> {
+        if qubit_map.len() != other.num_qubits {
+            return Err(format!(
+                "Qubit map has {} entries for a circuit on {} qubits",
+                qubit_map.len(),
+                other.num_qubits
+            ));
+        }
+        if let Some(&qubit) = qubit_map.iter().find(|&&q| q >= self.num_qubits) {
+            return Err(format!("Qubit map sends a qubit to {} but circuit has only {} qubits", qubit, self.num_qubits));
+        }
+        if (1..qubit_map.len()).any(|i| qubit_map[..i].contains(&qubit_map[i])) {
+            return Err("Qubit map must send qubits to distinct qubits".to_string());
+        }
+        if let Some(region) = other.regions.iter().find(|region| self.region(&region.name).is_some()) {
+            return Err(format!("Region '{}' already exists", region.name));
+        }
+
+        let offset = self.gates.len();
+        if !self.moments.is_empty() || !other.moments.is_empty() {
+            self.moments = self.moment_starts();
+            self.moments.extend(other.moment_starts().into_iter().map(|start| offset + start));
+        }
+        self.gates.extend(other.gates.iter().map(|gate| gate.map_qubits(|q| qubit_map[q])));
+        self.regions.extend(other.regions.iter().map(|region| Region {
+            name: region.name.clone(),
+            start: offset + region.start,
+            end: offset + region.end,
+        }));
+        self.noise_overrides.extend(other.noise_overrides.iter().map(|entry| NoiseOverride {
+            gate: offset + entry.gate,
+            noise: entry.noise.clone(),
+        }));
+        for barrier in other.barriers.iter().map(|barrier| offset + barrier) {
+            if self.barriers.last() != Some(&barrier) {
+                self.barriers.push(barrier);
+            }
+        }
+        self.measurements.extend(other.measurements.iter().map(|m| Measure {
+            position: offset + m.position,
+            qubit: qubit_map[m.qubit],
+            basis: m.basis,
+        }));
+        Ok(())
+    }
+
+    /// Copy with qubit q relabelled `mapping[q]`, on `max(mapping) + 1`
+    /// qubits, so a permutation keeps the register and larger labels embed
+    /// the circuit in a bigger layout. `mapping` must be injective.
+    /// Registers are dropped, like in `compose`.
+    pub fn remap_qubits(&self, mapping: &[usize]) -> Result<Circuit, String> {
+        let num_qubits = mapping.iter().max().map_or(0, |&max| max + 1);
+        let mut remapped = Circuit::new(num_qubits);
+        remapped.compose(self, mapping)?;
+        Ok(remapped)
+    }
+
+    fn check_qubits(&self, gate: &Gate) -> Result<(), String> {
+        for qubit in gate.qubits() {
+            if qubit >= self.num_qubits {
+                return Err(format!(
+                    "Gate acts on qubit {} but circuit has only {} qubits",
+                    qubit, self.num_qubits
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// `moments`, or one moment per gate when it is empty.
+    fn moment_starts(&self) -> Vec<usize> {
+        if self.moments.is_empty() {
+            (0..self.gates.len()).collect()
+        } else {
+            self.moments.clone()
+        }
+    }
+
+    /// Gate indices of moment `time`.
+    pub fn moment_range(&self, time: usize) -> Option<Range<usize>> {
+        if self.moments.is_empty() {
+            return (time < self.gates.len()).then(|| time..time + 1);
+        }
+        let start = *self.moments.get(time)?;
+        let end = self.moments.get(time + 1).copied().unwrap_or(self.gates.len());
+        Some(start..end)
+    }
+
+    /// The moment containing gate `gate`.
+    pub fn moment_of(&self, gate: usize) -> Option<usize> {
+        if gate >= self.gates.len() {
+            return None;
+        }
+        if self.moments.is_empty() {
+            return Some(gate);
+        }
+        Some(self.moments.partition_point(|&start| start <= gate) - 1)
+    }
+
+    /// Moments must start at gate 0, increase strictly, stay in range and
+    /// hold gates on disjoint qubits.
+    pub fn validate_moments(&self) -> Result<(), String> {
+        if self.moments.is_empty() {
+            return Ok(());
+        }
+        if self.moments[0] != 0 {
+            return Err(format!("First moment starts at gate {}, not 0", self.moments[0]));
+        }
+        if let Some(pair) = self.moments.windows(2).find(|pair| pair[0] >= pair[1]) {
+            return Err(format!("Moment starts {} and {} are not increasing", pair[0], pair[1]));
+        }
+        if let Some(&start) = self.moments.last().filter(|&&start| start >= self.gates.len()) {
+            return Err(format!("Moment starts at gate {} but the circuit has {} gates", start, self.gates.len()));
+        }
+        for time in 0..self.depth() {
+            let mut busy = vec![false; self.num_qubits];
+            for qubit in self.gates_at_time(time).iter().flat_map(|gate| gate.qubits()) {
+                if qubit >= self.num_qubits || std::mem::replace(&mut busy[qubit], true) {
+                    return Err(format!("Moment {} uses qubit {} twice or out of range", time, qubit));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add_region(&mut self, name: &str, start: usize, end: usize) -> Result<(), String> {
+        if start > end || end > self.gates.len() {
+            return Err(format!(
+                "Region {}..{} is out of bounds for a circuit with {} gates",
+                start,
+                end,
+                self.gates.len()
+            ));
+        }
+        if self.region(name).is_some() {
+            return Err(format!("Region '{}' already exists", name));
+        }
+        self.regions.push(Region {
+            name: name.to_string(),
+            start,
+            end,
+        });
+        Ok(())
+    }
+
+    /// Regions must lie inside the circuit and have distinct names.
+    pub fn validate_regions(&self) -> Result<(), String> {
+        for (i, region) in self.regions.iter().enumerate() {
+            if region.start > region.end || region.end > self.gates.len() {
+                return Err(format!(
+                    "Region '{}' {}..{} is out of bounds for a circuit with {} gates",
+                    region.name,
+                    region.start,
+                    region.end,
+                    self.gates.len()
+                ));
+            }
+            if self.regions[..i].iter().any(|other| other.name == region.name) {
+                return Err(format!("Region '{}' is defined twice", region.name));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn region(&self, name: &str) -> Option<&Region> {
+        self.regions.iter().find(|region| region.name == name)
+    }
+
+    /// Replaces the noise after gate `gate` with `noise`.
+    pub fn set_noise_override(&mut self, gate: usize, noise: GateNoise) -> Result<(), String> {
+        let Some(target) = self.gates.get(gate) else {
+            return Err(format!("No gate {} in a circuit with {} gates", gate, self.gates.len()));
+        };
+        noise.validate(target.qubits().len())?;
+        self.noise_overrides.retain(|entry| entry.gate != gate);
+        self.noise_overrides.push(NoiseOverride { gate, noise });
+        Ok(())
+    }
+
+    pub fn noise_override(&self, gate: usize) -> Option<&GateNoise> {
+        self.noise_overrides
+            .iter()
+            .find(|entry| entry.gate == gate)
+            .map(|entry| &entry.noise)
+    }
+
+    /// Overrides must name existing gates and hold valid channels.
+    pub fn validate_noise_overrides(&self) -> Result<(), String> {
+        for entry in &self.noise_overrides {
+            let gate = self
+                .gates
+                .get(entry.gate)
+                .ok_or_else(|| format!("Noise override for missing gate {}", entry.gate))?;
+            entry.noise.validate(gate.qubits().len())?;
+        }
+        Ok(())
+    }
+
+    /// Copy of the circuit without the gates flagged in `removed`, with
+    /// region bounds shifted to match.
+    #[doc(hidden)]
+    pub fn without_gates(&self, removed: &[bool]) -> Circuit {
+        // kept_before[i] = number of kept gates with index < i
+        let mut kept_before = Vec::with_capacity(self.gates.len() + 1);
+        kept_before.push(0);
+        for &is_removed in removed {
+            let last = *kept_before.last().unwrap();
+            kept_before.push(if is_removed { last } else { last + 1 });
+        }
+
+        Circuit {
+            num_qubits: self.num_qubits,
+            gates: self
+                .gates
+                .iter()
+                .zip(removed)
+                .filter(|(_, &is_removed)| !is_removed)
+                .map(|(gate, _)| gate.clone())
+                .collect(),
+            regions: self
+                .regions
+                .iter()
+                .map(|region| Region {
+                    name: region.name.clone(),
+                    start: kept_before[region.start],
+                    end: kept_before[region.end],
+                })
+                .collect(),
+            noise_overrides: self
+                .noise_overrides
+                .iter()
+                .filter(|entry| !removed[entry.gate])
+                .map(|entry| NoiseOverride {
+                    gate: kept_before[entry.gate],
+                    noise: entry.noise.clone(),
+                })
+                .collect(),
+            // Moments left empty are dropped
+            moments: (0..self.moments.len())
+                .filter(|&time| {
+                    let range = self.moment_range(time).expect("moment in range");
+                    kept_before[range.start] < kept_before[range.end]
+                })
+                .map(|time| kept_before[self.moments[time]])
+                .collect(),
+            registers: self.registers.clone(),
+            barriers: dedup_sorted(self.barriers.iter().map(|&barrier| kept_before[barrier])),
+            measurements: self
+                .measurements
+                .iter()
+                .map(|m| Measure {
+                    position: kept_before[m.position],
+                    ..*m
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether the gates in `range` may be put in another order without
+    /// moving one into another moment or across a barrier or measurement.
+    pub fn can_reorder(&self, range: Range<usize>) -> bool {
+        range.end <= self.gates.len()
+            && (range.start + 1..range.end).all(|position| {
+                (self.moments.is_empty() || self.splits_moment(position))
+                    && !self.has_barrier_before(position)
+                    && self.measurements_at(position).is_empty()
+            })
+    }
+
+    /// Copy of the circuit with gates `start..start + order.len()` rearranged
+    /// so that slot i holds the gate that was at `start + order[i]`. Noise
+    /// overrides follow their gates. The range must pass `can_reorder`, so
+    /// moments, barriers and measurements stay where they are.
+    pub fn with_reordered_gates(&self, start: usize, order: &[usize]) -> Result<Circuit, String> {
+        let end = start + order.len();
+        if end > self.gates.len() {
+            return Err(format!("Gates {}..{} are past the end of a circuit of {} gates", start, end, self.gates.len()));
+        }
+        if !self.can_reorder(start..end) {
+            return Err(format!("Gates {}..{} span a moment, barrier or measurement boundary", start, end));
+        }
+        let mut sorted = order.to_vec();
+        sorted.sort_unstable();
+        if sorted.iter().enumerate().any(|(i, &slot)| i != slot) {
+            return Err(format!("{:?} is not a permutation", order));
+        }
+        // new_index[old] for the gates in the range
+        let mut new_index = vec![0; order.len()];
+        for (slot, &old) in order.iter().enumerate() {
+            new_index[old] = slot;
+        }
+        let mut reordered = self.clone();
+        for (slot, &old) in order.iter().enumerate() {
+            reordered.gates[start + slot] = self.gates[start + old].clone();
+        }
+        for entry in &mut reordered.noise_overrides {
+            if (start..end).contains(&entry.gate) {
+                entry.gate = start + new_index[entry.gate - start];
+            }
+        }
+        Ok(reordered)
+    }
+
+    /// Copy of the circuit with each `(position, gate)` inserted before the
+    /// gate currently at `position`, with region bounds shifted to match.
+    /// Gates inserted at a region's start land inside the region, and ones
+    /// inserted at a barrier or measurement land after it.
+    #[doc(hidden)]
+    pub fn with_insertions(&self, mut insertions: Vec<(usize, Gate)>) -> Circuit {
+        insertions.sort_by_key(|(position, _)| *position);
+        let shift = |bound: usize| insertions.iter().filter(|(position, _)| *position < bound).count();
+
+        // Inserted gates get moments of their own, splitting the moment they land in
+        let mut gates = Vec::with_capacity(self.gates.len() + insertions.len());
+        let mut moments = Vec::new();
+        let mut after_insertion = false;
+        let mut pending = insertions.iter().peekable();
+        for (index, gate) in self.gates.iter().enumerate() {
+            while let Some((_, inserted)) = pending.next_if(|(position, _)| *position <= index) {
+                moments.push(gates.len());
+                gates.push(inserted.clone());
+                after_insertion = true;
+            }
+            if after_insertion || self.moments.binary_search(&index).is_ok() {
+                moments.push(gates.len());
+            }
+            gates.push(gate.clone());
+            after_insertion = false;
+        }
+        for (_, gate) in pending {
+            moments.push(gates.len());
+            gates.push(gate.clone());
+        }
+
+        Circuit {
+            num_qubits: self.num_qubits,
+            gates,
+            regions: self
+                .regions
+                .iter()
+                .map(|region| Region {
+                    name: region.name.clone(),
+                    start: region.start + shift(region.start),
+                    end: region.end + shift(region.end),
+                })
+                .collect(),
+            noise_overrides: self
+                .noise_overrides
+                .iter()
+                .map(|entry| NoiseOverride {
+                    gate: entry.gate + shift(entry.gate + 1),
+                    noise: entry.noise.clone(),
+                })
+                .collect(),
+            moments: if self.moments.is_empty() { Vec::new() } else { moments },
+            registers: self.registers.clone(),
+            barriers: self.barriers.iter().map(|&barrier| barrier + shift(barrier)).collect(),
+            measurements: self
+                .measurements
+                .iter()
+                .map(|m| Measure {
+                    position: m.position + shift(m.position),
+                    ..*m
+                })
+                .collect(),
+        }
+    }
+
+    /// The gates of moment `time`; empty past the end of the circuit.
+    pub fn gates_at_time(&self, time: usize) -> Vec<&Gate> {
+        self.moment_range(time)
+            .map_or_else(Vec::new, |range| self.gates[range].iter().collect())
+    }
+
+    /// Qubits not touched by any gate at the given time step. Returns an
+    /// empty list past the end of the circuit. Idle noise and the web
+    /// view's greyed wires and idle count are all read from here.
+    pub fn idle_qubits_at(&self, time: usize) -> Vec<usize> {
+        if time >= self.depth() {
+            return vec![];
+        }
+        let mut busy = vec![false; self.num_qubits];
+        for gate in self.gates_at_time(time) {
+            for qubit in gate.qubits() {
+                busy[qubit] = true;
+            }
+        }
+        (0..self.num_qubits).filter(|&qubit| !busy[qubit]).collect()
+    }
+
+    /// The qubits idle during the moment that gate `gate` closes; empty
+    /// when a later gate shares its moment. Idle noise uses this so it is
+    /// applied once per moment.
+    pub fn idle_qubits_after_gate(&self, gate: usize) -> Vec<usize> {
+        match self.moment_of(gate) {
+            Some(time) if self.moment_range(time).is_some_and(|range| range.end == gate + 1) => self.idle_qubits_at(time),
+            _ => vec![],
+        }
+    }
+
+    /// Number of moments.
+    pub fn depth(&self) -> usize {
+        if self.moments.is_empty() {
+            self.gates.len()
+        } else {
+            self.moments.len()
+        }
+    }
+
+    pub fn two_qubit_gate_count(&self) -> usize {
+        self.gates.iter().filter(|gate| matches!(gate, Gate::Two(_))).count()
+    }
+
+    /// One line per gate: its moment, the gate and the regions containing
+    /// it, below the `Display` summary.
+    pub fn to_pretty_string(&self) -> String {
+        let labels: Vec<String> = self.gates.iter().map(Gate::to_string).collect();
+        let moment_width = self.depth().saturating_sub(1).to_string().len();
+        let gate_width = labels.iter().map(String::len).max().unwrap_or(0);
+        let mut pretty = format!("{}\n", self);
+        for (index, label) in labels.iter().enumerate() {
+            let moment = self.moment_of(index).expect("gate in range");
+            let regions: Vec<&str> = self
+                .regions
+                .iter()
+                .filter(|region| (region.start..region.end).contains(&index))
+                .map(|region| region.name.as_str())
+                .collect();
+            let line = format!("{moment:>moment_width$}  {label:<gate_width$}  {}", regions.join(", "));
+            pretty.push_str(line.trim_end());
+            pretty.push('\n');
+        }
+        pretty
+    }
+
+    /// The circuit implementing U' for this circuit's U: gates reversed,
+    /// S and S† swapped. Regions, noise overrides and moments follow their
+    /// gates, so a region's range, the barriers and the measurements are
+    /// mirrored.
+    pub fn inverse(&self) -> Circuit {
+        let gates = self
+            .gates
+            .iter()
+            .rev()
+            .map(Gate::inverse)
+            .collect();
+        Circuit {
+            num_qubits: self.num_qubits,
+            gates,
+            // Regions past the end, which `validate_regions` rejects, are dropped
+            regions: self
+                .regions
+                .iter()
+                .filter_map(|region| {
+                    Some(Region {
+                        name: region.name.clone(),
+                        start: self.gates.len().checked_sub(region.end)?,
+                        end: self.gates.len().checked_sub(region.start)?,
+                    })
+                })
+                .collect(),
+            noise_overrides: self
+                .noise_overrides
+                .iter()
+                .map(|entry| NoiseOverride {
+                    gate: self.gates.len() - 1 - entry.gate,
+                    noise: entry.noise.clone(),
+                })
+                .collect(),
+            moments: (0..self.moments.len())
+                .rev()
+                .map(|time| self.gates.len() - self.moment_range(time).expect("moment in range").end)
+                .collect(),
+            registers: self.registers.clone(),
+            barriers: self.barriers.iter().rev().map(|&barrier| self.gates.len() - barrier).collect(),
+            measurements: self
+                .measurements
+                .iter()
+                .rev()
+                .map(|m| Measure {
+                    position: self.gates.len() - m.position,
+                    ..*m
+                })
+                .collect(),
+        }
+    }
+
+    /// The same circuit packed into as few moments as possible: each gate
+    /// goes to the moment after the last one using any of its qubits, so
+    /// the order of gates on every qubit is kept. Gates never cross a
+    /// barrier, measurement or region bound, so these stay where they are;
+    /// noise overrides follow their gates.
+    pub fn schedule(&self) -> Circuit {
+        let mut barriers: Vec<usize> = self
+            .regions
+            .iter()
+            .flat_map(|region| [region.start, region.end])
+            .chain(self.barriers.iter().copied())
+            .chain(self.measurements.iter().map(|m| m.position))
+            .chain([0, self.gates.len()])
+            .collect();
+        barriers.sort_unstable();
+        barriers.dedup();
+
+        let mut order = Vec::with_capacity(self.gates.len());
+        let mut moments = Vec::new();
+        for segment in barriers.windows(2) {
+            let mut next_free = vec![0; self.num_qubits];
+            let mut layered: Vec<(usize, usize)> = (segment[0]..segment[1])
+                .map(|index| {
+                    let qubits = self.gates[index].qubits();
+                    let layer = qubits.iter().map(|&q| next_free[q]).max().unwrap_or(0);
+                    for &q in &qubits {
+                        next_free[q] = layer + 1;
+                    }
+                    (layer, index)
+                })
+                .collect();
+            layered.sort_unstable();
+            for (i, &(layer, index)) in layered.iter().enumerate() {
+                if i == 0 || layered[i - 1].0 != layer {
+                    moments.push(order.len());
+                }
+                order.push(index);
+            }
+        }
+
+        let mut new_index = vec![0; self.gates.len()];
+        for (new, &old) in order.iter().enumerate() {
+            new_index[old] = new;
+        }
+        Circuit {
+            num_qubits: self.num_qubits,
+            gates: order.iter().map(|&index| self.gates[index].clone()).collect(),
+            regions: self.regions.clone(),
+            noise_overrides: self
+                .noise_overrides
+                .iter()
+                .map(|entry| NoiseOverride {
+                    gate: new_index[entry.gate],
+                    noise: entry.noise.clone(),
+                })
+                .collect(),
+            moments,
+            registers: self.registers.clone(),
+            barriers: self.barriers.clone(),
+            measurements: self.measurements.clone(),
+        }
+    }
+}
+
+/// Collects increasing values, dropping repeats.
+fn dedup_sorted(values: impl Iterator<Item = usize>) -> Vec<usize> {
+    let mut values: Vec<usize> = values.collect();
+    values.dedup();
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::pauli::PauliString;
+    use crate::physics::tableau::Tableau;
+
+    #[test]
+    fn test_circuit_creation() {
+        let circuit = Circuit::new(3);
+        assert_eq!(circuit.num_qubits, 3);
+        assert_eq!(circuit.gates.len(), 0);
+    }
+
+    #[test]
+    fn test_add_gate() {
+        let mut circuit = Circuit::new(2);
+        circuit
+            .add_gate(Gate::Single {
+                qubit: 0,
+                gate: SingleGate::H,
+            })
+            .unwrap();
+        circuit
+            .add_gate(Gate::Two(TwoGate::CNOT {
+                control: 0,
+                target: 1,
+            }))
+            .unwrap();
+        assert_eq!(circuit.gates.len(), 2);
+    }
+
+    #[test]
+    fn test_invalid_qubit_index() {
+        let mut circuit = Circuit::new(2);
+        let result = circuit.add_gate(Gate::Single {
+            qubit: 5,
+            gate: SingleGate::H,
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_gate_commutation() {
+        let cnot = |control, target| Gate::Two(TwoGate::CNOT { control, target });
+        let single = |qubit, gate| Gate::Single { qubit, gate };
+
+        // Shared control or shared target commute, control/target overlap does not
+        assert!(cnot(0, 1).commutes_with(&cnot(0, 2)));
+        assert!(cnot(0, 2).commutes_with(&cnot(1, 2)));
+        assert!(!cnot(0, 1).commutes_with(&cnot(1, 2)));
+
+        assert!(cnot(0, 1).commutes_with(&single(0, SingleGate::S)));
+        assert!(cnot(0, 1).commutes_with(&single(1, SingleGate::X)));
+        assert!(!cnot(0, 1).commutes_with(&single(0, SingleGate::H)));
+        assert!(cnot(0, 1).commutes_with(&Gate::Two(TwoGate::CZ { control: 0, target: 2 })));
+        assert!(!cnot(0, 1).commutes_with(&Gate::Two(TwoGate::CZ { control: 1, target: 2 })));
+        assert!(cnot(0, 1).commutes_with(&single(2, SingleGate::H)));
+    }
+
+    #[test]
+    fn test_regions() {
+        let mut circuit = Circuit::new(2);
+        for qubit in 0..2 {
+            circuit
+                .add_gate(Gate::Single {
+                    qubit,
+                    gate: SingleGate::H,
+                })
+                .unwrap();
+        }
+        circuit.add_region("prep", 0, 2).unwrap();
+        assert!(circuit.add_region("prep", 0, 1).is_err());
+        assert!(circuit.add_region("late", 1, 3).is_err());
+
+        let reduced = circuit.without_gates(&[true, false]);
+        assert_eq!(reduced.gates.len(), 1);
+        assert_eq!(reduced.region("prep").map(|r| (r.start, r.end)), Some((0, 1)));
+
+        circuit.validate_regions().unwrap();
+        circuit.regions[0].end = 3;
+        assert!(circuit.validate_regions().is_err());
+        assert!(circuit.inverse().regions.is_empty());
+    }
+
+    #[test]
+    fn test_noise_overrides_follow_gates() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CZ { control: 0, target: 1 })).unwrap();
+        circuit.set_noise_override(1, GateNoise::Depolarizing(0.2)).unwrap();
+        assert_eq!(GateNoise::Depolarizing(0.2).terms(2).len(), 15);
+        assert!(circuit.set_noise_override(1, GateNoise::Pauli(vec![(vec![SinglePauli::X], 0.1)])).is_err());
+
+        let shifted = circuit.with_insertions(vec![(0, Gate::Single { qubit: 1, gate: SingleGate::X })]);
+        assert_eq!(shifted.noise_override(2), Some(&GateNoise::Depolarizing(0.2)));
+        assert!(circuit.without_gates(&[false, true]).noise_overrides.is_empty());
+        assert!(circuit.inverse().noise_override(0).is_some());
+
+        // Equality is reflexive even for a NaN that bypassed validation
+        let nan = GateNoise::Pauli(vec![(vec![SinglePauli::X], f64::NAN)]);
+        assert_eq!(nan, nan.clone());
+        assert_ne!(GateNoise::Depolarizing(0.2), GateNoise::Depolarizing(0.1));
+    }
+
+    #[test]
+    fn test_pretty_formats() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 1, gate: SingleGate::Sdg }).unwrap();
+        circuit.add_region("bell", 0, 2).unwrap();
+
+        assert_eq!(circuit.to_string(), "Circuit(2 qubits, 3 gates, 1 two-qubit, regions: bell)");
+        assert_eq!(
+            circuit.to_pretty_string(),
+            "Circuit(2 qubits, 3 gates, 1 two-qubit, regions: bell)\n\
+             0  H(0)        bell\n\
+             1  CNOT(0, 1)  bell\n\
+             2  Sdg(1)\n"
+        );
+        assert_eq!(format!("{:#}", circuit), circuit.to_pretty_string().trim_end());
+    }
+
+    #[test]
+    fn test_idle_qubits_at() {
+        let mut circuit = Circuit::new(3);
+        circuit
+            .add_gate(Gate::Single {
+                qubit: 1,
+                gate: SingleGate::H,
+            })
+            .unwrap();
+        circuit
+            .add_gate(Gate::Two(TwoGate::CNOT {
+                control: 0,
+                target: 2,
+            }))
+            .unwrap();
+
+        assert_eq!(circuit.idle_qubits_at(0), vec![0, 2]);
+        assert_eq!(circuit.idle_qubits_at(1), vec![1]);
+        assert!(circuit.idle_qubits_at(2).is_empty());
+    }
+
+    #[test]
+    fn test_moments() {
+        let h = |qubit| Gate::Single { qubit, gate: SingleGate::H };
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(h(0)).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        circuit.add_region("second", 1, 2).unwrap();
+        circuit.add_gate_at(0, h(2)).unwrap();
+        circuit.add_gate_at(1, h(2)).unwrap();
+        assert!(circuit.add_gate_at(1, h(1)).is_err());
+        assert!(circuit.add_gate_at(3, h(1)).is_err());
+        circuit.add_gate_at(2, h(1)).unwrap();
+        circuit.validate_moments().unwrap();
+
+        assert_eq!(circuit.depth(), 3);
+        assert_eq!(circuit.moments, vec![0, 2, 4]);
+        assert_eq!(circuit.gates_at_time(0), vec![&h(0), &h(2)]);
+        assert_eq!(circuit.region("second").unwrap(), &Region { name: "second".to_string(), start: 2, end: 4 });
+        assert_eq!(circuit.idle_qubits_after_gate(0), Vec::<usize>::new());
+        assert_eq!(circuit.idle_qubits_after_gate(1), vec![1]);
+        assert_eq!(circuit.moment_of(3), Some(1));
+
+        // Removing a moment's only gate drops the moment
+        assert_eq!(circuit.without_gates(&[false, false, false, false, true]).moments, vec![0, 2]);
+        assert_eq!(circuit.inverse().moments, vec![0, 1, 3]);
+        let inserted = circuit.with_insertions(vec![(1, h(1))]);
+        assert_eq!(inserted.moments, vec![0, 1, 2, 3, 5]);
+        inserted.validate_moments().unwrap();
+
+        circuit.moments = vec![0, 1, 1];
+        assert!(circuit.validate_moments().is_err());
+        circuit.moments = vec![0, 3];
+        assert!(circuit.validate_moments().is_err());
+    }
+
+    #[test]
+    fn test_compose() {
+        let mut check = Circuit::new(3);
+        check.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 2 })).unwrap();
+        check.add_gate(Gate::Two(TwoGate::CNOT { control: 1, target: 2 })).unwrap();
+        check.add_region("round", 0, 2).unwrap();
+        check.set_noise_override(1, GateNoise::Depolarizing(0.2)).unwrap();
+
+        let mut circuit = Circuit::new(5);
+        circuit.add_gate(Gate::Single { qubit: 4, gate: SingleGate::H }).unwrap();
+        circuit.compose(&check, &[3, 4, 0]).unwrap();
+        assert_eq!(circuit.gates[2], Gate::Two(TwoGate::CNOT { control: 4, target: 0 }));
+        assert_eq!(circuit.region("round").unwrap().start, 1);
+        assert!(circuit.noise_override(2).is_some());
+        assert!(circuit.compose(&check, &[0, 1, 2]).is_err());
+        assert!(circuit.compose(&Circuit::new(2), &[1, 1]).is_err());
+        assert!(circuit.compose(&Circuit::new(1), &[5]).is_err());
+
+        let mut layered = Circuit::new(5);
+        layered.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        layered.add_gate_at(0, Gate::Single { qubit: 1, gate: SingleGate::H }).unwrap();
+        circuit.append(&layered).unwrap();
+        assert_eq!(circuit.depth(), 4);
+        circuit.validate_moments().unwrap();
+    }
+
+    #[test]
+    fn test_registers() {
+        let mut circuit = Circuit::new(0);
+        assert_eq!(circuit.add_register("data", 3).unwrap(), 0);
+        assert_eq!(circuit.add_register("ancilla", 2).unwrap(), 3);
+        assert!(circuit.add_register("data", 1).is_err());
+        assert_eq!(circuit.num_qubits, 5);
+
+        let control = circuit.qubit("data", 2).unwrap();
+        let target = circuit.qubit("ancilla", 1).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control, target })).unwrap();
+        assert_eq!(circuit.gates[0].qubits(), vec![2, 4]);
+        assert_eq!(circuit.register_of(4), Some(("ancilla", 1)));
+        assert!(circuit.qubit("ancilla", 2).is_err());
+        assert!(circuit.qubit("flag", 0).is_err());
+        assert_eq!(circuit.to_string(), "Circuit(5 qubits, 1 gates, 1 two-qubit, registers: data[3], ancilla[2])");
+
+        circuit.validate_registers().unwrap();
+        circuit.registers[1].start = 2;
+        assert!(circuit.validate_registers().is_err());
+        circuit.registers[1].start = 4;
+        assert!(circuit.validate_registers().is_err());
+    }
+
+    #[test]
+    fn test_remap_qubits() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(Gate::Two(TwoGate::CZ { control: 0, target: 1 })).unwrap();
+        circuit.add_region("all", 0, 1).unwrap();
+
+        let swapped = circuit.remap_qubits(&[1, 0]).unwrap();
+        assert_eq!(swapped.num_qubits, 2);
+        assert_eq!(swapped.gates, vec![Gate::Two(TwoGate::CZ { control: 1, target: 0 })]);
+        assert!(swapped.region("all").is_some());
+
+        let embedded = circuit.remap_qubits(&[6, 3]).unwrap();
+        assert_eq!(embedded.num_qubits, 7);
+        assert_eq!(embedded.gates[0].qubits(), vec![6, 3]);
+        assert!(circuit.remap_qubits(&[1, 1]).is_err());
+        assert!(circuit.remap_qubits(&[0]).is_err());
+    }
+
+    #[test]
+    fn test_inverse_undoes_circuit() {
+        let mut circuit = Circuit::new(3);
+        for gate in [
+            Gate::Single { qubit: 0, gate: SingleGate::H },
+            Gate::Single { qubit: 1, gate: SingleGate::S },
+            Gate::Two(TwoGate::CNOT { control: 0, target: 2 }),
+            Gate::Two(TwoGate::CZ { control: 1, target: 2 }),
+            Gate::Two(TwoGate::SWAP { qubit1: 0, qubit2: 1 }),
+        ] {
+            circuit.add_gate(gate).unwrap();
+        }
+        circuit.add_region("entangle", 2, 4).unwrap();
+        let inverse = circuit.inverse();
+        assert_eq!(inverse.gates[3], Gate::Single { qubit: 1, gate: SingleGate::Sdg });
+        assert_eq!(inverse.region("entangle").unwrap(), &Region { name: "entangle".to_string(), start: 1, end: 3 });
+
+        let mut tableau = Tableau::from_circuit(&circuit);
+        for gate in &inverse.gates {
+            tableau.apply_gate(gate);
+        }
+        assert_eq!(tableau, Tableau::identity(3));
+        assert_eq!(inverse.inverse(), circuit);
+    }
+
+    #[test]
+    fn test_schedule() {
+        let h = |qubit| Gate::Single { qubit, gate: SingleGate::H };
+        let mut circuit = Circuit::new(3);
+        for gate in [h(0), Gate::Two(TwoGate::CNOT { control: 0, target: 1 }), h(2), h(1), h(2)] {
+            circuit.add_gate(gate).unwrap();
+        }
+        circuit.set_noise_override(2, GateNoise::Depolarizing(0.1)).unwrap();
+        let scheduled = circuit.schedule();
+        scheduled.validate_moments().unwrap();
+        assert_eq!(scheduled.depth(), 3);
+        assert_eq!(scheduled.gates_at_time(0), vec![&h(0), &h(2)]);
+        assert_eq!(scheduled.noise_override(1), Some(&GateNoise::Depolarizing(0.1)));
+        let pauli = PauliString::from_str("XZY", 3).unwrap();
+        assert_eq!(pauli.conjugated_by(&scheduled), pauli.conjugated_by(&circuit));
+
+        // H(2) cannot leave its region
+        circuit.add_region("tail", 2, 5).unwrap();
+        let scheduled = circuit.schedule();
+        assert_eq!(scheduled.depth(), 4);
+        assert_eq!(scheduled.region("tail").unwrap().start, 2);
+        assert_eq!(scheduled.gates_at_time(2), vec![&h(2), &h(1)]);
+    }
+
+    #[test]
+    fn test_barriers() {
+        let h = |qubit| Gate::Single { qubit, gate: SingleGate::H };
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(h(0)).unwrap();
+        circuit.add_barrier();
+        circuit.add_barrier();
+        circuit.add_gate(h(1)).unwrap();
+        circuit.add_gate(h(0)).unwrap();
+        assert_eq!(circuit.barriers, vec![1]);
+        circuit.validate_barriers().unwrap();
+
+        // H(1) stays after the barrier instead of joining H(0)
+        let scheduled = circuit.schedule();
+        assert_eq!(scheduled.depth(), 2);
+        assert_eq!(scheduled.gates_at_time(1), vec![&h(1), &h(0)]);
+        assert!(scheduled.has_barrier_before(1));
+        scheduled.validate_barriers().unwrap();
+
+        assert_eq!(circuit.inverse().barriers, vec![2]);
+        assert_eq!(circuit.without_gates(&[true, false, false]).barriers, vec![0]);
+        assert_eq!(circuit.with_insertions(vec![(0, h(1)), (1, h(0))]).barriers, vec![2]);
+        let mut doubled = circuit.clone();
+        doubled.append(&circuit).unwrap();
+        assert_eq!(doubled.barriers, vec![1, 4]);
+
+        let mut split = scheduled.clone();
+        split.barriers = vec![2];
+        assert!(split.validate_barriers().is_err());
+        split.barriers = vec![4];
+        assert!(split.validate_barriers().is_err());
+    }
+
+    #[test]
+    fn test_measurements() {
+        let h = |qubit| Gate::Single { qubit, gate: SingleGate::H };
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(h(0)).unwrap();
+        circuit.add_measurement(0, MeasurementBasis::Z).unwrap();
+        circuit.add_gate(h(1)).unwrap();
+        circuit.add_measurement(1, MeasurementBasis::X).unwrap();
+        assert!(circuit.add_measurement(2, MeasurementBasis::Z).is_err());
+        assert_eq!(circuit.measurements_at(1), &[Measure { position: 1, qubit: 0, basis: MeasurementBasis::Z }]);
+        assert!(circuit.measurements_at(0).is_empty());
+        circuit.validate_measurements().unwrap();
+
+        // H(1) waits for the readout of qubit 0
+        assert_eq!(circuit.schedule().depth(), 2);
+        let remapped = circuit.remap_qubits(&[1, 0]).unwrap();
+        assert_eq!(remapped.measurements[1].qubit, 0);
+        assert_eq!(circuit.inverse().measurements[0].position, 0);
+        assert_eq!(circuit.without_gates(&[true, false]).measurements[0].position, 0);
+
+        let mut shuffled = circuit.clone();
+        shuffled.measurements.reverse();
+        assert!(shuffled.validate_measurements().is_err());
+    }
+}
+
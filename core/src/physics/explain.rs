@@ -0,0 +1,168 @@
+//! Explanations of single propagation steps.
+//!
+//! `explain_gate` describes why a gate turned one error pattern into the
+//! next: the Paulis on the gate's qubits before and after, the conjugation
+//! rule behind the change (e.g. "X on control spread to target") and the
+//! phase picked up. The fields are for programs, `summary` for people.
+
+use crate::physics::circuit::{Gate, SingleGate, TwoGate};
+use crate::physics::pauli::{PauliString, Phase, SinglePauli};
+use crate::physics::propagation::apply_gate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QubitChange {
+    pub qubit: usize,
+    pub before: SinglePauli,
+    pub after: SinglePauli,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepExplanation {
+    pub gate_index: usize,
+    pub gate: Gate,
+    /// One entry per qubit of the gate, in `Gate::qubits` order
+    pub changes: Vec<QubitChange>,
+    /// Factor taking the phase before the gate to the phase after it
+    pub phase_factor: Phase,
+    /// The conjugation rules that applied, one clause each
+    pub rules: Vec<String>,
+    /// e.g. "CNOT(0, 3): X on control spread to target; phase unchanged"
+    pub summary: String,
+}
+
+impl fmt::Display for StepExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.summary)
+    }
+}
+
+/// Explains gate `gate_index` of a circuit acting on the pattern `before`.
+pub fn explain_gate(gate_index: usize, gate: &Gate, before: &PauliString) -> StepExplanation {
+    let mut after = before.clone();
+    apply_gate(&mut after, gate);
+
+    let changes: Vec<QubitChange> = gate
+        .qubits()
+        .into_iter()
+        .map(|qubit| QubitChange {
+            qubit,
+            before: before.get_pauli(qubit),
+            after: after.get_pauli(qubit),
+        })
+        .collect();
+    let phase_factor = after.phase().multiply(before.phase().conjugate());
+
+    let rules = if changes.iter().all(|change| change.before == SinglePauli::I) {
+        vec![match changes.as_slice() {
+            [only] => format!("no error on qubit {}", only.qubit),
+            _ => "no error on these qubits".to_string(),
+        }]
+    } else {
+        rules_for(gate, &changes)
+    };
+    let phase = match phase_factor {
+        Phase::PlusOne => "phase unchanged".to_string(),
+        Phase::MinusOne => "phase flipped".to_string(),
+        factor => format!("phase multiplied by {}", factor),
+    };
+    let summary = format!("{}: {}; {}", gate, rules.join("; "), phase);
+
+    StepExplanation {
+        gate_index,
+        gate: gate.clone(),
+        changes,
+        phase_factor,
+        rules,
+        summary,
+    }
+}
+
+/// Rule clauses for a gate whose qubits carry at least one error.
+fn rules_for(gate: &Gate, changes: &[QubitChange]) -> Vec<String> {
+    match gate {
+        Gate::Single { gate, .. } => {
+            let pauli = changes[0].before;
+            vec![match gate {
+                SingleGate::I => format!("identity leaves {} unchanged", pauli),
+                SingleGate::X | SingleGate::Y | SingleGate::Z => {
+                    let name = format!("{:?}", gate);
+                    if pauli.to_string() == name {
+                        format!("{} commutes with {}", pauli, name)
+                    } else {
+                        format!("{} anticommutes with {}", pauli, name)
+                    }
+                }
+                SingleGate::H if pauli == SinglePauli::Y => "H keeps Y".to_string(),
+                SingleGate::H => "H swaps X and Z".to_string(),
+                SingleGate::S | SingleGate::Sdg => {
+                    let name = if *gate == SingleGate::S { "S" } else { "S†" };
+                    match pauli {
+                        SinglePauli::Z => format!("Z commutes with {}", name),
+                        _ => format!("{} turns {} into {}", name, pauli, changes[0].after),
+                    }
+                }
+            }]
+        }
+        Gate::Two(two_gate) => {
+            let (first, second) = (changes[0].before.to_bits(), changes[1].before.to_bits());
+            let mut rules = Vec::new();
+            match two_gate {
+                TwoGate::CNOT { .. } => {
+                    if first.0 {
+                        rules.push("X on control spread to target".to_string());
+                    }
+                    if second.1 {
+                        rules.push("Z on target spread to control".to_string());
+                    }
+                }
+                TwoGate::CZ { .. } => {
+                    if first.0 {
+                        rules.push("X on control added Z to target".to_string());
+                    }
+                    if second.0 {
+                        rules.push("X on target added Z to control".to_string());
+                    }
+                }
+                TwoGate::SWAP { .. } => {
+                    rules.push(format!(
+                        "{} and {} exchanged",
+                        changes[0].before, changes[1].before
+                    ));
+                }
+            }
+            if rules.is_empty() {
+                rules.push(format!("{} passes through unchanged", gate.kind()));
+            }
+            rules
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_gate() {
+        let cnot = Gate::Two(TwoGate::CNOT { control: 0, target: 3 });
+        let before = PauliString::from_str("XIII", 4).unwrap();
+        let explanation = explain_gate(2, &cnot, &before);
+        assert_eq!(explanation.summary, "CNOT(0, 3): X on control spread to target; phase unchanged");
+        assert_eq!(
+            explanation.changes[1],
+            QubitChange { qubit: 3, before: SinglePauli::I, after: SinglePauli::X }
+        );
+
+        let cz = Gate::Two(TwoGate::CZ { control: 0, target: 1 });
+        let explanation = explain_gate(0, &cz, &PauliString::from_str("ZI", 2).unwrap());
+        assert_eq!(explanation.rules, vec!["CZ passes through unchanged"]);
+
+        let x = Gate::Single { qubit: 1, gate: SingleGate::X };
+        let explanation = explain_gate(0, &x, &PauliString::from_str("IZ", 2).unwrap());
+        assert_eq!(explanation.summary, "X(1): Z anticommutes with X; phase flipped");
+        let idle = explain_gate(0, &x, &PauliString::new(2));
+        assert_eq!(idle.rules, vec!["no error on qubit 1"]);
+    }
+}
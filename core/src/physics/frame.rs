@@ -0,0 +1,111 @@
+//! Physical-qubit and logical-wire views of an error pattern.
+//!
+//! SWAP gates move states between physical qubits. In the physical frame
+//! an error is reported on the qubit it sits on; in the logical frame it
+//! is reported on the wire (the input qubit) whose state it now affects,
+//! as if every SWAP so far had been elided. A layout lists, for each
+//! physical qubit, the wire it currently holds.
+
+use crate::physics::circuit::{Circuit, Gate, TwoGate};
+use crate::physics::pauli::PauliString;
+use crate::physics::simulator::Snapshot;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorFrame {
+    #[default]
+    Physical,
+    Logical,
+}
+
+impl ErrorFrame {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "physical" => Ok(Self::Physical),
+            "logical" => Ok(Self::Logical),
+            _ => Err(format!("Unknown error frame '{}'", name)),
+        }
+    }
+}
+
+/// Wire held by each physical qubit after the first `time` gates.
+pub fn wire_layout(circuit: &Circuit, time: usize) -> Vec<usize> {
+    let mut layout: Vec<usize> = (0..circuit.num_qubits).collect();
+    for gate in circuit.gates.iter().take(time) {
+        track_swap(&mut layout, gate);
+    }
+    layout
+}
+
+fn track_swap(layout: &mut [usize], gate: &Gate) {
+    if let Gate::Two(TwoGate::SWAP { qubit1, qubit2 }) = gate {
+        layout.swap(*qubit1, *qubit2);
+    }
+}
+
+pub fn to_logical(physical: &PauliString, layout: &[usize]) -> PauliString {
+    let mut logical = PauliString::new(physical.num_qubits());
+    for (qubit, &wire) in layout.iter().enumerate() {
+        logical.set_pauli(wire, physical.get_pauli(qubit));
+    }
+    logical.set_phase(physical.phase());
+    logical
+}
+
+pub fn to_physical(logical: &PauliString, layout: &[usize]) -> PauliString {
+    let mut physical = PauliString::new(logical.num_qubits());
+    for (qubit, &wire) in layout.iter().enumerate() {
+        physical.set_pauli(qubit, logical.get_pauli(wire));
+    }
+    physical.set_phase(logical.phase());
+    physical
+}
+
+/// `snapshots` (in the physical frame, oldest first) seen in `frame`.
+pub fn snapshots_in_frame(circuit: &Circuit, snapshots: &[Snapshot], frame: ErrorFrame) -> Vec<Snapshot> {
+    if frame == ErrorFrame::Physical {
+        return snapshots.to_vec();
+    }
+    let mut time = snapshots.first().map_or(0, |s| s.time);
+    let mut layout = wire_layout(circuit, time);
+    snapshots
+        .iter()
+        .map(|snapshot| {
+            for gate in circuit.gates.iter().take(snapshot.time).skip(time) {
+                track_swap(&mut layout, gate);
+            }
+            time = time.max(snapshot.time);
+            Snapshot {
+                error_pattern: to_logical(&snapshot.error_pattern, &layout),
+                ..snapshot.clone()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::pauli::SinglePauli;
+    use crate::physics::simulator::Simulator;
+
+    #[test]
+    fn test_logical_frame_follows_swaps() {
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(Gate::Two(TwoGate::SWAP { qubit1: 0, qubit2: 1 })).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::SWAP { qubit1: 1, qubit2: 2 })).unwrap();
+        let mut simulator = Simulator::new(circuit);
+        simulator.inject_error(0, SinglePauli::X);
+        simulator.run();
+
+        // The X travelled 0 -> 1 -> 2 but stays on wire 0
+        assert_eq!(simulator.error_pattern().get_pauli(2), SinglePauli::X);
+        let logical = simulator.error_pattern_in(ErrorFrame::Logical);
+        assert_eq!(logical.get_pauli(0), SinglePauli::X);
+        assert_eq!(to_physical(&logical, &wire_layout(simulator.circuit(), 2)), *simulator.error_pattern());
+
+        let timeline = simulator.timeline_in(ErrorFrame::Logical);
+        assert!(timeline.iter().all(|s| s.error_pattern.get_pauli(0) == SinglePauli::X));
+        assert_eq!(ErrorFrame::parse("Logical"), Ok(ErrorFrame::Logical));
+    }
+}
@@ -0,0 +1,32 @@
+pub mod pauli;
+pub mod pauli_batch;
+pub mod pauli_group;
+pub mod centralizer;
+pub mod circuit;
+pub mod explain;
+pub mod frame;
+pub mod propagation;
+pub mod rule_table;
+pub mod simulator;
+pub mod small_pauli;
+pub mod sparse_pauli;
+pub mod tableau;
+pub mod timeline;
+pub mod twirl;
+
+pub use pauli::*;
+pub use pauli_batch::PauliBatch;
+pub use pauli_group::PauliGroup;
+pub use centralizer::{centralizer, commutes_with_all};
+pub use circuit::*;
+pub use explain::{explain_gate, QubitChange, StepExplanation};
+pub use frame::ErrorFrame;
+pub use propagation::*;
+pub use rule_table::{rule_table, rule_tables, ConjugationRule, RuleTable, GATE_KINDS};
+pub use simulator::*;
+pub use small_pauli::PauliStringN;
+pub use sparse_pauli::SparsePauliString;
+pub use tableau::*;
+pub use timeline::{Timeline, TimelineRetention};
+pub use twirl::{channel_twirl_set, gate_twirl_set, TwirlPair};
+
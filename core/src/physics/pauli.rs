@@ -0,0 +1,968 @@
+//! Pauli operator representation using bit-packed symplectic form.
+//!
+//! - x_bits: bit vector where bit i = 1 if X component on qubit i
+//! - z_bits: bit vector where bit i = 1 if Z component on qubit i
+//! - phase: overall phase factor(+1, -1, +i, -i)
+//!
+//! `encode` and `decode` give a fixed-size binary form for bulk storage.
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::{BitXor, BitXorAssign, Mul, Neg};
+use serde::{Deserialize, Serialize};
+use bitvec::prelude::*;
+use rand_core::Rng;
+
+/// Encoded as: 0 = +1, 1 = +i, 2 = -1, 3 = -i
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum Phase {
+    PlusOne = 0,
+    PlusI = 1,
+    MinusOne = 2,
+    MinusI = 3,
+}
+
+impl Phase {
+    pub fn from_u8(value: u8) -> Self {
+        match value & 3 {
+            0 => Phase::PlusOne,
+            1 => Phase::PlusI,
+            2 => Phase::MinusOne,
+            3 => Phase::MinusI,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn to_u8(self) -> u8 {
+        self as u8
+    }
+
+    pub fn multiply(self, other: Phase) -> Phase {
+        let result = (self.to_u8() + other.to_u8()) % 4;
+        Phase::from_u8(result)
+    }
+
+    pub fn negate(self) -> Phase {
+        Phase::from_u8((self.to_u8() + 2) % 4)
+    }
+
+    /// Complex conjugate: swaps +i and -i.
+    pub fn conjugate(self) -> Phase {
+        Phase::from_u8((4 - self.to_u8()) % 4)
+    }
+
+    pub fn is_real(self) -> bool {
+        matches!(self, Phase::PlusOne | Phase::MinusOne)
+    }
+}
+
+impl BitXor for Phase {
+    type Output = Phase;
+
+    fn bitxor(self, rhs: Phase) -> Self::Output {
+        self.multiply(rhs)
+    }
+}
+
+impl BitXorAssign for Phase {
+    fn bitxor_assign(&mut self, rhs: Phase) {
+        *self = self.multiply(rhs);
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::PlusOne => write!(f, ""),
+            Phase::PlusI => write!(f, "i"),
+            Phase::MinusOne => write!(f, "−"),
+            Phase::MinusI => write!(f, "−i"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SinglePauli {
+    I,
+    X,
+    Y,
+    Z,
+}
+
+impl SinglePauli {
+    /// (X component, Z component)
+    pub fn to_bits(self) -> (bool, bool) {
+        match self {
+            SinglePauli::I => (false, false),
+            SinglePauli::X => (true, false),
+            SinglePauli::Y => (true, true),
+            SinglePauli::Z => (false, true),
+        }
+    }
+
+    pub fn from_bits(x: bool, z: bool) -> Self {
+        match (x, z) {
+            (false, false) => SinglePauli::I,
+            (true, false) => SinglePauli::X,
+            (true, true) => SinglePauli::Y,
+            (false, true) => SinglePauli::Z,
+        }
+    }
+
+    /// `self * other` as a Pauli and a phase, in the convention of
+    /// `PauliString::multiply` (X * Z = iY).
+    pub fn multiply(self, other: SinglePauli) -> (SinglePauli, Phase) {
+        let (x1, z1) = self.to_bits();
+        let (x2, z2) = other.to_bits();
+        let exponent = (4 + u8::from(x1 && z2) - u8::from(z1 && x2)) % 4;
+        (SinglePauli::from_bits(x1 ^ x2, z1 ^ z2), Phase::from_u8(exponent))
+    }
+}
+
+impl fmt::Display for SinglePauli {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SinglePauli::I => write!(f, "I"),
+            SinglePauli::X => write!(f, "X"),
+            SinglePauli::Y => write!(f, "Y"),
+            SinglePauli::Z => write!(f, "Z"),
+        }
+    }
+}
+
+/// Uniform sample from 0..n.
+pub(crate) fn below<R: Rng + ?Sized>(rng: &mut R, n: usize) -> usize {
+    ((rng.next_u64() as u128 * n as u128) >> 64) as usize
+}
+
+/// Where qubit 0 sits in a written Pauli string. This crate writes qubit 0
+/// leftmost; Qiskit labels write it rightmost.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PauliOrder {
+    #[default]
+    Qubit0Leftmost,
+    Qubit0Rightmost,
+}
+
+/// Multi-qubit Pauli string using bit-packed symplectic representation.
+///
+/// For n qubits:
+/// Using bitvec
+/// - phase: Phase(2 bits)
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PauliString {
+    /// X components: bit i = 1 means X on qubit i
+    x_bits: BitVec<usize, Lsb0>,
+    z_bits: BitVec<usize, Lsb0>,
+    phase: Phase,
+    /// For bounds checking
+    num_qubits: usize,
+}
+
+impl PauliString {
+    pub fn new(num_qubits: usize) -> Self {
+        let x_bits = bitvec![usize, Lsb0; 0; num_qubits];
+        let z_bits = bitvec![usize, Lsb0; 0; num_qubits];
+        Self {
+            x_bits,
+            z_bits,
+            phase: Phase::PlusOne,
+            num_qubits,
+        }
+    }
+
+    /// Create from string representation (e.g., "X I Z" or "XIZ")
+    pub fn from_str(s: &str, num_qubits: usize) -> Result<Self, String> {
+        let mut x_bits = bitvec![usize, Lsb0; 0; num_qubits];
+        let mut z_bits = bitvec![usize, Lsb0; 0; num_qubits];
+        
+        let chars: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+        
+        if chars.len() != num_qubits {
+            return Err(format!(
+                "String length {} doesn't match num_qubits {}",
+                chars.len(),
+                num_qubits
+            ));
+        }
+
+        for (i, ch) in chars.iter().enumerate() {
+            if i >= num_qubits {
+                return Err(format!("Index {} out of range for {} qubits", i, num_qubits));
+            }
+            
+            match ch {
+                'I' | 'i' => {}
+                'X' | 'x' => {
+                    x_bits.set(i, true);
+                }
+                'Z' | 'z' => {
+                    z_bits.set(i, true);
+                }
+                'Y' | 'y' => {
+                    x_bits.set(i, true);
+                    z_bits.set(i, true);
+                }
+                _ => {
+                    return Err(format!("Invalid Pauli character: {}", ch));
+                }
+            }
+        }
+
+        Ok(Self {
+            x_bits,
+            z_bits,
+            phase: Phase::PlusOne,
+            num_qubits,
+        })
+    }
+
+    /// Parses `s` written in `order`, with an optional leading sign (`+`,
+    /// `-`, `i`, `+i`, `-i`).
+    pub fn from_str_in(s: &str, num_qubits: usize, order: PauliOrder) -> Result<Self, String> {
+        let s = s.trim();
+        let (phase, body) = [
+            ("+i", Phase::PlusI),
+            ("-i", Phase::MinusI),
+            ("i", Phase::PlusI),
+            ("+", Phase::PlusOne),
+            ("-", Phase::MinusOne),
+        ]
+        .iter()
+        .find_map(|(sign, phase)| s.strip_prefix(sign).map(|body| (*phase, body)))
+        .unwrap_or((Phase::PlusOne, s));
+        let mut pauli = Self::from_str(body, num_qubits)?;
+        if order == PauliOrder::Qubit0Rightmost {
+            pauli = pauli.reversed();
+        }
+        pauli.phase = phase;
+        Ok(pauli)
+    }
+
+    /// A sign (`+`, `-`, `+i`, `-i`) followed by one letter per qubit in
+    /// `order`; `from_str_in` reads it back.
+    pub fn to_string_in(&self, order: PauliOrder) -> String {
+        let sign = match self.phase {
+            Phase::PlusOne => "+",
+            Phase::PlusI => "+i",
+            Phase::MinusOne => "-",
+            Phase::MinusI => "-i",
+        };
+        let letters = (0..self.num_qubits).map(|q| self.get_pauli(q).to_string());
+        match order {
+            PauliOrder::Qubit0Leftmost => format!("{}{}", sign, letters.collect::<String>()),
+            PauliOrder::Qubit0Rightmost => format!("{}{}", sign, letters.rev().collect::<String>()),
+        }
+    }
+
+    /// The same operator with qubit i relabelled n-1-i, converting between
+    /// qubit-0-leftmost and qubit-0-rightmost readings of a string.
+    pub fn reversed(&self) -> Self {
+        let mut x_bits = self.x_bits.clone();
+        x_bits.reverse();
+        let mut z_bits = self.z_bits.clone();
+        z_bits.reverse();
+        Self {
+            x_bits,
+            z_bits,
+            phase: self.phase,
+            num_qubits: self.num_qubits,
+        }
+    }
+
+    /// Uniform over all 4^n Paulis, with phase +1.
+    pub fn random<R: Rng + ?Sized>(num_qubits: usize, rng: &mut R) -> Self {
+        let mut pauli = Self::new(num_qubits);
+        for qubit in 0..num_qubits {
+            let bits = below(rng, 4);
+            pauli.x_bits.set(qubit, bits & 1 == 1);
+            pauli.z_bits.set(qubit, bits & 2 == 2);
+        }
+        pauli
+    }
+
+    /// Uniform over the Paulis of exactly `weight` non-identity components,
+    /// with phase +1. Fails when `weight` exceeds `num_qubits`.
+    pub fn random_with_weight<R: Rng + ?Sized>(num_qubits: usize, weight: usize, rng: &mut R) -> Result<Self, String> {
+        if weight > num_qubits {
+            return Err(format!("Weight {} exceeds {} qubits", weight, num_qubits));
+        }
+        let mut qubits: Vec<usize> = (0..num_qubits).collect();
+        let mut pauli = Self::new(num_qubits);
+        for i in 0..weight {
+            qubits.swap(i, i + below(rng, num_qubits - i));
+            pauli.set_pauli(qubits[i], [SinglePauli::X, SinglePauli::Y, SinglePauli::Z][below(rng, 3)]);
+        }
+        Ok(pauli)
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn get_pauli(&self, qubit: usize) -> SinglePauli {
+        if qubit >= self.num_qubits {
+            panic!("Qubit index {} out of range (max {})", qubit, self.num_qubits);
+        }
+        
+        let x = self.x_bits[qubit] as u8;
+        let z = self.z_bits[qubit] as u8;
+        
+        match (x, z) {
+            (0, 0) => SinglePauli::I,
+            (1, 0) => SinglePauli::X,
+            (0, 1) => SinglePauli::Z,
+            (1, 1) => SinglePauli::Y,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn set_pauli(&mut self, qubit: usize, pauli: SinglePauli) {
+        if qubit >= self.num_qubits {
+            panic!("Qubit index {} out of range (max {})", qubit, self.num_qubits);
+        }
+        
+        match pauli {
+            SinglePauli::I => {
+                self.x_bits.set(qubit, false);
+                self.z_bits.set(qubit, false);
+            }
+            SinglePauli::X => {
+                self.x_bits.set(qubit, true);
+                self.z_bits.set(qubit, false);
+            }
+            SinglePauli::Z => {
+                self.x_bits.set(qubit, false);
+                self.z_bits.set(qubit, true);
+            }
+            SinglePauli::Y => {
+                self.x_bits.set(qubit, true);
+                self.z_bits.set(qubit, true);
+            }
+        }
+    }
+
+    /// Multiply two Pauli strings: self * other
+    ///
+    /// This implements the Pauli multiplication rules:
+    /// - X * Z = iY (with phase +i)
+    /// - Z * X = -iY (with phase -i)
+    /// - X * X = I
+    /// - Z * Z = I
+    /// - Y * Y = I
+    /// - etc.
+    pub fn multiply(&self, other: &Self) -> Self {
+        if self.num_qubits != other.num_qubits {
+            panic!("Cannot multiply Pauli strings with different qubit counts");
+        }
+
+        let mut new_x_bits = self.x_bits.clone();
+        new_x_bits ^= &other.x_bits;
+        let mut new_z_bits = self.z_bits.clone();
+        new_z_bits ^= &other.z_bits;
+
+        // Phase formula: phase = phase1 * phase2 * i^ω(P1, P2)
+        // where ω(P1, P2) = Σ_i (x1_i * z2_i - z1_i * x2_i) mod 4
+        let mut phase = self.phase.multiply(other.phase);
+        
+        let mut x1_and_z2 = self.x_bits.clone();
+        x1_and_z2 &= &other.z_bits;
+        let mut z1_and_x2 = self.z_bits.clone();
+        z1_and_x2 &= &other.x_bits;
+        
+        let positive_contrib = x1_and_z2.count_ones() as i32;
+        let negative_contrib = z1_and_x2.count_ones() as i32;
+        let phase_exponent = ((positive_contrib - negative_contrib) % 4 + 4) % 4;
+        
+        if phase_exponent != 0 {
+            let phase_factor = Phase::from_u8(phase_exponent as u8);
+            phase = phase.multiply(phase_factor);
+        }
+
+        Self {
+            x_bits: new_x_bits,
+            z_bits: new_z_bits,
+            phase,
+            num_qubits: self.num_qubits,
+        }
+    }
+
+    /// The Hermitian conjugate. Every letter is a Hermitian Pauli, so only
+    /// the phase is conjugated.
+    pub fn adjoint(&self) -> Self {
+        let mut adjoint = self.clone();
+        adjoint.phase = self.phase.conjugate();
+        adjoint
+    }
+
+    /// Whether this operator is Hermitian, i.e. its phase is ±1 and it can
+    /// be measured as an observable.
+    pub fn is_hermitian(&self) -> bool {
+        self.phase.is_real()
+    }
+
+    /// Same Pauli letters on every qubit, whatever the phases.
+    pub fn equals_up_to_phase(&self, other: &Self) -> bool {
+        self.x_bits == other.x_bits && self.z_bits == other.z_bits
+    }
+
+    /// This string with phase +1.
+    pub fn canonical(&self) -> Self {
+        let mut canonical = self.clone();
+        canonical.phase = Phase::PlusOne;
+        canonical
+    }
+
+    /// Hash key of the Pauli letters alone: the X and Z words in the layout
+    /// of `encode`. Strings of one size share a key exactly when
+    /// `equals_up_to_phase`, on any platform.
+    pub fn phase_free_key(&self) -> (Vec<u64>, Vec<u64>) {
+        let words = |bits: &BitVec<usize, Lsb0>| bits.chunks(64).map(|chunk| chunk.load_le::<u64>()).collect();
+        (words(&self.x_bits), words(&self.z_bits))
+    }
+
+    /// The GF(2) vector (x | z): bit q is the X component of qubit q and
+    /// bit n + q its Z component. The phase is dropped.
+    pub fn to_symplectic(&self) -> BitVec<usize, Lsb0> {
+        let mut bits = self.x_bits.clone();
+        bits.extend_from_bitslice(&self.z_bits);
+        bits
+    }
+
+    /// Inverse of `to_symplectic`, with phase +1.
+    pub fn from_symplectic(bits: &BitSlice<usize, Lsb0>) -> Result<Self, String> {
+        if !bits.len().is_multiple_of(2) {
+            return Err(format!("Symplectic vector has odd length {}", bits.len()));
+        }
+        let num_qubits = bits.len() / 2;
+        Ok(Self {
+            x_bits: bits[..num_qubits].to_bitvec(),
+            z_bits: bits[num_qubits..].to_bitvec(),
+            phase: Phase::PlusOne,
+            num_qubits,
+        })
+    }
+
+    /// Bytes `encode` writes for `num_qubits` qubits: the X words, the Z
+    /// words (little-endian u64, qubit q at bit q % 64 of word q / 64) and
+    /// one phase byte.
+    pub fn encoded_len(num_qubits: usize) -> usize {
+        2 * 8 * num_qubits.div_ceil(64) + 1
+    }
+
+    /// Appends the fixed-size binary form of `encoded_len` bytes to `out`.
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        for bits in [&self.x_bits, &self.z_bits] {
+            for chunk in bits.chunks(64) {
+                out.extend_from_slice(&chunk.load_le::<u64>().to_le_bytes());
+            }
+        }
+        out.push(self.phase.to_u8());
+    }
+
+    /// Inverse of `encode`; `bytes` must hold exactly one string.
+    pub fn decode(bytes: &[u8], num_qubits: usize) -> Result<Self, String> {
+        if bytes.len() != Self::encoded_len(num_qubits) {
+            return Err(format!(
+                "Expected {} bytes for {} qubits, got {}",
+                Self::encoded_len(num_qubits),
+                num_qubits,
+                bytes.len()
+            ));
+        }
+        let words = num_qubits.div_ceil(64);
+        let read = |offset: usize| -> Result<BitVec<usize, Lsb0>, String> {
+            let mut bits = bitvec![usize, Lsb0; 0; num_qubits];
+            for (i, chunk) in bits.chunks_mut(64).enumerate() {
+                let start = offset + 8 * i;
+                let word = u64::from_le_bytes(bytes[start..start + 8].try_into().expect("8 bytes"));
+                if chunk.len() < 64 && word >> chunk.len() != 0 {
+                    return Err("Encoded Pauli string sets bits beyond its qubits".to_string());
+                }
+                chunk.store_le(word);
+            }
+            Ok(bits)
+        };
+        let phase = bytes[16 * words];
+        if phase > 3 {
+            return Err(format!("Invalid encoded phase {}", phase));
+        }
+        Ok(Self {
+            x_bits: read(0)?,
+            z_bits: read(8 * words)?,
+            phase: Phase::from_u8(phase),
+            num_qubits,
+        })
+    }
+
+    /// Decodes back-to-back `encode` outputs of `num_qubits`-qubit strings.
+    pub fn decode_all(bytes: &[u8], num_qubits: usize) -> Result<Vec<Self>, String> {
+        let size = Self::encoded_len(num_qubits);
+        if !bytes.len().is_multiple_of(size) {
+            return Err(format!("{} bytes is not a whole number of {}-byte strings", bytes.len(), size));
+        }
+        bytes.chunks_exact(size).map(|chunk| Self::decode(chunk, num_qubits)).collect()
+    }
+
+    /// `self ⊗ other`: `self` on the first qubits, `other` on the next
+    /// `other.num_qubits()`, with the phases multiplied.
+    pub fn tensor(&self, other: &Self) -> Self {
+        let mut x_bits = self.x_bits.clone();
+        x_bits.extend_from_bitslice(&other.x_bits);
+        let mut z_bits = self.z_bits.clone();
+        z_bits.extend_from_bitslice(&other.z_bits);
+        Self {
+            x_bits,
+            z_bits,
+            phase: self.phase.multiply(other.phase),
+            num_qubits: self.num_qubits + other.num_qubits,
+        }
+    }
+
+    /// This string on qubits `offset..offset + num_qubits()` of a
+    /// `total_qubits` register, identity elsewhere, phase kept.
+    pub fn embed(&self, total_qubits: usize, offset: usize) -> Result<Self, String> {
+        if offset + self.num_qubits > total_qubits {
+            return Err(format!(
+                "Cannot embed {} qubits at offset {} into {} qubits",
+                self.num_qubits, offset, total_qubits
+            ));
+        }
+        let mut embedded = Self::new(total_qubits);
+        embedded.x_bits[offset..offset + self.num_qubits].copy_from_bitslice(&self.x_bits);
+        embedded.z_bits[offset..offset + self.num_qubits].copy_from_bitslice(&self.z_bits);
+        embedded.phase = self.phase;
+        Ok(embedded)
+    }
+
+    /// Check if two Pauli strings commute
+    pub fn commutes_with(&self, other: &Self) -> bool {
+        if self.num_qubits != other.num_qubits {
+            return false;
+        }
+        self.symplectic_product(other).is_multiple_of(2)
+    }
+
+    /// Number of qubits on which the two strings anticommute, i.e. the
+    /// symplectic product over the integers; its parity decides
+    /// `commutes_with`.
+    pub fn symplectic_product(&self, other: &Self) -> u32 {
+        if self.num_qubits != other.num_qubits {
+            panic!("Cannot compare Pauli strings with different qubit counts");
+        }
+
+        let mut symplectic_product = self.x_bits.clone();
+        symplectic_product &= &other.z_bits;
+        let mut temp = self.z_bits.clone();
+        temp &= &other.x_bits;
+        symplectic_product ^= &temp;
+        symplectic_product.count_ones() as u32
+    }
+
+    /// Number of non-identity components
+    pub fn weight(&self) -> usize {
+        self.x_bits.iter().zip(self.z_bits.iter()).filter(|(x, z)| **x || **z).count()
+    }
+
+    /// Indices of the non-identity components, ascending
+    pub fn support(&self) -> Vec<usize> {
+        (0..self.num_qubits).filter(|&q| self.x_bits[q] || self.z_bits[q]).collect()
+    }
+
+    pub fn x_bits(&self) -> &BitVec<usize, Lsb0> {
+        &self.x_bits
+    }
+
+    pub fn z_bits(&self) -> &BitVec<usize, Lsb0> {
+        &self.z_bits
+    }
+
+    pub fn set_x_bits(&mut self, x_bits: BitVec<usize, Lsb0>) {
+        if x_bits.len() != self.num_qubits {
+            panic!("x_bits length {} doesn't match num_qubits {}", x_bits.len(), self.num_qubits);
+        }
+        self.x_bits = x_bits;
+    }
+
+    pub fn set_z_bits(&mut self, z_bits: BitVec<usize, Lsb0>) {
+        if z_bits.len() != self.num_qubits {
+            panic!("z_bits length {} doesn't match num_qubits {}", z_bits.len(), self.num_qubits);
+        }
+        self.z_bits = z_bits;
+    }
+
+    pub fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+}
+
+/// `a * b` is `a.multiply(&b)`, for any mix of owned and borrowed operands.
+impl Mul<&PauliString> for &PauliString {
+    type Output = PauliString;
+
+    fn mul(self, rhs: &PauliString) -> PauliString {
+        self.multiply(rhs)
+    }
+}
+
+impl Mul<PauliString> for PauliString {
+    type Output = PauliString;
+
+    fn mul(self, rhs: PauliString) -> PauliString {
+        self.multiply(&rhs)
+    }
+}
+
+impl Mul<&PauliString> for PauliString {
+    type Output = PauliString;
+
+    fn mul(self, rhs: &PauliString) -> PauliString {
+        self.multiply(rhs)
+    }
+}
+
+impl Mul<PauliString> for &PauliString {
+    type Output = PauliString;
+
+    fn mul(self, rhs: PauliString) -> PauliString {
+        self.multiply(&rhs)
+    }
+}
+
+impl Neg for PauliString {
+    type Output = PauliString;
+
+    fn neg(mut self) -> PauliString {
+        self.phase = self.phase.negate();
+        self
+    }
+}
+
+impl Neg for &PauliString {
+    type Output = PauliString;
+
+    fn neg(self) -> PauliString {
+        -self.clone()
+    }
+}
+
+impl fmt::Display for PauliString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Display phase if not +1
+        if self.phase != Phase::PlusOne {
+            write!(f, "{}", self.phase)?;
+        }
+        
+        for i in 0..self.num_qubits {
+            write!(f, "{}", self.get_pauli(i))?;
+            if i < self.num_qubits - 1 {
+                write!(f, " ")?;
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+/// Weight histogram of `paulis`: entry w counts the strings of weight w,
+/// for w up to the largest qubit count seen. Over the elements of a
+/// stabilizer group this is the code's weight enumerator.
+pub fn weight_distribution<I>(paulis: I) -> Vec<usize>
+where
+    I: IntoIterator,
+    I::Item: Borrow<PauliString>,
+{
+    let mut counts = Vec::new();
+    for pauli in paulis {
+        let pauli = pauli.borrow();
+        if counts.len() <= pauli.num_qubits() {
+            counts.resize(pauli.num_qubits() + 1, 0);
+        }
+        counts[pauli.weight()] += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pauli_string_creation() {
+        let p = PauliString::new(3);
+        assert_eq!(p.num_qubits(), 3);
+        assert_eq!(p.get_pauli(0), SinglePauli::I);
+        assert_eq!(p.get_pauli(1), SinglePauli::I);
+        assert_eq!(p.get_pauli(2), SinglePauli::I);
+    }
+
+    #[test]
+    fn test_pauli_string_from_str() {
+        let p = PauliString::from_str("X I Z", 3).unwrap();
+        assert_eq!(p.get_pauli(0), SinglePauli::X);
+        assert_eq!(p.get_pauli(1), SinglePauli::I);
+        assert_eq!(p.get_pauli(2), SinglePauli::Z);
+    }
+
+    #[test]
+    fn test_pauli_string_weight_and_support() {
+        let p = PauliString::from_str("X I Y Z I", 5).unwrap();
+        assert_eq!(p.weight(), 3);
+        assert_eq!(p.support(), vec![0, 2, 3]);
+        assert_eq!(PauliString::new(4).weight(), 0);
+
+        // The 16 two-qubit Paulis: 1 identity, 6 of weight one, 9 of weight two
+        assert_eq!(weight_distribution(crate::physics::PauliGroup::new(2)), vec![1, 6, 9]);
+        assert_eq!(weight_distribution([&p, &p]), vec![0, 0, 0, 2, 0, 0]);
+        assert!(weight_distribution(Vec::<PauliString>::new()).is_empty());
+    }
+
+    #[test]
+    fn test_pauli_string_tensor() {
+        let mut data = PauliString::from_str("XZ", 2).unwrap();
+        data.set_phase(Phase::PlusI);
+        let mut ancilla = PauliString::from_str("IYI", 3).unwrap();
+        ancilla.set_phase(Phase::MinusOne);
+        let joint = data.tensor(&ancilla);
+        let mut expected = PauliString::from_str("XZIYI", 5).unwrap();
+        expected.set_phase(Phase::MinusI);
+        assert_eq!(joint, expected);
+        assert_eq!(PauliString::new(0).tensor(&data), data);
+
+        let embedded = data.embed(5, 2).unwrap();
+        let mut expected = PauliString::from_str("IIXZI", 5).unwrap();
+        expected.set_phase(Phase::PlusI);
+        assert_eq!(embedded, expected);
+        assert_eq!(data.embed(2, 0).unwrap(), data);
+        assert!(data.embed(5, 4).is_err());
+    }
+
+    #[test]
+    fn test_pauli_order_conventions() {
+        // Qiskit's "-XZI" has X on qubit 2 and Z on qubit 1
+        let pauli = PauliString::from_str_in("-XZI", 3, PauliOrder::Qubit0Rightmost).unwrap();
+        assert_eq!(pauli.get_pauli(2), SinglePauli::X);
+        assert_eq!(pauli.get_pauli(1), SinglePauli::Z);
+        assert_eq!(pauli.phase(), Phase::MinusOne);
+        assert_eq!(pauli.to_string_in(PauliOrder::Qubit0Leftmost), "-IZX");
+        assert_eq!(pauli.to_string_in(PauliOrder::Qubit0Rightmost), "-XZI");
+        assert_eq!(pauli.reversed().reversed(), pauli);
+    }
+
+    #[test]
+    fn test_pauli_operators() {
+        let x = PauliString::from_str("XI", 2).unwrap();
+        let z = PauliString::from_str("ZI", 2).unwrap();
+        let zz = PauliString::from_str("IZ", 2).unwrap();
+        assert_eq!(&x * &z * &zz, x.multiply(&z).multiply(&zz));
+        assert_eq!(x.clone() * z.clone(), x.multiply(&z));
+        let minus_x = -&x;
+        assert_eq!(minus_x.phase(), Phase::MinusOne);
+        assert_eq!(-minus_x, x);
+    }
+
+    #[test]
+    fn test_random_paulis() {
+        use rand::rngs::SmallRng;
+        use rand::SeedableRng;
+
+        let mut rng = SmallRng::seed_from_u64(2258);
+        let first = PauliString::random(40, &mut rng);
+        assert_eq!(first, PauliString::random(40, &mut SmallRng::seed_from_u64(2258)));
+        assert!(first.weight() > 20);
+        let mut seen = [0usize; 4];
+        for _ in 0..300 {
+            let pauli = PauliString::random_with_weight(6, 2, &mut rng).unwrap();
+            assert_eq!(pauli.weight(), 2);
+            for qubit in pauli.support() {
+                seen[pauli.get_pauli(qubit) as usize] += 1;
+            }
+        }
+        assert!(seen[1..].iter().all(|&count| count > 150));
+        assert!(PauliString::random_with_weight(2, 3, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_symplectic_roundtrip() {
+        let pauli = PauliString::from_str("XYZI", 4).unwrap();
+        let bits = pauli.to_symplectic();
+        assert_eq!(bits, bitvec![usize, Lsb0; 1, 1, 0, 0, 0, 1, 1, 0]);
+        assert_eq!(PauliString::from_symplectic(&bits).unwrap(), pauli);
+        assert!(PauliString::from_symplectic(&bits[..3]).is_err());
+    }
+
+    #[test]
+    fn test_phase_free_key() {
+        use std::collections::HashMap;
+
+        let mut minus = PauliString::from_str("XYZ", 3).unwrap();
+        minus.set_phase(Phase::MinusOne);
+        let plus = PauliString::from_str("XYZ", 3).unwrap();
+        assert_eq!(minus.phase_free_key(), plus.phase_free_key());
+        assert_eq!(plus.phase_free_key(), (vec![0b011], vec![0b110]));
+
+        let mut groups: HashMap<_, usize> = HashMap::new();
+        for pauli in [&minus, &plus, &PauliString::from_str("XYI", 3).unwrap()] {
+            *groups.entry(pauli.phase_free_key()).or_default() += 1;
+        }
+        assert_eq!(groups.len(), 2);
+        assert_eq!(PauliString::new(70).phase_free_key().0.len(), 2);
+    }
+
+    #[test]
+    fn test_binary_encoding() {
+        use rand::rngs::SmallRng;
+        use rand::SeedableRng;
+
+        let mut rng = SmallRng::seed_from_u64(2263);
+        let mut paulis: Vec<PauliString> = (0..3).map(|_| PauliString::random(70, &mut rng)).collect();
+        paulis[1].set_phase(Phase::MinusI);
+        let mut bytes = Vec::new();
+        for pauli in &paulis {
+            pauli.encode(&mut bytes);
+        }
+        assert_eq!(bytes.len(), 3 * PauliString::encoded_len(70));
+        assert_eq!(PauliString::encoded_len(70), 33);
+        assert_eq!(PauliString::decode_all(&bytes, 70).unwrap(), paulis);
+
+        assert!(PauliString::decode(&bytes[..32], 70).is_err());
+        // Bit 70 lies past the last qubit
+        let mut stray = bytes[..33].to_vec();
+        stray[8] |= 1 << 6;
+        assert!(PauliString::decode(&stray, 70).is_err());
+    }
+
+    #[test]
+    fn test_adjoint_and_hermiticity() {
+        let x = PauliString::from_str("XI", 2).unwrap();
+        let z = PauliString::from_str("ZZ", 2).unwrap();
+        assert!(x.is_hermitian());
+        assert!((-&x).equals_up_to_phase(&x));
+        assert_eq!((-&x).canonical(), x);
+        assert!(!x.equals_up_to_phase(&z));
+        // XI * ZZ carries a factor of ±i, so it is anti-Hermitian
+        let product = x.multiply(&z);
+        assert!(!product.is_hermitian());
+        assert_eq!(product.adjoint().phase(), product.phase().negate());
+        assert_eq!(product.adjoint().adjoint(), product);
+        assert_eq!(x.adjoint(), x);
+    }
+
+    #[test]
+    fn test_pauli_multiplication_basic() {
+        // X * I = X
+        let x = PauliString::from_str("X", 1).unwrap();
+        let i = PauliString::from_str("I", 1).unwrap();
+        let result = x.multiply(&i);
+        assert_eq!(result.get_pauli(0), SinglePauli::X);
+        assert_eq!(result.phase(), Phase::PlusOne);
+        
+        // I * X = X
+        let result = i.multiply(&x);
+        assert_eq!(result.get_pauli(0), SinglePauli::X);
+    }
+
+    #[test]
+    fn test_pauli_multiplication_x_z() {
+        // X * Z = iY
+        let x = PauliString::from_str("X", 1).unwrap();
+        let z = PauliString::from_str("Z", 1).unwrap();
+        let result = x.multiply(&z);
+        assert_eq!(result.get_pauli(0), SinglePauli::Y);
+        assert_eq!(result.phase(), Phase::PlusI);
+        
+        // Z * X = -iY
+        let result = z.multiply(&x);
+        assert_eq!(result.get_pauli(0), SinglePauli::Y);
+        assert_eq!(result.phase(), Phase::MinusI);
+
+        // The single-qubit table agrees with one-qubit strings
+        let paulis = [SinglePauli::I, SinglePauli::X, SinglePauli::Y, SinglePauli::Z];
+        for a in paulis {
+            for b in paulis {
+                let (mut left, mut right) = (PauliString::new(1), PauliString::new(1));
+                left.set_pauli(0, a);
+                right.set_pauli(0, b);
+                let product = left.multiply(&right);
+                assert_eq!(a.multiply(b), (product.get_pauli(0), product.phase()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_pauli_multiplication_self() {
+        // X * X = I
+        let x = PauliString::from_str("X", 1).unwrap();
+        let result = x.multiply(&x);
+        assert_eq!(result.get_pauli(0), SinglePauli::I);
+        
+        // Z * Z = I
+        let z = PauliString::from_str("Z", 1).unwrap();
+        let result = z.multiply(&z);
+        assert_eq!(result.get_pauli(0), SinglePauli::I);
+        
+        // Y * Y = I
+        let y = PauliString::from_str("Y", 1).unwrap();
+        let result = y.multiply(&y);
+        assert_eq!(result.get_pauli(0), SinglePauli::I);
+    }
+
+    #[test]
+    fn test_commutation() {
+        // X and Z anti-commute
+        let x = PauliString::from_str("X", 1).unwrap();
+        let z = PauliString::from_str("Z", 1).unwrap();
+        assert!(!x.commutes_with(&z));
+        
+        // X and X commute
+        assert!(x.commutes_with(&x));
+        
+        // I commutes with everything
+        let i = PauliString::from_str("I", 1).unwrap();
+        assert!(i.commutes_with(&x));
+        assert!(i.commutes_with(&z));
+
+        // XXYZ and ZXZZ anticommute on qubits 0 and 2
+        let a = PauliString::from_str("XXYZ", 4).unwrap();
+        let b = PauliString::from_str("ZXZZ", 4).unwrap();
+        assert_eq!(a.symplectic_product(&b), 2);
+        assert!(a.commutes_with(&b));
+    }
+
+    #[test]
+    fn test_more_than_64_qubits() {
+        let num_qubits = 100;
+        let mut p = PauliString::new(num_qubits);
+        assert_eq!(p.num_qubits(), num_qubits);
+        
+        p.set_pauli(0, SinglePauli::X);
+        assert_eq!(p.get_pauli(0), SinglePauli::X);
+        
+        p.set_pauli(65, SinglePauli::Z);
+        assert_eq!(p.get_pauli(65), SinglePauli::Z);
+        
+        p.set_pauli(99, SinglePauli::Y);
+        assert_eq!(p.get_pauli(99), SinglePauli::Y);
+        
+        let mut p1 = PauliString::new(num_qubits);
+        p1.set_pauli(0, SinglePauli::X);
+        p1.set_pauli(65, SinglePauli::Z);
+        
+        let mut p2 = PauliString::new(num_qubits);
+        p2.set_pauli(0, SinglePauli::Z);
+        p2.set_pauli(65, SinglePauli::X);
+        
+        let result = p1.multiply(&p2);
+        // On qubit 0:X*Z=iY(+i)
+        // On qubit 65:Z*X=-iY(-i)
+        assert_eq!(result.get_pauli(0), SinglePauli::Y);
+        assert_eq!(result.get_pauli(65), SinglePauli::Y);
+        assert_eq!(result.phase(), Phase::PlusOne);
+    }
+}
+
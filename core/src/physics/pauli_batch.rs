@@ -0,0 +1,285 @@
+//! Many Pauli strings on one register, stored as bit planes.
+//!
+//! `PauliBatch` keeps, for every qubit, one bit per string for its X and Z
+//! components, packed 64 strings to a `u64` word, plus two planes for the
+//! phase exponent. Multiplication, commutation tests and gate propagation
+//! then work on 64 strings per word operation instead of one `PauliString`
+//! at a time. Results equal the per-string operations exactly, phases
+//! included: gates use the truth table of `apply_gate` on the gate's
+//! operands and the phase (which the S rules read, not just update), and
+//! products use the phase rule of `PauliString::multiply`.
+
+use crate::physics::circuit::{Circuit, Gate};
+use crate::physics::pauli::{PauliString, Phase};
+use crate::physics::propagation::apply_gate;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PauliBatch {
+    num_qubits: usize,
+    len: usize,
+    /// Words per plane
+    words: usize,
+    /// Plane of qubit q at `q * words..(q + 1) * words`
+    x: Vec<u64>,
+    z: Vec<u64>,
+    /// Phase i^(low + 2 high) of every string
+    phase_low: Vec<u64>,
+    phase_high: Vec<u64>,
+}
+
+impl PauliBatch {
+    /// `len` identity strings on `num_qubits` qubits.
+    pub fn new(num_qubits: usize, len: usize) -> Self {
+        let words = len.div_ceil(64);
+        Self {
+            num_qubits,
+            len,
+            words,
+            x: vec![0; num_qubits * words],
+            z: vec![0; num_qubits * words],
+            phase_low: vec![0; words],
+            phase_high: vec![0; words],
+        }
+    }
+
+    pub fn from_paulis(paulis: &[PauliString]) -> Result<Self, String> {
+        let num_qubits = paulis.first().map_or(0, PauliString::num_qubits);
+        let mut batch = Self::new(num_qubits, paulis.len());
+        for (index, pauli) in paulis.iter().enumerate() {
+            batch.set(index, pauli)?;
+        }
+        Ok(batch)
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<PauliString> {
+        if index >= self.len {
+            return None;
+        }
+        let (word, bit) = (index / 64, index % 64);
+        let mut pauli = PauliString::new(self.num_qubits);
+        let mut x_bits = pauli.x_bits().clone();
+        let mut z_bits = pauli.z_bits().clone();
+        for qubit in 0..self.num_qubits {
+            x_bits.set(qubit, self.x[qubit * self.words + word] >> bit & 1 == 1);
+            z_bits.set(qubit, self.z[qubit * self.words + word] >> bit & 1 == 1);
+        }
+        pauli.set_x_bits(x_bits);
+        pauli.set_z_bits(z_bits);
+        let exponent = (self.phase_low[word] >> bit & 1) + 2 * (self.phase_high[word] >> bit & 1);
+        pauli.set_phase(Phase::from_u8(exponent as u8));
+        Some(pauli)
+    }
+
+    pub fn set(&mut self, index: usize, pauli: &PauliString) -> Result<(), String> {
+        if index >= self.len {
+            return Err(format!("Index {} out of range for a batch of {}", index, self.len));
+        }
+        if pauli.num_qubits() != self.num_qubits {
+            return Err(format!(
+                "Pauli string has {} qubits but the batch has {}",
+                pauli.num_qubits(),
+                self.num_qubits
+            ));
+        }
+        let (word, mask) = (index / 64, 1u64 << (index % 64));
+        let assign = |plane: &mut u64, bit: bool| {
+            if bit {
+                *plane |= mask;
+            } else {
+                *plane &= !mask;
+            }
+        };
+        for qubit in 0..self.num_qubits {
+            assign(&mut self.x[qubit * self.words + word], pauli.x_bits()[qubit]);
+            assign(&mut self.z[qubit * self.words + word], pauli.z_bits()[qubit]);
+        }
+        let exponent = pauli.phase().to_u8();
+        assign(&mut self.phase_low[word], exponent & 1 == 1);
+        assign(&mut self.phase_high[word], exponent & 2 == 2);
+        Ok(())
+    }
+
+    pub fn to_paulis(&self) -> Vec<PauliString> {
+        (0..self.len).map(|index| self.get(index).expect("index in range")).collect()
+    }
+
+    /// String i becomes `self[i] * other[i]`.
+    pub fn multiply_assign(&mut self, other: &PauliBatch) -> Result<(), String> {
+        if (self.num_qubits, self.len) != (other.num_qubits, other.len) {
+            return Err(format!(
+                "Cannot multiply a batch of {} {}-qubit strings by one of {} {}-qubit strings",
+                self.len, self.num_qubits, other.len, other.num_qubits
+            ));
+        }
+        for word in 0..self.words {
+            add_phase(&mut self.phase_low[word], &mut self.phase_high[word], other.phase_low[word], other.phase_high[word]);
+        }
+        for qubit in 0..self.num_qubits {
+            for word in 0..self.words {
+                let i = qubit * self.words + word;
+                // i^(x1 z2 - z1 x2) per qubit, as in `PauliString::multiply`
+                let plus = self.x[i] & other.z[i];
+                let minus = self.z[i] & other.x[i];
+                add_phase(&mut self.phase_low[word], &mut self.phase_high[word], plus ^ minus, minus & !plus);
+                self.x[i] ^= other.x[i];
+                self.z[i] ^= other.z[i];
+            }
+        }
+        Ok(())
+    }
+
+    /// For every string, whether it commutes with `pauli`.
+    pub fn commutes_with(&self, pauli: &PauliString) -> Result<Vec<bool>, String> {
+        if pauli.num_qubits() != self.num_qubits {
+            return Err(format!(
+                "Pauli string has {} qubits but the batch has {}",
+                pauli.num_qubits(),
+                self.num_qubits
+            ));
+        }
+        let mut anticommutes = vec![0u64; self.words];
+        for qubit in pauli.support() {
+            let (x, z) = (pauli.x_bits()[qubit], pauli.z_bits()[qubit]);
+            for (word, parity) in anticommutes.iter_mut().enumerate() {
+                let i = qubit * self.words + word;
+                if z {
+                    *parity ^= self.x[i];
+                }
+                if x {
+                    *parity ^= self.z[i];
+                }
+            }
+        }
+        Ok((0..self.len).map(|index| anticommutes[index / 64] >> (index % 64) & 1 == 0).collect())
+    }
+
+    /// Conjugates every string by `gate`, as `apply_gate` would.
+    pub fn apply_gate(&mut self, gate: &Gate) {
+        let qubits = gate.qubits();
+        if let Some(&qubit) = qubits.iter().find(|&&q| q >= self.num_qubits) {
+            panic!("Gate on qubit {} applied to a {}-qubit batch", qubit, self.num_qubits);
+        }
+        let table = truth_table(gate);
+        let operand_bits = 2 * qubits.len();
+        for word in 0..self.words {
+            // Input bits: x then z of each operand, then the phase
+            let mut inputs: Vec<u64> = qubits
+                .iter()
+                .flat_map(|&q| [self.x[q * self.words + word], self.z[q * self.words + word]])
+                .collect();
+            inputs.extend([self.phase_low[word], self.phase_high[word]]);
+            let mut outputs = vec![0u64; operand_bits + 2];
+            for (input, &output) in table.iter().enumerate() {
+                let mask = inputs.iter().enumerate().fold(!0u64, |mask, (b, &plane)| {
+                    mask & if input >> b & 1 == 1 { plane } else { !plane }
+                });
+                for (b, plane) in outputs.iter_mut().enumerate() {
+                    if output >> b & 1 == 1 {
+                        *plane |= mask;
+                    }
+                }
+            }
+            for (operand, &q) in qubits.iter().enumerate() {
+                self.x[q * self.words + word] = outputs[2 * operand];
+                self.z[q * self.words + word] = outputs[2 * operand + 1];
+            }
+            self.phase_low[word] = outputs[operand_bits];
+            self.phase_high[word] = outputs[operand_bits + 1];
+        }
+    }
+
+    /// Conjugates every string by the whole circuit.
+    pub fn conjugate_by(&mut self, circuit: &Circuit) {
+        for gate in &circuit.gates {
+            self.apply_gate(gate);
+        }
+    }
+}
+
+/// Lane-wise (low, high) += (add_low, add_high) mod 4.
+fn add_phase(low: &mut u64, high: &mut u64, add_low: u64, add_high: u64) {
+    *high ^= add_high ^ (*low & add_low);
+    *low ^= add_low;
+}
+
+/// `apply_gate` on the gate's operands, as a map on (x0, z0, x1, z1, ...,
+/// phase exponent) packed from the lowest bit.
+fn truth_table(gate: &Gate) -> Vec<usize> {
+    let qubits = gate.qubits();
+    let operand_bits = 2 * qubits.len();
+    let local = gate.map_qubits(|q| qubits.iter().position(|&p| p == q).expect("gate operand"));
+    (0..1usize << (operand_bits + 2))
+        .map(|input| {
+            let mut pauli = PauliString::new(qubits.len());
+            let mut x_bits = pauli.x_bits().clone();
+            let mut z_bits = pauli.z_bits().clone();
+            for operand in 0..qubits.len() {
+                x_bits.set(operand, input >> (2 * operand) & 1 == 1);
+                z_bits.set(operand, input >> (2 * operand + 1) & 1 == 1);
+            }
+            pauli.set_x_bits(x_bits);
+            pauli.set_z_bits(z_bits);
+            pauli.set_phase(Phase::from_u8((input >> operand_bits) as u8));
+            apply_gate(&mut pauli, &local);
+            (0..qubits.len()).fold((pauli.phase().to_u8() as usize) << operand_bits, |output, operand| {
+                output | (pauli.x_bits()[operand] as usize) << (2 * operand) | (pauli.z_bits()[operand] as usize) << (2 * operand + 1)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{SingleGate, TwoGate};
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_batch_matches_pauli_strings() {
+        let mut rng = SmallRng::seed_from_u64(2275);
+        let mut left: Vec<PauliString> = (0..130).map(|_| PauliString::random(5, &mut rng)).collect();
+        let right: Vec<PauliString> = (0..130).map(|_| PauliString::random(5, &mut rng)).collect();
+        left[3].set_phase(Phase::MinusI);
+        let mut batch = PauliBatch::from_paulis(&left).unwrap();
+        assert_eq!(batch.to_paulis(), left);
+
+        batch.multiply_assign(&PauliBatch::from_paulis(&right).unwrap()).unwrap();
+        let products: Vec<PauliString> = left.iter().zip(&right).map(|(l, r)| l.multiply(r)).collect();
+        assert_eq!(batch.to_paulis(), products);
+
+        let fixed = PauliString::from_str("XYZIX", 5).unwrap();
+        let commutes: Vec<bool> = products.iter().map(|p| p.commutes_with(&fixed)).collect();
+        assert_eq!(batch.commutes_with(&fixed).unwrap(), commutes);
+
+        let mut circuit = Circuit::new(5);
+        for gate in [
+            Gate::Single { qubit: 1, gate: SingleGate::H },
+            Gate::Single { qubit: 2, gate: SingleGate::S },
+            Gate::Two(TwoGate::CNOT { control: 4, target: 1 }),
+            Gate::Two(TwoGate::CZ { control: 0, target: 3 }),
+            Gate::Two(TwoGate::SWAP { qubit1: 2, qubit2: 0 }),
+            Gate::Single { qubit: 3, gate: SingleGate::Sdg },
+        ] {
+            circuit.add_gate(gate).unwrap();
+        }
+        batch.conjugate_by(&circuit);
+        let images: Vec<PauliString> = products.iter().map(|p| p.conjugated_by(&circuit)).collect();
+        assert_eq!(batch.to_paulis(), images);
+
+        assert!(batch.multiply_assign(&PauliBatch::new(5, 3)).is_err());
+        assert!(batch.set(130, &fixed).is_err());
+    }
+}
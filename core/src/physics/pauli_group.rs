@@ -0,0 +1,89 @@
+//! Enumeration of the n-qubit Pauli group.
+//!
+//! `PauliGroup` walks all 4^n Pauli strings (times the four phases, when
+//! asked) as a counter: qubit q is the q-th base-4 digit, with digit
+//! values I, X, Z, Y (x bit low, z bit high), and the phase changes
+//! slowest. Meant for brute force on a dozen or so qubits; for
+//! enumeration by weight see `codes::PaulisByWeight`.
+
+use crate::physics::pauli::{PauliString, Phase};
+
+pub struct PauliGroup {
+    num_qubits: usize,
+    next: u128,
+    end: u128,
+}
+
+impl PauliGroup {
+    /// The 4^n Paulis with phase +1.
+    pub fn new(num_qubits: usize) -> Self {
+        Self::with_phase_count(num_qubits, 1)
+    }
+
+    /// All 4^(n+1) elements, including phases.
+    pub fn with_phases(num_qubits: usize) -> Self {
+        Self::with_phase_count(num_qubits, 4)
+    }
+
+    fn with_phase_count(num_qubits: usize, phases: u128) -> Self {
+        assert!(num_qubits <= 62, "Cannot enumerate the Pauli group on {} qubits", num_qubits);
+        Self {
+            num_qubits,
+            next: 0,
+            end: phases << (2 * num_qubits),
+        }
+    }
+}
+
+impl Iterator for PauliGroup {
+    type Item = PauliString;
+
+    fn next(&mut self) -> Option<PauliString> {
+        if self.next == self.end {
+            return None;
+        }
+        let index = self.next;
+        self.next += 1;
+        let mut pauli = PauliString::new(self.num_qubits);
+        let mut x_bits = pauli.x_bits().clone();
+        let mut z_bits = pauli.z_bits().clone();
+        for qubit in 0..self.num_qubits {
+            x_bits.set(qubit, index >> (2 * qubit) & 1 == 1);
+            z_bits.set(qubit, index >> (2 * qubit + 1) & 1 == 1);
+        }
+        pauli.set_x_bits(x_bits);
+        pauli.set_z_bits(z_bits);
+        pauli.set_phase(Phase::from_u8((index >> (2 * self.num_qubits)) as u8));
+        Some(pauli)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.next;
+        match usize::try_from(remaining) {
+            Ok(remaining) => (remaining, Some(remaining)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_enumerates_every_element_once() {
+        let all: Vec<PauliString> = PauliGroup::new(3).collect();
+        assert_eq!(all.len(), 64);
+        assert_eq!(all[0], PauliString::new(3));
+        assert_eq!(all[1], PauliString::from_str("XII", 3).unwrap());
+        assert_eq!(all.iter().collect::<HashSet<_>>().len(), 64);
+        assert!(all.iter().all(|p| p.phase() == Phase::PlusOne));
+
+        let phased: Vec<PauliString> = PauliGroup::with_phases(2).collect();
+        assert_eq!(phased.len(), 64);
+        assert_eq!(phased.iter().collect::<HashSet<_>>().len(), 64);
+        assert_eq!(PauliGroup::new(0).count(), 1);
+        assert_eq!(PauliGroup::new(5).size_hint(), (1024, Some(1024)));
+    }
+}
@@ -4,7 +4,8 @@
 //! under Clifford gates via conjugation: P -> U P U'
 
 use crate::physics::pauli::{PauliString, Phase};
-use crate::physics::circuit::{Gate, SingleGate, TwoGate};
+use crate::perf::{self, Counter};
+use crate::physics::circuit::{Circuit, Gate, SingleGate, TwoGate};
 
 pub fn apply_single_gate(pauli: &mut PauliString, qubit: usize, gate: SingleGate) {
     if qubit >= pauli.num_qubits() {
@@ -23,9 +24,7 @@ pub fn apply_single_gate(pauli: &mut PauliString, qubit: usize, gate: SingleGate
             let z_bit = pauli.z_bits()[qubit];
             
             // Y X Y' = -X, Y Z Y' = -Z
-            if x_bit && !z_bit {
-                pauli.set_phase(pauli.phase().multiply(Phase::MinusOne));
-            } else if !x_bit && z_bit {
+            if x_bit != z_bit {
                 pauli.set_phase(pauli.phase().multiply(Phase::MinusOne));
             }
         }
@@ -177,6 +176,7 @@ pub fn apply_two_gate(pauli: &mut PauliString, gate: TwoGate) {
 }
 
 pub fn apply_gate(pauli: &mut PauliString, gate: &Gate) {
+    perf::count(Counter::GatesApplied, 1);
     match gate {
         Gate::Single { qubit, gate } => {
             apply_single_gate(pauli, *qubit, *gate);
@@ -187,6 +187,21 @@ pub fn apply_gate(pauli: &mut PauliString, gate: &Gate) {
     }
 }
 
+impl PauliString {
+    /// The image U P U' under the whole circuit U, without recording a
+    /// timeline.
+    pub fn conjugated_by(&self, circuit: &Circuit) -> PauliString {
+        if self.num_qubits() != circuit.num_qubits {
+            panic!("Cannot conjugate a Pauli string by a circuit with a different qubit count");
+        }
+        let mut image = self.clone();
+        for gate in &circuit.gates {
+            apply_gate(&mut image, gate);
+        }
+        image
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,5 +253,15 @@ mod tests {
         assert_eq!(p.get_pauli(0), SinglePauli::Z);
         assert_eq!(p.get_pauli(1), SinglePauli::Z);
     }
-}
 
+    #[test]
+    fn test_conjugated_by_circuit() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        // Z0 -> X0 -> X0 X1
+        let z0 = PauliString::from_str("ZI", 2).unwrap();
+        assert_eq!(z0.conjugated_by(&circuit), PauliString::from_str("XX", 2).unwrap());
+        assert_eq!(z0, PauliString::from_str("ZI", 2).unwrap());
+    }
+}
@@ -0,0 +1,116 @@
+//! Conjugation rule tables.
+//!
+//! For every gate kind, the image under conjugation of X, Y and Z on each
+//! operand, as Paulis on the operands and a phase. The tables are computed
+//! with `apply_gate`, so they cannot drift from what the simulator does;
+//! documentation and UIs should render them rather than restate the rules.
+
+use crate::physics::circuit::{Gate, GateKind, SingleGate, TwoGate};
+use crate::physics::pauli::{PauliString, Phase, SinglePauli};
+use crate::physics::propagation::apply_gate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Every gate kind, in `rule_tables` order.
+pub const GATE_KINDS: [GateKind; 10] = [
+    GateKind::Single(SingleGate::I),
+    GateKind::Single(SingleGate::X),
+    GateKind::Single(SingleGate::Y),
+    GateKind::Single(SingleGate::Z),
+    GateKind::Single(SingleGate::H),
+    GateKind::Single(SingleGate::S),
+    GateKind::Single(SingleGate::Sdg),
+    GateKind::CNOT,
+    GateKind::CZ,
+    GateKind::SWAP,
+];
+
+/// U P U' for a single-qubit Pauli P on one operand.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConjugationRule {
+    /// Index into `RuleTable::operands`
+    pub operand: usize,
+    pub input: SinglePauli,
+    /// One Pauli per operand
+    pub output: Vec<SinglePauli>,
+    pub phase: Phase,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleTable {
+    pub kind: GateKind,
+    /// Operand names, e.g. ["control", "target"]
+    pub operands: Vec<String>,
+    /// X, Y, Z on the first operand, then on the second
+    pub rules: Vec<ConjugationRule>,
+}
+
+impl fmt::Display for RuleTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} ({})", self.kind, self.operands.join(", "))?;
+        for rule in &self.rules {
+            let mut input = vec![SinglePauli::I; self.operands.len()];
+            input[rule.operand] = rule.input;
+            let letters = |paulis: &[SinglePauli]| paulis.iter().map(|p| p.to_string()).collect::<String>();
+            writeln!(f, "  {} -> {}{}", letters(&input), rule.phase, letters(&rule.output))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn rule_table(kind: GateKind) -> RuleTable {
+    let (gate, operands) = match kind {
+        GateKind::Single(gate) => (Gate::Single { qubit: 0, gate }, vec!["qubit"]),
+        GateKind::CNOT => (Gate::Two(TwoGate::CNOT { control: 0, target: 1 }), vec!["control", "target"]),
+        GateKind::CZ => (Gate::Two(TwoGate::CZ { control: 0, target: 1 }), vec!["control", "target"]),
+        GateKind::SWAP => (Gate::Two(TwoGate::SWAP { qubit1: 0, qubit2: 1 }), vec!["qubit1", "qubit2"]),
+    };
+    let arity = operands.len();
+    let mut rules = Vec::new();
+    for operand in 0..arity {
+        for input in [SinglePauli::X, SinglePauli::Y, SinglePauli::Z] {
+            let mut pauli = PauliString::new(arity);
+            pauli.set_pauli(operand, input);
+            apply_gate(&mut pauli, &gate);
+            rules.push(ConjugationRule {
+                operand,
+                input,
+                output: (0..arity).map(|q| pauli.get_pauli(q)).collect(),
+                phase: pauli.phase(),
+            });
+        }
+    }
+    RuleTable {
+        kind,
+        operands: operands.into_iter().map(str::to_string).collect(),
+        rules,
+    }
+}
+
+/// The tables of all gate kinds.
+pub fn rule_tables() -> Vec<RuleTable> {
+    GATE_KINDS.into_iter().map(rule_table).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_tables() {
+        let tables = rule_tables();
+        assert_eq!(tables.len(), GATE_KINDS.len());
+
+        let cnot = rule_table(GateKind::CNOT);
+        assert_eq!(cnot.rules.len(), 6);
+        let x_control = &cnot.rules[0];
+        assert_eq!(x_control.output, vec![SinglePauli::X, SinglePauli::X]);
+        assert_eq!(x_control.phase, Phase::PlusOne);
+        let z_target = &cnot.rules[5];
+        assert_eq!((z_target.operand, z_target.input), (1, SinglePauli::Z));
+        assert_eq!(z_target.output, vec![SinglePauli::Z, SinglePauli::Z]);
+
+        let hadamard = rule_table(GateKind::Single(SingleGate::H));
+        assert_eq!(hadamard.to_string(), "H (qubit)\n  X -> Z\n  Y -> −Y\n  Z -> X\n");
+    }
+}
@@ -0,0 +1,551 @@
+//! Quantum error propagation simulator.
+//!
+//! This module implements the core simulation engine that tracks how
+//! Pauli errors propagate through Clifford circuits.
+
+use crate::memory::{timeline_bytes, MemoryLimits};
+use crate::perf::{self, Counter};
+use crate::physics::circuit::Circuit;
+use crate::physics::explain::{explain_gate, StepExplanation};
+use crate::physics::frame::{snapshots_in_frame, to_logical, wire_layout, ErrorFrame};
+use crate::physics::pauli::PauliString;
+use crate::physics::propagation::apply_gate;
+use crate::physics::timeline::{Timeline, TimelineRetention};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub time: usize,
+    pub error_pattern: PauliString,
+    pub gate_applied: Option<usize>,
+    /// Classical bits recorded so far, e.g. measurement outcomes that
+    /// steer conditional corrections
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classical_bits: Vec<bool>,
+    /// For each circuit measurement reached so far, whether the error
+    /// pattern flipped its outcome
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub measurement_flips: Vec<bool>,
+}
+
+pub struct Simulator {
+    error_pattern: PauliString,
+    classical_bits: Vec<bool>,
+    measurement_flips: Vec<bool>,
+    circuit: Arc<Circuit>,
+    timeline: Timeline,
+    current_time: usize,
+}
+
+impl Simulator {
+    pub fn new(circuit: Circuit) -> Self {
+        let num_qubits = circuit.num_qubits;
+        let error_pattern = PauliString::new(num_qubits);
+        
+        let mut simulator = Self {
+            error_pattern,
+            classical_bits: Vec::new(),
+            measurement_flips: Vec::new(),
+            circuit: Arc::new(circuit),
+            timeline: Timeline::default(),
+            current_time: 0,
+        };
+        simulator.read_measurements();
+        
+        simulator.timeline.push(Snapshot {
+            time: 0,
+            error_pattern: simulator.error_pattern.clone(),
+            gate_applied: None,
+            classical_bits: Vec::new(),
+            measurement_flips: simulator.measurement_flips.clone(),
+        });
+        
+        simulator
+    }
+
+    /// An independent simulator at the current time, sharing the circuit.
+    /// Its timeline starts at the current snapshot, so it cannot step back
+    /// past the fork point; the original is unaffected by either.
+    pub fn fork(&self) -> Self {
+        let current = self.timeline.last().cloned().unwrap_or_else(|| Snapshot {
+            time: self.current_time,
+            error_pattern: self.error_pattern.clone(),
+            gate_applied: self.current_time.checked_sub(1),
+            classical_bits: self.classical_bits.clone(),
+            measurement_flips: self.measurement_flips.clone(),
+        });
+        perf::count(Counter::SnapshotCopiesAvoided, self.timeline.len().saturating_sub(1) as u64);
+        let timeline = Timeline::starting_at(self.timeline.retention(), current)
+            .expect("retention was validated when it was set");
+        Self {
+            error_pattern: self.error_pattern.clone(),
+            classical_bits: self.classical_bits.clone(),
+            measurement_flips: self.measurement_flips.clone(),
+            circuit: Arc::clone(&self.circuit),
+            timeline,
+            current_time: self.current_time,
+        }
+    }
+
+    /// Switches the timeline to `retention`, keeping the snapshots so far.
+    pub fn set_timeline_retention(&mut self, retention: TimelineRetention) -> Result<(), String> {
+        let mut snapshots = self.timeline.all().into_owned().into_iter();
+        let first = snapshots.next().expect("the timeline always holds the current snapshot");
+        let mut timeline = Timeline::starting_at(retention, first)?;
+        for snapshot in snapshots {
+            timeline.push(snapshot);
+        }
+        self.timeline = timeline;
+        Ok(())
+    }
+
+    /// Sets the error on `qubit` at the current time, before the
+    /// measurements at this position are read.
+    pub fn inject_error(&mut self, qubit: usize, pauli: crate::physics::pauli::SinglePauli) {
+        self.error_pattern.set_pauli(qubit, pauli);
+        let here = self.circuit.measurements_at(self.current_time).len();
+        self.measurement_flips.truncate(self.measurement_flips.len() - here);
+        self.read_measurements();
+        if let Some(last) = self.timeline.last_mut() {
+            last.error_pattern = self.error_pattern.clone();
+            last.measurement_flips = self.measurement_flips.clone();
+        }
+    }
+
+    /// Appends classical bits at the current time step; later snapshots
+    /// carry them along.
+    pub fn record_classical_bits(&mut self, bits: &[bool]) {
+        self.classical_bits.extend_from_slice(bits);
+        if let Some(last) = self.timeline.last_mut() {
+            last.classical_bits = self.classical_bits.clone();
+        }
+    }
+
+    pub fn classical_bits(&self) -> &[bool] {
+        &self.classical_bits
+    }
+
+    /// One entry per circuit measurement reached so far, in circuit order:
+    /// whether the error pattern at that point anticommutes with the
+    /// measured observable, i.e. flips the outcome. Measurements are read
+    /// as soon as their position is reached, so only errors injected at
+    /// that position or earlier change them.
+    pub fn measurement_flips(&self) -> &[bool] {
+        &self.measurement_flips
+    }
+
+    fn read_measurements(&mut self) {
+        let error = &self.error_pattern;
+        let flips = self
+            .circuit
+            .measurements_at(self.current_time)
+            .iter()
+            .map(|m| m.basis.flipped_by(error, m.qubit));
+        self.measurement_flips.extend(flips);
+    }
+
+    pub fn error_pattern(&self) -> &PauliString {
+        &self.error_pattern
+    }
+
+    /// The current error pattern on physical qubits or on logical wires.
+    pub fn error_pattern_in(&self, frame: ErrorFrame) -> Cow<'_, PauliString> {
+        match frame {
+            ErrorFrame::Physical => Cow::Borrowed(&self.error_pattern),
+            ErrorFrame::Logical => {
+                Cow::Owned(to_logical(&self.error_pattern, &wire_layout(&self.circuit, self.current_time)))
+            }
+        }
+    }
+
+    pub fn current_time(&self) -> usize {
+        self.current_time
+    }
+
+    /// Number of moments; `current_time` still counts gates.
+    pub fn depth(&self) -> usize {
+        self.circuit.depth()
+    }
+
+    /// Moments completely applied so far.
+    pub fn current_moment(&self) -> usize {
+        self.circuit.moment_of(self.current_time).unwrap_or(self.circuit.depth())
+    }
+
+    /// Applies the rest of the current moment, one snapshot per gate.
+    pub fn step_moment_forward(&mut self) -> bool {
+        let Some(range) = self.circuit.moment_range(self.current_moment()) else {
+            return false;
+        };
+        while self.current_time < range.end && self.step_forward() {}
+        true
+    }
+
+    /// Undoes gates back to the start of the moment of the last applied
+    /// gate; false if nothing could be undone.
+    pub fn step_moment_backward(&mut self) -> bool {
+        let Some(last) = self.current_time.checked_sub(1) else {
+            return false;
+        };
+        let start = self.circuit.moment_range(self.circuit.moment_of(last).expect("applied gate")).expect("moment").start;
+        let before = self.current_time;
+        while self.current_time > start && self.step_backward() {}
+        self.current_time < before
+    }
+
+    pub fn step_forward(&mut self) -> bool {
+        if self.current_time >= self.circuit.gates.len() {
+            return false;
+        }
+
+        let gate = &self.circuit.gates[self.current_time];
+        apply_gate(&mut self.error_pattern, gate);
+        
+        self.current_time += 1;
+        self.read_measurements();
+        
+        perf::count(Counter::SnapshotsCreated, 1);
+        self.timeline.push(Snapshot {
+            time: self.current_time,
+            error_pattern: self.error_pattern.clone(),
+            gate_applied: Some(self.current_time - 1),
+            classical_bits: self.classical_bits.clone(),
+            measurement_flips: self.measurement_flips.clone(),
+        });
+        
+        true
+    }
+
+    /// Why the last step turned the previous pattern into the current one;
+    /// None at time 0 or when the previous snapshot was dropped by the
+    /// retention policy. The previous pattern is read from the timeline, as
+    /// CNOT propagation cannot be undone by applying its inverse.
+    pub fn explain_last_step(&self) -> Option<StepExplanation> {
+        let index = self.current_time.checked_sub(1)?;
+        let before = self.timeline.get(index)?;
+        Some(explain_gate(index, &self.circuit.gates[index], &before.error_pattern))
+    }
+
+    /// Returns false at time 0 or when the previous snapshot was dropped by
+    /// the retention policy.
+    pub fn step_backward(&mut self) -> bool {
+        if self.current_time == 0 || self.current_time <= self.timeline.first_available() {
+            return false;
+        }
+
+        self.timeline.pop();
+        self.current_time -= 1;
+        
+        if let Some(prev_snapshot) = self.timeline.last() {
+            self.error_pattern = prev_snapshot.error_pattern.clone();
+            self.classical_bits = prev_snapshot.classical_bits.clone();
+            self.measurement_flips = prev_snapshot.measurement_flips.clone();
+        }
+        
+        true
+    }
+
+    pub fn reset(&mut self) {
+        self.current_time = 0;
+        self.error_pattern = PauliString::new(self.circuit.num_qubits);
+        self.classical_bits.clear();
+        self.measurement_flips.clear();
+        self.read_measurements();
+        self.timeline.clear();
+        self.timeline.push(Snapshot {
+            time: 0,
+            error_pattern: self.error_pattern.clone(),
+            gate_applied: None,
+            classical_bits: Vec::new(),
+            measurement_flips: self.measurement_flips.clone(),
+        });
+    }
+
+    pub fn run(&mut self) {
+        while self.step_forward() {}
+    }
+
+    /// Runs to the end unless the full timeline would exceed `limits`.
+    pub fn run_within(&mut self, limits: &MemoryLimits) -> Result<(), String> {
+        let snapshots = self.circuit.gates.len() + 1;
+        let bits = self.classical_bits.len() + self.circuit.measurements.len();
+        let bytes = timeline_bytes(self.circuit.num_qubits, snapshots, bits);
+        limits.check(&format!("A {}-snapshot timeline", snapshots), bytes)?;
+        self.run();
+        Ok(())
+    }
+
+    /// The snapshot at `time` if it is held in memory, which under the
+    /// default retention is every snapshot; see `load_snapshot`.
+    pub fn get_snapshot(&self, time: usize) -> Option<&Snapshot> {
+        let first = self.timeline.len() - self.timeline.resident().len();
+        self.timeline.resident().get(time.checked_sub(first)?)
+    }
+
+    /// Snapshots held in memory, oldest first; the whole timeline unless a
+    /// retention policy evicted some.
+    pub fn timeline(&self) -> &[Snapshot] {
+        self.timeline.resident()
+    }
+
+    /// The snapshot at `time`, read back from disk if it was spilled;
+    /// dropped ones are `None`.
+    pub fn load_snapshot(&self, time: usize) -> Option<Cow<'_, Snapshot>> {
+        self.timeline.get(time)
+    }
+
+    /// All available snapshots, oldest first, including spilled ones.
+    pub fn load_timeline(&self) -> Cow<'_, [Snapshot]> {
+        self.timeline.all()
+    }
+
+    /// All available snapshots with error patterns in `frame`.
+    pub fn timeline_in(&self, frame: ErrorFrame) -> Cow<'_, [Snapshot]> {
+        match frame {
+            ErrorFrame::Physical => self.load_timeline(),
+            ErrorFrame::Logical => Cow::Owned(snapshots_in_frame(&self.circuit, &self.load_timeline(), frame)),
+        }
+    }
+
+    pub fn circuit(&self) -> &Circuit {
+        &self.circuit
+    }
+
+    pub fn shared_circuit(&self) -> Arc<Circuit> {
+        Arc::clone(&self.circuit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{Circuit, Gate, MeasurementBasis, SingleGate, TwoGate};
+    use crate::physics::pauli::SinglePauli;
+
+    #[test]
+    fn test_simulator_basic() {
+        let mut circuit = Circuit::new(2);
+        circuit
+            .add_gate(Gate::Single {
+                qubit: 0,
+                gate: SingleGate::H,
+            })
+            .unwrap();
+        circuit
+            .add_gate(Gate::Two(TwoGate::CNOT {
+                control: 0,
+                target: 1,
+            }))
+            .unwrap();
+
+        let mut sim = Simulator::new(circuit);
+        sim.inject_error(0, SinglePauli::X);
+        
+        assert!(sim.step_forward());
+        // H X H' = Z
+        assert_eq!(sim.error_pattern().get_pauli(0), SinglePauli::Z);
+        
+        assert!(sim.step_forward());
+        assert_eq!(sim.error_pattern().get_pauli(0), SinglePauli::Z);
+        assert_eq!(sim.error_pattern().get_pauli(1), SinglePauli::I);
+    }
+
+    #[test]
+    fn test_run_within_memory_limit() {
+        let mut circuit = Circuit::new(1000);
+        for _ in 0..100 {
+            circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::X }).unwrap();
+        }
+        let mut sim = Simulator::new(circuit);
+        assert!(sim.run_within(&MemoryLimits::new(4096)).is_err());
+        assert_eq!(sim.current_time(), 0);
+        assert!(sim.run_within(&MemoryLimits::default()).is_ok());
+        assert_eq!(sim.current_time(), 100);
+    }
+
+    #[test]
+    #[cfg(feature = "spill")]
+    fn test_spilled_timeline_is_transparent() {
+        let mut circuit = Circuit::new(2);
+        for _ in 0..30 {
+            circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        }
+        let mut sim = Simulator::new(circuit);
+        sim.set_timeline_retention(TimelineRetention::Spill { resident: 5 }).unwrap();
+        sim.inject_error(0, SinglePauli::X);
+        sim.run();
+        assert!(sim.timeline().len() < 10);
+        assert_eq!(sim.load_timeline().len(), 31);
+        assert!(sim.get_snapshot(3).is_none());
+        assert_eq!(sim.load_snapshot(3).unwrap().error_pattern.get_pauli(0), SinglePauli::Z);
+        assert_eq!(sim.get_snapshot(30), sim.timeline().last());
+        while sim.step_backward() {}
+        assert_eq!(sim.current_time(), 0);
+        assert_eq!(sim.error_pattern().get_pauli(0), SinglePauli::X);
+
+        sim.set_timeline_retention(TimelineRetention::Drop { resident: 5 }).unwrap();
+        sim.run();
+        assert!(sim.get_snapshot(0).is_none());
+        while sim.step_backward() {}
+        assert!(sim.current_time() > 0);
+    }
+
+    #[test]
+    fn test_step_by_moment() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        circuit.add_gate_at(0, Gate::Single { qubit: 1, gate: SingleGate::H }).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        let mut sim = Simulator::new(circuit);
+        sim.inject_error(0, SinglePauli::Z);
+        assert_eq!(sim.depth(), 2);
+
+        assert!(sim.step_forward());
+        assert_eq!(sim.current_moment(), 0);
+        assert!(sim.step_moment_forward());
+        assert_eq!((sim.current_time(), sim.current_moment()), (2, 1));
+        assert!(sim.step_moment_forward());
+        assert!(!sim.step_moment_forward());
+        assert_eq!(sim.error_pattern(), &PauliString::from_str("XX", 2).unwrap());
+
+        assert!(sim.step_moment_backward());
+        assert!(sim.step_moment_backward());
+        assert_eq!(sim.current_time(), 0);
+        assert!(!sim.step_moment_backward());
+    }
+
+    #[test]
+    fn test_measurement_flips() {
+        // Syndrome extraction of Z0 Z1 onto ancilla 2, then X readouts of the data
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 2 })).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 1, target: 2 })).unwrap();
+        circuit.add_measurement(2, MeasurementBasis::Z).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        circuit.add_measurement(0, MeasurementBasis::X).unwrap();
+        circuit.add_measurement(1, MeasurementBasis::X).unwrap();
+
+        let mut sim = Simulator::new(circuit);
+        sim.inject_error(0, SinglePauli::X);
+        sim.step_forward();
+        assert!(sim.measurement_flips().is_empty());
+        sim.step_forward();
+        assert_eq!(sim.measurement_flips(), &[true]);
+        sim.step_forward();
+        // Injected after the readout, so it cannot unflip it
+        sim.inject_error(2, SinglePauli::Z);
+        sim.run();
+        // H turns X0 into Z0, which flips the X readout of qubit 0 only
+        assert_eq!(sim.measurement_flips(), &[true, true, false]);
+        assert_eq!(sim.get_snapshot(2).unwrap().measurement_flips, vec![true]);
+
+        assert!(sim.step_backward());
+        assert_eq!(sim.measurement_flips(), &[true]);
+        sim.reset();
+        assert!(sim.measurement_flips().is_empty());
+    }
+
+    #[test]
+    fn test_error_injected_before_first_readout() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_measurement(0, MeasurementBasis::Z).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 1, gate: SingleGate::H }).unwrap();
+
+        let mut sim = Simulator::new(circuit);
+        assert_eq!(sim.measurement_flips(), &[false]);
+        sim.inject_error(0, SinglePauli::X);
+        assert_eq!(sim.measurement_flips(), &[true]);
+        assert_eq!(sim.get_snapshot(0).unwrap().measurement_flips, vec![true]);
+        sim.inject_error(0, SinglePauli::Z);
+        sim.run();
+        assert_eq!(sim.measurement_flips(), &[false]);
+        sim.reset();
+        assert_eq!(sim.measurement_flips(), &[false]);
+    }
+
+    #[test]
+    fn test_fork_branches_from_current_time() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        let mut sim = Simulator::new(circuit);
+        sim.inject_error(0, SinglePauli::Z);
+        sim.step_forward();
+
+        let mut branch = sim.fork();
+        assert!(Arc::ptr_eq(&sim.shared_circuit(), &branch.shared_circuit()));
+        assert_eq!(branch.timeline().len(), 1);
+        branch.inject_error(1, SinglePauli::Z);
+        branch.run();
+        sim.run();
+        assert_eq!(sim.error_pattern().get_pauli(1), SinglePauli::X);
+        assert_eq!(branch.error_pattern().get_pauli(1), SinglePauli::Y);
+        assert_eq!(branch.get_snapshot(2).unwrap().time, 2);
+        assert!(branch.get_snapshot(0).is_none());
+
+        assert!(branch.step_backward());
+        assert!(!branch.step_backward());
+        assert_eq!(branch.current_time(), 1);
+        assert_eq!(branch.error_pattern().get_pauli(1), SinglePauli::Z);
+    }
+
+    #[test]
+    fn test_cnot_x_propagation() {
+        let mut circuit = Circuit::new(2);
+        circuit
+            .add_gate(Gate::Two(TwoGate::CNOT {
+                control: 0,
+                target: 1,
+            }))
+            .unwrap();
+
+        let mut sim = Simulator::new(circuit);
+        sim.inject_error(0, SinglePauli::X);
+        sim.run();
+        assert_eq!(sim.error_pattern().get_pauli(0), SinglePauli::X);
+        assert_eq!(sim.error_pattern().get_pauli(1), SinglePauli::X);
+    }
+
+    #[test]
+    fn test_cnot_z_propagation() {
+        let mut circuit = Circuit::new(2);
+        circuit
+            .add_gate(Gate::Two(TwoGate::CNOT {
+                control: 0,
+                target: 1,
+            }))
+            .unwrap();
+
+        let mut sim = Simulator::new(circuit);
+        sim.inject_error(1, SinglePauli::Z);
+        assert!(sim.explain_last_step().is_none());
+        sim.run();
+        assert_eq!(sim.error_pattern().get_pauli(0), SinglePauli::Z);
+        assert_eq!(sim.error_pattern().get_pauli(1), SinglePauli::Z);
+
+        let explanation = sim.explain_last_step().unwrap();
+        assert_eq!(explanation.gate_index, 0);
+        assert_eq!(explanation.changes[1].before, SinglePauli::Z);
+        assert_eq!(explanation.summary, "CNOT(0, 1): Z on target spread to control; phase unchanged");
+    }
+
+    #[test]
+    fn test_explain_x_on_control() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        let mut sim = Simulator::new(circuit);
+        sim.inject_error(0, SinglePauli::X);
+        sim.run();
+
+        let explanation = sim.explain_last_step().unwrap();
+        assert_eq!(explanation.changes[0].before, SinglePauli::X);
+        assert_eq!(explanation.changes[1].before, SinglePauli::I);
+        assert_eq!(explanation.changes[1].after, SinglePauli::X);
+
+        sim.set_timeline_retention(TimelineRetention::Drop { resident: 1 }).unwrap();
+        assert!(sim.explain_last_step().is_none());
+    }
+}
+
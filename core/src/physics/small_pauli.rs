@@ -0,0 +1,294 @@
+//! Fixed-width Pauli strings for small registers.
+//!
+//! `PauliStringN<WORDS>` stores up to `64 * WORDS` qubits inline, with no
+//! heap allocation, and follows exactly the same conjugation rules (phases
+//! included) as `propagation::apply_gate` on `PauliString`. Propagation
+//! helpers switch to it when the circuit is small enough.
+
+use crate::physics::circuit::{Gate, SingleGate, TwoGate};
+use crate::physics::pauli::{PauliString, Phase, SinglePauli};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PauliStringN<const WORDS: usize> {
+    x: [u64; WORDS],
+    z: [u64; WORDS],
+    phase: Phase,
+    num_qubits: usize,
+}
+
+impl<const WORDS: usize> PauliStringN<WORDS> {
+    pub const CAPACITY: usize = 64 * WORDS;
+
+    pub fn new(num_qubits: usize) -> Self {
+        if num_qubits > Self::CAPACITY {
+            panic!("{} qubits do not fit in a {}-qubit register", num_qubits, Self::CAPACITY);
+        }
+        Self {
+            x: [0; WORDS],
+            z: [0; WORDS],
+            phase: Phase::PlusOne,
+            num_qubits,
+        }
+    }
+
+    pub fn from_pauli_string(pauli: &PauliString) -> Result<Self, String> {
+        if pauli.num_qubits() > Self::CAPACITY {
+            return Err(format!(
+                "{} qubits do not fit in a {}-qubit register",
+                pauli.num_qubits(),
+                Self::CAPACITY
+            ));
+        }
+        let mut small = Self::new(pauli.num_qubits());
+        for qubit in pauli.x_bits().iter_ones() {
+            small.x[qubit / 64] |= 1 << (qubit % 64);
+        }
+        for qubit in pauli.z_bits().iter_ones() {
+            small.z[qubit / 64] |= 1 << (qubit % 64);
+        }
+        small.phase = pauli.phase();
+        Ok(small)
+    }
+
+    pub fn to_pauli_string(&self) -> PauliString {
+        let mut pauli = PauliString::new(self.num_qubits);
+        for qubit in 0..self.num_qubits {
+            pauli.set_pauli(qubit, self.get_pauli(qubit));
+        }
+        pauli.set_phase(self.phase);
+        pauli
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+
+    fn check(&self, qubit: usize) {
+        if qubit >= self.num_qubits {
+            panic!("Qubit index {} out of range (max {})", qubit, self.num_qubits);
+        }
+    }
+
+    fn x_bit(&self, qubit: usize) -> bool {
+        self.x[qubit / 64] >> (qubit % 64) & 1 == 1
+    }
+
+    fn z_bit(&self, qubit: usize) -> bool {
+        self.z[qubit / 64] >> (qubit % 64) & 1 == 1
+    }
+
+    fn set_bits(&mut self, qubit: usize, x: bool, z: bool) {
+        let (word, mask) = (qubit / 64, 1u64 << (qubit % 64));
+        self.x[word] = if x { self.x[word] | mask } else { self.x[word] & !mask };
+        self.z[word] = if z { self.z[word] | mask } else { self.z[word] & !mask };
+    }
+
+    fn negate(&mut self) {
+        self.phase = self.phase.multiply(Phase::MinusOne);
+    }
+
+    pub fn get_pauli(&self, qubit: usize) -> SinglePauli {
+        self.check(qubit);
+        match (self.x_bit(qubit), self.z_bit(qubit)) {
+            (false, false) => SinglePauli::I,
+            (true, false) => SinglePauli::X,
+            (false, true) => SinglePauli::Z,
+            (true, true) => SinglePauli::Y,
+        }
+    }
+
+    pub fn set_pauli(&mut self, qubit: usize, pauli: SinglePauli) {
+        self.check(qubit);
+        let (x, z) = match pauli {
+            SinglePauli::I => (false, false),
+            SinglePauli::X => (true, false),
+            SinglePauli::Z => (false, true),
+            SinglePauli::Y => (true, true),
+        };
+        self.set_bits(qubit, x, z);
+    }
+
+    /// `self * other`, with the same phase convention as
+    /// `PauliString::multiply`.
+    pub fn multiply(&self, other: &Self) -> Self {
+        if self.num_qubits != other.num_qubits {
+            panic!("Cannot multiply Pauli strings with different qubit counts");
+        }
+        let mut product = *self;
+        let (mut positive, mut negative) = (0i32, 0i32);
+        for word in 0..WORDS {
+            positive += (self.x[word] & other.z[word]).count_ones() as i32;
+            negative += (self.z[word] & other.x[word]).count_ones() as i32;
+            product.x[word] ^= other.x[word];
+            product.z[word] ^= other.z[word];
+        }
+        let exponent = ((positive - negative) % 4 + 4) % 4;
+        product.phase = self.phase.multiply(other.phase).multiply(Phase::from_u8(exponent as u8));
+        product
+    }
+
+    pub fn commutes_with(&self, other: &Self) -> bool {
+        if self.num_qubits != other.num_qubits {
+            return false;
+        }
+        let ones: u32 = (0..WORDS)
+            .map(|w| ((self.x[w] & other.z[w]) ^ (self.z[w] & other.x[w])).count_ones())
+            .sum();
+        ones.is_multiple_of(2)
+    }
+
+    /// Conjugates by `gate`, matching `propagation::apply_gate`.
+    pub fn apply_gate(&mut self, gate: &Gate) {
+        match *gate {
+            Gate::Single { qubit, gate } => self.apply_single(qubit, gate),
+            Gate::Two(gate) => self.apply_two(gate),
+        }
+    }
+
+    fn apply_single(&mut self, qubit: usize, gate: SingleGate) {
+        if qubit >= self.num_qubits {
+            panic!("Qubit index {} out of range", qubit);
+        }
+        let (x, z) = (self.x_bit(qubit), self.z_bit(qubit));
+        match gate {
+            SingleGate::I => {}
+            SingleGate::X if z => self.negate(),
+            SingleGate::Y if x != z => self.negate(),
+            SingleGate::Z if x => self.negate(),
+            SingleGate::X | SingleGate::Y | SingleGate::Z => {}
+            SingleGate::H => {
+                self.set_bits(qubit, z, x);
+                if x && z {
+                    self.negate();
+                }
+            }
+            SingleGate::S if x => {
+                self.set_bits(qubit, x, !z);
+                if !z {
+                    self.phase = self.phase.multiply(Phase::PlusI);
+                } else if self.phase == Phase::MinusI {
+                    self.phase = Phase::PlusOne;
+                } else {
+                    self.negate();
+                }
+            }
+            SingleGate::Sdg if x => {
+                self.set_bits(qubit, x, !z);
+                if !z {
+                    self.phase = self.phase.multiply(Phase::MinusI);
+                } else if self.phase == Phase::PlusI {
+                    self.phase = Phase::PlusOne;
+                }
+            }
+            SingleGate::S | SingleGate::Sdg => {}
+        }
+    }
+
+    fn apply_two(&mut self, gate: TwoGate) {
+        match gate {
+            TwoGate::CNOT { control, target } | TwoGate::CZ { control, target } => {
+                if control >= self.num_qubits || target >= self.num_qubits {
+                    panic!("Qubit index out of range");
+                }
+                if control == target {
+                    panic!("Two-qubit gate control and target must be different");
+                }
+                let (x_c, z_c) = (self.x_bit(control), self.z_bit(control));
+                let (x_t, z_t) = (self.x_bit(target), self.z_bit(target));
+                if let TwoGate::CNOT { .. } = gate {
+                    // X on control spreads to target, Z on target toggles control
+                    self.set_bits(target, x_t || x_c, z_t);
+                    self.set_bits(control, x_c, z_c ^ z_t);
+                    if x_c && z_t {
+                        self.negate();
+                    }
+                } else {
+                    self.set_bits(target, x_t, z_t ^ x_c);
+                    self.set_bits(control, x_c, z_c ^ x_t);
+                    if x_c && x_t {
+                        self.negate();
+                    }
+                }
+            }
+            TwoGate::SWAP { qubit1, qubit2 } => {
+                if qubit1 >= self.num_qubits || qubit2 >= self.num_qubits {
+                    panic!("Qubit index out of range");
+                }
+                let (x1, z1) = (self.x_bit(qubit1), self.z_bit(qubit1));
+                let (x2, z2) = (self.x_bit(qubit2), self.z_bit(qubit2));
+                self.set_bits(qubit1, x2, z2);
+                self.set_bits(qubit2, x1, z1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::propagation::apply_gate;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    fn below(rng: &mut SmallRng, n: usize) -> usize {
+        rng.next_u32() as usize % n
+    }
+
+    fn random_gate(rng: &mut SmallRng, n: usize) -> Gate {
+        let a = below(rng, n);
+        let b = (a + 1 + below(rng, n - 1)) % n;
+        let singles = [
+            SingleGate::I,
+            SingleGate::X,
+            SingleGate::Y,
+            SingleGate::Z,
+            SingleGate::H,
+            SingleGate::S,
+            SingleGate::Sdg,
+        ];
+        match below(rng, 10) {
+            0 => Gate::Two(TwoGate::CNOT { control: a, target: b }),
+            1 => Gate::Two(TwoGate::CZ { control: a, target: b }),
+            2 => Gate::Two(TwoGate::SWAP { qubit1: a, qubit2: b }),
+            k => Gate::Single { qubit: a, gate: singles[k % singles.len()] },
+        }
+    }
+
+    fn check_agreement<const WORDS: usize>(n: usize, seed: u64) {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let paulis = [SinglePauli::I, SinglePauli::X, SinglePauli::Y, SinglePauli::Z];
+        for _ in 0..50 {
+            let mut wide = PauliString::new(n);
+            for qubit in 0..n {
+                wide.set_pauli(qubit, paulis[below(&mut rng, 4)]);
+            }
+            let mut small = PauliStringN::<WORDS>::from_pauli_string(&wide).unwrap();
+            for _ in 0..40 {
+                let gate = random_gate(&mut rng, n);
+                apply_gate(&mut wide, &gate);
+                small.apply_gate(&gate);
+                assert_eq!(small.to_pauli_string(), wide, "after {:?}", gate);
+            }
+            let other = PauliString::from_str(&"XZY".repeat(n).chars().take(n).collect::<String>(), n).unwrap();
+            let other_small = PauliStringN::<WORDS>::from_pauli_string(&other).unwrap();
+            assert_eq!(small.multiply(&other_small).to_pauli_string(), wide.multiply(&other));
+            assert_eq!(small.commutes_with(&other_small), wide.commutes_with(&other));
+        }
+    }
+
+    #[test]
+    fn test_matches_wide_propagation() {
+        check_agreement::<1>(5, 2240);
+        check_agreement::<1>(64, 2241);
+        check_agreement::<2>(100, 2242);
+        assert!(PauliStringN::<1>::from_pauli_string(&PauliString::new(65)).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+//! Sparse Pauli strings for large registers with low-weight errors.
+//!
+//! `SparsePauliString` stores only the non-identity qubits, so a weight-w
+//! error on a register of thousands of qubits costs O(w) to store, multiply
+//! and propagate. It follows exactly the same conventions (phases
+//! included) as `PauliString`: each gate is applied to the one or two
+//! qubits it touches through the fixed-width engine.
+
+use crate::physics::circuit::{Gate, TwoGate};
+use crate::physics::pauli::{PauliString, Phase, SinglePauli};
+use crate::physics::small_pauli::PauliStringN;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SparsePauliString {
+    num_qubits: usize,
+    /// Non-identity qubits only
+    paulis: BTreeMap<usize, SinglePauli>,
+    phase: Phase,
+}
+
+impl SparsePauliString {
+    pub fn new(num_qubits: usize) -> Self {
+        Self {
+            num_qubits,
+            paulis: BTreeMap::new(),
+            phase: Phase::PlusOne,
+        }
+    }
+
+    pub fn from_pauli_string(pauli: &PauliString) -> Self {
+        let mut sparse = Self::new(pauli.num_qubits());
+        for qubit in pauli.support() {
+            sparse.paulis.insert(qubit, pauli.get_pauli(qubit));
+        }
+        sparse.phase = pauli.phase();
+        sparse
+    }
+
+    pub fn to_pauli_string(&self) -> PauliString {
+        let mut pauli = PauliString::new(self.num_qubits);
+        for (&qubit, &p) in &self.paulis {
+            pauli.set_pauli(qubit, p);
+        }
+        pauli.set_phase(self.phase);
+        pauli
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub fn set_phase(&mut self, phase: Phase) {
+        self.phase = phase;
+    }
+
+    /// Number of non-identity qubits.
+    pub fn weight(&self) -> usize {
+        self.paulis.len()
+    }
+
+    /// Non-identity qubits and their Paulis, by qubit.
+    pub fn support(&self) -> impl Iterator<Item = (usize, SinglePauli)> + '_ {
+        self.paulis.iter().map(|(&qubit, &pauli)| (qubit, pauli))
+    }
+
+    pub fn get_pauli(&self, qubit: usize) -> SinglePauli {
+        if qubit >= self.num_qubits {
+            panic!("Qubit index {} out of range (max {})", qubit, self.num_qubits);
+        }
+        self.paulis.get(&qubit).copied().unwrap_or(SinglePauli::I)
+    }
+
+    pub fn set_pauli(&mut self, qubit: usize, pauli: SinglePauli) {
+        if qubit >= self.num_qubits {
+            panic!("Qubit index {} out of range (max {})", qubit, self.num_qubits);
+        }
+        if pauli == SinglePauli::I {
+            self.paulis.remove(&qubit);
+        } else {
+            self.paulis.insert(qubit, pauli);
+        }
+    }
+
+    /// `self * other`, with the same phase convention as
+    /// `PauliString::multiply`.
+    pub fn multiply(&self, other: &Self) -> Self {
+        if self.num_qubits != other.num_qubits {
+            panic!("Cannot multiply Pauli strings with different qubit counts");
+        }
+        let mut product = self.clone();
+        product.phase = self.phase.multiply(other.phase);
+        for (&qubit, &theirs) in &other.paulis {
+            let (pauli, phase) = self.get_pauli(qubit).multiply(theirs);
+            product.set_pauli(qubit, pauli);
+            product.phase = product.phase.multiply(phase);
+        }
+        product
+    }
+
+    pub fn commutes_with(&self, other: &Self) -> bool {
+        if self.num_qubits != other.num_qubits {
+            return false;
+        }
+        let (small, large) = if self.weight() <= other.weight() { (self, other) } else { (other, self) };
+        let anticommuting = small
+            .support()
+            .filter(|&(qubit, pauli)| {
+                let theirs = large.get_pauli(qubit);
+                theirs != SinglePauli::I && theirs != pauli
+            })
+            .count();
+        anticommuting % 2 == 0
+    }
+
+    /// Conjugates by `gate`, matching `propagation::apply_gate`.
+    pub fn apply_gate(&mut self, gate: &Gate) {
+        let qubits = gate.qubits();
+        if qubits.iter().any(|&q| q >= self.num_qubits) {
+            panic!("Qubit index out of range");
+        }
+        if qubits.iter().all(|q| !self.paulis.contains_key(q)) {
+            return;
+        }
+        // Run the gate on a local register holding just its operands
+        let mut local = PauliStringN::<1>::new(qubits.len());
+        for (i, &qubit) in qubits.iter().enumerate() {
+            local.set_pauli(i, self.get_pauli(qubit));
+        }
+        local.set_phase(self.phase);
+        local.apply_gate(&relabel(gate));
+        for (i, &qubit) in qubits.iter().enumerate() {
+            self.set_pauli(qubit, local.get_pauli(i));
+        }
+        self.phase = local.phase();
+    }
+}
+
+/// `gate` on local qubits 0 (and 1), in `Gate::qubits` order.
+fn relabel(gate: &Gate) -> Gate {
+    match gate {
+        Gate::Single { gate, .. } => Gate::Single { qubit: 0, gate: *gate },
+        Gate::Two(TwoGate::CNOT { .. }) => Gate::Two(TwoGate::CNOT { control: 0, target: 1 }),
+        Gate::Two(TwoGate::CZ { .. }) => Gate::Two(TwoGate::CZ { control: 0, target: 1 }),
+        Gate::Two(TwoGate::SWAP { .. }) => Gate::Two(TwoGate::SWAP { qubit1: 0, qubit2: 1 }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::SingleGate;
+    use crate::physics::propagation::apply_gate;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_matches_dense_propagation() {
+        let n = 300;
+        let mut rng = SmallRng::seed_from_u64(2252);
+        let mut below = |k: usize| rng.next_u32() as usize % k;
+        let paulis = [SinglePauli::X, SinglePauli::Y, SinglePauli::Z];
+        let singles = [SingleGate::H, SingleGate::S, SingleGate::Sdg, SingleGate::X, SingleGate::Y, SingleGate::Z];
+        for _ in 0..20 {
+            let mut dense = PauliString::new(n);
+            for _ in 0..3 {
+                dense.set_pauli(below(n), paulis[below(3)]);
+            }
+            let mut sparse = SparsePauliString::from_pauli_string(&dense);
+            for _ in 0..200 {
+                // Keep gates near the error so most of them act on it
+                let a = sparse.support().next().map_or(0, |(q, _)| q);
+                let b = (a + 1 + below(4)) % n;
+                let gate = match below(9) {
+                    0 => Gate::Two(TwoGate::CNOT { control: a, target: b }),
+                    1 => Gate::Two(TwoGate::CNOT { control: b, target: a }),
+                    2 => Gate::Two(TwoGate::CZ { control: a, target: b }),
+                    3 => Gate::Two(TwoGate::SWAP { qubit1: a, qubit2: b }),
+                    k => Gate::Single { qubit: if k % 2 == 0 { a } else { b }, gate: singles[below(6)] },
+                };
+                apply_gate(&mut dense, &gate);
+                sparse.apply_gate(&gate);
+                assert_eq!(sparse.to_pauli_string(), dense, "after {:?}", gate);
+            }
+            let other = PauliString::from_str(&"XZYI".repeat(n / 4), n).unwrap();
+            let other_sparse = SparsePauliString::from_pauli_string(&other);
+            assert_eq!(sparse.multiply(&other_sparse).to_pauli_string(), dense.multiply(&other));
+            assert_eq!(sparse.commutes_with(&other_sparse), dense.commutes_with(&other));
+            assert_eq!(sparse.weight(), dense.weight());
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! Stabilizer tableau of a Clifford circuit.
+//!
+//! Stores the images U X_i U' and U Z_i U' of every single-qubit generator
+//! as symplectic rows with a sign bit (Aaronson-Gottesman layout, Y = XZ
+//! bits with a Hermitian ±1 sign). Two circuits implement the same Clifford
+//! up to global phase exactly when their tableaux are equal.
+
+use crate::perf::{self, Counter};
+use crate::physics::circuit::{Circuit, Gate, SingleGate, TwoGate};
+use crate::physics::pauli::{PauliString, Phase};
+use bitvec::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct Row {
+    x: BitVec<usize, Lsb0>,
+    z: BitVec<usize, Lsb0>,
+    /// true = -1
+    sign: bool,
+}
+
+impl Row {
+    fn new(num_qubits: usize) -> Self {
+        Self {
+            x: bitvec![usize, Lsb0; 0; num_qubits],
+            z: bitvec![usize, Lsb0; 0; num_qubits],
+            sign: false,
+        }
+    }
+
+    fn to_pauli_string(&self) -> PauliString {
+        let mut pauli = PauliString::new(self.x.len());
+        pauli.set_x_bits(self.x.clone());
+        pauli.set_z_bits(self.z.clone());
+        if self.sign {
+            pauli.set_phase(Phase::MinusOne);
+        }
+        pauli
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Tableau {
+    num_qubits: usize,
+    /// Rows 0..n are the images of X_i, rows n..2n the images of Z_i
+    rows: Vec<Row>,
+}
+
+impl Tableau {
+    pub fn identity(num_qubits: usize) -> Self {
+        let mut rows = vec![Row::new(num_qubits); 2 * num_qubits];
+        for qubit in 0..num_qubits {
+            rows[qubit].x.set(qubit, true);
+            rows[num_qubits + qubit].z.set(qubit, true);
+        }
+        Self { num_qubits, rows }
+    }
+
+    pub fn from_circuit(circuit: &Circuit) -> Self {
+        let mut tableau = Self::identity(circuit.num_qubits);
+        for gate in &circuit.gates {
+            tableau.apply_gate(gate);
+        }
+        tableau
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.num_qubits
+    }
+
+    /// Image of X on `qubit` under the circuit, with a ±1 phase.
+    pub fn x_image(&self, qubit: usize) -> PauliString {
+        self.rows[qubit].to_pauli_string()
+    }
+
+    /// Image of Z on `qubit` under the circuit, with a ±1 phase.
+    pub fn z_image(&self, qubit: usize) -> PauliString {
+        self.rows[self.num_qubits + qubit].to_pauli_string()
+    }
+
+    /// Image of an arbitrary Pauli string under the circuit, up to phase.
+    pub fn image(&self, pauli: &PauliString) -> PauliString {
+        let mut image = PauliString::new(self.num_qubits);
+        for qubit in 0..self.num_qubits {
+            if pauli.x_bits()[qubit] {
+                image = image.multiply(&self.x_image(qubit));
+            }
+            if pauli.z_bits()[qubit] {
+                image = image.multiply(&self.z_image(qubit));
+            }
+        }
+        image
+    }
+
+    /// Appends a gate: the tableau of C becomes the tableau of G·C.
+    pub fn apply_gate(&mut self, gate: &Gate) {
+        perf::count(Counter::TableauApplications, 1);
+        match gate {
+            Gate::Single { qubit, gate } => self.apply_single(*qubit, *gate),
+            Gate::Two(TwoGate::CNOT { control, target }) => self.apply_cnot(*control, *target),
+            Gate::Two(TwoGate::CZ { control, target }) => {
+                self.apply_single(*target, SingleGate::H);
+                self.apply_cnot(*control, *target);
+                self.apply_single(*target, SingleGate::H);
+            }
+            Gate::Two(TwoGate::SWAP { qubit1, qubit2 }) => {
+                for row in &mut self.rows {
+                    row.x.swap(*qubit1, *qubit2);
+                    row.z.swap(*qubit1, *qubit2);
+                }
+            }
+        }
+    }
+
+    fn apply_single(&mut self, qubit: usize, gate: SingleGate) {
+        for row in &mut self.rows {
+            let x = row.x[qubit];
+            let z = row.z[qubit];
+            match gate {
+                SingleGate::I => {}
+                SingleGate::X => row.sign ^= z,
+                SingleGate::Y => row.sign ^= x ^ z,
+                SingleGate::Z => row.sign ^= x,
+                SingleGate::H => {
+                    row.sign ^= x && z;
+                    row.x.set(qubit, z);
+                    row.z.set(qubit, x);
+                }
+                SingleGate::S => {
+                    row.sign ^= x && z;
+                    row.z.set(qubit, z ^ x);
+                }
+                SingleGate::Sdg => {
+                    row.sign ^= x && !z;
+                    row.z.set(qubit, z ^ x);
+                }
+            }
+        }
+    }
+
+    fn apply_cnot(&mut self, control: usize, target: usize) {
+        for row in &mut self.rows {
+            let x_c = row.x[control];
+            let z_c = row.z[control];
+            let x_t = row.x[target];
+            let z_t = row.z[target];
+            row.sign ^= x_c && z_t && (x_t ^ z_c ^ true);
+            row.x.set(target, x_t ^ x_c);
+            row.z.set(control, z_c ^ z_t);
+        }
+    }
+
+    /// Synthesizes an H/S/CNOT/SWAP/Pauli circuit with this tableau by
+    /// Gaussian elimination, one qubit at a time.
+    pub fn to_circuit(&self) -> Circuit {
+        let n = self.num_qubits;
+        let mut work = self.clone();
+        let mut applied: Vec<Gate> = Vec::new();
+        let mut apply = |work: &mut Tableau, gate: Gate| {
+            work.apply_gate(&gate);
+            applied.push(gate);
+        };
+        let single = |qubit, gate| Gate::Single { qubit, gate };
+
+        for k in 0..n {
+            // Rows of already reduced qubits are fixed, so the X_k image is
+            // supported on qubits >= k. Turn it into a pure X string.
+            for q in k..n {
+                match (work.rows[k].x[q], work.rows[k].z[q]) {
+                    (false, true) => apply(&mut work, single(q, SingleGate::H)),
+                    (true, true) => apply(&mut work, single(q, SingleGate::S)),
+                    _ => {}
+                }
+            }
+            let pivot = (k..n)
+                .find(|&q| work.rows[k].x[q])
+                .expect("X image must anticommute with Z image");
+            if pivot != k {
+                apply(&mut work, Gate::Two(TwoGate::SWAP { qubit1: k, qubit2: pivot }));
+            }
+            for q in k + 1..n {
+                if work.rows[k].x[q] {
+                    apply(&mut work, Gate::Two(TwoGate::CNOT { control: k, target: q }));
+                }
+            }
+
+            // The Z_k image anticommutes with X_k, so it is Z or Y on qubit k.
+            // H S H fixes X and maps Y to Z.
+            let z_row = n + k;
+            if work.rows[z_row].x[k] {
+                apply(&mut work, single(k, SingleGate::H));
+                apply(&mut work, single(k, SingleGate::S));
+                apply(&mut work, single(k, SingleGate::H));
+            }
+            for q in k + 1..n {
+                match (work.rows[z_row].x[q], work.rows[z_row].z[q]) {
+                    (true, false) => apply(&mut work, single(q, SingleGate::H)),
+                    (true, true) => {
+                        apply(&mut work, single(q, SingleGate::S));
+                        apply(&mut work, single(q, SingleGate::H));
+                    }
+                    _ => {}
+                }
+            }
+            for q in k + 1..n {
+                if work.rows[z_row].z[q] {
+                    apply(&mut work, Gate::Two(TwoGate::CNOT { control: q, target: k }));
+                }
+            }
+        }
+
+        for k in 0..n {
+            if work.rows[k].sign {
+                apply(&mut work, single(k, SingleGate::Z));
+            }
+            if work.rows[n + k].sign {
+                apply(&mut work, single(k, SingleGate::X));
+            }
+        }
+        debug_assert_eq!(work, Tableau::identity(n));
+
+        // The applied gates reduce U to the identity: g_m ... g_1 U = I, so
+        // U = g_1' ... g_m', i.e. the inverted gates in reverse time order.
+        let mut circuit = Circuit::new(n);
+        circuit.gates = applied
+            .into_iter()
+            .rev()
+            .map(|gate| match gate {
+                Gate::Single { qubit, gate: SingleGate::S } => single(qubit, SingleGate::Sdg),
+                Gate::Single { qubit, gate: SingleGate::Sdg } => single(qubit, SingleGate::S),
+                other => other,
+            })
+            .collect();
+        circuit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circuit_from(num_qubits: usize, gates: Vec<Gate>) -> Circuit {
+        let mut circuit = Circuit::new(num_qubits);
+        for gate in gates {
+            circuit.add_gate(gate).unwrap();
+        }
+        circuit
+    }
+
+    #[test]
+    fn test_cnot_images() {
+        let circuit = circuit_from(2, vec![Gate::Two(TwoGate::CNOT { control: 0, target: 1 })]);
+        let tableau = Tableau::from_circuit(&circuit);
+        assert_eq!(tableau.x_image(0), PauliString::from_str("XX", 2).unwrap());
+        assert_eq!(tableau.x_image(1), PauliString::from_str("IX", 2).unwrap());
+        assert_eq!(tableau.z_image(0), PauliString::from_str("ZI", 2).unwrap());
+        assert_eq!(tableau.z_image(1), PauliString::from_str("ZZ", 2).unwrap());
+    }
+
+    #[test]
+    fn test_signs() {
+        // S X S' = Y, S Y S' = -X, H Y H' = -Y
+        let s = circuit_from(1, vec![Gate::Single { qubit: 0, gate: SingleGate::S }]);
+        let tableau = Tableau::from_circuit(&s);
+        assert_eq!(tableau.x_image(0), PauliString::from_str("Y", 1).unwrap());
+
+        let mut minus_x = PauliString::from_str("X", 1).unwrap();
+        minus_x.set_phase(Phase::MinusOne);
+        let ss = circuit_from(
+            1,
+            vec![
+                Gate::Single { qubit: 0, gate: SingleGate::S },
+                Gate::Single { qubit: 0, gate: SingleGate::S },
+            ],
+        );
+        assert_eq!(Tableau::from_circuit(&ss).x_image(0), minus_x);
+
+        // S S = Z as a Clifford
+        let z = circuit_from(1, vec![Gate::Single { qubit: 0, gate: SingleGate::Z }]);
+        assert_eq!(Tableau::from_circuit(&ss), Tableau::from_circuit(&z));
+    }
+
+    #[test]
+    fn test_cnot_is_self_inverse() {
+        let cnot = Gate::Two(TwoGate::CNOT { control: 0, target: 1 });
+        let circuit = circuit_from(2, vec![cnot.clone(), cnot]);
+        assert_eq!(Tableau::from_circuit(&circuit), Tableau::identity(2));
+    }
+
+    #[test]
+    fn test_synthesis_roundtrip() {
+        let circuit = circuit_from(
+            3,
+            vec![
+                Gate::Single { qubit: 0, gate: SingleGate::H },
+                Gate::Two(TwoGate::CNOT { control: 0, target: 1 }),
+                Gate::Single { qubit: 1, gate: SingleGate::S },
+                Gate::Two(TwoGate::CZ { control: 1, target: 2 }),
+                Gate::Single { qubit: 2, gate: SingleGate::Y },
+                Gate::Two(TwoGate::SWAP { qubit1: 0, qubit2: 2 }),
+                Gate::Single { qubit: 0, gate: SingleGate::Sdg },
+            ],
+        );
+        let tableau = Tableau::from_circuit(&circuit);
+        let synthesized = tableau.to_circuit();
+        assert_eq!(Tableau::from_circuit(&synthesized), tableau);
+    }
+
+    #[test]
+    fn test_synthesis_random_circuits() {
+        use rand::rngs::SmallRng;
+        use rand::{Rng, SeedableRng};
+
+        let singles = [
+            SingleGate::H,
+            SingleGate::S,
+            SingleGate::Sdg,
+            SingleGate::X,
+            SingleGate::Y,
+            SingleGate::Z,
+        ];
+        let mut rng = SmallRng::seed_from_u64(2204);
+        for _ in 0..50 {
+            let mut circuit = Circuit::new(4);
+            for _ in 0..30 {
+                let a = (rng.next_u32() % 4) as usize;
+                let b = (a + 1 + (rng.next_u32() % 3) as usize) % 4;
+                let gate = match rng.next_u32() % 4 {
+                    0 => Gate::Two(TwoGate::CNOT { control: a, target: b }),
+                    1 => Gate::Two(TwoGate::CZ { control: a, target: b }),
+                    _ => Gate::Single {
+                        qubit: a,
+                        gate: singles[(rng.next_u32() % 6) as usize],
+                    },
+                };
+                circuit.add_gate(gate).unwrap();
+            }
+            let tableau = Tableau::from_circuit(&circuit);
+            assert_eq!(Tableau::from_circuit(&tableau.to_circuit()), tableau);
+        }
+    }
+}
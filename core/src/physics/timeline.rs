@@ -0,0 +1,326 @@
+//! Snapshot storage for the simulator.
+//!
+//! By default every snapshot stays in memory. For very long runs a
+//! retention policy keeps only the most recent snapshots resident: once
+//! twice that many have accumulated, the older half is written to a
+//! temporary file as one chunk (`Spill`) or discarded (`Drop`). Spilled
+//! chunks are read back lazily by `get` and `all`, and reloaded when
+//! stepping backward reaches them. Snapshot `t` always describes time `t`.
+//! Spilling encodes JSON and needs the `spill` feature; without it (or without
+//! a writable temporary directory) `Spill` keeps everything in memory.
+
+use crate::physics::simulator::Snapshot;
+use std::borrow::Cow;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TimelineRetention {
+    #[default]
+    KeepAll,
+    /// Keep about `resident` snapshots in memory, spill older ones to disk
+    Spill { resident: usize },
+    /// Keep about `resident` snapshots in memory, discard older ones
+    Drop { resident: usize },
+}
+
+#[derive(Debug, Default)]
+pub struct Timeline {
+    retention: TimelineRetention,
+    /// Time of the oldest snapshot still available
+    start: usize,
+    /// Time of `resident[0]`
+    offset: usize,
+    resident: Vec<Snapshot>,
+    spill: Option<SpillFile>,
+}
+
+/// One JSON snapshot per line; each chunk is a byte range of the file.
+#[derive(Debug)]
+struct SpillFile {
+    path: PathBuf,
+    file: File,
+    /// (byte offset, snapshot count) of each chunk, oldest first
+    chunks: Vec<(u64, usize)>,
+}
+
+static SPILL_FILES: AtomicUsize = AtomicUsize::new(0);
+
+impl SpillFile {
+    fn create() -> Result<Self, String> {
+        if !cfg!(feature = "spill") {
+            return Err("Timeline spilling needs the spill feature (io in quantum-error-analyzer)".to_string());
+        }
+        let path = std::env::temp_dir().join(format!(
+            "quantum-error-analyzer-timeline-{}-{}.jsonl",
+            std::process::id(),
+            SPILL_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to create timeline spill file {}: {}", path.display(), e))?;
+        Ok(Self {
+            path,
+            file,
+            chunks: Vec::new(),
+        })
+    }
+
+    fn write_chunk(&mut self, snapshots: &[Snapshot]) -> Result<(), String> {
+        let start = self.file.seek(SeekFrom::End(0)).map_err(|e| e.to_string())?;
+        let mut text = String::new();
+        for snapshot in snapshots {
+            text.push_str(&encode(snapshot)?);
+            text.push('\n');
+        }
+        self.file.write_all(text.as_bytes()).map_err(|e| e.to_string())?;
+        self.chunks.push((start, snapshots.len()));
+        Ok(())
+    }
+
+    fn read_chunk(&self, chunk: usize) -> Result<Vec<Snapshot>, String> {
+        let (start, count) = self.chunks[chunk];
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(start)).map_err(|e| e.to_string())?;
+        BufReader::new(file)
+            .lines()
+            .take(count)
+            .map(|line| {
+                let line = line.map_err(|e| e.to_string())?;
+                decode(&line)
+            })
+            .collect()
+    }
+
+    /// Reads the newest chunk back and truncates it from the file.
+    fn pop_chunk(&mut self) -> Result<Vec<Snapshot>, String> {
+        let snapshots = self.read_chunk(self.chunks.len() - 1)?;
+        let (start, _) = self.chunks.pop().expect("read_chunk checked the index");
+        self.file.set_len(start).map_err(|e| e.to_string())?;
+        Ok(snapshots)
+    }
+}
+
+#[cfg(feature = "spill")]
+fn encode(snapshot: &Snapshot) -> Result<String, String> {
+    serde_json::to_string(snapshot).map_err(|e| e.to_string())
+}
+
+#[cfg(feature = "spill")]
+fn decode(line: &str) -> Result<Snapshot, String> {
+    serde_json::from_str(line).map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "spill"))]
+fn encode(_snapshot: &Snapshot) -> Result<String, String> {
+    Err("Timeline spilling needs the spill feature (io in quantum-error-analyzer)".to_string())
+}
+
+#[cfg(not(feature = "spill"))]
+fn decode(_line: &str) -> Result<Snapshot, String> {
+    Err("Timeline spilling needs the spill feature (io in quantum-error-analyzer)".to_string())
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+impl Timeline {
+    pub fn new(retention: TimelineRetention) -> Result<Self, String> {
+        let resident = match retention {
+            TimelineRetention::KeepAll => None,
+            TimelineRetention::Spill { resident } | TimelineRetention::Drop { resident } => Some(resident),
+        };
+        if resident == Some(0) {
+            return Err("Timeline retention must keep at least one snapshot resident".to_string());
+        }
+        Ok(Self {
+            retention,
+            ..Self::default()
+        })
+    }
+
+    pub fn retention(&self) -> TimelineRetention {
+        self.retention
+    }
+
+    /// A timeline whose history begins at `snapshot`; earlier times are
+    /// unavailable.
+    pub fn starting_at(retention: TimelineRetention, snapshot: Snapshot) -> Result<Self, String> {
+        let mut timeline = Self::new(retention)?;
+        timeline.start = snapshot.time;
+        timeline.offset = snapshot.time;
+        timeline.resident.push(snapshot);
+        Ok(timeline)
+    }
+
+    /// Number of snapshots, including spilled and dropped ones.
+    pub fn len(&self) -> usize {
+        self.offset + self.resident.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Snapshots held in memory (the most recent ones).
+    pub fn resident(&self) -> &[Snapshot] {
+        &self.resident
+    }
+
+    /// Snapshots at times before this were dropped (or precede the start).
+    pub fn first_available(&self) -> usize {
+        self.start
+    }
+
+    pub fn push(&mut self, snapshot: Snapshot) {
+        self.resident.push(snapshot);
+        let keep = match self.retention {
+            TimelineRetention::KeepAll => return,
+            TimelineRetention::Spill { resident } | TimelineRetention::Drop { resident } => resident,
+        };
+        if self.resident.len() < 2 * keep {
+            return;
+        }
+        let evicted = self.resident.len() - keep;
+        if let TimelineRetention::Spill { .. } = self.retention {
+            if self.spill.is_none() {
+                self.spill = SpillFile::create().ok();
+            }
+            // Without a writable spill file everything stays in memory
+            let written = match &mut self.spill {
+                Some(spill) => spill.write_chunk(&self.resident[..evicted]).is_ok(),
+                None => false,
+            };
+            if !written {
+                return;
+            }
+        }
+        self.resident.drain(..evicted);
+        self.offset += evicted;
+        if let TimelineRetention::Drop { .. } = self.retention {
+            self.start = self.offset;
+        }
+    }
+
+    /// Removes the newest snapshot, reloading a spilled chunk when the
+    /// resident part runs out. Returns `None` at a dropped snapshot.
+    pub fn pop(&mut self) -> Option<Snapshot> {
+        let snapshot = self.resident.pop()?;
+        if self.resident.is_empty() && self.offset > self.start {
+            if let Some(chunk) = self.spill.as_mut().and_then(|spill| spill.pop_chunk().ok()) {
+                self.offset -= chunk.len();
+                self.resident = chunk;
+            }
+        }
+        Some(snapshot)
+    }
+
+    pub fn last(&self) -> Option<&Snapshot> {
+        self.resident.last()
+    }
+
+    pub fn last_mut(&mut self) -> Option<&mut Snapshot> {
+        self.resident.last_mut()
+    }
+
+    pub fn clear(&mut self) {
+        self.resident.clear();
+        self.start = 0;
+        self.offset = 0;
+        self.spill = None;
+    }
+
+    /// The snapshot at `time`, read from disk if it was spilled.
+    pub fn get(&self, time: usize) -> Option<Cow<'_, Snapshot>> {
+        if time >= self.offset {
+            return self.resident.get(time - self.offset).map(Cow::Borrowed);
+        }
+        let spill = self.spill.as_ref().filter(|_| time >= self.start)?;
+        let mut start = self.start;
+        for (chunk, &(_, count)) in spill.chunks.iter().enumerate() {
+            if time < start + count {
+                return spill.read_chunk(chunk).ok()?.into_iter().nth(time - start).map(Cow::Owned);
+            }
+            start += count;
+        }
+        None
+    }
+
+    /// Every available snapshot, oldest first; borrowed unless some were
+    /// spilled.
+    pub fn all(&self) -> Cow<'_, [Snapshot]> {
+        match &self.spill {
+            Some(spill) if self.offset > self.start => {
+                let mut snapshots = Vec::with_capacity(self.len());
+                for chunk in 0..spill.chunks.len() {
+                    snapshots.extend(spill.read_chunk(chunk).unwrap_or_default());
+                }
+                snapshots.extend(self.resident.iter().cloned());
+                Cow::Owned(snapshots)
+            }
+            _ => Cow::Borrowed(&self.resident),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::pauli::PauliString;
+
+    fn snapshot(time: usize) -> Snapshot {
+        Snapshot {
+            time,
+            error_pattern: PauliString::new(3),
+            gate_applied: time.checked_sub(1),
+            classical_bits: Vec::new(),
+            measurement_flips: Vec::new(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "spill")]
+    fn test_spill_and_reload() {
+        let mut timeline = Timeline::new(TimelineRetention::Spill { resident: 4 }).unwrap();
+        for time in 0..20 {
+            timeline.push(snapshot(time));
+        }
+        assert_eq!(timeline.len(), 20);
+        assert!(timeline.resident().len() < 8);
+        assert_eq!(timeline.get(2).unwrap().time, 2);
+        assert_eq!(timeline.get(19).unwrap().time, 19);
+        assert!(timeline.get(20).is_none());
+        let all = timeline.all();
+        assert!(all.iter().enumerate().all(|(t, s)| s.time == t) && all.len() == 20);
+
+        // Popping past the resident part brings spilled chunks back
+        for time in (1..20).rev() {
+            assert_eq!(timeline.pop().unwrap().time, time);
+        }
+        assert_eq!(timeline.last().unwrap().time, 0);
+        assert_eq!(timeline.len(), 1);
+    }
+
+    #[test]
+    fn test_drop_retention() {
+        let mut timeline = Timeline::new(TimelineRetention::Drop { resident: 3 }).unwrap();
+        for time in 0..10 {
+            timeline.push(snapshot(time));
+        }
+        assert_eq!(timeline.len(), 10);
+        assert!(timeline.first_available() > 0);
+        assert!(timeline.get(0).is_none());
+        assert_eq!(timeline.get(9).unwrap().time, 9);
+        assert_eq!(timeline.all().len(), 10 - timeline.first_available());
+        assert!(Timeline::new(TimelineRetention::Drop { resident: 0 }).is_err());
+    }
+}
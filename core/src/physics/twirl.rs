@@ -0,0 +1,82 @@
+//! Pauli twirling sets.
+//!
+//! Twirling a channel on k qubits averages it over P · E · P for the 4^k
+//! Paulis P, which leaves a Pauli channel. Twirling a Clifford gate G
+//! draws P before the gate and undoes it after with P' = G P G', so the
+//! ideal gate is untouched while its noise is twirled. Both sets are
+//! returned on the whole register, acting only on the given qubits.
+
+use crate::physics::circuit::Gate;
+use crate::physics::pauli::PauliString;
+use crate::physics::pauli_group::PauliGroup;
+use crate::physics::propagation::apply_gate;
+use serde::{Deserialize, Serialize};
+
+/// One twirl of a gate: `before`, the gate, then `after` equals the gate.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TwirlPair {
+    pub before: PauliString,
+    /// `before` conjugated by the gate, sign included
+    pub after: PauliString,
+}
+
+/// The 4^k Paulis on `qubits`, identity first.
+pub fn channel_twirl_set(qubits: &[usize], num_qubits: usize) -> Result<Vec<PauliString>, String> {
+    if let Some(&qubit) = qubits.iter().find(|&&q| q >= num_qubits) {
+        return Err(format!("Qubit {} out of range for {} qubits", qubit, num_qubits));
+    }
+    if (1..qubits.len()).any(|i| qubits[..i].contains(&qubits[i])) {
+        return Err("Twirled qubits must be distinct".to_string());
+    }
+    Ok(PauliGroup::new(qubits.len())
+        .map(|local| {
+            let mut pauli = PauliString::new(num_qubits);
+            for (i, &qubit) in qubits.iter().enumerate() {
+                pauli.set_pauli(qubit, local.get_pauli(i));
+            }
+            pauli
+        })
+        .collect())
+}
+
+/// The 4^k twirl pairs of a gate on k qubits.
+pub fn gate_twirl_set(gate: &Gate, num_qubits: usize) -> Result<Vec<TwirlPair>, String> {
+    Ok(channel_twirl_set(&gate.qubits(), num_qubits)?
+        .into_iter()
+        .map(|before| {
+            let mut after = before.clone();
+            apply_gate(&mut after, gate);
+            TwirlPair { before, after }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{Circuit, TwoGate};
+    use crate::physics::pauli::SinglePauli;
+
+    #[test]
+    fn test_gate_twirl_set() {
+        let cnot = Gate::Two(TwoGate::CNOT { control: 2, target: 0 });
+        let pairs = gate_twirl_set(&cnot, 3).unwrap();
+        assert_eq!(pairs.len(), 16);
+        assert_eq!(pairs[0].before, PauliString::new(3));
+
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(cnot.clone()).unwrap();
+        for pair in &pairs {
+            assert_eq!(pair.before.conjugated_by(&circuit), pair.after);
+            assert_eq!(pair.before.get_pauli(1), SinglePauli::I);
+        }
+        // X on the control needs X on both qubits to undo
+        let x_control = PauliString::from_str("IIX", 3).unwrap();
+        let pair = pairs.iter().find(|pair| pair.before == x_control).unwrap();
+        assert_eq!(pair.after, PauliString::from_str("XIX", 3).unwrap());
+
+        assert_eq!(channel_twirl_set(&[0], 1).unwrap().len(), 4);
+        assert!(channel_twirl_set(&[0, 0], 2).is_err());
+        assert!(gate_twirl_set(&cnot, 2).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! k-fault-tolerance certification of a gadget.
+//!
+//! A gadget is certified k-fault-tolerant when every combination of s <= k
+//! faults leaves a residual data error of weight at most s modulo the
+//! stabilizers (the fault-free run must leave none at all). The check is
+//! exhaustive, so it is meant for the small gadgets FT papers argue about
+//! by hand.
+
+use crate::analysis::fault_sweep::{fault_locations, propagate_faults, Fault};
+use crate::analysis::syndrome_db::collect_combinations;
+use crate::codes::{Gadget, StabilizerCode};
+use crate::dem::model::check_data_qubits;
+use crate::physics::pauli::PauliString;
+use serde::{Deserialize, Serialize};
+
+/// Proof that every combination of up to `max_faults` faults was checked.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FtCertificate {
+    pub max_faults: usize,
+    /// Combinations checked, indexed by fault count
+    pub combinations_checked: Vec<usize>,
+    /// Largest reduced residual weight seen, indexed by fault count
+    pub worst_weight: Vec<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FtViolation {
+    pub faults: Vec<Fault>,
+    /// Error left on the code block, indexed by code qubit
+    pub residual: PauliString,
+    /// Weight of `residual` minimised over the stabilizer group
+    pub reduced_weight: usize,
+}
+
+/// The certificate, or the first violation found. Fails when the gadget's
+/// data qubits do not match the code length.
+pub fn certify_ft(
+    gadget: &Gadget,
+    code: &StabilizerCode,
+    k: usize,
+) -> Result<Result<FtCertificate, FtViolation>, String> {
+    check_data_qubits(gadget, code)?;
+
+    let locations = fault_locations(&gadget.circuit);
+    let mut certificate = FtCertificate {
+        max_faults: k,
+        combinations_checked: vec![0; k + 1],
+        worst_weight: vec![0; k + 1],
+    };
+    let mut violation = None;
+    let mut chosen = Vec::new();
+
+    for count in 0..=k {
+        collect_combinations(&locations, 0, count, &mut chosen, &mut |faults| {
+            if violation.is_some() {
+                return;
+            }
+            let (residual, weight) = reduced_residual(gadget, code, faults);
+            if weight > faults.len() {
+                violation = Some(FtViolation {
+                    faults: faults.to_vec(),
+                    residual,
+                    reduced_weight: weight,
+                });
+                return;
+            }
+            certificate.combinations_checked[count] += 1;
+            certificate.worst_weight[count] = certificate.worst_weight[count].max(weight);
+        });
+        if let Some(found) = violation {
+            return Ok(Err(found));
+        }
+    }
+    Ok(Ok(certificate))
+}
+
+/// The violation caused by `faults`, if their residual is too heavy.
+pub fn check_faults(gadget: &Gadget, code: &StabilizerCode, faults: &[Fault]) -> Option<FtViolation> {
+    let (residual, reduced_weight) = reduced_residual(gadget, code, faults);
+    (reduced_weight > faults.len()).then(|| FtViolation {
+        faults: faults.to_vec(),
+        residual,
+        reduced_weight,
+    })
+}
+
+fn reduced_residual(gadget: &Gadget, code: &StabilizerCode, faults: &[Fault]) -> (PauliString, usize) {
+    let residual = gadget.data_error(&propagate_faults(&gadget.circuit, faults));
+    let weight = code.reduced_weight(&residual);
+    (residual, weight)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::MeasurementBasis;
+    use crate::physics::circuit::{Circuit, Gate, TwoGate};
+
+    fn gadget(pairs: &[(usize, usize)]) -> Gadget {
+        let mut circuit = Circuit::new(5);
+        for &(control, target) in pairs {
+            circuit.add_gate(Gate::Two(TwoGate::CNOT { control, target })).unwrap();
+        }
+        for qubit in [3, 4] {
+            circuit.add_measurement(qubit, MeasurementBasis::Z).unwrap();
+        }
+        Gadget::new(circuit, vec![0, 1, 2]).unwrap()
+    }
+
+    #[test]
+    fn test_repetition_round_is_one_fault_tolerant() {
+        let round = gadget(&[(0, 3), (1, 3), (1, 4), (2, 4)]);
+        let certificate = certify_ft(&round, &StabilizerCode::repetition(3), 1).unwrap().unwrap();
+        assert_eq!(certificate.combinations_checked, vec![1, 24]);
+        assert_eq!(certificate.worst_weight, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_spreading_ancilla_is_rejected() {
+        // Ancilla 3 as CNOT control copies an X fault onto data 1 and 2
+        let bad = gadget(&[(3, 0), (3, 1), (3, 2)]);
+        let violation = certify_ft(&bad, &StabilizerCode::repetition(3), 1).unwrap().unwrap_err();
+        assert_eq!(violation.faults.len(), 1);
+        assert_eq!(violation.faults[0].qubit, 3);
+        assert_eq!(violation.reduced_weight, 2);
+        assert!(certify_ft(&bad, &StabilizerCode::repetition(5), 1).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+//! How much of an imported circuit is Clifford.
+//!
+//! Propagation analyses are exact only on Clifford circuits. The QASM
+//! importer replaces each non-Clifford rotation by an identity placeholder;
+//! this report says how many there are, where they sit and which angles
+//! they carry, so the user knows what must be rewritten or approximated.
+
+use crate::io::qasm::{NonCliffordContent, SymbolicRotation};
+use crate::physics::circuit::Circuit;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::FRAC_PI_4;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CliffordReport {
+    pub total_gates: usize,
+    pub clifford_gates: usize,
+    /// `clifford_gates / total_gates`, 1.0 for an empty circuit
+    pub clifford_fraction: f64,
+    /// Rotations by odd multiples of π/4 (T-like gates)
+    pub t_count: usize,
+    /// Non-Clifford rotations per qubit
+    pub per_qubit: Vec<usize>,
+    /// Gates before the first non-Clifford rotation; analyses restricted to
+    /// this prefix are exact
+    pub clifford_prefix: usize,
+    pub rotations: Vec<SymbolicRotation>,
+}
+
+impl CliffordReport {
+    pub fn is_clifford(&self) -> bool {
+        self.rotations.is_empty()
+    }
+}
+
+pub fn clifford_report(circuit: &Circuit, content: &NonCliffordContent) -> Result<CliffordReport, String> {
+    let mut per_qubit = vec![0; circuit.num_qubits];
+    for rotation in &content.rotations {
+        if rotation.gate >= circuit.gates.len() || rotation.qubit >= circuit.num_qubits {
+            return Err(format!(
+                "Rotation {}({}) at gate {} on qubit {} is outside the circuit",
+                rotation.axis, rotation.angle, rotation.gate, rotation.qubit
+            ));
+        }
+        per_qubit[rotation.qubit] += 1;
+    }
+
+    let total_gates = circuit.gates.len();
+    let clifford_gates = total_gates - content.rotations.len();
+    let t_count = content
+        .rotations
+        .iter()
+        .filter(|r| {
+            let eighths = r.radians / FRAC_PI_4;
+            (eighths - eighths.round()).abs() < 1e-9 && (eighths.round() as i64).rem_euclid(2) == 1
+        })
+        .count();
+
+    Ok(CliffordReport {
+        total_gates,
+        clifford_gates,
+        clifford_fraction: if total_gates == 0 {
+            1.0
+        } else {
+            clifford_gates as f64 / total_gates as f64
+        },
+        t_count,
+        per_qubit,
+        clifford_prefix: content.rotations.iter().map(|r| r.gate).min().unwrap_or(total_gates),
+        rotations: content.rotations.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::options::ImportOptions;
+    use crate::io::qasm::import_qasm_with_report;
+
+    #[test]
+    fn test_report_counts_rotations() {
+        let qasm = "OPENQASM 2.0;\nqreg q[2];\nh q[0];\ncx q[0],q[1];\nrz(pi/4) q[1];\nrx(0.1) q[0];\nrz(pi/2) q[0];\n";
+        let (circuit, content) = import_qasm_with_report(qasm, &ImportOptions::default()).unwrap();
+        let report = clifford_report(&circuit, &content).unwrap();
+        assert_eq!(report.total_gates, 5);
+        assert_eq!(report.clifford_gates, 3);
+        assert!((report.clifford_fraction - 0.6).abs() < 1e-12);
+        assert_eq!(report.t_count, 1);
+        assert_eq!(report.per_qubit, vec![1, 1]);
+        assert_eq!(report.clifford_prefix, 2);
+        assert!(!report.is_clifford());
+
+        let empty = clifford_report(&Circuit::new(1), &NonCliffordContent::default()).unwrap();
+        assert!(empty.is_clifford());
+        assert_eq!(empty.clifford_fraction, 1.0);
+    }
+}
@@ -0,0 +1,94 @@
+//! Commutation of a Pauli operator with a circuit.
+//!
+//! An operator commutes through a circuit when every gate maps it to
+//! itself, sign included, so it is conserved at every moment. That is
+//! stronger than commuting with the circuit's overall unitary, where later
+//! gates may undo what earlier ones did (H followed by H commutes with X
+//! as a whole, but X does not commute through the first H).
+
+use crate::physics::circuit::Circuit;
+use crate::physics::pauli::PauliString;
+use crate::physics::propagation::apply_gate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Obstruction {
+    /// Index of the first gate that does not fix the operator
+    pub gate: usize,
+    /// What that gate maps the operator to
+    pub image: PauliString,
+}
+
+/// The first gate that does not map `pauli` to itself, if any.
+pub fn first_obstruction(circuit: &Circuit, pauli: &PauliString) -> Result<Option<Obstruction>, String> {
+    check_size(circuit, pauli)?;
+    Ok(obstruction_iter(circuit, pauli).next())
+}
+
+/// Every gate that does not map `pauli` to itself, in circuit order.
+pub fn obstructions(circuit: &Circuit, pauli: &PauliString) -> Result<Vec<Obstruction>, String> {
+    check_size(circuit, pauli)?;
+    Ok(obstruction_iter(circuit, pauli).collect())
+}
+
+fn obstruction_iter<'a>(circuit: &'a Circuit, pauli: &'a PauliString) -> impl Iterator<Item = Obstruction> + 'a {
+    circuit.gates.iter().enumerate().filter_map(move |(gate_index, gate)| {
+        let mut image = pauli.clone();
+        apply_gate(&mut image, gate);
+        (image != *pauli).then_some(Obstruction { gate: gate_index, image })
+    })
+}
+
+/// Whether every gate of `circuit` fixes `pauli`; false when their qubit
+/// counts differ.
+pub fn commutes_through(circuit: &Circuit, pauli: &PauliString) -> bool {
+    matches!(first_obstruction(circuit, pauli), Ok(None))
+}
+
+/// Whether the circuit as a whole maps `pauli` to itself.
+pub fn commutes_with_circuit(circuit: &Circuit, pauli: &PauliString) -> Result<bool, String> {
+    check_size(circuit, pauli)?;
+    Ok(pauli.conjugated_by(circuit) == *pauli)
+}
+
+fn check_size(circuit: &Circuit, pauli: &PauliString) -> Result<(), String> {
+    if pauli.num_qubits() != circuit.num_qubits {
+        return Err(format!(
+            "Operator has {} qubits but the circuit has {}",
+            pauli.num_qubits(),
+            circuit.num_qubits
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{Gate, SingleGate, TwoGate};
+
+    #[test]
+    fn test_conserved_operator_and_obstruction() {
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CZ { control: 1, target: 2 })).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+
+        // Z on a CZ qubit that no other gate touches is conserved
+        let z2 = PauliString::from_str("IIZ", 3).unwrap();
+        assert!(commutes_through(&circuit, &z2));
+        assert!(!commutes_through(&circuit, &PauliString::from_str("IZI", 3).unwrap()));
+
+        let x = PauliString::from_str("XII", 3).unwrap();
+        let obstruction = first_obstruction(&circuit, &x).unwrap().unwrap();
+        assert_eq!(obstruction.gate, 0);
+        assert_eq!(obstruction.image, PauliString::from_str("XXI", 3).unwrap());
+        assert!(!commutes_through(&circuit, &x));
+
+        let z = PauliString::from_str("ZII", 3).unwrap();
+        assert_eq!(first_obstruction(&circuit, &z).unwrap().map(|o| o.gate), Some(2));
+        assert!(commutes_with_circuit(&circuit, &z).unwrap());
+        assert!(first_obstruction(&circuit, &PauliString::new(2)).is_err());
+    }
+}
@@ -0,0 +1,134 @@
+//! Resource budgets for circuits (depth, two-qubit gate count, gate set).
+//!
+//! Constraints are plain data so they can be checked into a repository as
+//! JSON next to the circuits they guard.
+
+use crate::physics::circuit::{Circuit, Gate, GateKind};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Limits a circuit must respect. `None` means unconstrained.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitConstraints {
+    pub max_depth: Option<usize>,
+    pub max_two_qubit_gates: Option<usize>,
+    pub allowed_gates: Option<Vec<GateKind>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConstraintViolation {
+    DepthExceeded { depth: usize, max: usize },
+    TwoQubitGatesExceeded { count: usize, max: usize },
+    DisallowedGate { index: usize, gate: Gate },
+}
+
+impl fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintViolation::DepthExceeded { depth, max } => {
+                write!(f, "Circuit depth {} exceeds budget of {}", depth, max)
+            }
+            ConstraintViolation::TwoQubitGatesExceeded { count, max } => {
+                write!(f, "Two-qubit gate count {} exceeds budget of {}", count, max)
+            }
+            ConstraintViolation::DisallowedGate { index, gate } => {
+                write!(f, "Gate {} ({}) is not in the allowed gate set", index, gate)
+            }
+        }
+    }
+}
+
+impl CircuitConstraints {
+    /// Returns every violation, in order: depth, two-qubit count, then
+    /// disallowed gates by position. An empty list means the circuit passes.
+    pub fn check(&self, circuit: &Circuit) -> Vec<ConstraintViolation> {
+        let mut violations = Vec::new();
+
+        if let Some(max) = self.max_depth {
+            let depth = circuit.depth();
+            if depth > max {
+                violations.push(ConstraintViolation::DepthExceeded { depth, max });
+            }
+        }
+
+        if let Some(max) = self.max_two_qubit_gates {
+            let count = circuit.two_qubit_gate_count();
+            if count > max {
+                violations.push(ConstraintViolation::TwoQubitGatesExceeded { count, max });
+            }
+        }
+
+        if let Some(allowed) = &self.allowed_gates {
+            for (index, gate) in circuit.gates.iter().enumerate() {
+                if !allowed.contains(&gate.kind()) {
+                    violations.push(ConstraintViolation::DisallowedGate {
+                        index,
+                        gate: gate.clone(),
+                    });
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{SingleGate, TwoGate};
+
+    fn bell_circuit() -> Circuit {
+        let mut circuit = Circuit::new(2);
+        circuit
+            .add_gate(Gate::Single {
+                qubit: 0,
+                gate: SingleGate::H,
+            })
+            .unwrap();
+        circuit
+            .add_gate(Gate::Two(TwoGate::CNOT {
+                control: 0,
+                target: 1,
+            }))
+            .unwrap();
+        circuit
+    }
+
+    #[test]
+    fn test_unconstrained_passes() {
+        assert!(CircuitConstraints::default().check(&bell_circuit()).is_empty());
+    }
+
+    #[test]
+    fn test_budget_violations() {
+        let constraints = CircuitConstraints {
+            max_depth: Some(1),
+            max_two_qubit_gates: Some(0),
+            allowed_gates: Some(vec![GateKind::Single(SingleGate::H), GateKind::CZ]),
+        };
+        let violations = constraints.check(&bell_circuit());
+        assert_eq!(
+            violations,
+            vec![
+                ConstraintViolation::DepthExceeded { depth: 2, max: 1 },
+                ConstraintViolation::TwoQubitGatesExceeded { count: 1, max: 0 },
+                ConstraintViolation::DisallowedGate {
+                    index: 1,
+                    gate: Gate::Two(TwoGate::CNOT {
+                        control: 0,
+                        target: 1
+                    }),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_constraints_from_json() {
+        let json = r#"{"max_depth": 10, "max_two_qubit_gates": null, "allowed_gates": ["CNOT", {"Single": "H"}]}"#;
+        let constraints: CircuitConstraints = serde_json::from_str(json).unwrap();
+        assert_eq!(constraints.max_depth, Some(10));
+        assert!(constraints.check(&bell_circuit()).is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+//! Error-detection coverage of a gadget.
+//!
+//! Every combination of up to `max_faults` faults (at distinct locations)
+//! is propagated through the gadget. A combination is detected when it
+//! flips a gadget measurement or leaves a residual with a non-trivial code
+//! syndrome, i.e. when an ideal next round would notice it. Undetected
+//! combinations are listed, split into harmless ones (residual in the
+//! stabilizer group) and logical errors.
+
+use crate::analysis::fault_sweep::{fault_locations, propagate_faults, Fault};
+use crate::analysis::syndrome_db::collect_combinations;
+use crate::codes::{Gadget, StabilizerCode};
+use crate::physics::pauli::PauliString;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UndetectedFault {
+    pub faults: Vec<Fault>,
+    /// Error left on the code block, indexed by code qubit
+    pub residual: PauliString,
+    pub logical_error: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageLevel {
+    pub fault_count: usize,
+    pub combinations: usize,
+    pub detected: usize,
+    /// In `fault_locations` combination order
+    pub undetected: Vec<UndetectedFault>,
+}
+
+impl CoverageLevel {
+    /// Fraction of combinations that are detected.
+    pub fn coverage(&self) -> f64 {
+        if self.combinations == 0 {
+            return 1.0;
+        }
+        self.detected as f64 / self.combinations as f64
+    }
+
+    pub fn logical_errors(&self) -> impl Iterator<Item = &UndetectedFault> {
+        self.undetected.iter().filter(|u| u.logical_error)
+    }
+
+    /// Fraction of combinations that matter (detected or logical errors)
+    /// that are detected.
+    pub fn harmful_coverage(&self) -> f64 {
+        let harmful = self.detected + self.logical_errors().count();
+        if harmful == 0 {
+            return 1.0;
+        }
+        self.detected as f64 / harmful as f64
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectionCoverage {
+    /// Indexed by fault count, starting at 1
+    pub levels: Vec<CoverageLevel>,
+}
+
+pub fn detection_coverage(
+    gadget: &Gadget,
+    code: &StabilizerCode,
+    max_faults: usize,
+) -> Result<DetectionCoverage, String> {
+    if gadget.data_qubits.len() != code.num_qubits {
+        return Err(format!(
+            "Gadget has {} data qubits but code '{}' has {}",
+            gadget.data_qubits.len(),
+            code.name,
+            code.num_qubits
+        ));
+    }
+
+    let locations = fault_locations(&gadget.circuit);
+    let mut levels = Vec::new();
+    let mut chosen = Vec::new();
+    for count in 1..=max_faults {
+        let mut level = CoverageLevel {
+            fault_count: count,
+            combinations: 0,
+            detected: 0,
+            undetected: Vec::new(),
+        };
+        collect_combinations(&locations, 0, count, &mut chosen, &mut |faults| {
+            let error = propagate_faults(&gadget.circuit, faults);
+            let residual = gadget.data_error(&error);
+            level.combinations += 1;
+            let flagged = gadget.measurement_flips(&error).contains(&true);
+            if flagged || code.syndrome(&residual).contains(&true) {
+                level.detected += 1;
+                return;
+            }
+            level.undetected.push(UndetectedFault {
+                faults: faults.to_vec(),
+                logical_error: !code.in_stabilizer_group(&residual),
+                residual,
+            });
+        });
+        levels.push(level);
+    }
+    Ok(DetectionCoverage { levels })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::MeasurementBasis;
+    use crate::physics::circuit::{Circuit, Gate, TwoGate};
+    use crate::physics::pauli::SinglePauli;
+
+    #[test]
+    fn test_repetition_round_coverage() {
+        let mut circuit = Circuit::new(5);
+        for (control, target) in [(0, 3), (1, 3), (1, 4), (2, 4)] {
+            circuit.add_gate(Gate::Two(TwoGate::CNOT { control, target })).unwrap();
+        }
+        for qubit in [3, 4] {
+            circuit.add_measurement(qubit, MeasurementBasis::Z).unwrap();
+        }
+        let gadget = Gadget::new(circuit, vec![0, 1, 2]).unwrap();
+        let coverage = detection_coverage(&gadget, &StabilizerCode::repetition(3), 2).unwrap();
+
+        let singles = &coverage.levels[0];
+        assert_eq!(singles.combinations, 24);
+        assert_eq!(singles.detected + singles.undetected.len(), 24);
+        // The bit-flip code cannot see phase flips on the data
+        let phase_flip = Fault {
+            after_gate: 0,
+            qubit: 0,
+            pauli: SinglePauli::Z,
+        };
+        assert!(singles.logical_errors().any(|u| u.faults == [phase_flip.clone()]));
+        // A Z on an ancilla after its last CNOT is invisible and harmless
+        assert!(singles.undetected.iter().any(|u| !u.logical_error));
+        assert!(singles.coverage() < singles.harmful_coverage());
+        assert_eq!(coverage.levels[1].combinations, 28 * 9);
+        assert!(detection_coverage(&gadget, &StabilizerCode::repetition(5), 1).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! Determinism of declared detectors.
+//!
+//! A detector is the parity of entries of the shot record (see
+//! `codes::experiments`): gadget readouts, then the final perfect syndrome,
+//! then the logical Z and X readouts. Every entry measures a Pauli operator
+//! at the end of the circuit, and the parity measures their product P. With
+//! all qubits starting in |0>, the parity is fixed exactly when U' P U is a
+//! product of Z operators, which is checked on the exact tableau.
+
+use crate::codes::{Gadget, MeasurementBasis, StabilizerCode};
+use crate::physics::pauli::{PauliString, SinglePauli};
+use crate::physics::tableau::Tableau;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NondeterministicDetector {
+    pub detector: usize,
+    /// Record entries the detector combines
+    pub record: Vec<usize>,
+    /// Measured product pulled back to the start of the circuit
+    pub initial_operator: PauliString,
+}
+
+/// Detectors whose parity is random under the noiseless circuit.
+pub fn check_detectors(
+    gadget: &Gadget,
+    code: &StabilizerCode,
+    detectors: &[Vec<usize>],
+) -> Result<Vec<NondeterministicDetector>, String> {
+    let n = gadget.circuit.num_qubits;
+    let entries = record_operators(gadget, code);
+    let pull_back = Tableau::from_circuit(&gadget.circuit.inverse());
+    let mut offending = Vec::new();
+    for (detector, record) in detectors.iter().enumerate() {
+        let mut product = PauliString::new(n);
+        for &index in record {
+            let entry = entries
+                .get(index)
+                .ok_or_else(|| format!("Detector {} references record entry {} of {}", detector, index, entries.len()))?;
+            product = product.multiply(entry);
+        }
+        let initial_operator = pull_back.image(&product);
+        if initial_operator.x_bits().any() {
+            offending.push(NondeterministicDetector {
+                detector,
+                record: record.clone(),
+                initial_operator,
+            });
+        }
+    }
+    Ok(offending)
+}
+
+/// The Pauli operator measured by each shot record entry, at the end of
+/// the circuit.
+pub(crate) fn record_operators(gadget: &Gadget, code: &StabilizerCode) -> Vec<PauliString> {
+    let n = gadget.circuit.num_qubits;
+    let on_circuit = |operator: &PauliString| {
+        let mut placed = PauliString::new(n);
+        for (i, &qubit) in gadget.data_qubits.iter().enumerate() {
+            placed.set_pauli(qubit, operator.get_pauli(i));
+        }
+        placed
+    };
+    let mut entries: Vec<PauliString> = gadget
+        .measurements()
+        .iter()
+        .map(|m| {
+            let mut readout = PauliString::new(n);
+            let pauli = match m.basis {
+                MeasurementBasis::Z => SinglePauli::Z,
+                MeasurementBasis::X => SinglePauli::X,
+            };
+            readout.set_pauli(m.qubit, pauli);
+            readout
+        })
+        .collect();
+    entries.extend(code.stabilizers.iter().map(on_circuit));
+    entries.extend(code.logical_z.iter().chain(&code.logical_x).map(on_circuit));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::{memory_x, memory_z, stability};
+    use crate::noise::NoiseModel;
+
+    #[test]
+    fn test_presets_are_deterministic() {
+        for kind in [memory_z, memory_x, stability] {
+            let experiment = kind(3, 2, NoiseModel::default()).unwrap();
+            let offending = experiment.nondeterministic_annotations().unwrap();
+            assert!(offending.is_empty(), "{}: {:?}", experiment.name, offending);
+        }
+    }
+
+    #[test]
+    fn test_misdeclared_detector_is_reported() {
+        // The X readout of a fresh ancilla is random
+        let experiment = memory_z(3, 1, NoiseModel::default()).unwrap();
+        let mut gadget = experiment.gadget.clone();
+        gadget.circuit.measurements[0].basis = MeasurementBasis::X;
+        let offending = check_detectors(&gadget, &experiment.code, &experiment.detectors).unwrap();
+        assert_eq!(offending.iter().map(|d| d.detector).collect::<Vec<_>>(), vec![0, 2]);
+        assert_eq!(offending[0].record, vec![0]);
+        assert!(check_detectors(&gadget, &experiment.code, &[vec![99]]).is_err());
+    }
+}
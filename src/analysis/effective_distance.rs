@@ -0,0 +1,201 @@
+//! Circuit-level effective distance.
+//!
+//! The effective distance of a gadget is the fewest faults that flip a
+//! logical observable while every detector (gadget measurement flips and
+//! the ideal final syndrome) stays quiet. Fault effects add up over GF(2),
+//! so the search is meet-in-the-middle: for weight k, every set of
+//! ceil(k/2) faults is indexed by its detector signature, and every set of
+//! floor(k/2) faults looks up a partner with the same signature and a
+//! different observable signature. Two halves sharing a location combine
+//! into a lighter fault set, which earlier weights would already have
+//! found, so the merged result always has weight exactly k.
+
+use crate::analysis::fault_sweep::{fault_locations, propagate_faults, Fault};
+use crate::codes::{Gadget, StabilizerCode};
+use crate::physics::pauli::SinglePauli;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UndetectableLogical {
+    /// At distinct locations, sorted by gate index, then qubit
+    pub faults: Vec<Fault>,
+    /// Flipped observables, in `StabilizerCode::observable_flips` order
+    pub observables: Vec<bool>,
+}
+
+impl UndetectableLogical {
+    /// The effective distance found.
+    pub fn weight(&self) -> usize {
+        self.faults.len()
+    }
+}
+
+/// Detector and observable flips of one fault or a set of faults.
+#[derive(Clone)]
+struct Signature {
+    detectors: Vec<bool>,
+    observables: Vec<bool>,
+}
+
+impl Signature {
+    fn xor(&mut self, other: &Signature) {
+        self.detectors.iter_mut().zip(&other.detectors).for_each(|(a, b)| *a ^= b);
+        self.observables.iter_mut().zip(&other.observables).for_each(|(a, b)| *a ^= b);
+    }
+}
+
+/// A fault set found for one half of the search.
+struct HalfSet {
+    faults: Vec<Fault>,
+    signature: Signature,
+}
+
+/// A minimum-weight undetectable logical fault set of at most `max_weight`
+/// faults, or None if every set that small is detected or harmless. Cost
+/// grows as (3L)^ceil(k/2) for L fault locations.
+pub fn min_undetectable_logical(
+    gadget: &Gadget,
+    code: &StabilizerCode,
+    max_weight: usize,
+) -> Result<Option<UndetectableLogical>, String> {
+    if gadget.data_qubits.len() != code.num_qubits {
+        return Err(format!(
+            "Gadget has {} data qubits but code '{}' has {}",
+            gadget.data_qubits.len(),
+            code.name,
+            code.num_qubits
+        ));
+    }
+    let locations: Vec<Vec<(Fault, Signature)>> = fault_locations(&gadget.circuit)
+        .chunk_by(|a, b| (a.after_gate, a.qubit) == (b.after_gate, b.qubit))
+        .map(|group| group.iter().map(|fault| (fault.clone(), signature(gadget, code, fault))).collect())
+        .collect();
+    let empty = Signature {
+        detectors: vec![false; gadget.measurements().len() + code.stabilizers.len()],
+        observables: vec![false; 2 * code.num_logical_qubits()],
+    };
+
+    let mut halves: Vec<Vec<HalfSet>> = Vec::new();
+    for weight in 1..=max_weight {
+        let (small, large) = (weight / 2, weight - weight / 2);
+        while halves.len() <= large {
+            let size = halves.len();
+            let mut sets = Vec::new();
+            enumerate(&locations, 0, size, &mut Vec::new(), empty.clone(), &mut sets);
+            halves.push(sets);
+        }
+        let mut index: BTreeMap<&[bool], Vec<&HalfSet>> = BTreeMap::new();
+        for set in &halves[large] {
+            index.entry(&set.signature.detectors).or_default().push(set);
+        }
+        for set in &halves[small] {
+            let Some(partners) = index.get(set.signature.detectors.as_slice()) else {
+                continue;
+            };
+            if let Some(partner) = partners.iter().find(|p| p.signature.observables != set.signature.observables) {
+                let mut flips = set.signature.clone();
+                flips.xor(&partner.signature);
+                return Ok(Some(UndetectableLogical {
+                    faults: merge(&set.faults, &partner.faults),
+                    observables: flips.observables,
+                }));
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn signature(gadget: &Gadget, code: &StabilizerCode, fault: &Fault) -> Signature {
+    let error = propagate_faults(&gadget.circuit, std::slice::from_ref(fault));
+    let residual = gadget.data_error(&error);
+    let mut detectors = gadget.measurement_flips(&error);
+    detectors.extend(code.syndrome(&residual));
+    Signature {
+        detectors,
+        observables: code.observable_flips(&residual),
+    }
+}
+
+/// Every set of `remaining` faults at distinct locations from `start` on.
+fn enumerate(
+    locations: &[Vec<(Fault, Signature)>],
+    start: usize,
+    remaining: usize,
+    chosen: &mut Vec<Fault>,
+    signature: Signature,
+    sets: &mut Vec<HalfSet>,
+) {
+    if remaining == 0 {
+        sets.push(HalfSet {
+            faults: chosen.clone(),
+            signature,
+        });
+        return;
+    }
+    for location in start..locations.len() {
+        for (fault, effect) in &locations[location] {
+            let mut combined = signature.clone();
+            combined.xor(effect);
+            chosen.push(fault.clone());
+            enumerate(locations, location + 1, remaining - 1, chosen, combined, sets);
+            chosen.pop();
+        }
+    }
+}
+
+/// Union of two fault sets, multiplying faults at a shared location.
+fn merge(a: &[Fault], b: &[Fault]) -> Vec<Fault> {
+    let bits = |pauli: SinglePauli| match pauli {
+        SinglePauli::I => (false, false),
+        SinglePauli::X => (true, false),
+        SinglePauli::Y => (true, true),
+        SinglePauli::Z => (false, true),
+    };
+    let mut merged: BTreeMap<(usize, usize), (bool, bool)> = BTreeMap::new();
+    for fault in a.iter().chain(b) {
+        let (x, z) = bits(fault.pauli);
+        let entry = merged.entry((fault.after_gate, fault.qubit)).or_default();
+        *entry = (entry.0 ^ x, entry.1 ^ z);
+    }
+    merged
+        .into_iter()
+        .filter_map(|((after_gate, qubit), bits)| {
+            let pauli = match bits {
+                (false, false) => return None,
+                (true, false) => SinglePauli::X,
+                (true, true) => SinglePauli::Y,
+                (false, true) => SinglePauli::Z,
+            };
+            Some(Fault { after_gate, qubit, pauli })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{Circuit, Gate, SingleGate};
+
+    fn idle_block(n: usize) -> Gadget {
+        let mut circuit = Circuit::new(n);
+        for qubit in 0..n {
+            circuit.add_gate(Gate::Single { qubit, gate: SingleGate::I }).unwrap();
+        }
+        Gadget::new(circuit, (0..n).collect()).unwrap()
+    }
+
+    #[test]
+    fn test_effective_distance_of_idle_blocks() {
+        let steane = StabilizerCode::steane();
+        assert!(min_undetectable_logical(&idle_block(7), &steane, 2).unwrap().is_none());
+        let found = min_undetectable_logical(&idle_block(7), &steane, 4).unwrap().unwrap();
+        assert_eq!(found.weight(), 3);
+        assert!(found.observables.contains(&true));
+
+        // Phase flips slip past the bit-flip repetition code
+        let repetition = min_undetectable_logical(&idle_block(3), &StabilizerCode::repetition(3), 3).unwrap();
+        assert_eq!(repetition.unwrap().faults[0].pauli, SinglePauli::Z);
+        assert!(min_undetectable_logical(&idle_block(3), &steane, 1).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! Clifford equivalence checking via stabilizer tableaux.
+//!
+//! Circuits from different layout or routing passes may agree only after
+//! renaming wires. `find_relabeling` searches for such a renaming by
+//! backtracking over qubit assignments, pruned by per-qubit signatures
+//! (Pauli counts and signs of each generator image) and by checking the
+//! already assigned block of the tableau at every step.
+
+use crate::physics::circuit::Circuit;
+use crate::physics::pauli::{PauliString, Phase, SinglePauli};
+use crate::physics::tableau::Tableau;
+
+/// Whether two circuits implement the same Clifford unitary up to a global
+/// phase.
+pub fn circuits_equivalent(a: &Circuit, b: &Circuit) -> bool {
+    a.num_qubits == b.num_qubits && Tableau::from_circuit(a) == Tableau::from_circuit(b)
+}
+
+/// A permutation `p` such that `b` with every qubit `q` renamed to `p[q]`
+/// is equivalent to `a`, or None if there is none. The identity is tried
+/// first, so equivalent circuits get `0..n`.
+pub fn find_relabeling(a: &Circuit, b: &Circuit) -> Option<Vec<usize>> {
+    if a.num_qubits != b.num_qubits {
+        return None;
+    }
+    let search = RelabelingSearch {
+        a: GeneratorImages::new(a),
+        b: GeneratorImages::new(b),
+    };
+    let mut relabeling = Vec::with_capacity(a.num_qubits);
+    let mut used = vec![false; a.num_qubits];
+    search.extend(&mut relabeling, &mut used).then_some(relabeling)
+}
+
+pub fn circuits_equivalent_up_to_relabeling(a: &Circuit, b: &Circuit) -> bool {
+    find_relabeling(a, b).is_some()
+}
+
+/// Images of X_i and Z_i, with a relabeling-invariant signature per qubit.
+struct GeneratorImages {
+    images: Vec<[PauliString; 2]>,
+    signatures: Vec<[([usize; 3], Phase); 2]>,
+}
+
+impl GeneratorImages {
+    fn new(circuit: &Circuit) -> Self {
+        let tableau = Tableau::from_circuit(circuit);
+        let images: Vec<[PauliString; 2]> = (0..circuit.num_qubits)
+            .map(|qubit| [tableau.x_image(qubit), tableau.z_image(qubit)])
+            .collect();
+        let signatures = images
+            .iter()
+            .map(|pair| pair.clone().map(|image| (pauli_counts(&image), image.phase())))
+            .collect();
+        Self { images, signatures }
+    }
+}
+
+fn pauli_counts(pauli: &PauliString) -> [usize; 3] {
+    let mut counts = [0; 3];
+    for qubit in 0..pauli.num_qubits() {
+        match pauli.get_pauli(qubit) {
+            SinglePauli::X => counts[0] += 1,
+            SinglePauli::Y => counts[1] += 1,
+            SinglePauli::Z => counts[2] += 1,
+            SinglePauli::I => {}
+        }
+    }
+    counts
+}
+
+struct RelabelingSearch {
+    a: GeneratorImages,
+    b: GeneratorImages,
+}
+
+impl RelabelingSearch {
+    /// Assigns `b`'s next qubit and recurses; on success `relabeling` is
+    /// complete.
+    fn extend(&self, relabeling: &mut Vec<usize>, used: &mut [bool]) -> bool {
+        let qubit = relabeling.len();
+        if qubit == used.len() {
+            return true;
+        }
+        for target in 0..used.len() {
+            if used[target] || self.b.signatures[qubit] != self.a.signatures[target] {
+                continue;
+            }
+            relabeling.push(target);
+            if self.consistent(relabeling) {
+                used[target] = true;
+                if self.extend(relabeling, used) {
+                    return true;
+                }
+                used[target] = false;
+            }
+            relabeling.pop();
+        }
+        false
+    }
+
+    /// Whether the newest assignment agrees with the earlier ones on the
+    /// assigned block of the tableau.
+    fn consistent(&self, relabeling: &[usize]) -> bool {
+        let qubit = relabeling.len() - 1;
+        let target = relabeling[qubit];
+        relabeling.iter().enumerate().all(|(other, &other_target)| {
+            (0..2).all(|generator| {
+                self.b.images[qubit][generator].get_pauli(other)
+                    == self.a.images[target][generator].get_pauli(other_target)
+                    && self.b.images[other][generator].get_pauli(qubit)
+                        == self.a.images[other_target][generator].get_pauli(target)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{Gate, SingleGate, TwoGate};
+
+    #[test]
+    fn test_cz_equals_h_cnot_h() {
+        let mut cz = Circuit::new(2);
+        cz.add_gate(Gate::Two(TwoGate::CZ { control: 0, target: 1 })).unwrap();
+
+        let mut decomposed = Circuit::new(2);
+        decomposed.add_gate(Gate::Single { qubit: 1, gate: SingleGate::H }).unwrap();
+        decomposed.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        decomposed.add_gate(Gate::Single { qubit: 1, gate: SingleGate::H }).unwrap();
+
+        assert!(circuits_equivalent(&cz, &decomposed));
+    }
+
+    #[test]
+    fn test_sign_difference_detected() {
+        let mut x = Circuit::new(1);
+        x.add_gate(Gate::Single { qubit: 0, gate: SingleGate::X }).unwrap();
+        assert!(!circuits_equivalent(&x, &Circuit::new(1)));
+        assert!(!circuits_equivalent(&Circuit::new(1), &Circuit::new(2)));
+    }
+
+    #[test]
+    fn test_equivalence_up_to_relabeling() {
+        // A GHZ-style preparation routed onto a different wire order
+        let gates = [
+            Gate::Single { qubit: 0, gate: SingleGate::H },
+            Gate::Two(TwoGate::CNOT { control: 0, target: 1 }),
+            Gate::Two(TwoGate::CNOT { control: 1, target: 2 }),
+            Gate::Single { qubit: 2, gate: SingleGate::S },
+        ];
+        let layout = [2, 0, 1];
+        let (mut a, mut b) = (Circuit::new(3), Circuit::new(3));
+        for gate in &gates {
+            a.add_gate(gate.clone()).unwrap();
+            b.add_gate(gate.map_qubits(|q| layout[q])).unwrap();
+        }
+        assert!(!circuits_equivalent(&a, &b));
+        let relabeling = find_relabeling(&a, &b).unwrap();
+        let mut renamed = Circuit::new(3);
+        for gate in &b.gates {
+            renamed.add_gate(gate.map_qubits(|q| relabeling[q])).unwrap();
+        }
+        assert!(circuits_equivalent(&a, &renamed));
+        assert_eq!(find_relabeling(&a, &a), Some(vec![0, 1, 2]));
+
+        let mut different = b.clone();
+        different.add_gate(Gate::Single { qubit: 0, gate: SingleGate::X }).unwrap();
+        assert!(!circuits_equivalent_up_to_relabeling(&a, &different));
+    }
+}
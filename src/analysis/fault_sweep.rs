@@ -0,0 +1,153 @@
+//! Exhaustive single-fault sweep.
+//!
+//! A fault is a single-qubit Pauli (X, Y or Z) striking one operand of a
+//! gate right after it is applied. The sweep injects every such fault in
+//! turn and propagates it to the end of the circuit. Results follow
+//! `fault_locations` order: gate index, then operand, then X, Y, Z.
+
+use crate::perf::{self, Counter};
+use crate::physics::circuit::Circuit;
+use crate::physics::pauli::{PauliString, SinglePauli};
+use crate::physics::propagation::apply_gate;
+use crate::physics::small_pauli::PauliStringN;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Fault {
+    pub after_gate: usize,
+    pub qubit: usize,
+    pub pauli: SinglePauli,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FaultOutcome {
+    pub fault: Fault,
+    /// Error pattern at the end of the circuit
+    pub output: PauliString,
+}
+
+/// All single-fault locations, ordered by gate index, then operand order,
+/// then X, Y, Z.
+pub fn fault_locations(circuit: &Circuit) -> Vec<Fault> {
+    let mut faults = Vec::new();
+    for (after_gate, gate) in circuit.gates.iter().enumerate() {
+        for qubit in gate.qubits() {
+            for pauli in [SinglePauli::X, SinglePauli::Y, SinglePauli::Z] {
+                faults.push(Fault {
+                    after_gate,
+                    qubit,
+                    pauli,
+                });
+            }
+        }
+    }
+    faults
+}
+
+/// Propagates a single fault from its location to the end of the circuit.
+pub fn propagate_fault(circuit: &Circuit, fault: &Fault) -> PauliString {
+    propagate_faults_from(circuit, std::slice::from_ref(fault), fault.after_gate + 1)
+}
+
+/// Propagates several simultaneous faults to the end of the circuit. Faults
+/// on the same qubit and location compose by multiplication.
+pub fn propagate_faults(circuit: &Circuit, faults: &[Fault]) -> PauliString {
+    propagate_faults_from(circuit, faults, 0)
+}
+
+/// Circuits of up to 128 qubits run on fixed-width registers.
+fn propagate_faults_from(circuit: &Circuit, faults: &[Fault], first_gate: usize) -> PauliString {
+    if circuit.num_qubits <= PauliStringN::<1>::CAPACITY {
+        propagate_small::<1>(circuit, faults, first_gate)
+    } else if circuit.num_qubits <= PauliStringN::<2>::CAPACITY {
+        propagate_small::<2>(circuit, faults, first_gate)
+    } else {
+        propagate_wide(circuit, faults, first_gate)
+    }
+}
+
+fn propagate_small<const WORDS: usize>(circuit: &Circuit, faults: &[Fault], first_gate: usize) -> PauliString {
+    let mut error = PauliStringN::<WORDS>::new(circuit.num_qubits);
+    let mut single = PauliStringN::<WORDS>::new(circuit.num_qubits);
+    for fault in faults.iter().filter(|fault| fault.after_gate + 1 == first_gate) {
+        single.set_pauli(fault.qubit, fault.pauli);
+        error = error.multiply(&single);
+        single.set_pauli(fault.qubit, SinglePauli::I);
+    }
+    for (index, gate) in circuit.gates.iter().enumerate().skip(first_gate) {
+        error.apply_gate(gate);
+        perf::count(Counter::GatesApplied, 1);
+        for fault in faults.iter().filter(|fault| fault.after_gate == index) {
+            single.set_pauli(fault.qubit, fault.pauli);
+            error = error.multiply(&single);
+            single.set_pauli(fault.qubit, SinglePauli::I);
+        }
+    }
+    error.to_pauli_string()
+}
+
+fn propagate_wide(circuit: &Circuit, faults: &[Fault], first_gate: usize) -> PauliString {
+    let mut error = PauliString::new(circuit.num_qubits);
+    for fault in faults.iter().filter(|fault| fault.after_gate + 1 == first_gate) {
+        let mut single = PauliString::new(circuit.num_qubits);
+        single.set_pauli(fault.qubit, fault.pauli);
+        error = error.multiply(&single);
+    }
+    for (index, gate) in circuit.gates.iter().enumerate().skip(first_gate) {
+        apply_gate(&mut error, gate);
+        for fault in faults.iter().filter(|fault| fault.after_gate == index) {
+            let mut single = PauliString::new(circuit.num_qubits);
+            single.set_pauli(fault.qubit, fault.pauli);
+            error = error.multiply(&single);
+        }
+    }
+    error
+}
+
+pub fn single_fault_sweep(circuit: &Circuit) -> Vec<FaultOutcome> {
+    fault_locations(circuit)
+        .into_iter()
+        .map(|fault| {
+            let output = propagate_fault(circuit, &fault);
+            FaultOutcome { fault, output }
+        })
+        .collect()
+}
+
+/// Number of `qubits` on which `error` acts non-trivially.
+pub(crate) fn restricted_weight(error: &PauliString, qubits: &[usize]) -> usize {
+    qubits
+        .iter()
+        .filter(|&&q| error.x_bits()[q] || error.z_bits()[q])
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{Gate, SingleGate, TwoGate};
+
+    #[test]
+    fn test_sweep_order_and_propagation() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+
+        let sweep = single_fault_sweep(&circuit);
+        // 3 faults after H, 6 after CNOT
+        assert_eq!(sweep.len(), 9);
+        assert_eq!(
+            sweep[0].fault,
+            Fault {
+                after_gate: 0,
+                qubit: 0,
+                pauli: SinglePauli::X
+            }
+        );
+        // X after H spreads through the CNOT
+        assert_eq!(sweep[0].output.get_pauli(1), SinglePauli::X);
+        // Faults after the last gate are not propagated further
+        assert_eq!(sweep[8].output.get_pauli(1), SinglePauli::Z);
+        assert_eq!(sweep[8].output.get_pauli(0), SinglePauli::I);
+    }
+}
@@ -0,0 +1,40 @@
+pub mod certification;
+pub mod clifford_content;
+pub mod commutation;
+pub mod constraints;
+pub mod coverage;
+pub mod determinism;
+pub mod effective_distance;
+pub mod equivalence;
+pub mod fault_sweep;
+pub mod observables;
+pub mod oracle;
+pub mod postselection;
+pub mod residual_class;
+pub mod resources;
+pub mod round_cache;
+pub mod scenario;
+pub mod shrink;
+pub mod stats;
+pub mod symmetry;
+pub mod syndrome_db;
+
+pub use certification::{certify_ft, FtCertificate, FtViolation};
+pub use clifford_content::{clifford_report, CliffordReport};
+pub use commutation::{commutes_through, commutes_with_circuit, first_obstruction, obstructions, Obstruction};
+pub use constraints::{CircuitConstraints, ConstraintViolation};
+pub use coverage::{detection_coverage, CoverageLevel, DetectionCoverage, UndetectedFault};
+pub use determinism::{check_detectors, NondeterministicDetector};
+pub use effective_distance::{min_undetectable_logical, UndetectableLogical};
+pub use equivalence::{circuits_equivalent, circuits_equivalent_up_to_relabeling, find_relabeling};
+pub use fault_sweep::{propagate_faults, single_fault_sweep, Fault, FaultOutcome};
+pub use observables::infer_observables;
+pub use oracle::{transfer_table, ExpectedSyndrome, ExpectedTransfer, OracleMismatch, OracleReport, OracleResults};
+pub use postselection::{condition, ConditionedShots, ShotCondition};
+pub use residual_class::{classify_residual, residual_class_counts, ResidualClass, ResidualClassCounts};
+pub use resources::{estimate_resources, OperationEstimate, ResourceReport};
+pub use round_cache::RoundCache;
+pub use scenario::{ScenarioAction, ScenarioNode, ScenarioOutcome, ScenarioRecord, ScenarioTree};
+pub use shrink::{minimize_violation, shrink_faults};
+pub use symmetry::{check_symmetries, Symmetry, SymmetryCheck};
+pub use syndrome_db::{SyndromeDatabase, SyndromeEntry};
@@ -0,0 +1,130 @@
+//! Observable annotations inferred from a code's logical operators.
+//!
+//! The gadget's data block is taken to start in the code space and its
+//! ancillas in |0>. A set of shot record entries reads out logical L when
+//! the product of the operators they measure, pulled back to the start of
+//! the circuit, equals L times code stabilizers on the data and Z operators
+//! on the ancillas. The ancilla readouts are searched first, so a logical
+//! that the gadget moves onto its measurement layer is annotated with those
+//! readouts; otherwise the final logical readout entries of the record are
+//! used.
+
+use crate::analysis::determinism::record_operators;
+use crate::codes::{Gadget, StabilizerCode};
+use crate::physics::pauli::{PauliString, SinglePauli};
+use crate::physics::tableau::Tableau;
+
+/// Record entries reading out each record observable (logical Z operators
+/// first, then logical X), or `None` when no parity of record entries does.
+pub fn infer_observables(gadget: &Gadget, code: &StabilizerCode) -> Vec<Option<Vec<usize>>> {
+    let n = gadget.circuit.num_qubits;
+    let pull_back = Tableau::from_circuit(&gadget.circuit.inverse());
+    let entries: Vec<Vec<bool>> = record_operators(gadget, code)
+        .iter()
+        .map(|operator| symplectic(&pull_back.image(operator)))
+        .collect();
+
+    let on_data = |operator: &PauliString| {
+        let mut placed = PauliString::new(n);
+        for (i, &qubit) in gadget.data_qubits.iter().enumerate() {
+            placed.set_pauli(qubit, operator.get_pauli(i));
+        }
+        placed
+    };
+    let mut initial_stabilizers: Vec<Vec<bool>> = code.stabilizers.iter().map(|s| symplectic(&on_data(s))).collect();
+    for qubit in (0..n).filter(|q| !gadget.data_qubits.contains(q)) {
+        let mut z = PauliString::new(n);
+        z.set_pauli(qubit, SinglePauli::Z);
+        initial_stabilizers.push(symplectic(&z));
+    }
+
+    let readouts: Vec<usize> = (0..gadget.measurements().len()).collect();
+    let final_logicals = readouts.len() + code.stabilizers.len()..entries.len();
+    let stages = [readouts.clone(), readouts.iter().copied().chain(final_logicals).collect()];
+    code.logical_z
+        .iter()
+        .chain(&code.logical_x)
+        .map(|logical| {
+            let target = symplectic(&on_data(logical));
+            stages.iter().find_map(|usable| {
+                let mut columns: Vec<Vec<bool>> = usable.iter().map(|&i| entries[i].clone()).collect();
+                columns.extend(initial_stabilizers.iter().cloned());
+                let chosen = solve(&columns, &target)?;
+                Some(usable.iter().enumerate().filter(|&(k, _)| chosen[k]).map(|(_, &i)| i).collect())
+            })
+        })
+        .collect()
+}
+
+fn symplectic(pauli: &PauliString) -> Vec<bool> {
+    pauli.to_symplectic().iter().by_vals().collect()
+}
+
+/// A subset of `columns` summing to `target` over GF(2), as a mask.
+fn solve(columns: &[Vec<bool>], target: &[bool]) -> Option<Vec<bool>> {
+    // Each pivot row keeps the combination of columns it was built from
+    let mut pivots: Vec<(usize, Vec<bool>, Vec<bool>)> = Vec::new();
+    let reduce = |vector: &mut Vec<bool>, mask: &mut Vec<bool>, pivots: &[(usize, Vec<bool>, Vec<bool>)]| {
+        for (bit, row, row_mask) in pivots {
+            if vector[*bit] {
+                for (v, r) in vector.iter_mut().zip(row) {
+                    *v ^= r;
+                }
+                for (m, r) in mask.iter_mut().zip(row_mask) {
+                    *m ^= r;
+                }
+            }
+        }
+    };
+    for (index, column) in columns.iter().enumerate() {
+        let mut vector = column.clone();
+        let mut mask = vec![false; columns.len()];
+        mask[index] = true;
+        reduce(&mut vector, &mut mask, &pivots);
+        if let Some(bit) = vector.iter().position(|&b| b) {
+            pivots.push((bit, vector, mask));
+        }
+    }
+    let mut vector = target.to_vec();
+    let mut mask = vec![false; columns.len()];
+    reduce(&mut vector, &mut mask, &pivots);
+    if vector.iter().any(|&b| b) {
+        None
+    } else {
+        Some(mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::MeasurementBasis;
+    use crate::physics::circuit::{Circuit, Gate, TwoGate};
+
+    #[test]
+    fn test_readout_gadget_and_memory() {
+        // Copy each repetition-code qubit onto an ancilla and read it in Z:
+        // any single readout reveals the logical Z, nothing reveals X
+        let mut circuit = Circuit::new(6);
+        for i in 0..3 {
+            circuit
+                .add_gate(Gate::Two(TwoGate::CNOT { control: i, target: 3 + i }))
+                .unwrap();
+        }
+        for qubit in 3..6 {
+            circuit.add_measurement(qubit, MeasurementBasis::Z).unwrap();
+        }
+        let gadget = Gadget::new(circuit, vec![0, 1, 2]).unwrap();
+        let code = StabilizerCode::repetition(3);
+        let observables = infer_observables(&gadget, &code);
+        assert_eq!(observables.len(), 2);
+        let z = observables[0].as_ref().unwrap();
+        assert_eq!(z.len(), 1);
+        assert!(z[0] < 3);
+        assert_eq!(observables[1], None);
+
+        // Without readouts the final perfect readout carries both logicals
+        let idle = Gadget::new(Circuit::new(3), vec![0, 1, 2]).unwrap();
+        assert_eq!(infer_observables(&idle, &code), vec![Some(vec![2]), Some(vec![3])]);
+    }
+}
@@ -0,0 +1,208 @@
+//! Differential testing against results exported from another tool.
+//!
+//! An `OracleResults` holds expected transfers (an input Pauli and the
+//! Pauli it becomes after the whole circuit) and expected syndromes (an
+//! error and the code syndrome it triggers), written as strings so a table
+//! dumped from Python deserializes directly. The crate recomputes each
+//! entry and reports every disagreement. Paulis may carry a leading sign
+//! (`+`, `-`, `i`, `+i`, `-i`) and are read in `pauli_order`, so tables
+//! from Qiskit (qubit 0 rightmost) compare without manual reversal.
+
+use crate::codes::StabilizerCode;
+use crate::physics::circuit::Circuit;
+use crate::physics::pauli::{PauliOrder, PauliString, SinglePauli};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectedTransfer {
+    pub input: String,
+    pub output: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectedSyndrome {
+    pub error: String,
+    /// In `StabilizerCode::stabilizers` order
+    pub syndrome: Vec<bool>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OracleResults {
+    /// Compare transfers up to sign, for tools that drop phases
+    #[serde(default)]
+    pub ignore_phase: bool,
+    #[serde(default)]
+    pub pauli_order: PauliOrder,
+    #[serde(default)]
+    pub transfers: Vec<ExpectedTransfer>,
+    #[serde(default)]
+    pub syndromes: Vec<ExpectedSyndrome>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OracleMismatch {
+    Transfer {
+        index: usize,
+        input: String,
+        expected: String,
+        actual: String,
+    },
+    Syndrome {
+        index: usize,
+        error: String,
+        expected: Vec<bool>,
+        actual: Vec<bool>,
+    },
+    /// The entry could not be read, e.g. a Pauli of the wrong length
+    Invalid {
+        section: String,
+        index: usize,
+        reason: String,
+    },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OracleReport {
+    /// Entries compared, including mismatching ones
+    pub checked: usize,
+    /// In entry order, transfers first
+    pub mismatches: Vec<OracleMismatch>,
+}
+
+impl OracleReport {
+    pub fn agrees(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+impl OracleResults {
+    pub fn expect_transfer(&mut self, input: &str, output: &str) {
+        self.transfers.push(ExpectedTransfer {
+            input: input.to_string(),
+            output: output.to_string(),
+        });
+    }
+
+    pub fn expect_syndrome(&mut self, error: &str, syndrome: Vec<bool>) {
+        self.syndromes.push(ExpectedSyndrome {
+            error: error.to_string(),
+            syndrome,
+        });
+    }
+
+    /// Compares the transfers with `circuit` and the syndromes with `code`.
+    /// Pass None to skip the syndromes.
+    pub fn compare(&self, circuit: &Circuit, code: Option<&StabilizerCode>) -> OracleReport {
+        let mut report = OracleReport::default();
+        for (index, expected) in self.transfers.iter().enumerate() {
+            report.checked += 1;
+            let parse = |s: &str| PauliString::from_str_in(s, circuit.num_qubits, self.pauli_order);
+            let parsed = parse(&expected.input).and_then(|input| Ok((input, parse(&expected.output)?)));
+            let (input, output) = match parsed {
+                Ok(pair) => pair,
+                Err(reason) => {
+                    report.mismatches.push(invalid("transfers", index, reason));
+                    continue;
+                }
+            };
+            let actual = input.conjugated_by(circuit);
+            let same = if self.ignore_phase {
+                actual.equals_up_to_phase(&output)
+            } else {
+                actual == output
+            };
+            if !same {
+                report.mismatches.push(OracleMismatch::Transfer {
+                    index,
+                    input: expected.input.clone(),
+                    expected: expected.output.clone(),
+                    actual: actual.to_string_in(self.pauli_order),
+                });
+            }
+        }
+
+        let Some(code) = code else {
+            return report;
+        };
+        for (index, expected) in self.syndromes.iter().enumerate() {
+            report.checked += 1;
+            let error = match PauliString::from_str_in(&expected.error, code.num_qubits, self.pauli_order) {
+                Ok(error) => error,
+                Err(reason) => {
+                    report.mismatches.push(invalid("syndromes", index, reason));
+                    continue;
+                }
+            };
+            let actual = code.syndrome(&error);
+            if actual != expected.syndrome {
+                report.mismatches.push(OracleMismatch::Syndrome {
+                    index,
+                    error: expected.error.clone(),
+                    expected: expected.syndrome.clone(),
+                    actual,
+                });
+            }
+        }
+        report
+    }
+}
+
+/// Images of every X_i and Z_i under `circuit`, written in `order` as the
+/// oracle reads them, for exporting this crate's results to another tool.
+pub fn transfer_table(circuit: &Circuit, order: PauliOrder) -> Vec<ExpectedTransfer> {
+    let n = circuit.num_qubits;
+    let mut table = Vec::with_capacity(2 * n);
+    for qubit in 0..n {
+        for pauli in [SinglePauli::X, SinglePauli::Z] {
+            let mut image = PauliString::new(n);
+            image.set_pauli(qubit, pauli);
+            table.push(ExpectedTransfer {
+                input: image.to_string_in(order),
+                output: image.conjugated_by(circuit).to_string_in(order),
+            });
+        }
+    }
+    table
+}
+
+fn invalid(section: &str, index: usize, reason: String) -> OracleMismatch {
+    OracleMismatch::Invalid {
+        section: section.to_string(),
+        index,
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{Gate, SingleGate, TwoGate};
+
+    #[test]
+    fn test_oracle_comparison() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+
+        // Our own table always agrees with itself
+        let exported = OracleResults {
+            transfers: transfer_table(&circuit, PauliOrder::Qubit0Rightmost),
+            pauli_order: PauliOrder::Qubit0Rightmost,
+            ..Default::default()
+        };
+        assert!(exported.compare(&circuit, None).agrees());
+
+        let json = r#"{"transfers": [{"input": "ZI", "output": "XX"}, {"input": "IX", "output": "-IX"}, {"input": "X", "output": "X"}],
+                       "syndromes": [{"error": "XII", "syndrome": [true, false]}, {"error": "IXI", "syndrome": [false, false]}]}"#;
+        let mut oracle: OracleResults = serde_json::from_str(json).unwrap();
+        let report = oracle.compare(&circuit, Some(&StabilizerCode::repetition(3)));
+        assert_eq!(report.checked, 5);
+        assert_eq!(report.mismatches.len(), 3);
+        assert!(matches!(&report.mismatches[0], OracleMismatch::Transfer { index: 1, actual, .. } if actual == "+IX"));
+        assert!(matches!(&report.mismatches[1], OracleMismatch::Invalid { index: 2, .. }));
+        assert!(matches!(&report.mismatches[2], OracleMismatch::Syndrome { index: 1, actual, .. } if actual == &[true, true]));
+
+        oracle.ignore_phase = true;
+        assert_eq!(oracle.compare(&circuit, None).mismatches.len(), 1);
+    }
+}
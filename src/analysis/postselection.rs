@@ -0,0 +1,160 @@
+//! Post-selection and conditioning of sampled shots.
+//!
+//! A `ShotCondition` is a predicate on one `ShotRecord` (heralds, detector
+//! values, or combinations of them). `condition` keeps the shots that meet
+//! it and reports the acceptance rate together with the observable flip
+//! rate among the kept shots, i.e. the raw logical error rate conditioned
+//! on the pattern. Pass `ConditionedShots::records` to `verify_decoder` for
+//! the decoded rate of the same subset.
+
+use crate::analysis::stats::{wilson_interval, ConfidenceInterval};
+use crate::noise::sampler::ShotRecord;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShotCondition {
+    /// No herald was raised
+    NoHerald,
+    /// A herald was raised on one of these qubits (on any qubit if empty)
+    Heralded(Vec<usize>),
+    /// No detection event at all
+    NoDetections,
+    /// Every listed detector has the given value; missing detectors fail
+    DetectorPattern(Vec<(usize, bool)>),
+    Not(Box<ShotCondition>),
+    All(Vec<ShotCondition>),
+    Any(Vec<ShotCondition>),
+}
+
+impl ShotCondition {
+    pub fn holds(&self, shot: &ShotRecord) -> bool {
+        match self {
+            ShotCondition::NoHerald => !shot.is_heralded(),
+            ShotCondition::Heralded(qubits) => shot
+                .heralds
+                .iter()
+                .any(|herald| qubits.is_empty() || qubits.contains(&herald.qubit)),
+            ShotCondition::NoDetections => !shot.detectors.contains(&true),
+            ShotCondition::DetectorPattern(pattern) => pattern
+                .iter()
+                .all(|&(detector, value)| shot.detectors.get(detector) == Some(&value)),
+            ShotCondition::Not(inner) => !inner.holds(shot),
+            ShotCondition::All(conditions) => conditions.iter().all(|c| c.holds(shot)),
+            ShotCondition::Any(conditions) => conditions.iter().any(|c| c.holds(shot)),
+        }
+    }
+}
+
+/// The shots of a sample that meet a condition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConditionedShots<'a> {
+    shots: &'a [ShotRecord],
+    /// Indices of the kept shots in the original sample
+    pub kept: Vec<usize>,
+}
+
+impl<'a> ConditionedShots<'a> {
+    pub fn total(&self) -> usize {
+        self.shots.len()
+    }
+
+    pub fn acceptance_rate(&self) -> f64 {
+        if self.shots.is_empty() {
+            0.0
+        } else {
+            self.kept.len() as f64 / self.shots.len() as f64
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a ShotRecord> + '_ {
+        self.kept.iter().map(|&index| &self.shots[index])
+    }
+
+    /// Copies of the kept shots, e.g. for `verify_decoder`.
+    pub fn records(&self) -> Vec<ShotRecord> {
+        self.iter().cloned().collect()
+    }
+
+    /// Kept shots flipping observable `observable`, or any observable if
+    /// None.
+    pub fn observable_flips(&self, observable: Option<usize>) -> usize {
+        self.iter()
+            .filter(|shot| match observable {
+                Some(index) => shot.observables.get(index) == Some(&true),
+                None => shot.observables.contains(&true),
+            })
+            .count()
+    }
+
+    /// Conditional logical error rate without decoding; 0 when nothing
+    /// was kept.
+    pub fn logical_error_rate(&self, observable: Option<usize>) -> f64 {
+        if self.kept.is_empty() {
+            0.0
+        } else {
+            self.observable_flips(observable) as f64 / self.kept.len() as f64
+        }
+    }
+
+    /// Wilson interval of `logical_error_rate`.
+    pub fn logical_error_interval(
+        &self,
+        observable: Option<usize>,
+        confidence: f64,
+    ) -> Result<ConfidenceInterval, String> {
+        wilson_interval(self.observable_flips(observable), self.kept.len(), confidence)
+    }
+}
+
+pub fn condition<'a>(shots: &'a [ShotRecord], condition: &ShotCondition) -> ConditionedShots<'a> {
+    ConditionedShots {
+        shots,
+        kept: (0..shots.len()).filter(|&i| condition.holds(&shots[i])).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::model::Herald;
+    use crate::physics::pauli::PauliString;
+
+    fn shot(detectors: &[bool], flipped: bool, herald: Option<usize>) -> ShotRecord {
+        ShotRecord {
+            faults: Vec::new(),
+            detectors: detectors.to_vec(),
+            observables: vec![flipped, false],
+            heralds: herald.map(|qubit| Herald { after_gate: 0, qubit }).into_iter().collect(),
+            residual: PauliString::new(1),
+        }
+    }
+
+    #[test]
+    fn test_postselection_and_conditioning() {
+        let shots = vec![
+            shot(&[false, false], false, None),
+            shot(&[true, false], true, None),
+            shot(&[true, false], false, Some(2)),
+            shot(&[false, true], true, Some(0)),
+        ];
+
+        let unheralded = condition(&shots, &ShotCondition::NoHerald);
+        assert_eq!(unheralded.kept, vec![0, 1]);
+        assert_eq!(unheralded.acceptance_rate(), 0.5);
+        assert_eq!(unheralded.logical_error_rate(None), 0.5);
+        assert_eq!(unheralded.logical_error_rate(Some(1)), 0.0);
+
+        let first_fired = condition(&shots, &ShotCondition::DetectorPattern(vec![(0, true)]));
+        assert_eq!(first_fired.kept, vec![1, 2]);
+        assert_eq!(first_fired.records().len(), 2);
+
+        let combined = ShotCondition::All(vec![
+            ShotCondition::Not(Box::new(ShotCondition::NoDetections)),
+            ShotCondition::Any(vec![ShotCondition::NoHerald, ShotCondition::Heralded(vec![0])]),
+        ]);
+        let selected = condition(&shots, &combined);
+        assert_eq!(selected.kept, vec![1, 3]);
+        assert_eq!(selected.logical_error_interval(None, 0.95).unwrap().estimate, 1.0);
+        assert!(condition(&shots, &ShotCondition::DetectorPattern(vec![(5, false)])).kept.is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+//! Classification of residual errors by logical effect.
+//!
+//! For each logical qubit i, a residual in the normalizer acts as I, X, Z
+//! or Y: it flips logical Z_i exactly when it carries an X_i part, and
+//! logical X_i exactly when it carries a Z_i part. Trivial action splits
+//! into the identity itself and a non-trivial stabilizer-equivalent error.
+//! Residuals with a syndrome are outside the normalizer and counted apart.
+//! Per-class frequencies show the logical bias of a noise model or decoder.
+
+use crate::codes::StabilizerCode;
+use crate::physics::pauli::PauliString;
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResidualClass {
+    Identity,
+    /// Non-trivial, but acts as the identity on this logical qubit
+    Stabilizer,
+    LogicalX,
+    LogicalZ,
+    LogicalY,
+    /// Leaves a syndrome, so the state is not in the code space
+    Detectable,
+}
+
+/// Shots per class on one logical qubit.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResidualClassCounts {
+    pub identity: usize,
+    pub stabilizer: usize,
+    pub logical_x: usize,
+    pub logical_z: usize,
+    pub logical_y: usize,
+    pub detectable: usize,
+}
+
+impl ResidualClassCounts {
+    pub fn count(&self, class: ResidualClass) -> usize {
+        match class {
+            ResidualClass::Identity => self.identity,
+            ResidualClass::Stabilizer => self.stabilizer,
+            ResidualClass::LogicalX => self.logical_x,
+            ResidualClass::LogicalZ => self.logical_z,
+            ResidualClass::LogicalY => self.logical_y,
+            ResidualClass::Detectable => self.detectable,
+        }
+    }
+
+    pub fn add(&mut self, class: ResidualClass) {
+        let count = match class {
+            ResidualClass::Identity => &mut self.identity,
+            ResidualClass::Stabilizer => &mut self.stabilizer,
+            ResidualClass::LogicalX => &mut self.logical_x,
+            ResidualClass::LogicalZ => &mut self.logical_z,
+            ResidualClass::LogicalY => &mut self.logical_y,
+            ResidualClass::Detectable => &mut self.detectable,
+        };
+        *count += 1;
+    }
+
+    pub fn total(&self) -> usize {
+        self.identity + self.stabilizer + self.logical_x + self.logical_z + self.logical_y + self.detectable
+    }
+
+    /// Fraction of the counted shots in `class`; 0 when none were counted.
+    pub fn frequency(&self, class: ResidualClass) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => self.count(class) as f64 / total as f64,
+        }
+    }
+}
+
+/// The class of `residual` on every logical qubit of `code`.
+pub fn classify_residual(code: &StabilizerCode, residual: &PauliString) -> Result<Vec<ResidualClass>, String> {
+    if residual.num_qubits() != code.num_qubits {
+        return Err(format!(
+            "Residual has {} qubits but code '{}' has {}",
+            residual.num_qubits(),
+            code.name,
+            code.num_qubits
+        ));
+    }
+    let detectable = code.syndrome(residual).contains(&true);
+    let trivial = if residual.weight() == 0 {
+        ResidualClass::Identity
+    } else {
+        ResidualClass::Stabilizer
+    };
+    Ok(code
+        .logical_x
+        .iter()
+        .zip(&code.logical_z)
+        .map(|(logical_x, logical_z)| {
+            if detectable {
+                return ResidualClass::Detectable;
+            }
+            match (!logical_z.commutes_with(residual), !logical_x.commutes_with(residual)) {
+                (false, false) => trivial,
+                (true, false) => ResidualClass::LogicalX,
+                (false, true) => ResidualClass::LogicalZ,
+                (true, true) => ResidualClass::LogicalY,
+            }
+        })
+        .collect())
+}
+
+/// Class counts over many residuals, one entry per logical qubit.
+pub fn residual_class_counts<I>(code: &StabilizerCode, residuals: I) -> Result<Vec<ResidualClassCounts>, String>
+where
+    I: IntoIterator,
+    I::Item: Borrow<PauliString>,
+{
+    let mut counts = vec![ResidualClassCounts::default(); code.num_logical_qubits()];
+    for residual in residuals {
+        for (count, class) in counts.iter_mut().zip(classify_residual(code, residual.borrow())?) {
+            count.add(class);
+        }
+    }
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_repetition_residuals() {
+        let code = StabilizerCode::repetition(3);
+        let pauli = |s: &str| PauliString::from_str(s, 3).unwrap();
+        let classes = |s: &str| classify_residual(&code, &pauli(s)).unwrap();
+        assert_eq!(classes("III"), vec![ResidualClass::Identity]);
+        assert_eq!(classes("ZZI"), vec![ResidualClass::Stabilizer]);
+        assert_eq!(classes("XXX"), vec![ResidualClass::LogicalX]);
+        assert_eq!(classes("ZII"), vec![ResidualClass::LogicalZ]);
+        assert_eq!(classes("YXX"), vec![ResidualClass::LogicalY]);
+        assert_eq!(classes("XII"), vec![ResidualClass::Detectable]);
+
+        let residuals = [pauli("III"), pauli("ZII"), pauli("IZI"), pauli("XII")];
+        let counts = residual_class_counts(&code, &residuals).unwrap();
+        assert_eq!(counts[0].logical_z, 2);
+        assert_eq!(counts[0].frequency(ResidualClass::Identity), 0.25);
+        assert!(classify_residual(&code, &PauliString::new(2)).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! Resource estimation for logical circuits.
+//!
+//! Counts come straight from the lowered circuit. With a noise model, each
+//! operation's logical error is projected to first order: every gate or
+//! idle error mechanism inside the operation's region is propagated to the
+//! end of the circuit, every block is decoded with its lookup table, and the
+//! probabilities of mechanisms leaving a logical error on any block are
+//! summed (a union bound). Gadget ancillas exist from the start of the
+//! lowered circuit, so idle noise on them is charged to the operations that
+//! run before the gadget.
+
+use crate::analysis::fault_sweep::propagate_faults;
+use crate::codes::logical_circuit::{LogicalCircuit, LogicalOperation};
+use crate::noise::model::NoiseModel;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OperationEstimate {
+    pub operation: usize,
+    pub gates: usize,
+    pub two_qubit_gates: usize,
+    /// Projected logical error probability; `None` without a noise model
+    pub logical_error: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResourceReport {
+    pub physical_qubits: usize,
+    pub moments: usize,
+    pub two_qubit_gates: usize,
+    /// Gadgets that read out ancillas
+    pub measurement_rounds: usize,
+    pub measurements: usize,
+    pub operations: Vec<OperationEstimate>,
+}
+
+impl ResourceReport {
+    /// Sum of the per-operation projections.
+    pub fn total_logical_error(&self) -> Option<f64> {
+        self.operations.iter().map(|op| op.logical_error).sum()
+    }
+}
+
+pub fn estimate_resources(logical: &LogicalCircuit, noise: Option<&NoiseModel>) -> Result<ResourceReport, String> {
+    let lowered = logical.lower()?;
+    let circuit = &lowered.circuit;
+
+    let mut logical_errors = vec![0.0; logical.operations.len()];
+    if let Some(noise) = noise {
+        noise.validate()?;
+        let gadgets = (0..logical.blocks.len())
+            .map(|block| lowered.block_gadget(block))
+            .collect::<Result<Vec<_>, _>>()?;
+        let tables: Vec<_> = logical.blocks.iter().map(|code| code.lookup_table()).collect();
+        for (faults, probability) in noise.mechanisms(circuit) {
+            let error = propagate_faults(circuit, &faults);
+            let fails = logical.blocks.iter().enumerate().any(|(block, code)| {
+                let residual = gadgets[block].data_error(&error);
+                let net = residual.multiply(&tables[block][&code.syndrome(&residual)]);
+                code.is_logical_error(&net)
+            });
+            if !fails {
+                continue;
+            }
+            let at = faults[0].after_gate;
+            if let Some(index) = (0..logical.operations.len()).find(|&i| {
+                let region = circuit.region(&format!("op{}", i)).expect("lowering marks every operation");
+                (region.start..region.end).contains(&at)
+            }) {
+                logical_errors[index] += probability;
+            }
+        }
+    }
+
+    let operations = (0..logical.operations.len())
+        .map(|index| {
+            let region = circuit.region(&format!("op{}", index)).expect("lowering marks every operation");
+            let gates = &circuit.gates[region.start..region.end];
+            OperationEstimate {
+                operation: index,
+                gates: gates.len(),
+                two_qubit_gates: gates.iter().filter(|gate| gate.qubits().len() == 2).count(),
+                logical_error: noise.map(|_| logical_errors[index]),
+            }
+        })
+        .collect();
+
+    Ok(ResourceReport {
+        physical_qubits: circuit.num_qubits,
+        moments: circuit.depth(),
+        two_qubit_gates: circuit.two_qubit_gate_count(),
+        measurement_rounds: logical
+            .operations
+            .iter()
+            .filter(|op| matches!(op, LogicalOperation::Gadget { gadget, .. } if !gadget.measurements().is_empty()))
+            .count(),
+        measurements: lowered.circuit.measurements.len(),
+        operations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::{Gadget, MeasurementBasis, StabilizerCode};
+    use crate::physics::circuit::{Circuit, Gate, SingleGate, TwoGate};
+
+    #[test]
+    fn test_resource_report() {
+        let mut logical = LogicalCircuit::new();
+        let a = logical.add_block(StabilizerCode::steane());
+        let b = logical.add_block(StabilizerCode::steane());
+        logical
+            .add_operation(LogicalOperation::Transversal { block: a, gate: SingleGate::H })
+            .unwrap();
+        logical
+            .add_operation(LogicalOperation::TransversalCnot { control: a, target: b })
+            .unwrap();
+        // A bare ancilla coupled to two data qubits of block b is not fault
+        // tolerant: one CNOT fault can leave a weight-2 error
+        let mut coupling = Circuit::new(8);
+        for target in [0, 1] {
+            coupling
+                .add_gate(Gate::Two(TwoGate::CNOT { control: 7, target }))
+                .unwrap();
+        }
+        coupling.add_measurement(7, MeasurementBasis::X).unwrap();
+        let gadget = Gadget::new(coupling, (0..7).collect()).unwrap();
+        logical
+            .add_operation(LogicalOperation::Gadget { block: b, gadget: Box::new(gadget) })
+            .unwrap();
+
+        let counts = estimate_resources(&logical, None).unwrap();
+        assert_eq!(counts.physical_qubits, 15);
+        assert_eq!(counts.moments, 16);
+        assert_eq!(counts.two_qubit_gates, 9);
+        assert_eq!(counts.measurement_rounds, 1);
+        assert_eq!(counts.total_logical_error(), None);
+
+        // Transversal operations only spread single faults to single faults;
+        // idle noise is off so the waiting ancilla stays clean
+        let noise = NoiseModel {
+            idle_depolarizing: 0.0,
+            ..NoiseModel::uniform(0.01)
+        };
+        let noisy = estimate_resources(&logical, Some(&noise)).unwrap();
+        assert_eq!(noisy.operations[0].logical_error, Some(0.0));
+        assert_eq!(noisy.operations[1].logical_error, Some(0.0));
+        assert!(noisy.operations[2].logical_error.unwrap() > 0.0);
+    }
+}
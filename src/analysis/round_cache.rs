@@ -0,0 +1,276 @@
+//! Reuse of repeated syndrome-extraction rounds.
+//!
+//! Memory experiments repeat the same round many times, often on fresh
+//! ancillas. `RoundCache` relabels each marked round onto the qubits it
+//! touches, in increasing order, and groups rounds whose relabelled gates
+//! agree. Per distinct round it remembers how an incoming error on those
+//! qubits leaves the round (first level) and how faults inside the round
+//! do (second level), both filled on demand. Propagation then steps gate by
+//! gate only through unmarked gates; a round is a lookup once any equal
+//! round has seen the same input. Qubits a round does not touch pass
+//! through unchanged, so results equal `propagate_faults` exactly.
+
+use crate::analysis::fault_sweep::{fault_locations, Fault, FaultOutcome};
+use crate::physics::circuit::{Circuit, Gate};
+use crate::physics::pauli::{PauliString, SinglePauli};
+use crate::physics::propagation::apply_gate;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// A fault inside a round: gate offset, local qubit and Pauli.
+type LocalFault = (usize, usize, SinglePauli);
+
+struct RoundKind {
+    /// The round's gates on local qubits
+    gates: Vec<Gate>,
+    num_qubits: usize,
+}
+
+struct Round {
+    gates: Range<usize>,
+    kind: usize,
+    /// Local qubit i is global qubit `qubits[i]`
+    qubits: Vec<usize>,
+}
+
+pub struct RoundCache {
+    circuit: Circuit,
+    /// Sorted by gate range, disjoint
+    rounds: Vec<Round>,
+    kinds: Vec<RoundKind>,
+    /// (kind, local input) to local output, phase +1 in
+    transfers: HashMap<(usize, PauliString), PauliString>,
+    /// (kind, local faults, local input) to local output
+    fault_exits: HashMap<(usize, Vec<LocalFault>, PauliString), PauliString>,
+}
+
+impl RoundCache {
+    /// Caches `rounds`, gate ranges of `circuit` that must not overlap.
+    pub fn new(circuit: &Circuit, mut rounds: Vec<Range<usize>>) -> Result<Self, String> {
+        rounds.sort_by_key(|round| round.start);
+        for (i, round) in rounds.iter().enumerate() {
+            if round.start > round.end || round.end > circuit.gates.len() {
+                return Err(format!(
+                    "Round {}..{} is out of bounds for a circuit with {} gates",
+                    round.start,
+                    round.end,
+                    circuit.gates.len()
+                ));
+            }
+            if i > 0 && rounds[i - 1].end > round.start {
+                return Err(format!("Rounds overlap at gate {}", round.start));
+            }
+        }
+
+        let mut kinds: Vec<RoundKind> = Vec::new();
+        let mut cached = Vec::with_capacity(rounds.len());
+        for range in rounds {
+            let gates = &circuit.gates[range.clone()];
+            let mut qubits: Vec<usize> = gates.iter().flat_map(Gate::qubits).collect();
+            qubits.sort_unstable();
+            qubits.dedup();
+            let local: Vec<Gate> = gates
+                .iter()
+                .map(|gate| gate.map_qubits(|q| qubits.binary_search(&q).expect("touched qubit")))
+                .collect();
+            let kind = match kinds.iter().position(|kind| kind.gates == local) {
+                Some(kind) => kind,
+                None => {
+                    kinds.push(RoundKind {
+                        gates: local,
+                        num_qubits: qubits.len(),
+                    });
+                    kinds.len() - 1
+                }
+            };
+            cached.push(Round {
+                gates: range,
+                kind,
+                qubits,
+            });
+        }
+
+        Ok(Self {
+            circuit: circuit.clone(),
+            rounds: cached,
+            kinds,
+            transfers: HashMap::new(),
+            fault_exits: HashMap::new(),
+        })
+    }
+
+    /// Caches the regions whose names start with `prefix`, e.g. "round "
+    /// for the rounds of `codes::experiments`.
+    pub fn from_regions(circuit: &Circuit, prefix: &str) -> Result<Self, String> {
+        let rounds = circuit
+            .regions
+            .iter()
+            .filter(|region| region.name.starts_with(prefix))
+            .map(|region| region.start..region.end)
+            .collect();
+        Self::new(circuit, rounds)
+    }
+
+    pub fn circuit(&self) -> &Circuit {
+        &self.circuit
+    }
+
+    pub fn num_rounds(&self) -> usize {
+        self.rounds.len()
+    }
+
+    /// Rounds that differ after relabelling; each has its own cache entries.
+    pub fn num_distinct_rounds(&self) -> usize {
+        self.kinds.len()
+    }
+
+    /// Entries of both cache levels.
+    pub fn cached_entries(&self) -> usize {
+        self.transfers.len() + self.fault_exits.len()
+    }
+
+    /// `propagate_faults` through the cache.
+    pub fn propagate(&mut self, faults: &[Fault]) -> PauliString {
+        let mut faults = faults.to_vec();
+        faults.sort_by_key(|fault| fault.after_gate);
+        let mut error = PauliString::new(self.circuit.num_qubits);
+        let mut next = 0;
+        for r in 0..self.rounds.len() {
+            let range = self.rounds[r].gates.clone();
+            error = self.propagate_gates(error, next..range.start, &faults);
+            let inside: Vec<&Fault> = faults.iter().filter(|f| range.contains(&f.after_gate)).collect();
+            error = self.through_round(r, error, &inside);
+            next = range.end;
+        }
+        self.propagate_gates(error, next..self.circuit.gates.len(), &faults)
+    }
+
+    /// `single_fault_sweep` through the cache.
+    pub fn sweep(&mut self) -> Vec<FaultOutcome> {
+        fault_locations(&self.circuit)
+            .into_iter()
+            .map(|fault| {
+                let output = self.propagate(std::slice::from_ref(&fault));
+                FaultOutcome { fault, output }
+            })
+            .collect()
+    }
+
+    fn propagate_gates(&self, mut error: PauliString, gates: Range<usize>, faults: &[Fault]) -> PauliString {
+        for index in gates {
+            apply_gate(&mut error, &self.circuit.gates[index]);
+            for fault in faults.iter().filter(|fault| fault.after_gate == index) {
+                error = error.multiply(&single(self.circuit.num_qubits, fault.qubit, fault.pauli));
+            }
+        }
+        error
+    }
+
+    fn through_round(&mut self, r: usize, error: PauliString, faults: &[&Fault]) -> PauliString {
+        let round = &self.rounds[r];
+        let kind = &self.kinds[round.kind];
+        let mut input = PauliString::new(kind.num_qubits);
+        for (local, &qubit) in round.qubits.iter().enumerate() {
+            input.set_pauli(local, error.get_pauli(qubit));
+        }
+        // Faults on untouched qubits meet no gate, so they can wait for the end
+        let mut local_faults: Vec<LocalFault> = Vec::new();
+        let mut outside = Vec::new();
+        for &fault in faults {
+            match round.qubits.binary_search(&fault.qubit) {
+                Ok(local) => local_faults.push((fault.after_gate - round.gates.start, local, fault.pauli)),
+                Err(_) => outside.push(fault),
+            }
+        }
+
+        let output = if local_faults.is_empty() {
+            let key = (round.kind, input);
+            match self.transfers.get(&key) {
+                Some(output) => output.clone(),
+                None => {
+                    let output = run_local(kind, &key.1, &[]);
+                    self.transfers.insert(key, output.clone());
+                    output
+                }
+            }
+        } else {
+            let key = (round.kind, local_faults, input);
+            match self.fault_exits.get(&key) {
+                Some(output) => output.clone(),
+                None => {
+                    let output = run_local(kind, &key.2, &key.1);
+                    self.fault_exits.insert(key, output.clone());
+                    output
+                }
+            }
+        };
+
+        let mut result = error;
+        for (local, &qubit) in self.rounds[r].qubits.iter().enumerate() {
+            result.set_pauli(qubit, output.get_pauli(local));
+        }
+        result.set_phase(result.phase().multiply(output.phase()));
+        for fault in outside {
+            result = result.multiply(&single(self.circuit.num_qubits, fault.qubit, fault.pauli));
+        }
+        result
+    }
+}
+
+/// Runs a round on local qubits from `input`, which has phase +1.
+fn run_local(kind: &RoundKind, input: &PauliString, faults: &[LocalFault]) -> PauliString {
+    let mut error = input.clone();
+    for (offset, gate) in kind.gates.iter().enumerate() {
+        apply_gate(&mut error, gate);
+        for &(_, qubit, pauli) in faults.iter().filter(|fault| fault.0 == offset) {
+            error = error.multiply(&single(kind.num_qubits, qubit, pauli));
+        }
+    }
+    error
+}
+
+fn single(num_qubits: usize, qubit: usize, pauli: SinglePauli) -> PauliString {
+    let mut single = PauliString::new(num_qubits);
+    single.set_pauli(qubit, pauli);
+    single
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::fault_sweep::{propagate_faults, single_fault_sweep};
+    use crate::physics::circuit::{SingleGate, TwoGate};
+
+    #[test]
+    fn test_cached_rounds_match_propagation() {
+        // Three rounds checking qubits 0 and 1, each on a fresh ancilla
+        let mut circuit = Circuit::new(5);
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        for round in 0..3 {
+            let start = circuit.gates.len();
+            for control in [0, 1] {
+                circuit.add_gate(Gate::Two(TwoGate::CNOT { control, target: 2 + round })).unwrap();
+            }
+            circuit.add_gate(Gate::Single { qubit: 2 + round, gate: SingleGate::S }).unwrap();
+            circuit.add_region(&format!("round {}", round), start, circuit.gates.len()).unwrap();
+        }
+
+        let mut cache = RoundCache::from_regions(&circuit, "round ").unwrap();
+        assert_eq!((cache.num_rounds(), cache.num_distinct_rounds()), (3, 1));
+        assert_eq!(cache.sweep(), single_fault_sweep(&circuit));
+        let entries = cache.cached_entries();
+        assert_eq!(cache.sweep(), single_fault_sweep(&circuit));
+        assert_eq!(cache.cached_entries(), entries);
+
+        // Idle faults off the round's qubits, out of order
+        let faults = [
+            Fault { after_gate: 5, qubit: 2, pauli: SinglePauli::Z },
+            Fault { after_gate: 2, qubit: 4, pauli: SinglePauli::Y },
+            Fault { after_gate: 1, qubit: 4, pauli: SinglePauli::X },
+        ];
+        assert_eq!(cache.propagate(&faults), propagate_faults(&circuit, &faults));
+
+        assert!(RoundCache::new(&circuit, vec![1..4, 3..5]).is_err());
+        assert!(RoundCache::new(&circuit, vec![8..11, 11..12]).is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Branching what-if explorations over one circuit.
+//!
+//! Each node of a `ScenarioTree` holds a simulator forked from its parent
+//! after one action (an injected error, some steps, recorded classical
+//! bits). Siblings share the circuit and nothing else, so exploring "what
+//! if another error happens here" never recomputes the path to the branch
+//! point. Outcomes (syndrome, decoded logical failure) can be attached for
+//! a code block and exported with the tree.
+
+use crate::codes::StabilizerCode;
+use crate::physics::circuit::Circuit;
+use crate::physics::pauli::{PauliString, SinglePauli};
+use crate::physics::simulator::Simulator;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScenarioAction {
+    InjectError { qubit: usize, pauli: SinglePauli },
+    /// Step forward up to this many gates
+    Step(usize),
+    RunToEnd,
+    RecordBits(Vec<bool>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScenarioOutcome {
+    pub syndrome: Vec<bool>,
+    /// The lookup decoder leaves a logical error
+    pub logical_error: bool,
+}
+
+pub struct ScenarioNode {
+    pub parent: Option<usize>,
+    pub action: Option<ScenarioAction>,
+    pub label: String,
+    pub children: Vec<usize>,
+    pub outcome: Option<ScenarioOutcome>,
+    simulator: Simulator,
+}
+
+impl ScenarioNode {
+    pub fn simulator(&self) -> &Simulator {
+        &self.simulator
+    }
+}
+
+/// Serializable view of one node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScenarioRecord {
+    pub id: usize,
+    pub parent: Option<usize>,
+    pub action: Option<ScenarioAction>,
+    pub label: String,
+    pub children: Vec<usize>,
+    pub time: usize,
+    pub error_pattern: PauliString,
+    pub classical_bits: Vec<bool>,
+    pub outcome: Option<ScenarioOutcome>,
+}
+
+/// Nodes are addressed by id; pruned ids are never reused.
+pub struct ScenarioTree {
+    nodes: Vec<Option<ScenarioNode>>,
+}
+
+impl ScenarioTree {
+    pub const ROOT: usize = 0;
+
+    pub fn new(circuit: Circuit) -> Self {
+        Self {
+            nodes: vec![Some(ScenarioNode {
+                parent: None,
+                action: None,
+                label: "root".to_string(),
+                children: Vec::new(),
+                outcome: None,
+                simulator: Simulator::new(circuit),
+            })],
+        }
+    }
+
+    pub fn node(&self, id: usize) -> Option<&ScenarioNode> {
+        self.nodes.get(id).and_then(Option::as_ref)
+    }
+
+    fn node_or_err(&self, id: usize) -> Result<&ScenarioNode, String> {
+        self.node(id).ok_or_else(|| format!("No scenario node {}", id))
+    }
+
+    /// Ids of live nodes, in creation order.
+    pub fn ids(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len()).filter(|&id| self.nodes[id].is_some())
+    }
+
+    pub fn leaves(&self) -> Vec<usize> {
+        self.ids().filter(|&id| self.nodes[id].as_ref().is_some_and(|n| n.children.is_empty())).collect()
+    }
+
+    /// Path of ids from the root to `id`.
+    pub fn path(&self, id: usize) -> Result<Vec<usize>, String> {
+        let mut path = vec![id];
+        let mut current = self.node_or_err(id)?;
+        while let Some(parent) = current.parent {
+            path.push(parent);
+            current = self.node_or_err(parent)?;
+        }
+        path.reverse();
+        Ok(path)
+    }
+
+    /// Forks `parent`, applies `action` and returns the new node's id.
+    pub fn branch(&mut self, parent: usize, action: ScenarioAction, label: &str) -> Result<usize, String> {
+        let mut simulator = self.node_or_err(parent)?.simulator.fork();
+        match &action {
+            ScenarioAction::InjectError { qubit, pauli } => {
+                if *qubit >= simulator.circuit().num_qubits {
+                    return Err(format!("Qubit {} out of range", qubit));
+                }
+                simulator.inject_error(*qubit, *pauli);
+            }
+            ScenarioAction::Step(steps) => {
+                for _ in 0..*steps {
+                    if !simulator.step_forward() {
+                        break;
+                    }
+                }
+            }
+            ScenarioAction::RunToEnd => simulator.run(),
+            ScenarioAction::RecordBits(bits) => simulator.record_classical_bits(bits),
+        }
+        let id = self.nodes.len();
+        self.nodes.push(Some(ScenarioNode {
+            parent: Some(parent),
+            action: Some(action),
+            label: label.to_string(),
+            children: Vec::new(),
+            outcome: None,
+            simulator,
+        }));
+        self.nodes[parent].as_mut().expect("checked above").children.push(id);
+        Ok(id)
+    }
+
+    /// Removes `id` and its descendants; returns how many nodes went.
+    pub fn prune(&mut self, id: usize) -> Result<usize, String> {
+        let parent = self
+            .node_or_err(id)?
+            .parent
+            .ok_or("The root scenario cannot be pruned")?;
+        if let Some(node) = self.nodes[parent].as_mut() {
+            node.children.retain(|&child| child != id);
+        }
+        let mut stack = vec![id];
+        let mut removed = 0;
+        while let Some(next) = stack.pop() {
+            if let Some(node) = self.nodes[next].take() {
+                stack.extend(node.children);
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Attaches the syndrome and lookup-decoded outcome of the error on
+    /// `data_qubits` to every node.
+    pub fn evaluate(&mut self, code: &StabilizerCode, data_qubits: &[usize]) -> Result<(), String> {
+        if data_qubits.len() != code.num_qubits {
+            return Err(format!(
+                "{} data qubits given but code '{}' has {}",
+                data_qubits.len(),
+                code.name,
+                code.num_qubits
+            ));
+        }
+        let table = code.lookup_table();
+        for node in self.nodes.iter_mut().flatten() {
+            let error = node.simulator.error_pattern();
+            let mut residual = PauliString::new(code.num_qubits);
+            for (i, &qubit) in data_qubits.iter().enumerate() {
+                if qubit >= error.num_qubits() {
+                    return Err(format!("Qubit {} out of range", qubit));
+                }
+                residual.set_pauli(i, error.get_pauli(qubit));
+            }
+            let syndrome = code.syndrome(&residual);
+            let net = residual.multiply(&table[&syndrome]);
+            node.outcome = Some(ScenarioOutcome {
+                syndrome,
+                logical_error: code.is_logical_error(&net),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn export(&self) -> Vec<ScenarioRecord> {
+        self.ids()
+            .map(|id| {
+                let node = self.nodes[id].as_ref().expect("ids() yields live nodes");
+                ScenarioRecord {
+                    id,
+                    parent: node.parent,
+                    action: node.action.clone(),
+                    label: node.label.clone(),
+                    children: node.children.clone(),
+                    time: node.simulator.current_time(),
+                    error_pattern: node.simulator.error_pattern().clone(),
+                    classical_bits: node.simulator.classical_bits().to_vec(),
+                    outcome: node.outcome.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Rebuilds an exported tree on `circuit` by replaying every action.
+    /// Ids are renumbered densely; labels and outcomes are kept.
+    pub fn from_records(circuit: Circuit, records: &[ScenarioRecord]) -> Result<Self, String> {
+        let mut tree = Self::new(circuit);
+        let mut new_id = std::collections::BTreeMap::new();
+        for record in records {
+            let Some(parent) = record.parent else {
+                new_id.insert(record.id, Self::ROOT);
+                let root = tree.nodes[Self::ROOT].as_mut().expect("fresh tree has a root");
+                root.label = record.label.clone();
+                root.outcome = record.outcome.clone();
+                continue;
+            };
+            let parent = *new_id
+                .get(&parent)
+                .ok_or_else(|| format!("Scenario {} appears before its parent {}", record.id, parent))?;
+            let action = record
+                .action
+                .clone()
+                .ok_or_else(|| format!("Scenario {} has a parent but no action", record.id))?;
+            let id = tree.branch(parent, action, &record.label)?;
+            tree.nodes[id].as_mut().expect("just branched").outcome = record.outcome.clone();
+            new_id.insert(record.id, id);
+        }
+        Ok(tree)
+    }
+
+    pub fn export_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(&self.export()).map_err(|e| format!("Failed to serialize scenarios: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{Gate, SingleGate};
+
+    #[test]
+    fn test_branch_evaluate_prune() {
+        let mut circuit = Circuit::new(3);
+        for qubit in 0..3 {
+            circuit.add_gate(Gate::Single { qubit, gate: SingleGate::I }).unwrap();
+        }
+        let mut tree = ScenarioTree::new(circuit);
+        let one = tree
+            .branch(ScenarioTree::ROOT, ScenarioAction::InjectError { qubit: 0, pauli: SinglePauli::X }, "X0")
+            .unwrap();
+        let halfway = tree.branch(one, ScenarioAction::Step(2), "advance").unwrap();
+        let two = tree
+            .branch(halfway, ScenarioAction::InjectError { qubit: 1, pauli: SinglePauli::X }, "then X1")
+            .unwrap();
+        let end = tree.branch(two, ScenarioAction::RunToEnd, "finish").unwrap();
+        assert_eq!(tree.path(end).unwrap(), vec![0, one, halfway, two, end]);
+        assert_eq!(tree.node(halfway).unwrap().simulator().current_time(), 2);
+        assert_eq!(tree.node(one).unwrap().simulator().current_time(), 0);
+
+        tree.evaluate(&StabilizerCode::repetition(3), &[0, 1, 2]).unwrap();
+        assert_eq!(tree.node(one).unwrap().outcome.as_ref().map(|o| o.logical_error), Some(false));
+        assert_eq!(tree.node(end).unwrap().outcome.as_ref().map(|o| o.logical_error), Some(true));
+
+        let exported = tree.export();
+        assert_eq!(exported.len(), 5);
+        assert_eq!(exported[4].time, 3);
+        assert!(tree.export_json().unwrap().contains("then X1"));
+
+        assert_eq!(tree.prune(halfway).unwrap(), 3);
+        assert_eq!(tree.leaves(), vec![one]);
+        assert!(tree.node(end).is_none());
+        assert!(tree.prune(ScenarioTree::ROOT).is_err());
+    }
+}
@@ -0,0 +1,104 @@
+//! Counterexample shrinking.
+//!
+//! Given a fault set for which some check fails, greedily drop faults and
+//! simplify Y faults to X or Z while the check keeps failing, until no
+//! single step preserves the failure.
+
+use crate::analysis::certification::{check_faults, FtViolation};
+use crate::analysis::fault_sweep::Fault;
+use crate::codes::{Gadget, StabilizerCode};
+use crate::physics::pauli::SinglePauli;
+
+/// Shrinks `faults` to a locally minimal set on which `fails` still holds.
+/// `fails(faults)` must be true for the input.
+pub fn shrink_faults<F>(faults: &[Fault], mut fails: F) -> Vec<Fault>
+where
+    F: FnMut(&[Fault]) -> bool,
+{
+    let mut current = faults.to_vec();
+    loop {
+        if let Some(smaller) = (0..current.len())
+            .map(|skip| {
+                let mut candidate = current.clone();
+                candidate.remove(skip);
+                candidate
+            })
+            .find(|candidate| fails(candidate))
+        {
+            current = smaller;
+            continue;
+        }
+
+        let simpler = current.iter().enumerate().find_map(|(index, fault)| {
+            if fault.pauli != SinglePauli::Y {
+                return None;
+            }
+            [SinglePauli::X, SinglePauli::Z].into_iter().find_map(|pauli| {
+                let mut candidate = current.clone();
+                candidate[index].pauli = pauli;
+                fails(&candidate).then_some(candidate)
+            })
+        });
+        match simpler {
+            Some(candidate) => current = candidate,
+            None => return current,
+        }
+    }
+}
+
+/// Shrinks a certification failure to a minimal fault set that still
+/// violates fault tolerance.
+pub fn minimize_violation(gadget: &Gadget, code: &StabilizerCode, violation: &FtViolation) -> FtViolation {
+    let faults = shrink_faults(&violation.faults, |faults| check_faults(gadget, code, faults).is_some());
+    check_faults(gadget, code, &faults).unwrap_or_else(|| violation.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::MeasurementBasis;
+    use crate::physics::circuit::{Circuit, Gate, TwoGate};
+
+    fn fault(after_gate: usize, qubit: usize, pauli: SinglePauli) -> Fault {
+        Fault {
+            after_gate,
+            qubit,
+            pauli,
+        }
+    }
+
+    #[test]
+    fn test_shrink_with_predicate() {
+        let faults = vec![
+            fault(0, 0, SinglePauli::Z),
+            fault(1, 2, SinglePauli::Y),
+            fault(2, 1, SinglePauli::X),
+        ];
+        // Fails whenever some fault on qubit 2 has an X component
+        let shrunk = shrink_faults(&faults, |faults| {
+            faults
+                .iter()
+                .any(|f| f.qubit == 2 && matches!(f.pauli, SinglePauli::X | SinglePauli::Y))
+        });
+        assert_eq!(shrunk, vec![fault(1, 2, SinglePauli::X)]);
+    }
+
+    #[test]
+    fn test_minimize_certification_violation() {
+        let mut circuit = Circuit::new(4);
+        for target in 0..3 {
+            circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 3, target })).unwrap();
+        }
+        circuit.add_measurement(3, MeasurementBasis::Z).unwrap();
+        let gadget = Gadget::new(circuit, vec![0, 1, 2]).unwrap();
+        let code = StabilizerCode::repetition(3);
+
+        // A Y hook on the ancilla, plus a data fault that only adds weight
+        let blob = vec![fault(0, 3, SinglePauli::Y), fault(2, 0, SinglePauli::X)];
+        let violation = check_faults(&gadget, &code, &blob).unwrap();
+
+        let minimal = minimize_violation(&gadget, &code, &violation);
+        assert_eq!(minimal.faults, vec![fault(0, 3, SinglePauli::X)]);
+        assert_eq!(minimal.reduced_weight, 2);
+    }
+}
@@ -0,0 +1,498 @@
+//! Statistical post-processing of sampled shot data.
+//!
+//! - Binomial confidence intervals (Wilson score, Clopper-Pearson exact)
+//! - Percentile bootstrap over shots
+//! - Curve fits for threshold and Λ-factor extraction
+
+use rand_core::Rng;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ConfidenceInterval {
+    pub estimate: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+/// Wilson score interval for `successes` out of `trials` at the given
+/// two-sided confidence level (e.g. 0.95).
+pub fn wilson_interval(successes: usize, trials: usize, confidence: f64) -> Result<ConfidenceInterval, String> {
+    check_binomial(successes, trials, confidence)?;
+
+    let n = trials as f64;
+    let p_hat = successes as f64 / n;
+    let z = normal_quantile(0.5 + confidence / 2.0);
+    let z2 = z * z;
+
+    let denominator = 1.0 + z2 / n;
+    let center = (p_hat + z2 / (2.0 * n)) / denominator;
+    let half_width = z / denominator * (p_hat * (1.0 - p_hat) / n + z2 / (4.0 * n * n)).sqrt();
+
+    Ok(ConfidenceInterval {
+        estimate: p_hat,
+        lower: (center - half_width).max(0.0),
+        upper: (center + half_width).min(1.0),
+    })
+}
+
+/// Clopper-Pearson ("exact") interval, obtained by inverting the binomial
+/// CDF through the regularized incomplete beta function.
+pub fn clopper_pearson_interval(successes: usize, trials: usize, confidence: f64) -> Result<ConfidenceInterval, String> {
+    check_binomial(successes, trials, confidence)?;
+
+    let alpha = 1.0 - confidence;
+    let k = successes as f64;
+    let n = trials as f64;
+
+    let lower = if successes == 0 {
+        0.0
+    } else {
+        inverse_regularized_beta(alpha / 2.0, k, n - k + 1.0)
+    };
+    let upper = if successes == trials {
+        1.0
+    } else {
+        inverse_regularized_beta(1.0 - alpha / 2.0, k + 1.0, n - k)
+    };
+
+    Ok(ConfidenceInterval {
+        estimate: k / n,
+        lower,
+        upper,
+    })
+}
+
+/// Percentile bootstrap: resamples `samples` with replacement `resamples`
+/// times and reports the empirical quantiles of `statistic`.
+pub fn bootstrap<T, F, R>(
+    samples: &[T],
+    statistic: F,
+    resamples: usize,
+    confidence: f64,
+    rng: &mut R,
+) -> Result<ConfidenceInterval, String>
+where
+    T: Clone,
+    F: Fn(&[T]) -> f64,
+    R: Rng + ?Sized,
+{
+    if samples.is_empty() {
+        return Err("Cannot bootstrap an empty sample".to_string());
+    }
+    if resamples == 0 {
+        return Err("Number of resamples must be positive".to_string());
+    }
+    check_confidence(confidence)?;
+
+    let n = samples.len();
+    let mut buffer = Vec::with_capacity(n);
+    let mut values = Vec::with_capacity(resamples);
+    for _ in 0..resamples {
+        buffer.clear();
+        for _ in 0..n {
+            // Multiply-shift maps a uniform u64 onto 0..n without modulo bias
+            let index = ((rng.next_u64() as u128 * n as u128) >> 64) as usize;
+            buffer.push(samples[index].clone());
+        }
+        values.push(statistic(&buffer));
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+
+    let alpha = 1.0 - confidence;
+    Ok(ConfidenceInterval {
+        estimate: statistic(samples),
+        lower: quantile_sorted(&values, alpha / 2.0),
+        upper: quantile_sorted(&values, 1.0 - alpha / 2.0),
+    })
+}
+
+/// Fraction of `true` entries, e.g. the logical error rate of a set of shots.
+pub fn failure_rate(shots: &[bool]) -> f64 {
+    if shots.is_empty() {
+        return 0.0;
+    }
+    shots.iter().filter(|&&failed| failed).count() as f64 / shots.len() as f64
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LinearFit {
+    pub slope: f64,
+    pub intercept: f64,
+    pub r_squared: f64,
+}
+
+/// Ordinary least-squares fit of `ys` against `xs`.
+pub fn linear_fit(xs: &[f64], ys: &[f64]) -> Result<LinearFit, String> {
+    if xs.len() != ys.len() {
+        return Err(format!("Length mismatch: {} x values, {} y values", xs.len(), ys.len()));
+    }
+    if xs.len() < 2 {
+        return Err("Linear fit requires at least two points".to_string());
+    }
+
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+
+    let mut sxx = 0.0;
+    let mut sxy = 0.0;
+    let mut syy = 0.0;
+    for (x, y) in xs.iter().zip(ys) {
+        sxx += (x - mean_x) * (x - mean_x);
+        sxy += (x - mean_x) * (y - mean_y);
+        syy += (y - mean_y) * (y - mean_y);
+    }
+    if sxx == 0.0 {
+        return Err("Linear fit requires at least two distinct x values".to_string());
+    }
+
+    let slope = sxy / sxx;
+    let r_squared = if syy == 0.0 { 1.0 } else { sxy * sxy / (sxx * syy) };
+    Ok(LinearFit {
+        slope,
+        intercept: mean_y - slope * mean_x,
+        r_squared,
+    })
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LambdaFit {
+    /// Error suppression factor per distance step d -> d + 2
+    pub lambda: f64,
+    /// Prefactor A in p_L(d) = A / Λ^((d + 1) / 2)
+    pub prefactor: f64,
+    pub r_squared: f64,
+}
+
+/// Fits p_L(d) = A / Λ^((d + 1) / 2) to logical error rates measured at
+/// several code distances (log-linear least squares).
+pub fn fit_lambda(distances: &[usize], logical_error_rates: &[f64]) -> Result<LambdaFit, String> {
+    if distances.len() != logical_error_rates.len() {
+        return Err(format!(
+            "Length mismatch: {} distances, {} error rates",
+            distances.len(),
+            logical_error_rates.len()
+        ));
+    }
+    if let Some(rate) = logical_error_rates.iter().find(|&&p| p <= 0.0 || p > 1.0) {
+        return Err(format!("Logical error rate {} must be in (0, 1]", rate));
+    }
+
+    let xs: Vec<f64> = distances.iter().map(|&d| (d as f64 + 1.0) / 2.0).collect();
+    let ys: Vec<f64> = logical_error_rates.iter().map(|p| p.ln()).collect();
+    let fit = linear_fit(&xs, &ys)?;
+
+    Ok(LambdaFit {
+        lambda: (-fit.slope).exp(),
+        prefactor: fit.intercept.exp(),
+        r_squared: fit.r_squared,
+    })
+}
+
+/// Logical error rate as a function of physical error rate for one distance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThresholdCurve {
+    pub distance: usize,
+    pub physical_error_rates: Vec<f64>,
+    pub logical_error_rates: Vec<f64>,
+}
+
+/// Estimates the threshold as the mean crossing point of consecutive-distance
+/// curves, interpolating linearly in log-log space. All curves must share
+/// the same physical error rate grid.
+pub fn estimate_threshold(curves: &[ThresholdCurve]) -> Result<f64, String> {
+    if curves.len() < 2 {
+        return Err("Threshold estimation requires curves for at least two distances".to_string());
+    }
+    let grid = &curves[0].physical_error_rates;
+    for curve in curves {
+        if curve.physical_error_rates != *grid || curve.logical_error_rates.len() != grid.len() {
+            return Err(format!(
+                "Curve for distance {} does not match the shared physical error rate grid",
+                curve.distance
+            ));
+        }
+        if curve.physical_error_rates.iter().chain(&curve.logical_error_rates).any(|&p| p <= 0.0) {
+            return Err(format!("Curve for distance {} contains non-positive rates", curve.distance));
+        }
+    }
+
+    let mut sorted: Vec<&ThresholdCurve> = curves.iter().collect();
+    sorted.sort_by_key(|curve| curve.distance);
+
+    let mut crossings = Vec::new();
+    for pair in sorted.windows(2) {
+        let (small, large) = (pair[0], pair[1]);
+        // Sign of log(p_L(large) / p_L(small)) flips at the crossing
+        let diffs: Vec<f64> = small
+            .logical_error_rates
+            .iter()
+            .zip(&large.logical_error_rates)
+            .map(|(a, b)| b.ln() - a.ln())
+            .collect();
+        for i in 0..diffs.len().saturating_sub(1) {
+            let (d0, d1) = (diffs[i], diffs[i + 1]);
+            if d0 == 0.0 {
+                crossings.push(grid[i]);
+            } else if d0.signum() != d1.signum() && d1 != 0.0 {
+                let (x0, x1) = (grid[i].ln(), grid[i + 1].ln());
+                crossings.push((x0 + (x1 - x0) * d0 / (d0 - d1)).exp());
+            }
+        }
+    }
+
+    if crossings.is_empty() {
+        return Err("Curves do not cross within the sampled physical error rates".to_string());
+    }
+    Ok(crossings.iter().sum::<f64>() / crossings.len() as f64)
+}
+
+fn check_confidence(confidence: f64) -> Result<(), String> {
+    if !(confidence > 0.0 && confidence < 1.0) {
+        return Err(format!("Confidence level {} must be in (0, 1)", confidence));
+    }
+    Ok(())
+}
+
+fn check_binomial(successes: usize, trials: usize, confidence: f64) -> Result<(), String> {
+    if trials == 0 {
+        return Err("Number of trials must be positive".to_string());
+    }
+    if successes > trials {
+        return Err(format!("Successes {} exceed trials {}", successes, trials));
+    }
+    check_confidence(confidence)
+}
+
+fn quantile_sorted(values: &[f64], q: f64) -> f64 {
+    let position = q * (values.len() - 1) as f64;
+    let low = position.floor() as usize;
+    let high = position.ceil() as usize;
+    values[low] + (values[high] - values[low]) * (position - low as f64)
+}
+
+/// Inverse of the standard normal CDF (Acklam's rational approximation,
+/// relative error below 1.2e-9).
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239e0,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838e0,
+        -2.549732539343734e0,
+        4.374664141464968e0,
+        2.938163982698783e0,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996e0,
+        3.754408661907416e0,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    if p < P_LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -normal_quantile(1.0 - p)
+    }
+}
+
+/// Lanczos approximation of ln Γ(x) for x > 0.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 6] = [
+        76.18009172947146,
+        -86.50532032941677,
+        24.01409824083091,
+        -1.231739572450155,
+        0.1208650973866179e-2,
+        -0.5395239384953e-5,
+    ];
+    let tmp = x + 5.5 - (x + 0.5) * (x + 5.5).ln();
+    let mut series = 1.000000000190015;
+    for (j, coeff) in COEFFS.iter().enumerate() {
+        series += coeff / (x + 1.0 + j as f64);
+    }
+    -tmp + (2.5066282746310005 * series / x).ln()
+}
+
+/// Regularized incomplete beta function I_x(a, b).
+fn regularized_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    // The continued fraction converges quickly only on this side of the mean
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_front.exp() * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - ln_front.exp() * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+/// Lentz evaluation of the continued fraction for I_x(a, b).
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 300;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        let even = m * (b - m) * x / ((a + m2 - 1.0) * (a + m2));
+        d = 1.0 + even * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + even / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        h *= d * c;
+
+        let odd = -(a + m) * (a + b + m) * x / ((a + m2) * (a + m2 + 1.0));
+        d = 1.0 + odd * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + odd / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+/// Solves I_x(a, b) = target for x by bisection (I_x is monotone in x).
+fn inverse_regularized_beta(target: f64, a: f64, b: f64) -> f64 {
+    let mut low = 0.0;
+    let mut high = 1.0;
+    for _ in 0..100 {
+        let mid = (low + high) / 2.0;
+        if regularized_beta(mid, a, b) < target {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_wilson_interval() {
+        // 10 failures in 100 shots: reference values (0.0552, 0.1744)
+        let ci = wilson_interval(10, 100, 0.95).unwrap();
+        assert!((ci.estimate - 0.1).abs() < 1e-12);
+        assert!((ci.lower - 0.0552).abs() < 1e-3);
+        assert!((ci.upper - 0.1744).abs() < 1e-3);
+
+        assert!(wilson_interval(0, 0, 0.95).is_err());
+        assert!(wilson_interval(5, 4, 0.95).is_err());
+    }
+
+    #[test]
+    fn test_clopper_pearson_interval() {
+        // 10 failures in 100 shots: reference values (0.0490, 0.1762)
+        let ci = clopper_pearson_interval(10, 100, 0.95).unwrap();
+        assert!((ci.lower - 0.0490).abs() < 1e-3);
+        assert!((ci.upper - 0.1762).abs() < 1e-3);
+
+        // Zero failures: upper bound follows the "rule of three"-like closed form
+        let ci = clopper_pearson_interval(0, 50, 0.95).unwrap();
+        assert_eq!(ci.lower, 0.0);
+        assert!((ci.upper - (1.0 - 0.025f64.powf(1.0 / 50.0))).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bootstrap_is_reproducible() {
+        let shots: Vec<bool> = (0..200).map(|i| i % 5 == 0).collect();
+
+        let mut rng = SmallRng::seed_from_u64(7);
+        let first = bootstrap(&shots, failure_rate, 500, 0.95, &mut rng).unwrap();
+        let mut rng = SmallRng::seed_from_u64(7);
+        let second = bootstrap(&shots, failure_rate, 500, 0.95, &mut rng).unwrap();
+
+        assert_eq!(first, second);
+        assert!((first.estimate - 0.2).abs() < 1e-12);
+        assert!(first.lower < 0.2 && first.upper > 0.2);
+    }
+
+    #[test]
+    fn test_fit_lambda() {
+        // p_L = 0.1 / 4^((d + 1) / 2)
+        let distances = [3, 5, 7];
+        let rates: Vec<f64> = distances
+            .iter()
+            .map(|&d| 0.1 / 4f64.powf((d as f64 + 1.0) / 2.0))
+            .collect();
+        let fit = fit_lambda(&distances, &rates).unwrap();
+        assert!((fit.lambda - 4.0).abs() < 1e-9);
+        assert!((fit.prefactor - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_estimate_threshold() {
+        // p_L(d) = (p / p_th)^((d + 1) / 2) crosses exactly at p_th
+        let p_th: f64 = 0.01;
+        let grid = vec![0.002, 0.005, 0.008, 0.012, 0.02];
+        let curves: Vec<ThresholdCurve> = [3usize, 5]
+            .iter()
+            .map(|&d| ThresholdCurve {
+                distance: d,
+                physical_error_rates: grid.clone(),
+                logical_error_rates: grid
+                    .iter()
+                    .map(|p| (p / p_th).powf((d as f64 + 1.0) / 2.0) * 0.1)
+                    .collect(),
+            })
+            .collect();
+        let threshold = estimate_threshold(&curves).unwrap();
+        assert!((threshold - p_th).abs() < 1e-9);
+    }
+}
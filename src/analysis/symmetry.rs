@@ -0,0 +1,97 @@
+//! Conservation checks for declared symmetries.
+//!
+//! A symmetry is a Pauli operator (total parity, a sublattice parity, a
+//! conserved logical) that a construction is meant to preserve. Every gate
+//! that does not map it to itself is flagged; a circuit can still preserve
+//! the symmetry overall when later gates undo the damage, which is
+//! reported separately.
+
+use crate::analysis::commutation::{commutes_with_circuit, obstructions, Obstruction};
+use crate::physics::circuit::Circuit;
+use crate::physics::pauli::{PauliString, SinglePauli};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Symmetry {
+    pub name: String,
+    pub operator: PauliString,
+}
+
+impl Symmetry {
+    pub fn new(name: &str, operator: PauliString) -> Self {
+        Self {
+            name: name.to_string(),
+            operator,
+        }
+    }
+
+    /// `pauli` on every qubit, e.g. total Z parity.
+    pub fn total_parity(num_qubits: usize, pauli: SinglePauli) -> Self {
+        let mut operator = PauliString::new(num_qubits);
+        for qubit in 0..num_qubits {
+            operator.set_pauli(qubit, pauli);
+        }
+        Self::new(&format!("total {:?} parity", pauli), operator)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymmetryCheck {
+    pub name: String,
+    /// Gates that break the symmetry, in circuit order
+    pub breaking_gates: Vec<Obstruction>,
+    /// The circuit as a whole maps the operator to itself
+    pub preserved_by_circuit: bool,
+}
+
+impl SymmetryCheck {
+    /// Conserved at every moment of the circuit.
+    pub fn is_conserved(&self) -> bool {
+        self.breaking_gates.is_empty()
+    }
+}
+
+/// One check per symmetry, in the order given.
+pub fn check_symmetries(circuit: &Circuit, symmetries: &[Symmetry]) -> Result<Vec<SymmetryCheck>, String> {
+    symmetries
+        .iter()
+        .map(|symmetry| {
+            let context = |e: String| format!("Symmetry '{}': {}", symmetry.name, e);
+            Ok(SymmetryCheck {
+                name: symmetry.name.clone(),
+                breaking_gates: obstructions(circuit, &symmetry.operator).map_err(context)?,
+                preserved_by_circuit: commutes_with_circuit(circuit, &symmetry.operator).map_err(context)?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{Gate, SingleGate, TwoGate};
+
+    #[test]
+    fn test_parity_breaking_gates() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(Gate::Two(TwoGate::CZ { control: 0, target: 1 })).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::X }).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 1, gate: SingleGate::X }).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::SWAP { qubit1: 0, qubit2: 1 })).unwrap();
+
+        let symmetries = [
+            Symmetry::total_parity(2, SinglePauli::Z),
+            Symmetry::total_parity(2, SinglePauli::X),
+        ];
+        let checks = check_symmetries(&circuit, &symmetries).unwrap();
+        // Each X flips the sign of ZZ, together they restore it
+        assert_eq!(checks[0].breaking_gates.iter().map(|o| o.gate).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(checks[0].preserved_by_circuit && !checks[0].is_conserved());
+        // CZ maps XX to YY
+        assert_eq!(checks[1].breaking_gates.iter().map(|o| o.gate).collect::<Vec<_>>(), vec![0]);
+        assert!(!checks[1].preserved_by_circuit);
+
+        let wrong = Symmetry::new("short", PauliString::new(1));
+        assert!(check_symmetries(&circuit, &[wrong]).unwrap_err().contains("short"));
+    }
+}
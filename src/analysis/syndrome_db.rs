@@ -0,0 +1,174 @@
+//! Precomputed syndrome patterns of a gadget.
+//!
+//! Every combination of at most `max_faults` faults (at distinct locations)
+//! is propagated through the gadget once; the resulting measurement flips
+//! and residual data error are stored and indexed by syndrome. Intended for
+//! small gadgets: the number of entries grows as (3L)^k for L locations.
+//! Entries are ordered by fault count, then by `fault_locations` order.
+
+use crate::analysis::fault_sweep::{fault_locations, propagate_faults, Fault};
+use crate::codes::{Gadget, StabilizerCode};
+use crate::physics::pauli::PauliString;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyndromeEntry {
+    pub faults: Vec<Fault>,
+    /// Flipped gadget measurements, in `Gadget::measurements` order
+    pub syndrome: Vec<bool>,
+    /// Error left on the code block, indexed by code qubit
+    pub residual: PauliString,
+    /// Code syndrome of `residual`, as an ideal next round would see it
+    pub residual_syndrome: Vec<bool>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SyndromeDatabase {
+    pub max_faults: usize,
+    entries: Vec<SyndromeEntry>,
+    by_syndrome: BTreeMap<Vec<bool>, Vec<usize>>,
+}
+
+impl SyndromeDatabase {
+    pub fn build(gadget: &Gadget, code: &StabilizerCode, max_faults: usize) -> Result<Self, String> {
+        if gadget.data_qubits.len() != code.num_qubits {
+            return Err(format!(
+                "Gadget has {} data qubits but code '{}' has {}",
+                gadget.data_qubits.len(),
+                code.name,
+                code.num_qubits
+            ));
+        }
+
+        let locations = fault_locations(&gadget.circuit);
+        let mut entries = Vec::new();
+        let mut chosen = Vec::new();
+        for count in 0..=max_faults {
+            collect_combinations(&locations, 0, count, &mut chosen, &mut |faults| {
+                let error = propagate_faults(&gadget.circuit, faults);
+                let residual = gadget.data_error(&error);
+                entries.push(SyndromeEntry {
+                    faults: faults.to_vec(),
+                    syndrome: gadget.measurement_flips(&error),
+                    residual_syndrome: code.syndrome(&residual),
+                    residual,
+                });
+            });
+        }
+
+        let mut by_syndrome: BTreeMap<Vec<bool>, Vec<usize>> = BTreeMap::new();
+        for (index, entry) in entries.iter().enumerate() {
+            by_syndrome.entry(entry.syndrome.clone()).or_default().push(index);
+        }
+        Ok(Self {
+            max_faults,
+            entries,
+            by_syndrome,
+        })
+    }
+
+    pub fn entries(&self) -> &[SyndromeEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// All fault combinations producing `syndrome`, fewest faults first.
+    pub fn lookup(&self, syndrome: &[bool]) -> Vec<&SyndromeEntry> {
+        self.by_syndrome
+            .get(syndrome)
+            .map(|indices| indices.iter().map(|&i| &self.entries[i]).collect())
+            .unwrap_or_default()
+    }
+
+    /// Lookup decoding: the residual of a minimum-fault explanation.
+    pub fn most_likely(&self, syndrome: &[bool]) -> Option<&SyndromeEntry> {
+        self.by_syndrome.get(syndrome).map(|indices| &self.entries[indices[0]])
+    }
+}
+
+/// Calls `visit` with every choice of `remaining` faults from
+/// `locations[start..]`, at most one Pauli per (gate, qubit) location.
+/// Relies on `fault_locations` listing the three Paulis of a location
+/// consecutively.
+pub(crate) fn collect_combinations(
+    locations: &[Fault],
+    start: usize,
+    remaining: usize,
+    chosen: &mut Vec<Fault>,
+    visit: &mut dyn FnMut(&[Fault]),
+) {
+    if remaining == 0 {
+        visit(chosen);
+        return;
+    }
+    for index in start..locations.len() {
+        let fault = &locations[index];
+        let next_location = locations[index..]
+            .iter()
+            .position(|f| (f.after_gate, f.qubit) != (fault.after_gate, fault.qubit))
+            .map_or(locations.len(), |offset| index + offset);
+        chosen.push(fault.clone());
+        collect_combinations(locations, next_location, remaining - 1, chosen, visit);
+        chosen.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::MeasurementBasis;
+    use crate::physics::circuit::{Circuit, Gate, TwoGate};
+    use crate::physics::pauli::SinglePauli;
+
+    /// ZZ checks of the 3-qubit repetition code onto ancillas 3 and 4.
+    fn repetition_round() -> Gadget {
+        let mut circuit = Circuit::new(5);
+        for (control, target) in [(0, 3), (1, 3), (1, 4), (2, 4)] {
+            circuit.add_gate(Gate::Two(TwoGate::CNOT { control, target })).unwrap();
+        }
+        for qubit in [3, 4] {
+            circuit.add_measurement(qubit, MeasurementBasis::Z).unwrap();
+        }
+        Gadget::new(circuit, vec![0, 1, 2]).unwrap()
+    }
+
+    #[test]
+    fn test_entry_counts() {
+        let gadget = repetition_round();
+        let code = StabilizerCode::repetition(3);
+        // 4 gates x 2 operands x 3 Paulis
+        assert_eq!(SyndromeDatabase::build(&gadget, &code, 0).unwrap().len(), 1);
+        assert_eq!(SyndromeDatabase::build(&gadget, &code, 1).unwrap().len(), 25);
+        // Pairs of distinct locations: C(8, 2) * 9
+        assert_eq!(SyndromeDatabase::build(&gadget, &code, 2).unwrap().len(), 25 + 28 * 9);
+    }
+
+    #[test]
+    fn test_lookup() {
+        let gadget = repetition_round();
+        let code = StabilizerCode::repetition(3);
+        let database = SyndromeDatabase::build(&gadget, &code, 1).unwrap();
+
+        let clean = database.most_likely(&[false, false]).unwrap();
+        assert!(clean.faults.is_empty());
+
+        // X on data qubit 1 after its first CNOT reaches only ancilla 4
+        let entries = database.lookup(&[false, true]);
+        assert!(entries.iter().any(|entry| entry.faults
+            == vec![Fault {
+                after_gate: 1,
+                qubit: 1,
+                pauli: SinglePauli::X
+            }]
+            && entry.residual == PauliString::from_str("IXI", 3).unwrap()
+            && entry.residual_syndrome == vec![true, true]));
+    }
+}
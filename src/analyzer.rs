@@ -0,0 +1,182 @@
+//! One-stop facade over the analysis subsystems.
+//!
+//! `Analyzer` bundles a gadget, its code, a noise model, a decoder and a
+//! few options, so the common questions take one call each: which fault
+//! combinations go undetected (`analyze_sweep`), how often the decoder
+//! fails (`estimate_logical_error`), and a self-contained HTML page with
+//! both (`report_html`). Anything finer-grained is still available from
+//! the underlying modules.
+
+use crate::analysis::coverage::{detection_coverage, DetectionCoverage};
+use crate::codes::{Gadget, StabilizerCode};
+use crate::decoding::{verify_decoder, Decoder, DecodingReport, LookupDecoder};
+use crate::noise::model::NoiseModel;
+use crate::noise::sampler::sample_shots;
+use crate::physics::circuit::Circuit;
+use rand_core::Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnalyzerOptions {
+    /// Shots sampled by `estimate_logical_error`
+    pub shots: usize,
+    /// Largest fault combination `analyze_sweep` enumerates
+    pub max_faults: usize,
+}
+
+impl Default for AnalyzerOptions {
+    fn default() -> Self {
+        Self {
+            shots: 1000,
+            max_faults: 1,
+        }
+    }
+}
+
+pub struct Analyzer {
+    pub gadget: Gadget,
+    pub code: StabilizerCode,
+    pub noise: NoiseModel,
+    pub options: AnalyzerOptions,
+    decoder: Box<dyn Decoder>,
+}
+
+impl Analyzer {
+    /// Noiseless, with the code's lookup decoder and default options.
+    pub fn new(gadget: Gadget, code: StabilizerCode) -> Result<Self, String> {
+        if gadget.data_qubits.len() != code.num_qubits {
+            return Err(format!(
+                "Gadget has {} data qubits but code '{}' has {}",
+                gadget.data_qubits.len(),
+                code.name,
+                code.num_qubits
+            ));
+        }
+        let decoder = Box::new(LookupDecoder::new(&code));
+        Ok(Self {
+            gadget,
+            code,
+            noise: NoiseModel::default(),
+            options: AnalyzerOptions::default(),
+            decoder,
+        })
+    }
+
+    /// A circuit whose first `code.num_qubits` qubits hold the code block
+    /// and which measures nothing.
+    pub fn from_circuit(circuit: Circuit, code: StabilizerCode) -> Result<Self, String> {
+        let data_qubits = (0..code.num_qubits).collect();
+        Self::new(Gadget::new(circuit, data_qubits)?, code)
+    }
+
+    pub fn with_noise(mut self, noise: NoiseModel) -> Self {
+        self.noise = noise;
+        self
+    }
+
+    pub fn with_decoder(mut self, decoder: impl Decoder + 'static) -> Self {
+        self.decoder = Box::new(decoder);
+        self
+    }
+
+    pub fn with_options(mut self, options: AnalyzerOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Detection coverage of every combination of up to
+    /// `options.max_faults` faults.
+    pub fn analyze_sweep(&self) -> Result<DetectionCoverage, String> {
+        detection_coverage(&self.gadget, &self.code, self.options.max_faults)
+    }
+
+    /// Samples `options.shots` shots under the noise model and decodes them.
+    pub fn estimate_logical_error<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<DecodingReport, String> {
+        self.noise.validate()?;
+        let shots = sample_shots(&self.gadget, &self.code, &self.noise, self.options.shots, rng)?;
+        verify_decoder(self.decoder.as_mut(), &self.code, &shots)
+    }
+
+    /// Both analyses as a standalone HTML page.
+    pub fn report_html<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<String, String> {
+        let coverage = self.analyze_sweep()?;
+        let decoding = self.estimate_logical_error(rng)?;
+        let noise = serde_json::to_string_pretty(&self.noise).map_err(|e| e.to_string())?;
+
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str(&format!("<title>{} analysis</title>\n", escape(&self.code.name)));
+        html.push_str("</head>\n<body>\n");
+        html.push_str(&format!("<h1>{} analysis</h1>\n", escape(&self.code.name)));
+        html.push_str(&format!(
+            "<p>{} qubits, {} gates, {} data qubits, {} measurements</p>\n",
+            self.gadget.circuit.num_qubits,
+            self.gadget.circuit.gates.len(),
+            self.gadget.data_qubits.len(),
+            self.gadget.measurements().len()
+        ));
+
+        html.push_str("<h2>Detection coverage</h2>\n<table>\n");
+        html.push_str("<tr><th>Faults</th><th>Combinations</th><th>Detected</th><th>Coverage</th><th>Undetected logical errors</th></tr>\n");
+        for level in &coverage.levels {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.4}</td><td>{}</td></tr>\n",
+                level.fault_count,
+                level.combinations,
+                level.detected,
+                level.coverage(),
+                level.logical_errors().count()
+            ));
+        }
+        html.push_str("</table>\n");
+
+        html.push_str("<h2>Logical error rate</h2>\n");
+        html.push_str(&format!(
+            "<p>{} failures in {} shots: {:.6}</p>\n",
+            decoding.failures.len(),
+            decoding.shots,
+            decoding.logical_error_rate()
+        ));
+        html.push_str(&format!("<h3>Noise model</h3>\n<pre>{}</pre>\n", escape(&noise)));
+        html.push_str("</body>\n</html>\n");
+        Ok(html)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{Gate, SingleGate};
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_analyzer_one_call_methods() {
+        let mut circuit = Circuit::new(3);
+        for qubit in 0..3 {
+            circuit.add_gate(Gate::Single { qubit, gate: SingleGate::I }).unwrap();
+        }
+        let mut analyzer = Analyzer::from_circuit(circuit, StabilizerCode::repetition(3))
+            .unwrap()
+            .with_noise(NoiseModel::code_capacity(0.05))
+            .with_options(AnalyzerOptions {
+                shots: 200,
+                max_faults: 2,
+            });
+
+        let coverage = analyzer.analyze_sweep().unwrap();
+        assert_eq!(coverage.levels.len(), 2);
+        // Phase flips on an idle repetition block are never detected
+        assert!(coverage.levels[0].logical_errors().count() > 0);
+
+        let mut rng = SmallRng::seed_from_u64(2254);
+        assert_eq!(analyzer.estimate_logical_error(&mut rng).unwrap().shots, 200);
+        let html = analyzer.report_html(&mut rng).unwrap();
+        assert!(html.contains("<h1>repetition-3 analysis</h1>"));
+        assert!(html.contains("Detection coverage"));
+    }
+}
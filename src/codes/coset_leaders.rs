@@ -0,0 +1,168 @@
+//! Minimum-weight coset leaders and code distance.
+//!
+//! Paulis are generated lazily in order of increasing weight, so leaders for
+//! low-weight syndromes (and the distance of a good code) are found without
+//! touching the 4^n elements of the full Pauli group.
+
+use crate::codes::stabilizer_code::{in_span, StabilizerCode};
+use crate::physics::pauli::{PauliString, SinglePauli};
+use std::collections::{BTreeMap, HashSet};
+
+const PAULIS: [SinglePauli; 3] = [SinglePauli::X, SinglePauli::Y, SinglePauli::Z];
+
+/// All n-qubit Paulis (without phase) of weight at most `max_weight`, by
+/// weight, then support, then X < Y < Z per qubit.
+pub struct PaulisByWeight {
+    num_qubits: usize,
+    max_weight: usize,
+    support: Vec<usize>,
+    letters: Vec<usize>,
+    done: bool,
+}
+
+impl PaulisByWeight {
+    pub fn new(num_qubits: usize, max_weight: usize) -> Self {
+        Self {
+            num_qubits,
+            max_weight: max_weight.min(num_qubits),
+            support: Vec::new(),
+            letters: Vec::new(),
+            done: false,
+        }
+    }
+
+    fn current(&self) -> PauliString {
+        let mut pauli = PauliString::new(self.num_qubits);
+        for (&qubit, &letter) in self.support.iter().zip(&self.letters) {
+            pauli.set_pauli(qubit, PAULIS[letter]);
+        }
+        pauli
+    }
+
+    /// Moves to the next element; false once everything has been produced.
+    fn advance(&mut self) -> bool {
+        // Next letter assignment on the same support
+        if let Some(i) = self.letters.iter().rposition(|&l| l < 2) {
+            self.letters[i] += 1;
+            self.letters[i + 1..].iter_mut().for_each(|l| *l = 0);
+            return true;
+        }
+        // Next support of the same weight
+        let w = self.support.len();
+        if let Some(i) = (0..w).rev().find(|&i| self.support[i] < self.num_qubits - w + i) {
+            self.support[i] += 1;
+            for j in i + 1..w {
+                self.support[j] = self.support[j - 1] + 1;
+            }
+            self.letters.iter_mut().for_each(|l| *l = 0);
+            return true;
+        }
+        // Next weight
+        if w < self.max_weight {
+            self.support = (0..w + 1).collect();
+            self.letters = vec![0; w + 1];
+            return true;
+        }
+        false
+    }
+}
+
+impl Iterator for PaulisByWeight {
+    type Item = PauliString;
+
+    fn next(&mut self) -> Option<PauliString> {
+        if self.done {
+            return None;
+        }
+        let pauli = self.current();
+        self.done = !self.advance();
+        Some(pauli)
+    }
+}
+
+/// Yields one minimum-weight error per syndrome, in order of increasing
+/// weight, stopping once every reachable syndrome has a leader.
+pub struct CosetLeaders<'a> {
+    code: &'a StabilizerCode,
+    paulis: PaulisByWeight,
+    seen: HashSet<Vec<bool>>,
+    remaining: usize,
+}
+
+impl Iterator for CosetLeaders<'_> {
+    type Item = (Vec<bool>, PauliString);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining > 0 {
+            let pauli = self.paulis.next()?;
+            let syndrome = self.code.syndrome(&pauli);
+            if self.seen.insert(syndrome.clone()) {
+                self.remaining -= 1;
+                return Some((syndrome, pauli));
+            }
+        }
+        None
+    }
+}
+
+impl StabilizerCode {
+    pub fn coset_leaders(&self) -> CosetLeaders<'_> {
+        CosetLeaders {
+            code: self,
+            paulis: PaulisByWeight::new(self.num_qubits, self.num_qubits),
+            seen: HashSet::new(),
+            remaining: 1 << rank(&self.stabilizers),
+        }
+    }
+
+    /// Lookup-decoder table: syndrome to minimum-weight correction, sorted
+    /// by syndrome.
+    pub fn lookup_table(&self) -> BTreeMap<Vec<bool>, PauliString> {
+        self.coset_leaders().collect()
+    }
+
+    /// Minimum weight of a logical operator, or None for a code without
+    /// logical qubits.
+    pub fn distance(&self) -> Option<usize> {
+        if self.num_logical_qubits() == 0 {
+            return None;
+        }
+        PaulisByWeight::new(self.num_qubits, self.num_qubits)
+            .skip(1)
+            .find(|pauli| self.is_logical_error(pauli))
+            .map(|pauli| pauli.weight())
+    }
+}
+
+/// Number of independent generators over GF(2).
+fn rank(generators: &[PauliString]) -> usize {
+    (0..generators.len())
+        .filter(|&i| !in_span(&generators[..i], &generators[i]))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paulis_by_weight_counts() {
+        // sum over w <= 2 of C(4, w) 3^w = 1 + 12 + 54
+        let all: Vec<PauliString> = PaulisByWeight::new(4, 2).collect();
+        assert_eq!(all.len(), 67);
+        assert_eq!(all[1], PauliString::from_str("XIII", 4).unwrap());
+        assert_eq!(PaulisByWeight::new(2, 2).count(), 16);
+    }
+
+    #[test]
+    fn test_steane_leaders_and_distance() {
+        let code = StabilizerCode::steane();
+        let table = code.lookup_table();
+        // 64 syndromes: identity, 21 single-qubit errors, 42 of weight two
+        assert_eq!(table.len(), 64);
+        let weights: Vec<usize> = table.values().map(PauliString::weight).collect();
+        assert_eq!(weights.iter().filter(|&&w| w == 1).count(), 21);
+        assert_eq!(code.distance(), Some(3));
+        assert_eq!(StabilizerCode::repetition(5).distance(), Some(1));
+    }
+}
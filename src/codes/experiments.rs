@@ -0,0 +1,253 @@
+//! Standard benchmark experiments on repetition codes.
+//!
+//! Every experiment repeats syndrome extraction for a number of rounds with
+//! fresh ancillas per round, followed by the final perfect readout of the
+//! shot layout (`noise::sampler::ShotRecord`). Detectors and observables are
+//! annotated as parities over the shot *record*: its detectors followed by
+//! its observables. Round-0 detectors compare against the deterministic
+//! initial outcome, later ones against the previous round, and the last set
+//! compares the final readout with the last round. The gates of round r
+//! form the circuit region `round r`, which noise schedules can target.
+
+use crate::analysis::determinism::{check_detectors, NondeterministicDetector};
+use crate::codes::gadget::{Gadget, MeasurementBasis};
+use crate::codes::stabilizer_code::StabilizerCode;
+use crate::noise::model::NoiseModel;
+use crate::noise::sampler::{sample_shots, ShotRecord};
+use crate::physics::circuit::{Circuit, Gate, SingleGate, TwoGate};
+use crate::physics::pauli::{PauliString, SinglePauli};
+use rand_core::Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Experiment {
+    pub name: String,
+    pub code: StabilizerCode,
+    pub gadget: Gadget,
+    pub noise: NoiseModel,
+    pub rounds: usize,
+    /// Each detector is the parity of these record entries
+    pub detectors: Vec<Vec<usize>>,
+    /// Each observable is the parity of these record entries
+    pub observables: Vec<Vec<usize>>,
+}
+
+impl Experiment {
+    pub fn sample_shots<R: Rng + ?Sized>(&self, shots: usize, rng: &mut R) -> Result<Vec<ShotRecord>, String> {
+        sample_shots(&self.gadget, &self.code, &self.noise, shots, rng)
+    }
+
+    /// Declared detectors and observables whose noiseless parity is random;
+    /// observables are numbered after the detectors.
+    pub fn nondeterministic_annotations(&self) -> Result<Vec<NondeterministicDetector>, String> {
+        let annotations: Vec<Vec<usize>> = self.detectors.iter().chain(&self.observables).cloned().collect();
+        check_detectors(&self.gadget, &self.code, &annotations)
+    }
+
+    /// The annotated detectors of `shot`.
+    pub fn detection_events(&self, shot: &ShotRecord) -> Vec<bool> {
+        parities(&self.detectors, shot)
+    }
+
+    /// The annotated observables of `shot`.
+    pub fn observable_flips(&self, shot: &ShotRecord) -> Vec<bool> {
+        parities(&self.observables, shot)
+    }
+}
+
+fn parities(annotations: &[Vec<usize>], shot: &ShotRecord) -> Vec<bool> {
+    let record: Vec<bool> = shot.detectors.iter().chain(&shot.observables).copied().collect();
+    annotations
+        .iter()
+        .map(|indices| indices.iter().fold(false, |parity, &i| parity ^ record[i]))
+        .collect()
+}
+
+/// Logical |0> memory on the bit-flip repetition code; the observable is
+/// the logical Z readout.
+pub fn memory_z(distance: usize, rounds: usize, noise: NoiseModel) -> Result<Experiment, String> {
+    check_distance(distance)?;
+    let code = StabilizerCode::repetition(distance);
+    memory(code, SinglePauli::Z, rounds, noise)
+}
+
+/// Logical |+> memory on the phase-flip repetition code; the observable is
+/// the logical X readout.
+pub fn memory_x(distance: usize, rounds: usize, noise: NoiseModel) -> Result<Experiment, String> {
+    check_distance(distance)?;
+    let code = StabilizerCode::phase_flip_repetition(distance);
+    memory(code, SinglePauli::X, rounds, noise)
+}
+
+/// Stability experiment on a ring of `distance` qubits with ZZ checks
+/// between neighbours. The checks multiply to the identity, so the parity of
+/// any one round is deterministic; the observable is the round-0 parity,
+/// which only time-like (measurement) error chains can flip.
+pub fn stability(distance: usize, rounds: usize, noise: NoiseModel) -> Result<Experiment, String> {
+    if distance < 3 {
+        return Err("Stability experiment needs a ring of at least 3 qubits".to_string());
+    }
+    let checks: Vec<PauliString> = (0..distance)
+        .map(|i| {
+            let mut check = PauliString::new(distance);
+            check.set_pauli(i, SinglePauli::Z);
+            check.set_pauli((i + 1) % distance, SinglePauli::Z);
+            check
+        })
+        .collect();
+    let code = StabilizerCode::new(&format!("ring-{}", distance), checks, Vec::new(), Vec::new())?;
+    let mut experiment = repeated_extraction(code, MeasurementBasis::Z, rounds, noise)?;
+    experiment.name = format!("stability-{}", distance);
+    experiment.observables = vec![(0..distance).collect()];
+    Ok(experiment)
+}
+
+fn check_distance(distance: usize) -> Result<(), String> {
+    if distance < 2 {
+        return Err("Repetition code needs distance >= 2".to_string());
+    }
+    Ok(())
+}
+
+fn memory(code: StabilizerCode, basis: SinglePauli, rounds: usize, noise: NoiseModel) -> Result<Experiment, String> {
+    let (check_basis, observable) = match basis {
+        // X errors flip the logical Z readout, observable 0
+        SinglePauli::Z => (MeasurementBasis::Z, 0),
+        _ => (MeasurementBasis::X, code.num_logical_qubits()),
+    };
+    let name = format!("memory-{}-{}", basis, code.num_qubits);
+    let mut experiment = repeated_extraction(code, check_basis, rounds, noise)?;
+    let record_observables = experiment.gadget.measurements().len() + experiment.code.stabilizers.len();
+    experiment.name = name;
+    experiment.observables = vec![vec![record_observables + observable]];
+    Ok(experiment)
+}
+
+/// `rounds` rounds of extraction of the weight-2 `code` checks, all of type
+/// `basis`, each on a fresh ancilla measured in that basis. Data qubits start
+/// in the +1 eigenstate of `basis`.
+fn repeated_extraction(
+    code: StabilizerCode,
+    basis: MeasurementBasis,
+    rounds: usize,
+    noise: NoiseModel,
+) -> Result<Experiment, String> {
+    if rounds == 0 {
+        return Err("An experiment needs at least one round".to_string());
+    }
+    let n = code.num_qubits;
+    let checks: Vec<Vec<usize>> = code
+        .stabilizers
+        .iter()
+        .map(PauliString::support)
+        .collect();
+
+    let mut circuit = Circuit::new(n + rounds * checks.len());
+    if basis == MeasurementBasis::X {
+        // Data starts in |+...+>, where the X checks are deterministic
+        for qubit in 0..n {
+            circuit.add_gate(Gate::Single { qubit, gate: SingleGate::H })?;
+        }
+    }
+    for round in 0..rounds {
+        let start = circuit.gates.len();
+        for (i, support) in checks.iter().enumerate() {
+            let ancilla = n + round * checks.len() + i;
+            if basis == MeasurementBasis::X {
+                circuit.add_gate(Gate::Single { qubit: ancilla, gate: SingleGate::H })?;
+            }
+            for &data in support {
+                let cnot = match basis {
+                    MeasurementBasis::Z => TwoGate::CNOT { control: data, target: ancilla },
+                    MeasurementBasis::X => TwoGate::CNOT { control: ancilla, target: data },
+                };
+                circuit.add_gate(Gate::Two(cnot))?;
+            }
+            circuit.add_measurement(ancilla, basis)?;
+        }
+        circuit.add_region(&format!("round {}", round), start, circuit.gates.len())?;
+    }
+    noise.validate_for(&circuit)?;
+
+    let per_round = checks.len();
+    let final_readout = rounds * per_round;
+    let mut detectors: Vec<Vec<usize>> = (0..per_round).map(|i| vec![i]).collect();
+    for round in 1..rounds {
+        for i in 0..per_round {
+            detectors.push(vec![(round - 1) * per_round + i, round * per_round + i]);
+        }
+    }
+    for i in 0..per_round {
+        detectors.push(vec![(rounds - 1) * per_round + i, final_readout + i]);
+    }
+
+    Ok(Experiment {
+        name: String::new(),
+        gadget: Gadget::new(circuit, (0..n).collect())?,
+        code,
+        noise,
+        rounds,
+        detectors,
+        observables: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_memory_layout_and_noiseless_shots() {
+        let experiment = memory_z(3, 2, NoiseModel::default()).unwrap();
+        assert_eq!(experiment.gadget.measurements().len(), 4);
+        // Two checks per round for two rounds, plus the final comparison
+        assert_eq!(experiment.detectors.len(), 6);
+        assert_eq!(experiment.observables, vec![vec![6]]);
+
+        let mut rng = SmallRng::seed_from_u64(2224);
+        for kind in [memory_z, memory_x, stability] {
+            let experiment = kind(3, 3, NoiseModel::default()).unwrap();
+            let shot = &experiment.sample_shots(1, &mut rng).unwrap()[0];
+            assert!(experiment.detection_events(shot).iter().all(|&d| !d));
+            assert!(experiment.observable_flips(shot).iter().all(|&o| !o));
+        }
+        assert!(memory_x(3, 0, NoiseModel::default()).is_err());
+        assert!(memory_z(1, 1, NoiseModel::default()).is_err());
+    }
+
+    #[test]
+    fn test_measurement_error_lights_two_detectors() {
+        // A flipped round-0 readout of the first ring check fires the
+        // round-0 and round-1 detectors and flips the observable
+        let experiment = stability(3, 2, NoiseModel::default()).unwrap();
+        let mut detectors = vec![false; 6 + 3];
+        detectors[0] = true;
+        let shot = ShotRecord {
+            faults: Vec::new(),
+            detectors,
+            observables: Vec::new(),
+            heralds: Vec::new(),
+            residual: PauliString::new(3),
+        };
+        let events = experiment.detection_events(&shot);
+        assert_eq!(events.iter().filter(|&&d| d).count(), 2);
+        assert!(events[0] && events[3]);
+        assert_eq!(experiment.observable_flips(&shot), vec![true]);
+
+        // Readout noise alone never flips a memory observable
+        let memory = memory_z(3, 2, NoiseModel::default()).unwrap();
+        let mut rng = SmallRng::seed_from_u64(2224);
+        let noisy = Experiment {
+            noise: NoiseModel {
+                measurement_flip: 0.5,
+                ..NoiseModel::default()
+            },
+            ..memory
+        };
+        for shot in noisy.sample_shots(20, &mut rng).unwrap() {
+            assert_eq!(noisy.observable_flips(&shot), vec![false]);
+        }
+    }
+}
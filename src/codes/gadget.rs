@@ -0,0 +1,81 @@
+use crate::physics::circuit::Circuit;
+pub use crate::physics::circuit::{Measure, MeasurementBasis};
+use crate::physics::pauli::PauliString;
+use serde::{Deserialize, Serialize};
+
+/// A circuit acting on one code block: `data_qubits[i]` holds code qubit i,
+/// and the circuit's measurements read out ancillas that no later gate
+/// touches, so the error at the end of the circuit decides their outcomes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Gadget {
+    pub circuit: Circuit,
+    pub data_qubits: Vec<usize>,
+}
+
+impl Gadget {
+    pub fn new(circuit: Circuit, data_qubits: Vec<usize>) -> Result<Self, String> {
+        if let Some(&qubit) = data_qubits.iter().find(|&&qubit| qubit >= circuit.num_qubits) {
+            return Err(format!(
+                "Qubit {} out of range for a {}-qubit circuit",
+                qubit, circuit.num_qubits
+            ));
+        }
+        circuit.validate_measurements()?;
+        for m in &circuit.measurements {
+            if data_qubits.contains(&m.qubit) {
+                return Err(format!("Data qubit {} cannot be measured", m.qubit));
+            }
+            if let Some(gate) = circuit.gates[m.position..].iter().find(|gate| gate.qubits().contains(&m.qubit)) {
+                return Err(format!("{} acts on qubit {} after it is measured", gate, m.qubit));
+            }
+        }
+        Ok(Self { circuit, data_qubits })
+    }
+
+    /// The ancilla readouts, in circuit order.
+    pub fn measurements(&self) -> &[Measure] {
+        &self.circuit.measurements
+    }
+
+    /// Which measurement outcomes `error` flips.
+    pub fn measurement_flips(&self, error: &PauliString) -> Vec<bool> {
+        self.measurements()
+            .iter()
+            .map(|m| m.basis.flipped_by(error, m.qubit))
+            .collect()
+    }
+
+    /// `error` restricted to the data qubits, indexed by code qubit.
+    pub fn data_error(&self, error: &PauliString) -> PauliString {
+        let mut restricted = PauliString::new(self.data_qubits.len());
+        for (i, &qubit) in self.data_qubits.iter().enumerate() {
+            restricted.set_pauli(i, error.get_pauli(qubit));
+        }
+        restricted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{Gate, SingleGate};
+
+    #[test]
+    fn test_flips_and_data_error() {
+        let mut circuit = Circuit::new(3);
+        circuit.add_measurement(1, MeasurementBasis::Z).unwrap();
+        let gadget = Gadget::new(circuit, vec![2, 0]).unwrap();
+
+        let error = PauliString::from_str("ZYX", 3).unwrap();
+        assert_eq!(gadget.measurement_flips(&error), vec![true]);
+        assert_eq!(gadget.data_error(&error), PauliString::from_str("XZ", 2).unwrap());
+
+        let mut on_data = Circuit::new(2);
+        on_data.add_measurement(0, MeasurementBasis::X).unwrap();
+        assert!(Gadget::new(on_data, vec![0]).is_err());
+        let mut reused = Circuit::new(2);
+        reused.add_measurement(1, MeasurementBasis::Z).unwrap();
+        reused.add_gate(Gate::Single { qubit: 1, gate: SingleGate::H }).unwrap();
+        assert!(Gadget::new(reused, vec![0]).unwrap_err().contains("after it is measured"));
+    }
+}
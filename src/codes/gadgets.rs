@@ -0,0 +1,322 @@
+//! Library of common fault-tolerance primitives.
+//!
+//! Each builder returns a gadget annotated with regions (`cat 0`,
+//! `verify 0`, `couple 0`, ...) so analyses can be scoped to one part of
+//! it. Extraction gadgets put the code block on qubits `0..n` and their
+//! ancillas after it, and say which measurements form each syndrome bit.
+
+use crate::codes::gadget::{Gadget, MeasurementBasis};
+use crate::codes::stabilizer_code::StabilizerCode;
+use crate::physics::circuit::{Circuit, Gate, SingleGate, TwoGate};
+use crate::physics::pauli::{PauliString, SinglePauli};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtractionGadget {
+    pub gadget: Gadget,
+    /// For each stabilizer of the code, in order, the measurements (indices
+    /// into `gadget.measurements()`) whose parity is its syndrome bit
+    pub syndrome_bits: Vec<Vec<usize>>,
+    /// Verification measurements; a 1 rejects the ancilla
+    pub verification: Vec<usize>,
+}
+
+impl ExtractionGadget {
+    /// Syndrome bits read off measurement outcomes.
+    pub fn syndrome(&self, outcomes: &[bool]) -> Vec<bool> {
+        self.syndrome_bits
+            .iter()
+            .map(|bits| bits.iter().fold(false, |parity, &m| parity ^ outcomes[m]))
+            .collect()
+    }
+}
+
+fn cnot(control: usize, target: usize) -> Gate {
+    Gate::Two(TwoGate::CNOT { control, target })
+}
+
+fn single(qubit: usize, gate: SingleGate) -> Gate {
+    Gate::Single { qubit, gate }
+}
+
+/// Appends gates built by `build` and labels them `name`.
+fn in_region<F>(circuit: &mut Circuit, name: &str, build: F) -> Result<(), String>
+where
+    F: FnOnce(&mut Circuit) -> Result<(), String>,
+{
+    let start = circuit.gates.len();
+    build(circuit)?;
+    let end = circuit.gates.len();
+    circuit.add_region(name, start, end)
+}
+
+/// Verification block: CNOTs copy the Z parity of `qubits` onto `ancilla`,
+/// which is then measured in Z.
+pub fn append_z_parity_check(circuit: &mut Circuit, qubits: &[usize], ancilla: usize) -> Result<(), String> {
+    for &qubit in qubits {
+        circuit.add_gate(cnot(qubit, ancilla))?;
+    }
+    Ok(())
+}
+
+/// Cat-state preparation (|0...0> + |1...1>) on `qubits` by a CNOT chain.
+fn append_cat(circuit: &mut Circuit, qubits: &[usize]) -> Result<(), String> {
+    circuit.add_gate(single(qubits[0], SingleGate::H))?;
+    for pair in qubits.windows(2) {
+        circuit.add_gate(cnot(pair[0], pair[1]))?;
+    }
+    Ok(())
+}
+
+/// A `width`-qubit cat state on qubits `0..width`. When `verified`, qubit
+/// `width` checks Z on the first and last cat qubits, catching the single
+/// chain faults that would spread into a high-weight error.
+pub fn cat_state(width: usize, verified: bool) -> Result<Gadget, String> {
+    if width == 0 {
+        return Err("A cat state needs at least one qubit".to_string());
+    }
+    let qubits: Vec<usize> = (0..width).collect();
+    let verifier = (verified && width > 1).then_some(width);
+    let mut circuit = Circuit::new(width + verifier.map_or(0, |_| 1));
+    in_region(&mut circuit, "cat", |c| append_cat(c, &qubits))?;
+    if let Some(ancilla) = verifier {
+        in_region(&mut circuit, "verify", |c| {
+            append_z_parity_check(c, &[0, width - 1], ancilla)
+        })?;
+        circuit.add_measurement(ancilla, MeasurementBasis::Z)?;
+    }
+    Gadget::new(circuit, qubits)
+}
+
+/// Shor-style extraction: each stabilizer is measured with its own cat
+/// state (optionally verified), coupled transversally and read out in X.
+pub fn shor_extraction(code: &StabilizerCode, verified: bool) -> Result<ExtractionGadget, String> {
+    let n = code.num_qubits;
+    let supports: Vec<Vec<(usize, SinglePauli)>> = code.stabilizers.iter().map(support).collect();
+    let ancillas: usize = supports
+        .iter()
+        .map(|s| s.len() + usize::from(verified && s.len() > 1))
+        .sum();
+    let mut circuit = Circuit::new(n + ancillas);
+    let mut syndrome_bits = Vec::new();
+    let mut verification = Vec::new();
+    let mut next = n;
+
+    for (index, support) in supports.iter().enumerate() {
+        if support.is_empty() {
+            return Err(format!("Stabilizer {} of code '{}' is the identity", index, code.name));
+        }
+        let cat: Vec<usize> = (next..next + support.len()).collect();
+        next += cat.len();
+        in_region(&mut circuit, &format!("cat {}", index), |c| append_cat(c, &cat))?;
+        if verified && cat.len() > 1 {
+            let ancilla = next;
+            next += 1;
+            in_region(&mut circuit, &format!("verify {}", index), |c| {
+                append_z_parity_check(c, &[cat[0], cat[cat.len() - 1]], ancilla)
+            })?;
+            verification.push(circuit.measurements.len());
+            circuit.add_measurement(ancilla, MeasurementBasis::Z)?;
+        }
+        in_region(&mut circuit, &format!("couple {}", index), |c| {
+            for (&ancilla, &(qubit, pauli)) in cat.iter().zip(support) {
+                match pauli {
+                    SinglePauli::X => c.add_gate(cnot(ancilla, qubit))?,
+                    SinglePauli::Z => c.add_gate(Gate::Two(TwoGate::CZ { control: ancilla, target: qubit }))?,
+                    _ => {
+                        // Controlled-Y = S CNOT S'
+                        c.add_gate(single(qubit, SingleGate::Sdg))?;
+                        c.add_gate(cnot(ancilla, qubit))?;
+                        c.add_gate(single(qubit, SingleGate::S))?;
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        let first = circuit.measurements.len();
+        syndrome_bits.push((first..first + cat.len()).collect());
+        for &qubit in &cat {
+            circuit.add_measurement(qubit, MeasurementBasis::X)?;
+        }
+    }
+
+    Ok(ExtractionGadget {
+        gadget: Gadget::new(circuit, (0..n).collect())?,
+        syndrome_bits,
+        verification,
+    })
+}
+
+/// Steane-style extraction for a CSS code: an encoded |+> block copies X
+/// errors out through data-controlled CNOTs and is read in Z, then an
+/// encoded |0> block copies Z errors through ancilla-controlled CNOTs and
+/// is read in X. Ancilla encoders are unverified.
+pub fn steane_extraction(code: &StabilizerCode) -> Result<ExtractionGadget, String> {
+    let n = code.num_qubits;
+    let z_checks = code.css_checks(SinglePauli::X)?;
+    let x_checks = code.css_checks(SinglePauli::Z)?;
+    let rows = |checks: &[usize], pauli: SinglePauli| -> Vec<Vec<bool>> {
+        checks
+            .iter()
+            .map(|&i| (0..n).map(|q| code.stabilizers[i].get_pauli(q) == pauli).collect())
+            .collect()
+    };
+    let (plus_block, zero_block): (Vec<usize>, Vec<usize>) = ((n..2 * n).collect(), (2 * n..3 * n).collect());
+    let mut circuit = Circuit::new(3 * n);
+
+    // |+>_L: the |0>_L construction on the Z checks, then H everywhere
+    in_region(&mut circuit, "prepare plus", |c| {
+        append_css_encoder(c, &plus_block, &rows(&z_checks, SinglePauli::Z))?;
+        plus_block.iter().try_for_each(|&q| c.add_gate(single(q, SingleGate::H)))
+    })?;
+    in_region(&mut circuit, "prepare zero", |c| {
+        append_css_encoder(c, &zero_block, &rows(&x_checks, SinglePauli::X))
+    })?;
+    in_region(&mut circuit, "couple x errors", |c| {
+        (0..n).try_for_each(|q| c.add_gate(cnot(q, plus_block[q])))
+    })?;
+    in_region(&mut circuit, "couple z errors", |c| {
+        (0..n).try_for_each(|q| c.add_gate(cnot(zero_block[q], q)))
+    })?;
+
+    for &qubit in &plus_block {
+        circuit.add_measurement(qubit, MeasurementBasis::Z)?;
+    }
+    for &qubit in &zero_block {
+        circuit.add_measurement(qubit, MeasurementBasis::X)?;
+    }
+    let syndrome_bits = code
+        .stabilizers
+        .iter()
+        .enumerate()
+        .map(|(index, stabilizer)| {
+            let offset = if z_checks.contains(&index) { 0 } else { n };
+            support(stabilizer).into_iter().map(|(q, _)| offset + q).collect()
+        })
+        .collect();
+    Ok(ExtractionGadget {
+        gadget: Gadget::new(circuit, (0..n).collect())?,
+        syndrome_bits,
+        verification: Vec::new(),
+    })
+}
+
+/// Prepares the state stabilized by X on each row and by every Z that
+/// commutes with them (|0>_L when the rows are a CSS code's X checks),
+/// starting from |0...0>. Rows are reduced to echelon form; each pivot gets
+/// an H and fans out to the rest of its row.
+fn append_css_encoder(circuit: &mut Circuit, qubits: &[usize], rows: &[Vec<bool>]) -> Result<(), String> {
+    let reduced = reduced_echelon(rows);
+    for (pivot, _) in &reduced {
+        circuit.add_gate(single(qubits[*pivot], SingleGate::H))?;
+    }
+    for (pivot, row) in &reduced {
+        for (q, _) in row.iter().enumerate().filter(|&(q, &bit)| bit && q != *pivot) {
+            circuit.add_gate(cnot(qubits[*pivot], qubits[q]))?;
+        }
+    }
+    Ok(())
+}
+
+/// Fully reduced row echelon form over GF(2): (pivot column, row) pairs,
+/// each pivot column zero in every other row. Dependent rows are dropped.
+fn reduced_echelon(rows: &[Vec<bool>]) -> Vec<(usize, Vec<bool>)> {
+    let mut reduced: Vec<(usize, Vec<bool>)> = Vec::new();
+    for row in rows {
+        let mut row = row.clone();
+        for (pivot, other) in &reduced {
+            if row[*pivot] {
+                row.iter_mut().zip(other).for_each(|(a, b)| *a ^= b);
+            }
+        }
+        let Some(pivot) = row.iter().position(|&bit| bit) else {
+            continue;
+        };
+        for (_, other) in reduced.iter_mut() {
+            if other[pivot] {
+                other.iter_mut().zip(&row).for_each(|(a, b)| *a ^= b);
+            }
+        }
+        reduced.push((pivot, row));
+    }
+    reduced
+}
+
+fn support(pauli: &PauliString) -> Vec<(usize, SinglePauli)> {
+    (0..pauli.num_qubits())
+        .map(|q| (q, pauli.get_pauli(q)))
+        .filter(|&(_, p)| p != SinglePauli::I)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::determinism::check_detectors;
+    use crate::physics::simulator::Simulator;
+
+    fn extracted_syndrome(extraction: &ExtractionGadget, qubit: usize, pauli: SinglePauli) -> Vec<bool> {
+        let mut simulator = Simulator::new(extraction.gadget.circuit.clone());
+        simulator.inject_error(qubit, pauli);
+        simulator.run();
+        extraction.syndrome(&extraction.gadget.measurement_flips(simulator.error_pattern()))
+    }
+
+    #[test]
+    fn test_extraction_gadgets_read_the_syndrome() {
+        let code = StabilizerCode::steane();
+        for extraction in [
+            shor_extraction(&code, true).unwrap(),
+            steane_extraction(&code).unwrap(),
+        ] {
+            // With the block encoded in |0>_L first, noiseless syndrome bits
+            // and verifications are deterministic
+            let mut encoded = Circuit::new(extraction.gadget.circuit.num_qubits);
+            let x_checks: Vec<Vec<bool>> = code
+                .stabilizers
+                .iter()
+                .filter(|s| s.x_bits().any())
+                .map(|s| (0..7).map(|q| s.x_bits()[q]).collect())
+                .collect();
+            append_css_encoder(&mut encoded, &(0..7).collect::<Vec<_>>(), &x_checks).unwrap();
+            encoded.append(&extraction.gadget.circuit).unwrap();
+            let run = Gadget::new(encoded, (0..7).collect()).unwrap();
+            let annotations: Vec<Vec<usize>> = extraction
+                .syndrome_bits
+                .iter()
+                .cloned()
+                .chain(extraction.verification.iter().map(|&m| vec![m]))
+                .collect();
+            assert!(check_detectors(&run, &code, &annotations).unwrap().is_empty());
+
+            for pauli in [SinglePauli::X, SinglePauli::Y, SinglePauli::Z] {
+                let mut error = PauliString::new(7);
+                error.set_pauli(3, pauli);
+                assert_eq!(extracted_syndrome(&extraction, 3, pauli), code.syndrome(&error));
+            }
+        }
+        assert!(steane_extraction(&StabilizerCode::new(
+            "non-css",
+            vec![PauliString::from_str("XZ", 2).unwrap()],
+            vec![],
+            vec![]
+        )
+        .unwrap())
+        .is_err());
+    }
+
+    #[test]
+    fn test_verified_cat_state() {
+        let gadget = cat_state(4, true).unwrap();
+        assert_eq!(gadget.circuit.num_qubits, 5);
+        assert_eq!(gadget.circuit.region("verify").map(|r| r.end - r.start), Some(2));
+        // An X fault in the middle of the chain is caught by the verifier
+        let mut simulator = Simulator::new(gadget.circuit.clone());
+        simulator.step_forward();
+        simulator.step_forward();
+        simulator.inject_error(1, SinglePauli::X);
+        simulator.run();
+        assert_eq!(gadget.measurement_flips(simulator.error_pattern()), vec![true]);
+        assert!(cat_state(0, false).is_err());
+    }
+}
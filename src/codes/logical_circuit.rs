@@ -0,0 +1,242 @@
+//! Logical circuits over code blocks.
+//!
+//! Nodes are code blocks (one `StabilizerCode` instance each) and operations
+//! are transversal gates or gadgets acting on a block. Lowering lays the
+//! blocks out side by side, gives every gadget fresh ancillas and marks each
+//! operation as a circuit region `op<i>`, so the physical circuit can be fed
+//! to the gadget-level analyses one block at a time.
+
+use crate::codes::gadget::{Gadget, Measure};
+use crate::codes::stabilizer_code::StabilizerCode;
+use crate::physics::circuit::{Circuit, Gate, SingleGate, TwoGate};
+use crate::physics::propagation::apply_single_gate;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogicalOperation {
+    /// `gate` on every qubit of the block
+    Transversal { block: usize, gate: SingleGate },
+    /// Qubit-wise CNOT between two blocks of the same CSS code
+    TransversalCnot { control: usize, target: usize },
+    /// A gadget whose data qubits are the block's qubits
+    Gadget { block: usize, gadget: Box<Gadget> },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogicalCircuit {
+    pub blocks: Vec<StabilizerCode>,
+    pub operations: Vec<LogicalOperation>,
+}
+
+/// Physical form of a logical circuit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoweredCircuit {
+    pub circuit: Circuit,
+    /// Physical qubits of each block, indexed by code qubit
+    pub blocks: Vec<Vec<usize>>,
+}
+
+impl LoweredCircuit {
+    /// The whole circuit seen as a gadget on one block, so that sweeps,
+    /// certification, sampling and DEMs apply to that block's residual.
+    pub fn block_gadget(&self, block: usize) -> Result<Gadget, String> {
+        let data_qubits = self
+            .blocks
+            .get(block)
+            .ok_or_else(|| format!("No block {}", block))?
+            .clone();
+        Gadget::new(self.circuit.clone(), data_qubits)
+    }
+}
+
+impl LogicalCircuit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a code block and returns its index.
+    pub fn add_block(&mut self, code: StabilizerCode) -> usize {
+        self.blocks.push(code);
+        self.blocks.len() - 1
+    }
+
+    pub fn add_operation(&mut self, operation: LogicalOperation) -> Result<(), String> {
+        self.check(&operation)?;
+        self.operations.push(operation);
+        Ok(())
+    }
+
+    fn block(&self, block: usize) -> Result<&StabilizerCode, String> {
+        self.blocks.get(block).ok_or_else(|| format!("No block {}", block))
+    }
+
+    fn check(&self, operation: &LogicalOperation) -> Result<(), String> {
+        match operation {
+            LogicalOperation::Transversal { block, gate } => {
+                let code = self.block(*block)?;
+                for stabilizer in &code.stabilizers {
+                    let mut image = stabilizer.clone();
+                    for qubit in 0..code.num_qubits {
+                        apply_single_gate(&mut image, qubit, *gate);
+                    }
+                    if !code.in_stabilizer_group(&image) {
+                        return Err(format!(
+                            "Transversal {:?} does not preserve the stabilizers of '{}'",
+                            gate, code.name
+                        ));
+                    }
+                }
+            }
+            LogicalOperation::TransversalCnot { control, target } => {
+                let (a, b) = (self.block(*control)?, self.block(*target)?);
+                if control == target {
+                    return Err("Transversal CNOT needs two different blocks".to_string());
+                }
+                if a != b || !a.is_css() {
+                    return Err(format!(
+                        "Transversal CNOT needs two blocks of the same CSS code, got '{}' and '{}'",
+                        a.name, b.name
+                    ));
+                }
+            }
+            LogicalOperation::Gadget { block, gadget } => {
+                let code = self.block(*block)?;
+                if gadget.data_qubits.len() != code.num_qubits {
+                    return Err(format!(
+                        "Gadget has {} data qubits but code '{}' has {}",
+                        gadget.data_qubits.len(),
+                        code.name,
+                        code.num_qubits
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn lower(&self) -> Result<LoweredCircuit, String> {
+        let mut blocks = Vec::new();
+        let mut next = 0;
+        for code in &self.blocks {
+            blocks.push((next..next + code.num_qubits).collect::<Vec<usize>>());
+            next += code.num_qubits;
+        }
+        let ancillas: usize = self
+            .operations
+            .iter()
+            .map(|operation| match operation {
+                LogicalOperation::Gadget { gadget, .. } => gadget.circuit.num_qubits - gadget.data_qubits.len(),
+                _ => 0,
+            })
+            .sum();
+
+        let mut circuit = Circuit::new(next + ancillas);
+        let mut regions = Vec::new();
+        for (index, operation) in self.operations.iter().enumerate() {
+            self.check(operation)?;
+            let start = circuit.gates.len();
+            match operation {
+                LogicalOperation::Transversal { block, gate } => {
+                    for &qubit in &blocks[*block] {
+                        circuit.add_gate(Gate::Single { qubit, gate: *gate })?;
+                    }
+                }
+                LogicalOperation::TransversalCnot { control, target } => {
+                    for (&c, &t) in blocks[*control].iter().zip(&blocks[*target]) {
+                        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: c, target: t }))?;
+                    }
+                }
+                LogicalOperation::Gadget { block, gadget } => {
+                    // Gadget qubit -> physical qubit
+                    let mut map = vec![usize::MAX; gadget.circuit.num_qubits];
+                    for (i, &qubit) in gadget.data_qubits.iter().enumerate() {
+                        map[qubit] = blocks[*block][i];
+                    }
+                    for slot in map.iter_mut().filter(|slot| **slot == usize::MAX) {
+                        *slot = next;
+                        next += 1;
+                    }
+                    for gate in &gadget.circuit.gates {
+                        circuit.add_gate(gate.map_qubits(|q| map[q]))?;
+                    }
+                    circuit.measurements.extend(gadget.measurements().iter().map(|m| Measure {
+                        position: start + m.position,
+                        qubit: map[m.qubit],
+                        basis: m.basis,
+                    }));
+                    for region in &gadget.circuit.regions {
+                        let name = format!("op{}/{}", index, region.name);
+                        regions.push((name, start + region.start, start + region.end));
+                    }
+                }
+            }
+            regions.push((format!("op{}", index), start, circuit.gates.len()));
+        }
+        for (name, start, end) in regions {
+            circuit.add_region(&name, start, end)?;
+        }
+
+        Ok(LoweredCircuit { circuit, blocks })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::fault_sweep::{propagate_fault, Fault};
+    use crate::codes::gadget::MeasurementBasis;
+    use crate::physics::pauli::SinglePauli;
+
+    #[test]
+    fn test_lower_two_steane_blocks() {
+        let mut logical = LogicalCircuit::new();
+        let a = logical.add_block(StabilizerCode::steane());
+        let b = logical.add_block(StabilizerCode::steane());
+        logical
+            .add_operation(LogicalOperation::Transversal { block: a, gate: SingleGate::H })
+            .unwrap();
+        logical
+            .add_operation(LogicalOperation::TransversalCnot { control: a, target: b })
+            .unwrap();
+
+        // One-ancilla gadget reading out the first qubit of block b
+        let mut readout = Circuit::new(8);
+        readout
+            .add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 7 }))
+            .unwrap();
+        readout.add_measurement(7, MeasurementBasis::Z).unwrap();
+        let gadget = Gadget::new(readout, (0..7).collect()).unwrap();
+        logical
+            .add_operation(LogicalOperation::Gadget { block: b, gadget: Box::new(gadget) })
+            .unwrap();
+
+        let lowered = logical.lower().unwrap();
+        assert_eq!(lowered.circuit.num_qubits, 15);
+        assert_eq!(lowered.circuit.gates.len(), 7 + 7 + 1);
+        assert_eq!(
+            lowered.circuit.measurements,
+            vec![Measure { position: 15, qubit: 14, basis: MeasurementBasis::Z }]
+        );
+        assert_eq!(lowered.circuit.region("op1").map(|r| (r.start, r.end)), Some((7, 14)));
+
+        // An X fault on block a after the H layer spreads to block b
+        let fault = Fault { after_gate: 6, qubit: 0, pauli: SinglePauli::X };
+        let error = propagate_fault(&lowered.circuit, &fault);
+        let on_b = lowered.block_gadget(b).unwrap().data_error(&error);
+        assert_eq!(on_b.get_pauli(0), SinglePauli::X);
+    }
+
+    #[test]
+    fn test_invalid_operations() {
+        let mut logical = LogicalCircuit::new();
+        let steane = logical.add_block(StabilizerCode::steane());
+        let repetition = logical.add_block(StabilizerCode::repetition(3));
+        // H swaps the repetition code's ZZ checks for XX
+        let h = LogicalOperation::Transversal { block: repetition, gate: SingleGate::H };
+        assert!(logical.add_operation(h).is_err());
+        let cnot = LogicalOperation::TransversalCnot { control: steane, target: repetition };
+        assert!(logical.add_operation(cnot).is_err());
+        let missing = LogicalOperation::Transversal { block: 5, gate: SingleGate::X };
+        assert!(logical.add_operation(missing).is_err());
+    }
+}
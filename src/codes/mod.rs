@@ -0,0 +1,15 @@
+pub mod coset_leaders;
+pub mod experiments;
+pub mod gadget;
+pub mod gadgets;
+pub mod logical_circuit;
+pub mod stabilizer_code;
+pub mod templates;
+
+pub use coset_leaders::{CosetLeaders, PaulisByWeight};
+pub use experiments::{memory_x, memory_z, stability, Experiment};
+pub use gadget::{Gadget, Measure, MeasurementBasis};
+pub use gadgets::{cat_state, shor_extraction, steane_extraction, ExtractionGadget};
+pub use logical_circuit::{LogicalCircuit, LogicalOperation, LoweredCircuit};
+pub use stabilizer_code::StabilizerCode;
+pub use templates::{magic_state_injection, t_teleportation, ConditionalGate, FeedForwardTemplate};
@@ -0,0 +1,323 @@
+use crate::physics::pauli::{PauliString, SinglePauli};
+use serde::{Deserialize, Serialize};
+
+/// A stabilizer code given by its generators and logical operator pairs.
+/// `logical_x[i]` and `logical_z[i]` act on the i-th logical qubit.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StabilizerCode {
+    pub name: String,
+    pub num_qubits: usize,
+    pub stabilizers: Vec<PauliString>,
+    pub logical_x: Vec<PauliString>,
+    pub logical_z: Vec<PauliString>,
+}
+
+impl StabilizerCode {
+    pub fn new(
+        name: &str,
+        stabilizers: Vec<PauliString>,
+        logical_x: Vec<PauliString>,
+        logical_z: Vec<PauliString>,
+    ) -> Result<Self, String> {
+        let num_qubits = stabilizers
+            .first()
+            .or(logical_x.first())
+            .map(|p| p.num_qubits())
+            .ok_or("Code needs at least one stabilizer or logical operator")?;
+        if stabilizers
+            .iter()
+            .chain(&logical_x)
+            .chain(&logical_z)
+            .any(|p| p.num_qubits() != num_qubits)
+        {
+            return Err(format!("All operators must act on {} qubits", num_qubits));
+        }
+        if logical_x.len() != logical_z.len() {
+            return Err(format!(
+                "Got {} logical X but {} logical Z operators",
+                logical_x.len(),
+                logical_z.len()
+            ));
+        }
+        for (i, a) in stabilizers.iter().enumerate() {
+            for b in &stabilizers[i + 1..] {
+                if !a.commutes_with(b) {
+                    return Err(format!("Stabilizers {} and {} anticommute", a, b));
+                }
+            }
+            for logical in logical_x.iter().chain(&logical_z) {
+                if !a.commutes_with(logical) {
+                    return Err(format!("Logical {} anticommutes with stabilizer {}", logical, a));
+                }
+            }
+        }
+        for (i, x) in logical_x.iter().enumerate() {
+            for (j, z) in logical_z.iter().enumerate() {
+                if x.commutes_with(z) == (i == j) {
+                    return Err(format!("Logical X{} and Z{} have the wrong commutation", i, j));
+                }
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            num_qubits,
+            stabilizers,
+            logical_x,
+            logical_z,
+        })
+    }
+
+    /// Distance-d bit-flip repetition code: ZZ checks, logical X = X...X.
+    pub fn repetition(distance: usize) -> Self {
+        assert!(distance >= 2, "Repetition code needs distance >= 2");
+        let mut stabilizers = Vec::new();
+        for i in 0..distance - 1 {
+            let mut check = PauliString::new(distance);
+            check.set_pauli(i, SinglePauli::Z);
+            check.set_pauli(i + 1, SinglePauli::Z);
+            stabilizers.push(check);
+        }
+        let logical_x = PauliString::from_str(&"X".repeat(distance), distance).unwrap();
+        let mut logical_z = PauliString::new(distance);
+        logical_z.set_pauli(0, SinglePauli::Z);
+        Self::new(&format!("repetition-{}", distance), stabilizers, vec![logical_x], vec![logical_z]).unwrap()
+    }
+
+    /// Distance-d phase-flip repetition code: XX checks, logical Z = Z...Z.
+    pub fn phase_flip_repetition(distance: usize) -> Self {
+        assert!(distance >= 2, "Repetition code needs distance >= 2");
+        let mut stabilizers = Vec::new();
+        for i in 0..distance - 1 {
+            let mut check = PauliString::new(distance);
+            check.set_pauli(i, SinglePauli::X);
+            check.set_pauli(i + 1, SinglePauli::X);
+            stabilizers.push(check);
+        }
+        let mut logical_x = PauliString::new(distance);
+        logical_x.set_pauli(0, SinglePauli::X);
+        let logical_z = PauliString::from_str(&"Z".repeat(distance), distance).unwrap();
+        Self::new(
+            &format!("phase-flip-repetition-{}", distance),
+            stabilizers,
+            vec![logical_x],
+            vec![logical_z],
+        )
+        .unwrap()
+    }
+
+    /// The [[7,1,3]] Steane code.
+    pub fn steane() -> Self {
+        let rows = ["IIIXXXX", "IXXIIXX", "XIXIXIX"];
+        let mut stabilizers: Vec<PauliString> = rows.iter().map(|r| PauliString::from_str(r, 7).unwrap()).collect();
+        stabilizers.extend(
+            rows.iter()
+                .map(|r| PauliString::from_str(&r.replace('X', "Z"), 7).unwrap()),
+        );
+        Self::new(
+            "steane",
+            stabilizers,
+            vec![PauliString::from_str("XXXXXXX", 7).unwrap()],
+            vec![PauliString::from_str("ZZZZZZZ", 7).unwrap()],
+        )
+        .unwrap()
+    }
+
+    /// Distance-d rotated surface code on a d x d grid, qubit `row * d + col`.
+    /// X checks close the top and bottom edges and Z checks the left and
+    /// right ones; logical X runs down column 0 and logical Z along row 0.
+    pub fn rotated_surface(distance: usize) -> Self {
+        assert!(distance >= 2, "Surface code needs distance >= 2");
+        let d = distance as isize;
+        let mut stabilizers = Vec::new();
+        // Plaquette (r, c) covers the grid cells (r..=r + 1, c..=c + 1)
+        for r in -1..d {
+            for c in -1..d {
+                let x_type = (r + c).rem_euclid(2) == 0;
+                let on_row_edge = r == -1 || r == d - 1;
+                let on_col_edge = c == -1 || c == d - 1;
+                let kept = match (on_row_edge, on_col_edge) {
+                    (false, false) => true,
+                    (true, false) => x_type,
+                    (false, true) => !x_type,
+                    (true, true) => false,
+                };
+                if !kept {
+                    continue;
+                }
+                let mut check = PauliString::new(distance * distance);
+                for (qr, qc) in [(r, c), (r, c + 1), (r + 1, c), (r + 1, c + 1)] {
+                    if (0..d).contains(&qr) && (0..d).contains(&qc) {
+                        let pauli = if x_type { SinglePauli::X } else { SinglePauli::Z };
+                        check.set_pauli((qr * d + qc) as usize, pauli);
+                    }
+                }
+                stabilizers.push(check);
+            }
+        }
+        let mut logical_x = PauliString::new(distance * distance);
+        let mut logical_z = PauliString::new(distance * distance);
+        for i in 0..distance {
+            logical_x.set_pauli(i * distance, SinglePauli::X);
+            logical_z.set_pauli(i, SinglePauli::Z);
+        }
+        Self::new(&format!("rotated-surface-{}", distance), stabilizers, vec![logical_x], vec![logical_z]).unwrap()
+    }
+
+    pub fn num_logical_qubits(&self) -> usize {
+        self.logical_x.len()
+    }
+
+    /// Whether every stabilizer is purely X-type or purely Z-type.
+    pub fn is_css(&self) -> bool {
+        self.stabilizers
+            .iter()
+            .all(|s| !s.x_bits().any() || !s.z_bits().any())
+    }
+
+    /// Indices of the stabilizers that detect `error_type` (X or Z) errors:
+    /// the Z-type checks for X errors and the X-type checks for Z errors.
+    pub fn css_checks(&self, error_type: SinglePauli) -> Result<Vec<usize>, String> {
+        if !self.is_css() {
+            return Err(format!("Code '{}' is not CSS", self.name));
+        }
+        let detecting = |s: &PauliString| match error_type {
+            SinglePauli::X => Ok(s.z_bits().any()),
+            SinglePauli::Z => Ok(s.x_bits().any()),
+            other => Err(format!("CSS sectors are X or Z, not {}", other)),
+        };
+        let mut checks = Vec::new();
+        for (index, stabilizer) in self.stabilizers.iter().enumerate() {
+            if detecting(stabilizer)? {
+                checks.push(index);
+            }
+        }
+        Ok(checks)
+    }
+
+    /// The code restricted to the checks detecting `error_type` errors.
+    pub fn css_sector(&self, error_type: SinglePauli) -> Result<Self, String> {
+        let checks = self.css_checks(error_type)?;
+        Self::new(
+            &format!("{}-{}", self.name, error_type),
+            checks.iter().map(|&i| self.stabilizers[i].clone()).collect(),
+            self.logical_x.clone(),
+            self.logical_z.clone(),
+        )
+    }
+
+    /// One bit per stabilizer, true where `error` anticommutes with it.
+    pub fn syndrome(&self, error: &PauliString) -> Vec<bool> {
+        self.stabilizers.iter().map(|s| !s.commutes_with(error)).collect()
+    }
+
+    /// Which logical observables `error` flips: one bit per logical Z
+    /// operator, then one per logical X operator.
+    pub fn observable_flips(&self, error: &PauliString) -> Vec<bool> {
+        self.logical_z
+            .iter()
+            .chain(&self.logical_x)
+            .map(|logical| !logical.commutes_with(error))
+            .collect()
+    }
+
+    /// Whether `pauli` is a product of stabilizers, ignoring phase.
+    pub fn in_stabilizer_group(&self, pauli: &PauliString) -> bool {
+        in_span(&self.stabilizers, pauli)
+    }
+
+    /// Whether `error` is undetectable but acts non-trivially on the code space.
+    pub fn is_logical_error(&self, error: &PauliString) -> bool {
+        self.syndrome(error).iter().all(|&bit| !bit) && !self.in_stabilizer_group(error)
+    }
+
+    /// Minimum weight of `error` times any stabilizer. Enumerates the whole
+    /// group, so only suitable for codes with a handful of generators.
+    pub fn reduced_weight(&self, error: &PauliString) -> usize {
+        let mut best = error.weight();
+        for mask in 1u64..(1u64 << self.stabilizers.len()) {
+            let mut candidate = error.clone();
+            for (i, stabilizer) in self.stabilizers.iter().enumerate() {
+                if mask >> i & 1 == 1 {
+                    candidate = candidate.multiply(stabilizer);
+                }
+            }
+            best = best.min(candidate.weight());
+        }
+        best
+    }
+}
+
+/// GF(2) membership of `target` in the span of `generators` (symplectic
+/// bits only).
+pub(crate) fn in_span(generators: &[PauliString], target: &PauliString) -> bool {
+    let to_bits = |p: &PauliString| -> Vec<bool> { p.to_symplectic().iter().by_vals().collect() };
+    let mut rows: Vec<Vec<bool>> = generators.iter().map(to_bits).collect();
+    let mut target = to_bits(target);
+
+    let mut rank = 0;
+    for col in 0..target.len() {
+        let Some(pivot) = (rank..rows.len()).find(|&r| rows[r][col]) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        for r in 0..rows.len() {
+            if r != rank && rows[r][col] {
+                let pivot_row = rows[rank].clone();
+                rows[r].iter_mut().zip(&pivot_row).for_each(|(a, b)| *a ^= b);
+            }
+        }
+        if target[col] {
+            target.iter_mut().zip(&rows[rank]).for_each(|(a, b)| *a ^= b);
+        }
+        rank += 1;
+    }
+    target.iter().all(|&bit| !bit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repetition_syndrome() {
+        let code = StabilizerCode::repetition(3);
+        let error = PauliString::from_str("IXI", 3).unwrap();
+        assert_eq!(code.syndrome(&error), vec![true, true]);
+        assert!(code.is_logical_error(&PauliString::from_str("XXX", 3).unwrap()));
+        assert!(code.in_stabilizer_group(&PauliString::from_str("ZIZ", 3).unwrap()));
+        assert_eq!(code.observable_flips(&PauliString::from_str("XXX", 3).unwrap()), vec![true, false]);
+    }
+
+    #[test]
+    fn test_steane_group_membership() {
+        let code = StabilizerCode::steane();
+        assert_eq!(code.num_logical_qubits(), 1);
+        // Product of the first two X checks
+        assert!(code.in_stabilizer_group(&PauliString::from_str("IXXXXII", 7).unwrap()));
+        assert!(!code.in_stabilizer_group(&PauliString::from_str("XXXXXXX", 7).unwrap()));
+        assert!(code.is_logical_error(&PauliString::from_str("XXXXXXX", 7).unwrap()));
+        assert!(!code.is_logical_error(&PauliString::from_str("XIIIIII", 7).unwrap()));
+        assert_eq!(code.reduced_weight(&PauliString::from_str("IXXXXXI", 7).unwrap()), 1);
+    }
+
+    #[test]
+    fn test_rotated_surface_code() {
+        let code = StabilizerCode::rotated_surface(3);
+        assert_eq!(code.num_qubits, 9);
+        assert_eq!(code.stabilizers.len(), 8);
+        assert!(code.is_css());
+        assert_eq!(code.css_checks(SinglePauli::X).unwrap().len(), 4);
+        // Weight-2 errors are detected; a full column of X is logical
+        assert!(!code.is_logical_error(&PauliString::from_str("XIIXIIIII", 9).unwrap()));
+        assert!(code.is_logical_error(&PauliString::from_str("IXIIXIIXI", 9).unwrap()));
+        assert_eq!(StabilizerCode::rotated_surface(5).stabilizers.len(), 24);
+    }
+
+    #[test]
+    fn test_invalid_code_rejected() {
+        let x = PauliString::from_str("XI", 2).unwrap();
+        let z = PauliString::from_str("ZI", 2).unwrap();
+        assert!(StabilizerCode::new("bad", vec![x, z], vec![], vec![]).is_err());
+    }
+}
@@ -0,0 +1,182 @@
+//! Clifford + measurement + feed-forward templates for non-Clifford gates.
+//!
+//! A logical T is applied by consuming a magic state |A> = T|+> that is
+//! assumed to be supplied on `magic_qubit`. Everything the template itself
+//! does is Clifford, so each measurement branch can be analyzed by the
+//! Pauli engine. A fault that flips a measurement selects the wrong branch,
+//! which is why both branches are exposed.
+
+use crate::codes::gadget::{Gadget, MeasurementBasis};
+use crate::physics::circuit::{Circuit, Gate, SingleGate, TwoGate};
+use crate::physics::pauli::SinglePauli;
+use crate::physics::simulator::{Simulator, Snapshot};
+use serde::{Deserialize, Serialize};
+
+/// `gate` is applied after the gadget when measurement `measurement`
+/// (an index into `Gadget::measurements`) reads 1.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConditionalGate {
+    pub measurement: usize,
+    pub gate: Gate,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedForwardTemplate {
+    pub name: String,
+    pub gadget: Gadget,
+    pub input_qubit: usize,
+    pub magic_qubit: usize,
+    pub output_qubit: usize,
+    /// Applied in order, each only if its measurement reads 1
+    pub corrections: Vec<ConditionalGate>,
+}
+
+impl FeedForwardTemplate {
+    /// The Clifford circuit executed for the given measurement outcomes.
+    pub fn branch(&self, outcomes: &[bool]) -> Result<Circuit, String> {
+        if outcomes.len() != self.gadget.measurements().len() {
+            return Err(format!(
+                "Expected {} measurement outcomes, got {}",
+                self.gadget.measurements().len(),
+                outcomes.len()
+            ));
+        }
+        let mut circuit = self.gadget.circuit.clone();
+        for correction in &self.corrections {
+            if outcomes[correction.measurement] {
+                circuit.add_gate(correction.gate.clone())?;
+            }
+        }
+        Ok(circuit)
+    }
+
+    /// Step-by-step Pauli frame of one run with `errors` injected before the
+    /// first gate. Each readout records `reference` XOR the frame's flip;
+    /// those bits choose the branch, and every snapshot from the end of the
+    /// gadget on carries them.
+    pub fn trace(&self, reference: &[bool], errors: &[(usize, SinglePauli)]) -> Result<Vec<Snapshot>, String> {
+        let num_gates = self.gadget.circuit.gates.len();
+        let mut gadget_run = Simulator::new(self.gadget.circuit.clone());
+        for &(qubit, pauli) in errors {
+            if qubit >= self.gadget.circuit.num_qubits {
+                return Err(format!("Qubit {} out of range", qubit));
+            }
+            gadget_run.inject_error(qubit, pauli);
+        }
+        gadget_run.run();
+        let flips = self.gadget.measurement_flips(gadget_run.error_pattern());
+        if reference.len() != flips.len() {
+            return Err(format!(
+                "Expected {} reference outcomes, got {}",
+                flips.len(),
+                reference.len()
+            ));
+        }
+        let outcomes: Vec<bool> = reference.iter().zip(&flips).map(|(r, f)| r ^ f).collect();
+
+        let mut simulator = Simulator::new(self.branch(&outcomes)?);
+        for &(qubit, pauli) in errors {
+            simulator.inject_error(qubit, pauli);
+        }
+        for _ in 0..num_gates {
+            simulator.step_forward();
+        }
+        simulator.record_classical_bits(&outcomes);
+        simulator.run();
+        Ok(simulator.timeline().to_vec())
+    }
+}
+
+/// T-gate teleportation: CNOT(input -> magic), measure the magic qubit in
+/// Z, and apply S to the input on outcome 1. The output stays on qubit 0.
+pub fn t_teleportation() -> FeedForwardTemplate {
+    let mut circuit = Circuit::new(2);
+    circuit
+        .add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 }))
+        .unwrap();
+    circuit.add_measurement(1, MeasurementBasis::Z).unwrap();
+    FeedForwardTemplate {
+        name: "t-teleportation".to_string(),
+        gadget: Gadget::new(circuit, vec![0]).unwrap(),
+        input_qubit: 0,
+        magic_qubit: 1,
+        output_qubit: 0,
+        corrections: vec![ConditionalGate {
+            measurement: 0,
+            gate: Gate::Single {
+                qubit: 0,
+                gate: SingleGate::S,
+            },
+        }],
+    }
+}
+
+/// Magic-state injection: CNOT(magic -> input), measure the input in Z, and
+/// apply X then S to the magic qubit on outcome 1. The output moves to
+/// qubit 1.
+pub fn magic_state_injection() -> FeedForwardTemplate {
+    let mut circuit = Circuit::new(2);
+    circuit
+        .add_gate(Gate::Two(TwoGate::CNOT { control: 1, target: 0 }))
+        .unwrap();
+    circuit.add_measurement(0, MeasurementBasis::Z).unwrap();
+    let correction = |gate| ConditionalGate {
+        measurement: 0,
+        gate: Gate::Single { qubit: 1, gate },
+    };
+    FeedForwardTemplate {
+        name: "magic-state-injection".to_string(),
+        gadget: Gadget::new(circuit, vec![1]).unwrap(),
+        input_qubit: 0,
+        magic_qubit: 1,
+        output_qubit: 1,
+        corrections: vec![correction(SingleGate::X), correction(SingleGate::S)],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::fault_sweep::{propagate_fault, Fault};
+
+    #[test]
+    fn test_branches() {
+        let template = t_teleportation();
+        assert_eq!(template.branch(&[false]).unwrap().gates.len(), 1);
+        let corrected = template.branch(&[true]).unwrap();
+        assert_eq!(corrected.gates.last(), Some(&Gate::Single { qubit: 0, gate: SingleGate::S }));
+        assert!(template.branch(&[]).is_err());
+
+        let injection = magic_state_injection();
+        assert_eq!(injection.branch(&[true]).unwrap().gates.len(), 3);
+    }
+
+    #[test]
+    fn test_magic_qubit_fault_flips_measurement() {
+        let template = t_teleportation();
+        let fault = Fault {
+            after_gate: 0,
+            qubit: 1,
+            pauli: SinglePauli::X,
+        };
+        let error = propagate_fault(&template.gadget.circuit, &fault);
+        assert_eq!(template.gadget.measurement_flips(&error), vec![true]);
+    }
+
+    #[test]
+    fn test_trace_records_steering_bits() {
+        // An X on the magic qubit flips the readout, so reference 0 records
+        // a 1 and the S correction runs
+        let template = t_teleportation();
+        let trace = template.trace(&[false], &[(1, SinglePauli::X)]).unwrap();
+        assert_eq!(trace.len(), 3);
+        assert!(trace[0].classical_bits.is_empty());
+        assert_eq!(trace[1].classical_bits, vec![true]);
+        assert_eq!(trace[2].classical_bits, vec![true]);
+
+        let clean = template.trace(&[false], &[]).unwrap();
+        assert_eq!(clean.len(), 2);
+        assert_eq!(clean[1].classical_bits, vec![false]);
+        assert!(template.trace(&[], &[]).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! Independent X and Z decoding for CSS codes.
+//!
+//! Z-type checks only see X errors and X-type checks only see Z errors, so
+//! the final-round detectors, the observables and the DEM split into two
+//! smaller problems whose corrections are recombined. Leading
+//! gadget detectors carry no sector information and are left out.
+
+use crate::codes::StabilizerCode;
+use crate::decoding::lookup::LookupDecoder;
+use crate::decoding::syndrome::{Correction, Syndrome};
+use crate::decoding::Decoder;
+use crate::dem::model::DetectorErrorModel;
+use crate::physics::pauli::SinglePauli;
+use serde::{Deserialize, Serialize};
+
+/// Detector and observable indices of each sector, in the shot layout
+/// (`leading_detectors` gadget detectors, then one per stabilizer).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CssSplit {
+    /// Detectors seeing X errors (Z-type checks)
+    pub x_detectors: Vec<usize>,
+    /// Detectors seeing Z errors (X-type checks)
+    pub z_detectors: Vec<usize>,
+    /// Observables flipped by X errors (logical Z operators)
+    pub x_observables: Vec<usize>,
+    /// Observables flipped by Z errors (logical X operators)
+    pub z_observables: Vec<usize>,
+}
+
+impl CssSplit {
+    pub fn new(code: &StabilizerCode, leading_detectors: usize) -> Result<Self, String> {
+        let shift = |checks: Vec<usize>| checks.into_iter().map(|i| i + leading_detectors).collect();
+        let k = code.num_logical_qubits();
+        Ok(Self {
+            x_detectors: shift(code.css_checks(SinglePauli::X)?),
+            z_detectors: shift(code.css_checks(SinglePauli::Z)?),
+            x_observables: (0..k).collect(),
+            z_observables: (k..2 * k).collect(),
+        })
+    }
+
+    /// (X-sector, Z-sector) detection events.
+    pub fn split_detectors(&self, detectors: &[bool]) -> (Vec<bool>, Vec<bool>) {
+        let pick = |indices: &[usize]| indices.iter().map(|&i| detectors[i]).collect();
+        (pick(&self.x_detectors), pick(&self.z_detectors))
+    }
+
+    /// (X-sector, Z-sector) models.
+    pub fn split_dem(&self, dem: &DetectorErrorModel) -> (DetectorErrorModel, DetectorErrorModel) {
+        (
+            dem.project(&self.x_detectors, &self.x_observables),
+            dem.project(&self.z_detectors, &self.z_observables),
+        )
+    }
+}
+
+/// Decodes each sector with its own decoder and recombines the corrections.
+/// A sector decoder only resolves its own error type, so the X part of the
+/// X-sector correction and the Z part of the Z-sector correction are kept.
+pub struct CssDecoder<X, Z> {
+    pub split: CssSplit,
+    pub x_decoder: X,
+    pub z_decoder: Z,
+}
+
+impl CssDecoder<LookupDecoder, LookupDecoder> {
+    /// Lookup decoding per sector; tables only range over one check type,
+    /// so they are far smaller than the full-code table.
+    pub fn lookup(code: &StabilizerCode, leading_detectors: usize) -> Result<Self, String> {
+        Ok(Self {
+            split: CssSplit::new(code, leading_detectors)?,
+            x_decoder: LookupDecoder::new(&code.css_sector(SinglePauli::X)?),
+            z_decoder: LookupDecoder::new(&code.css_sector(SinglePauli::Z)?),
+        })
+    }
+}
+
+impl<X: Decoder, Z: Decoder> Decoder for CssDecoder<X, Z> {
+    fn decode(&mut self, syndrome: &Syndrome) -> Result<Correction, String> {
+        let x_correction = self.x_decoder.decode(&syndrome.select(&self.split.x_detectors))?;
+        let z_correction = self.z_decoder.decode(&syndrome.select(&self.split.z_detectors))?;
+        Ok(x_correction.with_z_part_of(&z_correction))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::pauli::PauliString;
+
+    #[test]
+    fn test_steane_split_decoding() {
+        let code = StabilizerCode::steane();
+        let split = CssSplit::new(&code, 1).unwrap();
+        assert_eq!(split.x_detectors, vec![4, 5, 6]);
+        assert_eq!(split.z_detectors, vec![1, 2, 3]);
+
+        // Y on qubit 4 is an X and a Z error at once
+        let error = PauliString::from_str("IIIIYII", 7).unwrap();
+        let mut detectors = vec![true];
+        detectors.extend(code.syndrome(&error));
+
+        let mut decoder = CssDecoder::lookup(&code, 1).unwrap();
+        let correction = decoder.decode(&Syndrome::from_bools(&detectors)).unwrap().to_pauli();
+        let net = error.multiply(&correction);
+        assert!(code.in_stabilizer_group(&net));
+        assert_eq!(correction, PauliString::from_str("IIIIYII", 7).unwrap());
+    }
+}
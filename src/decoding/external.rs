@@ -0,0 +1,95 @@
+//! Decoder running in a separate process.
+//!
+//! Line protocol over stdin/stdout: for every shot the crate writes the
+//! detection events as one line of `0`/`1` characters, and the process
+//! answers with one line holding the correction as a Pauli string over the
+//! code qubits (e.g. `IXI`). The process is started once and kept alive.
+
+use crate::decoding::syndrome::{Correction, Syndrome};
+use crate::decoding::Decoder;
+use crate::physics::pauli::PauliString;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+pub struct ExternalDecoder {
+    num_qubits: usize,
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl ExternalDecoder {
+    pub fn spawn(program: &str, args: &[&str], num_qubits: usize) -> Result<Self, String> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to start decoder '{}': {}", program, e))?;
+        let stdin = child.stdin.take();
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        Ok(Self {
+            num_qubits,
+            child,
+            stdin,
+            stdout,
+        })
+    }
+}
+
+impl Decoder for ExternalDecoder {
+    fn decode(&mut self, syndrome: &Syndrome) -> Result<Correction, String> {
+        let mut line: String = syndrome.bits().iter().map(|bit| if *bit { '1' } else { '0' }).collect();
+        line.push('\n');
+        let stdin = self.stdin.as_mut().ok_or("Decoder input is closed")?;
+        stdin
+            .write_all(line.as_bytes())
+            .and_then(|_| stdin.flush())
+            .map_err(|e| format!("Failed to write to decoder: {}", e))?;
+
+        let mut answer = String::new();
+        let read = self
+            .stdout
+            .read_line(&mut answer)
+            .map_err(|e| format!("Failed to read from decoder: {}", e))?;
+        if read == 0 {
+            return Err("Decoder exited before answering".to_string());
+        }
+        PauliString::from_str(answer.trim(), self.num_qubits)
+            .map(|pauli| Correction::from_pauli(&pauli))
+            .map_err(|e| format!("Invalid correction from decoder: {}", e))
+    }
+}
+
+impl Drop for ExternalDecoder {
+    fn drop(&mut self) {
+        // Closing stdin lets a well-behaved decoder exit on its own; one
+        // that is still running afterwards is killed rather than waited on
+        drop(self.stdin.take());
+        if !matches!(self.child.try_wait(), Ok(Some(_))) {
+            let _ = self.child.kill();
+        }
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_decoder() {
+        // Flips qubit 0 whenever the first detector fired
+        let script = "while read s; do case $s in 1*) echo XII;; *) echo III;; esac; done";
+        let mut decoder = ExternalDecoder::spawn("sh", &["-c", script], 3).unwrap();
+        assert_eq!(
+            decoder.decode(&Syndrome::from_bools(&[true, false])).unwrap().to_pauli(),
+            PauliString::from_str("XII", 3).unwrap()
+        );
+        assert!(decoder.decode(&Syndrome::from_bools(&[false, true])).unwrap().is_identity());
+
+        let mut broken = ExternalDecoder::spawn("sh", &["-c", "read s; echo Q"], 1).unwrap();
+        assert!(broken.decode(&Syndrome::from_bools(&[true])).is_err());
+        assert!(ExternalDecoder::spawn("/nonexistent/decoder", &[], 1).is_err());
+    }
+}
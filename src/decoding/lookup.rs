@@ -0,0 +1,57 @@
+use crate::codes::StabilizerCode;
+use crate::decoding::syndrome::{Correction, Syndrome};
+use crate::decoding::Decoder;
+use std::collections::HashMap;
+
+/// Minimum-weight lookup decoding of the final perfect syndrome round, i.e.
+/// the trailing `stabilizers.len()` detectors of a shot.
+#[derive(Clone, Debug)]
+pub struct LookupDecoder {
+    num_stabilizers: usize,
+    table: HashMap<Syndrome, Correction>,
+}
+
+impl LookupDecoder {
+    pub fn new(code: &StabilizerCode) -> Self {
+        Self {
+            num_stabilizers: code.stabilizers.len(),
+            table: code
+                .lookup_table()
+                .iter()
+                .map(|(syndrome, correction)| (Syndrome::from_bools(syndrome), Correction::from_pauli(correction)))
+                .collect(),
+        }
+    }
+}
+
+impl Decoder for LookupDecoder {
+    fn decode(&mut self, syndrome: &Syndrome) -> Result<Correction, String> {
+        if syndrome.len() < self.num_stabilizers {
+            return Err(format!(
+                "Expected at least {} detectors, got {}",
+                self.num_stabilizers,
+                syndrome.len()
+            ));
+        }
+        let final_round = syndrome.slice(syndrome.len() - self.num_stabilizers..syndrome.len());
+        self.table
+            .get(&final_round)
+            .cloned()
+            .ok_or_else(|| "Syndrome not reachable for this code".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::pauli::PauliString;
+
+    #[test]
+    fn test_lookup_decoding() {
+        let mut decoder = LookupDecoder::new(&StabilizerCode::repetition(3));
+        // A leading gadget detector is ignored
+        let correction = decoder.decode(&Syndrome::from_bools(&[true, true, false])).unwrap();
+        assert_eq!(correction.to_pauli(), PauliString::from_str("XII", 3).unwrap());
+        assert!(decoder.decode(&Syndrome::from_bools(&[true])).is_err());
+    }
+}
@@ -0,0 +1,25 @@
+pub mod css;
+pub mod external;
+pub mod lookup;
+pub mod syndrome;
+pub mod union_find;
+pub mod verification;
+
+pub use css::{CssDecoder, CssSplit};
+pub use external::ExternalDecoder;
+pub use lookup::LookupDecoder;
+pub use syndrome::{Correction, Syndrome};
+pub use union_find::UnionFindDecoder;
+pub use verification::{verify_decoder, DecodingFailure, DecodingReport};
+
+use crate::noise::sampler::ShotRecord;
+
+/// Maps detection events to a correction on the code block.
+pub trait Decoder {
+    fn decode(&mut self, syndrome: &Syndrome) -> Result<Correction, String>;
+
+    /// Decodes a full shot; herald-aware decoders override this.
+    fn decode_shot(&mut self, shot: &ShotRecord) -> Result<Correction, String> {
+        self.decode(&Syndrome::from_bools(&shot.detectors))
+    }
+}
@@ -0,0 +1,238 @@
+//! Bit-packed decoder inputs and outputs.
+//!
+//! `Syndrome` holds detection events and `Correction` the Pauli part of a
+//! correction, both as packed bits. XOR composes, AND intersects and
+//! `weight` counts set bits word by word, so decoder inner loops avoid
+//! `Vec<bool>` and phase bookkeeping.
+
+use crate::physics::pauli::PauliString;
+use bitvec::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::ops::{BitAnd, BitAndAssign, BitXor, BitXorAssign, Range};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Syndrome {
+    bits: BitVec,
+}
+
+impl Syndrome {
+    /// `len` detectors, none fired.
+    pub fn new(len: usize) -> Self {
+        Self {
+            bits: bitvec![0; len],
+        }
+    }
+
+    pub fn from_bools(detectors: &[bool]) -> Self {
+        Self {
+            bits: detectors.iter().copied().collect(),
+        }
+    }
+
+    pub fn to_bools(&self) -> Vec<bool> {
+        self.bits.iter().by_vals().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        self.bits[index]
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) {
+        self.bits.set(index, value);
+    }
+
+    /// Number of detection events
+    pub fn weight(&self) -> usize {
+        self.bits.count_ones()
+    }
+
+    /// No detector fired
+    pub fn is_trivial(&self) -> bool {
+        self.bits.not_any()
+    }
+
+    /// Indices of the fired detectors
+    pub fn fired(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bits.iter_ones()
+    }
+
+    /// The detectors in `range`, e.g. the final syndrome round of a shot.
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        Self {
+            bits: self.bits[range].to_bitvec(),
+        }
+    }
+
+    /// The detectors at `indices`, in that order.
+    pub fn select(&self, indices: &[usize]) -> Self {
+        Self {
+            bits: indices.iter().map(|&i| self.bits[i]).collect(),
+        }
+    }
+
+    pub fn bits(&self) -> &BitSlice {
+        &self.bits
+    }
+}
+
+impl From<&[bool]> for Syndrome {
+    fn from(detectors: &[bool]) -> Self {
+        Self::from_bools(detectors)
+    }
+}
+
+/// A Pauli correction without phase.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Correction {
+    x_bits: BitVec,
+    z_bits: BitVec,
+}
+
+impl Correction {
+    pub fn identity(num_qubits: usize) -> Self {
+        Self {
+            x_bits: bitvec![0; num_qubits],
+            z_bits: bitvec![0; num_qubits],
+        }
+    }
+
+    pub fn from_pauli(pauli: &PauliString) -> Self {
+        Self {
+            x_bits: pauli.x_bits().clone(),
+            z_bits: pauli.z_bits().clone(),
+        }
+    }
+
+    /// The correction with phase +1.
+    pub fn to_pauli(&self) -> PauliString {
+        let mut pauli = PauliString::new(self.num_qubits());
+        pauli.set_x_bits(self.x_bits.clone());
+        pauli.set_z_bits(self.z_bits.clone());
+        pauli
+    }
+
+    pub fn num_qubits(&self) -> usize {
+        self.x_bits.len()
+    }
+
+    /// Number of qubits acted on
+    pub fn weight(&self) -> usize {
+        (self.x_bits.clone() | &self.z_bits).count_ones()
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.x_bits.not_any() && self.z_bits.not_any()
+    }
+
+    pub fn x_bits(&self) -> &BitSlice {
+        &self.x_bits
+    }
+
+    pub fn z_bits(&self) -> &BitSlice {
+        &self.z_bits
+    }
+
+    /// This correction's X part together with `other`'s Z part, e.g. to
+    /// recombine the two sectors of a CSS decoder.
+    pub fn with_z_part_of(mut self, other: &Correction) -> Self {
+        self.z_bits.clone_from(&other.z_bits);
+        self
+    }
+}
+
+impl From<&PauliString> for Correction {
+    fn from(pauli: &PauliString) -> Self {
+        Self::from_pauli(pauli)
+    }
+}
+
+impl From<Correction> for PauliString {
+    fn from(correction: Correction) -> Self {
+        correction.to_pauli()
+    }
+}
+
+/// Implements `^`, `&` and their assigning forms bitwise over `$fields`.
+macro_rules! impl_bit_ops {
+    ($type:ty, $($field:ident),+) => {
+        impl BitXorAssign<&$type> for $type {
+            fn bitxor_assign(&mut self, other: &$type) {
+                $(
+                    assert_eq!(self.$field.len(), other.$field.len(), "Operand lengths differ");
+                    self.$field ^= &other.$field;
+                )+
+            }
+        }
+
+        impl BitAndAssign<&$type> for $type {
+            fn bitand_assign(&mut self, other: &$type) {
+                $(
+                    assert_eq!(self.$field.len(), other.$field.len(), "Operand lengths differ");
+                    self.$field &= &other.$field;
+                )+
+            }
+        }
+
+        impl BitXor for &$type {
+            type Output = $type;
+
+            fn bitxor(self, other: &$type) -> $type {
+                let mut result = self.clone();
+                result ^= other;
+                result
+            }
+        }
+
+        impl BitAnd for &$type {
+            type Output = $type;
+
+            fn bitand(self, other: &$type) -> $type {
+                let mut result = self.clone();
+                result &= other;
+                result
+            }
+        }
+    };
+}
+
+impl_bit_ops!(Syndrome, bits);
+impl_bit_ops!(Correction, x_bits, z_bits);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syndrome_algebra() {
+        let a = Syndrome::from_bools(&[true, true, false, true]);
+        let b = Syndrome::from_bools(&[false, true, true, true]);
+        assert_eq!((&a ^ &b).to_bools(), vec![true, false, true, false]);
+        assert_eq!((&a & &b).weight(), 2);
+        assert_eq!(a.fired().collect::<Vec<_>>(), vec![0, 1, 3]);
+        assert_eq!(a.slice(2..4), Syndrome::from_bools(&[false, true]));
+        assert_eq!(a.select(&[3, 2]), Syndrome::from_bools(&[true, false]));
+        assert!((&a ^ &a).is_trivial());
+    }
+
+    #[test]
+    fn test_correction_roundtrip() {
+        let pauli = PauliString::from_str("XIYZ", 4).unwrap();
+        let correction = Correction::from_pauli(&pauli);
+        assert_eq!(correction.weight(), 3);
+        assert_eq!(correction.to_pauli(), pauli);
+
+        // Composition drops the phase: Y * Z ~ X
+        let z = Correction::from_pauli(&PauliString::from_str("IIZI", 4).unwrap());
+        assert_eq!((&correction ^ &z).to_pauli(), PauliString::from_str("XIXZ", 4).unwrap());
+        assert_eq!((&correction & &z).weight(), 1);
+        assert!((&correction ^ &correction).is_identity());
+    }
+}
@@ -0,0 +1,267 @@
+//! Union-find decoding (Delfosse–Nickerson) of a CSS code's final syndrome.
+//!
+//! Each sector is a matching graph: one node per check of that sector plus a
+//! shared boundary node, and one edge per qubit joining the (at most two)
+//! checks it flips, or a check and the boundary. Clusters grow from the
+//! fired checks in half-edge steps until every cluster has even parity or
+//! reaches the boundary, and a spanning forest of each cluster is then
+//! peeled from the leaves to pick the edges of the correction.
+
+use crate::codes::StabilizerCode;
+use crate::decoding::syndrome::{Correction, Syndrome};
+use crate::decoding::Decoder;
+use crate::physics::pauli::{PauliString, SinglePauli};
+
+/// Union-find decoding of the final perfect syndrome round, i.e. the
+/// trailing `stabilizers.len()` detectors of a shot. The code must be CSS,
+/// and every qubit may flip at most two checks of each type.
+#[derive(Clone, Debug)]
+pub struct UnionFindDecoder {
+    num_qubits: usize,
+    num_stabilizers: usize,
+    sectors: Vec<MatchingGraph>,
+}
+
+/// The checks seeing one error type and the qubit edges between them.
+#[derive(Clone, Debug)]
+struct MatchingGraph {
+    error_type: SinglePauli,
+    /// Stabilizer index of each node; node `checks.len()` is the boundary
+    checks: Vec<usize>,
+    /// (node, node, qubit)
+    edges: Vec<(usize, usize, usize)>,
+}
+
+impl UnionFindDecoder {
+    pub fn new(code: &StabilizerCode) -> Result<Self, String> {
+        let sectors = [SinglePauli::X, SinglePauli::Z]
+            .into_iter()
+            .map(|error_type| MatchingGraph::new(code, error_type))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            num_qubits: code.num_qubits,
+            num_stabilizers: code.stabilizers.len(),
+            sectors,
+        })
+    }
+}
+
+impl Decoder for UnionFindDecoder {
+    fn decode(&mut self, syndrome: &Syndrome) -> Result<Correction, String> {
+        if syndrome.len() < self.num_stabilizers {
+            return Err(format!(
+                "Expected at least {} detectors, got {}",
+                self.num_stabilizers,
+                syndrome.len()
+            ));
+        }
+        let final_round = syndrome.slice(syndrome.len() - self.num_stabilizers..syndrome.len());
+        let mut correction = PauliString::new(self.num_qubits);
+        for sector in &self.sectors {
+            for qubit in sector.decode(&final_round)? {
+                let mut flip = PauliString::new(self.num_qubits);
+                flip.set_pauli(qubit, sector.error_type);
+                correction = correction.multiply(&flip);
+            }
+        }
+        Ok(Correction::from_pauli(&correction))
+    }
+}
+
+impl MatchingGraph {
+    fn new(code: &StabilizerCode, error_type: SinglePauli) -> Result<Self, String> {
+        let checks = code.css_checks(error_type)?;
+        let boundary = checks.len();
+        let mut edges = Vec::new();
+        for qubit in 0..code.num_qubits {
+            let mut error = PauliString::new(code.num_qubits);
+            error.set_pauli(qubit, error_type);
+            let flipped: Vec<usize> = (0..checks.len())
+                .filter(|&node| !code.stabilizers[checks[node]].commutes_with(&error))
+                .collect();
+            match flipped[..] {
+                [] => {}
+                [a] => edges.push((a, boundary, qubit)),
+                [a, b] => edges.push((a, b, qubit)),
+                _ => {
+                    return Err(format!(
+                        "{} on qubit {} flips {} checks; union-find needs at most two",
+                        error_type,
+                        qubit,
+                        flipped.len()
+                    ))
+                }
+            }
+        }
+        Ok(Self {
+            error_type,
+            checks,
+            edges,
+        })
+    }
+
+    /// Qubits to flip so that the fired checks of this sector are cleared.
+    fn decode(&self, syndrome: &Syndrome) -> Result<Vec<usize>, String> {
+        let boundary = self.checks.len();
+        let fired: Vec<bool> = self
+            .checks
+            .iter()
+            .map(|&check| syndrome.get(check))
+            .chain([false])
+            .collect();
+
+        // Growth: odd clusters away from the boundary add half an edge on
+        // every side they own until they merge into even or boundary ones
+        let mut clusters = Clusters::new(&fired, boundary);
+        let mut support = vec![0u8; self.edges.len()];
+        loop {
+            let active = |node: usize, clusters: &mut Clusters| {
+                let root = clusters.find(node);
+                clusters.odd[root] && !clusters.has_boundary[root]
+            };
+            let mut grown = Vec::new();
+            let mut any_active = false;
+            for (edge, &(a, b, _)) in self.edges.iter().enumerate() {
+                if support[edge] == 2 {
+                    continue;
+                }
+                let steps = u8::from(active(a, &mut clusters)) + u8::from(active(b, &mut clusters));
+                if steps > 0 {
+                    any_active = true;
+                    support[edge] = (support[edge] + steps).min(2);
+                    if support[edge] == 2 {
+                        grown.push(edge);
+                    }
+                }
+            }
+            if !any_active {
+                break;
+            }
+            for edge in grown {
+                let (a, b, _) = self.edges[edge];
+                clusters.union(a, b);
+            }
+        }
+        if (0..boundary).any(|node| {
+            let root = clusters.find(node);
+            clusters.odd[root] && !clusters.has_boundary[root]
+        }) {
+            return Err("Syndrome not reachable for this code".to_string());
+        }
+
+        // Peeling: a spanning forest of the fully grown edges, rooted at the
+        // boundary where a cluster touches it, cleared from the leaves
+        let mut adjacent = vec![Vec::new(); boundary + 1];
+        for (edge, &(a, b, _)) in self.edges.iter().enumerate() {
+            if support[edge] == 2 {
+                adjacent[a].push(edge);
+                adjacent[b].push(edge);
+            }
+        }
+        let mut parent: Vec<Option<usize>> = vec![None; boundary + 1];
+        let mut visited = vec![false; boundary + 1];
+        let mut order = Vec::new();
+        for root in std::iter::once(boundary).chain(0..boundary) {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut queue = std::collections::VecDeque::from([root]);
+            while let Some(node) = queue.pop_front() {
+                order.push(node);
+                for &edge in &adjacent[node] {
+                    let (a, b, _) = self.edges[edge];
+                    let next = if a == node { b } else { a };
+                    if !visited[next] {
+                        visited[next] = true;
+                        parent[next] = Some(edge);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        let mut marked = fired;
+        let mut qubits = Vec::new();
+        for &node in order.iter().rev() {
+            if let (true, Some(edge)) = (marked[node], parent[node]) {
+                let (a, b, qubit) = self.edges[edge];
+                marked[node] = false;
+                marked[if a == node { b } else { a }] ^= true;
+                qubits.push(qubit);
+            }
+        }
+        Ok(qubits)
+    }
+}
+
+/// Disjoint sets of graph nodes with the parity and boundary contact of
+/// each set stored at its root.
+struct Clusters {
+    parent: Vec<usize>,
+    odd: Vec<bool>,
+    has_boundary: Vec<bool>,
+}
+
+impl Clusters {
+    fn new(fired: &[bool], boundary: usize) -> Self {
+        Self {
+            parent: (0..fired.len()).collect(),
+            odd: fired.to_vec(),
+            has_boundary: (0..fired.len()).map(|node| node == boundary).collect(),
+        }
+    }
+
+    fn find(&mut self, node: usize) -> usize {
+        let mut root = node;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut node = node;
+        while self.parent[node] != root {
+            node = std::mem::replace(&mut self.parent[node], root);
+        }
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parent[b] = a;
+            self.odd[a] ^= self.odd[b];
+            self.has_boundary[a] |= self.has_boundary[b];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corrects_single_errors_on_surface_code() {
+        let code = StabilizerCode::rotated_surface(3);
+        let mut decoder = UnionFindDecoder::new(&code).unwrap();
+        for qubit in 0..code.num_qubits {
+            for pauli in [SinglePauli::X, SinglePauli::Y, SinglePauli::Z] {
+                let mut error = PauliString::new(code.num_qubits);
+                error.set_pauli(qubit, pauli);
+                let correction = decoder.decode(&Syndrome::from_bools(&code.syndrome(&error))).unwrap();
+                let net = error.multiply(&correction.to_pauli());
+                assert!(code.in_stabilizer_group(&net), "{} on qubit {}", pauli, qubit);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_repetition_code_and_rejects_steane() {
+        let code = StabilizerCode::repetition(5);
+        let mut decoder = UnionFindDecoder::new(&code).unwrap();
+        // Leading gadget detectors are ignored, as in the lookup decoder
+        let error = PauliString::from_str("IXXII", 5).unwrap();
+        let mut detectors = vec![true];
+        detectors.extend(code.syndrome(&error));
+        let correction = decoder.decode(&Syndrome::from_bools(&detectors)).unwrap();
+        assert_eq!(correction.to_pauli(), error);
+        assert!(UnionFindDecoder::new(&StabilizerCode::steane()).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+//! Decoder output verification.
+//!
+//! Every correction is applied to the shot's residual error and the product
+//! is reduced modulo the stabilizers, so a report shows what the decoder got
+//! wrong, not only how often.
+
+use crate::analysis::residual_class::{classify_residual, ResidualClassCounts};
+use crate::codes::StabilizerCode;
+use crate::decoding::Decoder;
+use crate::noise::sampler::ShotRecord;
+use crate::physics::pauli::PauliString;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodingFailure {
+    pub shot: usize,
+    pub correction: PauliString,
+    /// Residual error times correction
+    pub net_error: PauliString,
+    /// See `StabilizerCode::observable_flips`
+    pub flipped_observables: Vec<bool>,
+    /// The correction did not return to the code space
+    pub syndrome_left: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodingReport {
+    pub shots: usize,
+    pub failures: Vec<DecodingFailure>,
+    /// Number of failing shots flipping each observable
+    pub observable_flip_counts: Vec<usize>,
+    /// Shots by weight of the net error modulo stabilizers
+    pub residual_weight_histogram: Vec<usize>,
+    /// Logical class of the net error of every shot, per logical qubit
+    #[serde(default)]
+    pub residual_classes: Vec<ResidualClassCounts>,
+}
+
+impl DecodingReport {
+    pub fn logical_error_rate(&self) -> f64 {
+        if self.shots == 0 {
+            0.0
+        } else {
+            self.failures.len() as f64 / self.shots as f64
+        }
+    }
+}
+
+pub fn verify_decoder<D: Decoder + ?Sized>(
+    decoder: &mut D,
+    code: &StabilizerCode,
+    shots: &[ShotRecord],
+) -> Result<DecodingReport, String> {
+    let mut report = DecodingReport {
+        shots: shots.len(),
+        observable_flip_counts: vec![0; 2 * code.num_logical_qubits()],
+        residual_weight_histogram: vec![0; code.num_qubits + 1],
+        residual_classes: vec![ResidualClassCounts::default(); code.num_logical_qubits()],
+        ..DecodingReport::default()
+    };
+
+    for (index, shot) in shots.iter().enumerate() {
+        let correction = decoder.decode_shot(shot)?.to_pauli();
+        if correction.num_qubits() != code.num_qubits {
+            return Err(format!(
+                "Decoder returned a {}-qubit correction for a {}-qubit code",
+                correction.num_qubits(),
+                code.num_qubits
+            ));
+        }
+        let net_error = shot.residual.multiply(&correction);
+        report.residual_weight_histogram[code.reduced_weight(&net_error)] += 1;
+        for (counts, class) in report.residual_classes.iter_mut().zip(classify_residual(code, &net_error)?) {
+            counts.add(class);
+        }
+
+        let syndrome_left = code.syndrome(&net_error).contains(&true);
+        let flipped_observables = code.observable_flips(&net_error);
+        if syndrome_left || flipped_observables.contains(&true) {
+            for (count, &flipped) in report.observable_flip_counts.iter_mut().zip(&flipped_observables) {
+                *count += flipped as usize;
+            }
+            report.failures.push(DecodingFailure {
+                shot: index,
+                correction,
+                net_error,
+                flipped_observables,
+                syndrome_left,
+            });
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::Gadget;
+    use crate::decoding::{Correction, LookupDecoder, Syndrome};
+    use crate::noise::model::NoiseModel;
+    use crate::noise::sampler::sample_shots;
+    use crate::physics::circuit::{Circuit, Gate, SingleGate};
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    /// Always answers "no correction".
+    struct Trivial(usize);
+
+    impl Decoder for Trivial {
+        fn decode(&mut self, _: &Syndrome) -> Result<Correction, String> {
+            Ok(Correction::identity(self.0))
+        }
+    }
+
+    #[test]
+    fn test_report_shows_what_went_wrong() {
+        let mut circuit = Circuit::new(3);
+        for qubit in 0..3 {
+            circuit.add_gate(Gate::Single { qubit, gate: SingleGate::I }).unwrap();
+        }
+        let gadget = Gadget::new(circuit, vec![0, 1, 2]).unwrap();
+        let code = StabilizerCode::repetition(3);
+        let noise = NoiseModel {
+            single_qubit_depolarizing: 0.2,
+            ..NoiseModel::default()
+        };
+        let mut rng = SmallRng::seed_from_u64(2216);
+        let shots = sample_shots(&gadget, &code, &noise, 500, &mut rng).unwrap();
+
+        let lookup = verify_decoder(&mut LookupDecoder::new(&code), &code, &shots).unwrap();
+        let trivial = verify_decoder(&mut Trivial(3), &code, &shots).unwrap();
+        assert!(lookup.failures.len() < trivial.failures.len());
+        assert!(lookup.failures.iter().all(|failure| !failure.syndrome_left));
+        assert!(trivial.failures.iter().any(|failure| failure.syndrome_left));
+        assert_eq!(lookup.residual_weight_histogram.iter().sum::<usize>(), 500);
+    }
+}
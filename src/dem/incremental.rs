@@ -0,0 +1,195 @@
+//! Detector error models kept up to date under gate edits.
+//!
+//! `IncrementalDem` stores every mechanism's unmerged effect, grouped by
+//! the gate it follows. Replacing gate g only changes mechanisms whose
+//! error can reach g's qubits (old or new) before g acts, plus g's own
+//! noise. Mechanisms after g never pass through it, but idle noise is tied
+//! to the last gate of each moment, so the mechanisms of g's whole moment
+//! are rebuilt. The set of affected earlier mechanisms is found by walking
+//! g's backward lightcone: a gate touching the cone pulls all its qubits
+//! into it. The cone is a superset of the true one, so every mechanism left
+//! alone really is unchanged.
+
+use crate::analysis::fault_sweep::Fault;
+use crate::codes::{Gadget, StabilizerCode};
+use crate::dem::model::{check_data_qubits, gate_independent_errors, DemError, DetectorErrorModel};
+use crate::noise::model::NoiseModel;
+use crate::physics::circuit::Gate;
+
+pub struct IncrementalDem {
+    gadget: Gadget,
+    code: StabilizerCode,
+    noise: NoiseModel,
+    /// Mechanisms right after each gate, with their unmerged effects
+    by_gate: Vec<Vec<(Vec<Fault>, DemError)>>,
+    /// Data errors and readout flips
+    fixed: Vec<DemError>,
+}
+
+impl IncrementalDem {
+    pub fn new(gadget: Gadget, code: StabilizerCode, noise: NoiseModel) -> Result<Self, String> {
+        noise.validate_for(&gadget.circuit)?;
+        check_data_qubits(&gadget, &code)?;
+        let fixed = gate_independent_errors(&gadget, &code, &noise);
+        let mut dem = Self {
+            gadget,
+            code,
+            noise,
+            by_gate: Vec::new(),
+            fixed,
+        };
+        dem.by_gate = (0..dem.gadget.circuit.gates.len()).map(|g| dem.mechanisms_at(g)).collect();
+        Ok(dem)
+    }
+
+    pub fn gadget(&self) -> &Gadget {
+        &self.gadget
+    }
+
+    /// The merged model, equal to `DetectorErrorModel::from_gadget` on the
+    /// current gadget.
+    pub fn model(&self) -> DetectorErrorModel {
+        let mut dem = DetectorErrorModel::empty_for(&self.gadget, &self.code);
+        dem.errors = self
+            .by_gate
+            .iter()
+            .flatten()
+            .map(|(_, error)| error.clone())
+            .chain(self.fixed.iter().cloned())
+            .collect();
+        dem.filter(|e| e.probability > 0.0).merged()
+    }
+
+    /// Replaces gate `index` and recomputes the mechanisms it can affect.
+    /// Returns how many mechanisms were propagated again.
+    pub fn replace_gate(&mut self, index: usize, gate: Gate) -> Result<usize, String> {
+        let circuit = &self.gadget.circuit;
+        if index >= circuit.gates.len() {
+            return Err(format!("No gate {} in a circuit of {} gates", index, circuit.gates.len()));
+        }
+        if let Some(&qubit) = gate.qubits().iter().find(|&&q| q >= circuit.num_qubits) {
+            return Err(format!(
+                "Gate acts on qubit {} but circuit has only {} qubits",
+                qubit, circuit.num_qubits
+            ));
+        }
+        if let Some(m) = circuit.measurements.iter().find(|m| m.position <= index && gate.qubits().contains(&m.qubit)) {
+            return Err(format!("{} acts on qubit {} after it is measured", gate, m.qubit));
+        }
+
+        let mut cone = vec![false; circuit.num_qubits];
+        for qubit in circuit.gates[index].qubits().into_iter().chain(gate.qubits()) {
+            cone[qubit] = true;
+        }
+        let old = std::mem::replace(&mut self.gadget.circuit.gates[index], gate);
+        if let Err(e) = self.gadget.circuit.validate_moments() {
+            self.gadget.circuit.gates[index] = old;
+            return Err(e);
+        }
+
+        let moment = self
+            .gadget
+            .circuit
+            .moment_of(index)
+            .and_then(|time| self.gadget.circuit.moment_range(time))
+            .expect("gate lies in a moment");
+        let mut recomputed = 0;
+        for after_gate in moment.clone() {
+            self.by_gate[after_gate] = self.mechanisms_at(after_gate);
+            recomputed += self.by_gate[after_gate].len();
+        }
+        for after_gate in (0..index).rev() {
+            if moment.contains(&after_gate) {
+                continue;
+            }
+            for (faults, error) in &mut self.by_gate[after_gate] {
+                if faults.iter().any(|fault| cone[fault.qubit]) {
+                    *error = DemError::from_faults(&self.gadget, &self.code, faults, error.probability);
+                    recomputed += 1;
+                }
+            }
+            let qubits = self.gadget.circuit.gates[after_gate].qubits();
+            if qubits.iter().any(|&q| cone[q]) {
+                for q in qubits {
+                    cone[q] = true;
+                }
+            }
+        }
+        Ok(recomputed)
+    }
+
+    fn mechanisms_at(&self, after_gate: usize) -> Vec<(Vec<Fault>, DemError)> {
+        self.noise
+            .mechanisms_at(&self.gadget.circuit, after_gate)
+            .into_iter()
+            .map(|(faults, probability)| {
+                let error = DemError::from_faults(&self.gadget, &self.code, &faults, probability);
+                (faults, error)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codes::MeasurementBasis;
+    use crate::physics::circuit::{Circuit, SingleGate, TwoGate};
+
+    #[test]
+    fn test_edits_match_full_rebuild() {
+        // Repetition-3 round: ancillas 3 and 4 check qubit pairs
+        let mut circuit = Circuit::new(5);
+        for (control, target) in [(0, 3), (1, 3), (1, 4), (2, 4)] {
+            circuit.add_gate(Gate::Two(TwoGate::CNOT { control, target })).unwrap();
+        }
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::I }).unwrap();
+        for qubit in [3, 4] {
+            circuit.add_measurement(qubit, MeasurementBasis::Z).unwrap();
+        }
+        let gadget = Gadget::new(circuit, vec![0, 1, 2]).unwrap();
+        let code = StabilizerCode::repetition(3);
+        let noise = NoiseModel::circuit_level(0.01);
+        let mut dem = IncrementalDem::new(gadget, code.clone(), noise.clone()).unwrap();
+
+        let edits = [
+            (2, Gate::Two(TwoGate::CZ { control: 1, target: 4 })),
+            (4, Gate::Single { qubit: 2, gate: SingleGate::H }),
+            (0, Gate::Two(TwoGate::CNOT { control: 3, target: 0 })),
+        ];
+        for (index, gate) in edits {
+            dem.replace_gate(index, gate).unwrap();
+            let full = DetectorErrorModel::from_gadget(dem.gadget(), &code, &noise).unwrap();
+            assert_eq!(dem.model(), full);
+        }
+        // Nothing before gate 0 can be affected: only its own noise
+        let own = noise.mechanisms_at(&dem.gadget().circuit, 0).len();
+        assert_eq!(dem.replace_gate(0, Gate::Two(TwoGate::CNOT { control: 0, target: 3 })).unwrap(), own);
+        assert!(dem.replace_gate(9, Gate::Single { qubit: 0, gate: SingleGate::H }).is_err());
+    }
+
+    #[test]
+    fn test_idle_noise_follows_moment() {
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::I }).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 1, gate: SingleGate::I }).unwrap();
+        let circuit = circuit.schedule();
+        assert_eq!(circuit.moments, vec![0]);
+        let gadget = Gadget::new(circuit, vec![0, 1, 2]).unwrap();
+        let code = StabilizerCode::repetition(3);
+        let noise = NoiseModel {
+            idle_depolarizing: 0.03,
+            ..NoiseModel::default()
+        };
+        let mut dem = IncrementalDem::new(gadget, code.clone(), noise.clone()).unwrap();
+
+        // Qubit 2 stops idling and qubit 0 starts, although gate 0 is not
+        // the one carrying the moment's idle noise
+        dem.replace_gate(0, Gate::Single { qubit: 2, gate: SingleGate::I }).unwrap();
+        assert_eq!(dem.model(), DetectorErrorModel::from_gadget(dem.gadget(), &code, &noise).unwrap());
+        // Two gates of one moment on qubit 1
+        let before = dem.gadget().clone();
+        assert!(dem.replace_gate(0, Gate::Single { qubit: 1, gate: SingleGate::I }).is_err());
+        assert_eq!(dem.gadget(), &before);
+    }
+}
@@ -0,0 +1,116 @@
+//! Matching-graph export in the layout used by PyMatching/stim tooling.
+//!
+//! Nodes are detectors; a single virtual boundary node (index
+//! `num_detectors`) closes edges of errors that flip only one detector.
+//! Each edge carries `fault_ids` (the observables it flips), the error
+//! probability and the log-likelihood weight ln((1 - p) / p), matching
+//! `pymatching.Matching.add_edge`.
+
+use crate::dem::model::DetectorErrorModel;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MatchingEdge {
+    pub source: usize,
+    pub target: usize,
+    pub fault_ids: Vec<usize>,
+    pub weight: f64,
+    pub error_probability: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MatchingGraph {
+    pub num_detectors: usize,
+    pub num_observables: usize,
+    pub nodes: Vec<usize>,
+    pub boundary: Vec<usize>,
+    pub edges: Vec<MatchingEdge>,
+}
+
+impl MatchingGraph {
+    /// Fails on errors flipping more than two detectors, which are not
+    /// graphlike; errors flipping no detector are dropped.
+    pub fn from_dem(dem: &DetectorErrorModel) -> Result<Self, String> {
+        let boundary = dem.num_detectors;
+        let mut edges = Vec::new();
+        for error in &dem.errors {
+            let (source, target) = match error.detectors.as_slice() {
+                [] => continue,
+                &[only] => (only, boundary),
+                &[a, b] => (a, b),
+                more => {
+                    return Err(format!(
+                        "Error with probability {} flips {} detectors and is not graphlike",
+                        error.probability,
+                        more.len()
+                    ))
+                }
+            };
+            edges.push(MatchingEdge {
+                source,
+                target,
+                fault_ids: error.observables.clone(),
+                weight: ((1.0 - error.probability) / error.probability).ln(),
+                error_probability: error.probability,
+            });
+        }
+        Ok(Self {
+            num_detectors: dem.num_detectors,
+            num_observables: dem.num_observables,
+            nodes: (0..dem.num_detectors).collect(),
+            boundary: vec![boundary],
+            edges,
+        })
+    }
+}
+
+pub fn export_matching_graph_json(dem: &DetectorErrorModel) -> Result<String, String> {
+    let graph = MatchingGraph::from_dem(dem)?;
+    serde_json::to_string_pretty(&graph).map_err(|e| format!("Failed to serialize matching graph: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dem::model::DemError;
+
+    fn error(probability: f64, detectors: Vec<usize>, observables: Vec<usize>) -> DemError {
+        DemError {
+            probability,
+            detectors,
+            observables,
+        }
+    }
+
+    #[test]
+    fn test_edges_and_boundary() {
+        let dem = DetectorErrorModel {
+            num_detectors: 2,
+            num_observables: 1,
+            errors: vec![
+                error(0.1, vec![0], vec![0]),
+                error(0.1, vec![0, 1], vec![]),
+                error(0.2, vec![], vec![0]),
+            ],
+        };
+        let graph = MatchingGraph::from_dem(&dem).unwrap();
+        assert_eq!(graph.boundary, vec![2]);
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!((graph.edges[0].source, graph.edges[0].target), (0, 2));
+        assert_eq!(graph.edges[0].fault_ids, vec![0]);
+        assert!((graph.edges[1].weight - 9f64.ln()).abs() < 1e-12);
+
+        let json = export_matching_graph_json(&dem).unwrap();
+        assert!(json.contains("\"fault_ids\""));
+    }
+
+    #[test]
+    fn test_hyperedge_rejected() {
+        let dem = DetectorErrorModel {
+            num_detectors: 3,
+            num_observables: 0,
+            errors: vec![error(0.1, vec![0, 1, 2], vec![])],
+        };
+        assert!(MatchingGraph::from_dem(&dem).is_err());
+    }
+}
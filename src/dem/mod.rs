@@ -0,0 +1,9 @@
+pub mod incremental;
+pub mod matching;
+pub mod model;
+pub mod stim_format;
+
+pub use incremental::IncrementalDem;
+pub use matching::{export_matching_graph_json, MatchingEdge, MatchingGraph};
+pub use model::{DemError, DetectorErrorModel};
+pub use stim_format::{export_dem, import_dem};
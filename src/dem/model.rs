@@ -0,0 +1,355 @@
+//! Detector error model: a weighted hypergraph whose hyperedges are
+//! independent error mechanisms, each flipping a set of detectors and
+//! observables with some probability.
+//!
+//! For a gadget under circuit-level noise, every independent error
+//! mechanism of the noise model (one Pauli at one location, one data error,
+//! one readout flip) is propagated once; mechanisms flipping the same
+//! detectors and observables are merged. Detector and observable indices
+//! follow `noise::sampler::ShotRecord`. Bursts and heralded noise are
+//! correlated or classical and are not represented.
+//!
+//! Merged models (including everything `from_gadget` returns) list their
+//! errors sorted by (detectors, observables).
+
+use crate::analysis::fault_sweep::{propagate_faults, Fault};
+use crate::analysis::round_cache::RoundCache;
+use crate::codes::{Gadget, StabilizerCode};
+use crate::memory::{dem_bytes, MemoryLimits};
+use crate::noise::model::NoiseModel;
+use crate::physics::pauli::{PauliString, SinglePauli};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const PAULIS: [SinglePauli; 4] = [SinglePauli::I, SinglePauli::X, SinglePauli::Y, SinglePauli::Z];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DemError {
+    pub probability: f64,
+    pub detectors: Vec<usize>,
+    pub observables: Vec<usize>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DetectorErrorModel {
+    pub num_detectors: usize,
+    pub num_observables: usize,
+    pub errors: Vec<DemError>,
+}
+
+impl DetectorErrorModel {
+    pub fn from_gadget(gadget: &Gadget, code: &StabilizerCode, noise: &NoiseModel) -> Result<Self, String> {
+        Self::from_gadget_within(gadget, code, noise, &MemoryLimits::default())
+    }
+
+    /// `from_gadget`, failing before propagation when the unmerged model
+    /// would exceed `limits`.
+    pub fn from_gadget_within(
+        gadget: &Gadget,
+        code: &StabilizerCode,
+        noise: &NoiseModel,
+        limits: &MemoryLimits,
+    ) -> Result<Self, String> {
+        noise.validate_for(&gadget.circuit)?;
+        check_data_qubits(gadget, code)?;
+
+        let mechanisms = noise.mechanisms(&gadget.circuit);
+        let count = mechanisms.len() + 3 * code.num_qubits + gadget.measurements().len();
+        limits.check(&format!("A detector error model with {} mechanisms", count), dem_bytes(count))?;
+
+        let mut dem = Self::empty_for(gadget, code);
+        for (faults, probability) in mechanisms {
+            dem.errors.push(DemError::from_faults(gadget, code, &faults, probability));
+        }
+        dem.errors.extend(gate_independent_errors(gadget, code, noise));
+        Ok(dem.filter(|e| e.probability > 0.0).merged())
+    }
+
+    /// `from_gadget`, propagating through the cached rounds of `cache`,
+    /// which must have been built for the gadget's circuit. Repeated rounds
+    /// are handled once, however many times they occur.
+    pub fn from_gadget_with_rounds(
+        gadget: &Gadget,
+        code: &StabilizerCode,
+        noise: &NoiseModel,
+        cache: &mut RoundCache,
+    ) -> Result<Self, String> {
+        noise.validate_for(&gadget.circuit)?;
+        check_data_qubits(gadget, code)?;
+        if *cache.circuit() != gadget.circuit {
+            return Err("Round cache was built for a different circuit".to_string());
+        }
+        let mut dem = Self::empty_for(gadget, code);
+        for (faults, probability) in noise.mechanisms(&gadget.circuit) {
+            let error = cache.propagate(&faults);
+            dem.errors.push(DemError::from_error(gadget, code, &error, probability));
+        }
+        dem.errors.extend(gate_independent_errors(gadget, code, noise));
+        Ok(dem.filter(|e| e.probability > 0.0).merged())
+    }
+
+    /// No mechanisms, sized for `gadget` protecting `code`.
+    pub(crate) fn empty_for(gadget: &Gadget, code: &StabilizerCode) -> Self {
+        Self {
+            num_detectors: gadget.measurements().len() + code.stabilizers.len(),
+            num_observables: 2 * code.num_logical_qubits(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Combines mechanisms with identical effects (p = p1 + p2 - 2 p1 p2,
+    /// the chance that exactly one fires) and drops those with no effect.
+    /// The result is sorted by (detectors, observables).
+    pub fn merged(&self) -> Self {
+        let mut merged: BTreeMap<(Vec<usize>, Vec<usize>), f64> = BTreeMap::new();
+        for error in &self.errors {
+            if error.detectors.is_empty() && error.observables.is_empty() {
+                continue;
+            }
+            let key = (error.detectors.clone(), error.observables.clone());
+            let p = merged.entry(key).or_insert(0.0);
+            *p = *p * (1.0 - error.probability) + error.probability * (1.0 - *p);
+        }
+        Self {
+            num_detectors: self.num_detectors,
+            num_observables: self.num_observables,
+            errors: merged
+                .into_iter()
+                .map(|((detectors, observables), probability)| DemError {
+                    probability,
+                    detectors,
+                    observables,
+                })
+                .collect(),
+        }
+    }
+
+    /// Keeps the mechanisms for which `keep` returns true.
+    pub fn filter<F: Fn(&DemError) -> bool>(&self, keep: F) -> Self {
+        Self {
+            errors: self.errors.iter().filter(|e| keep(e)).cloned().collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Multiplies every probability by `factor`, capped at 1.
+    pub fn rescaled(&self, factor: f64) -> Self {
+        Self {
+            errors: self
+                .errors
+                .iter()
+                .map(|e| DemError {
+                    probability: (e.probability * factor).clamp(0.0, 1.0),
+                    ..e.clone()
+                })
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Restricts the model to the given detectors and observables, which are
+    /// renumbered in the order listed, then merges what became identical.
+    /// Used to split a CSS model into its X and Z sectors.
+    pub fn project(&self, detectors: &[usize], observables: &[usize]) -> Self {
+        let renumber = |kept: &[usize], indices: &[usize]| -> Vec<usize> {
+            let mut mapped: Vec<usize> = indices
+                .iter()
+                .filter_map(|i| kept.iter().position(|k| k == i))
+                .collect();
+            mapped.sort_unstable();
+            mapped
+        };
+        Self {
+            num_detectors: detectors.len(),
+            num_observables: observables.len(),
+            errors: self
+                .errors
+                .iter()
+                .map(|e| DemError {
+                    probability: e.probability,
+                    detectors: renumber(detectors, &e.detectors),
+                    observables: renumber(observables, &e.observables),
+                })
+                .collect(),
+        }
+        .merged()
+    }
+
+    /// Whether every mechanism flips at most two detectors.
+    pub fn is_graphlike(&self) -> bool {
+        self.errors.iter().all(|e| e.detectors.len() <= 2)
+    }
+
+    /// Mechanisms flipping `detector`.
+    pub fn errors_touching(&self, detector: usize) -> impl Iterator<Item = &DemError> {
+        self.errors.iter().filter(move |e| e.detectors.contains(&detector))
+    }
+}
+
+impl DemError {
+    /// The effect of `faults` firing together in `gadget`.
+    pub(crate) fn from_faults(gadget: &Gadget, code: &StabilizerCode, faults: &[Fault], probability: f64) -> Self {
+        Self::from_error(gadget, code, &propagate_faults(&gadget.circuit, faults), probability)
+    }
+
+    /// The effect of `error`, an error at the end of the gadget's circuit.
+    pub(crate) fn from_error(gadget: &Gadget, code: &StabilizerCode, error: &PauliString, probability: f64) -> Self {
+        let residual = gadget.data_error(error);
+        let mut detector_bits = gadget.measurement_flips(error);
+        detector_bits.extend(code.syndrome(&residual));
+        Self {
+            probability,
+            detectors: ones(&detector_bits),
+            observables: ones(&code.observable_flips(&residual)),
+        }
+    }
+}
+
+pub(crate) fn check_data_qubits(gadget: &Gadget, code: &StabilizerCode) -> Result<(), String> {
+    if gadget.data_qubits.len() != code.num_qubits {
+        return Err(format!(
+            "Gadget has {} data qubits but code '{}' has {}",
+            gadget.data_qubits.len(),
+            code.name,
+            code.num_qubits
+        ));
+    }
+    Ok(())
+}
+
+/// Data errors and readout flips, which do not depend on the gates: data
+/// errors only reach the final syndrome, readout flips only their own
+/// detector. Zero-probability entries are included.
+pub(crate) fn gate_independent_errors(gadget: &Gadget, code: &StabilizerCode, noise: &NoiseModel) -> Vec<DemError> {
+    let mut errors = Vec::new();
+    let offset = gadget.measurements().len();
+    for qubit in 0..code.num_qubits {
+        for &pauli in &PAULIS[1..] {
+            let mut residual = PauliString::new(code.num_qubits);
+            residual.set_pauli(qubit, pauli);
+            errors.push(DemError {
+                probability: noise.data_depolarizing / 3.0,
+                detectors: ones(&code.syndrome(&residual)).into_iter().map(|d| d + offset).collect(),
+                observables: ones(&code.observable_flips(&residual)),
+            });
+        }
+    }
+    for detector in 0..offset {
+        errors.push(DemError {
+            probability: noise.measurement_flip,
+            detectors: vec![detector],
+            observables: Vec::new(),
+        });
+    }
+    errors
+}
+
+fn ones(bits: &[bool]) -> Vec<usize> {
+    bits.iter().enumerate().filter(|(_, &bit)| bit).map(|(i, _)| i).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{Circuit, Gate, MeasurementBasis, SingleGate, TwoGate};
+
+    #[test]
+    fn test_repetition_memory_dem() {
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(Gate::Single { qubit: 1, gate: SingleGate::I }).unwrap();
+        let gadget = Gadget::new(circuit, vec![0, 1, 2]).unwrap();
+        let code = StabilizerCode::repetition(3);
+        let noise = NoiseModel {
+            single_qubit_depolarizing: 0.03,
+            ..NoiseModel::default()
+        };
+
+        let dem = DetectorErrorModel::from_gadget(&gadget, &code, &noise).unwrap();
+        assert_eq!(dem.num_detectors, 2);
+        assert_eq!(dem.num_observables, 2);
+        // X and Y on qubit 1 flip both checks; Y and Z anticommute with the
+        // logical X = XXX, observable 1
+        let both: Vec<&DemError> = dem.errors.iter().filter(|e| e.detectors == vec![0, 1]).collect();
+        assert_eq!(both.len(), 2);
+        let x_only = both.iter().find(|e| e.observables.is_empty()).unwrap();
+        assert!((x_only.probability - 0.01).abs() < 1e-12);
+        assert!(dem.errors.iter().any(|e| e.detectors.is_empty() && e.observables == vec![1]));
+        // Stable order: sorted by effect, identical across builds
+        assert!(dem
+            .errors
+            .windows(2)
+            .all(|w| (&w[0].detectors, &w[0].observables) < (&w[1].detectors, &w[1].observables)));
+        assert_eq!(DetectorErrorModel::from_gadget(&gadget, &code, &noise).unwrap(), dem);
+
+        // Code capacity: X on qubit 2 flips the last check and no observable
+        let capacity = DetectorErrorModel::from_gadget(&gadget, &code, &NoiseModel::code_capacity(0.03)).unwrap();
+        let end = capacity.errors.iter().find(|e| e.detectors == vec![1] && e.observables.is_empty());
+        assert!((end.unwrap().probability - 0.01).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_no_idle_noise_after_readout() {
+        // Ancilla 2 is read after the CNOT and then idles
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 2 })).unwrap();
+        circuit.add_measurement(2, MeasurementBasis::Z).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::I }).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 1, gate: SingleGate::I }).unwrap();
+        let gadget = Gadget::new(circuit, vec![0, 1]).unwrap();
+        let noise = NoiseModel {
+            idle_depolarizing: 0.03,
+            ..NoiseModel::default()
+        };
+
+        let dem = DetectorErrorModel::from_gadget(&gadget, &StabilizerCode::repetition(2), &noise).unwrap();
+        assert!(!dem.errors.is_empty());
+        assert_eq!(dem.errors_touching(0).count(), 0);
+    }
+
+    #[test]
+    fn test_round_cache_matches_full_build() {
+        let experiment = crate::codes::experiments::memory_z(3, 4, NoiseModel::circuit_level(0.01)).unwrap();
+        let gadget = &experiment.gadget;
+        let mut cache = RoundCache::from_regions(&gadget.circuit, "round ").unwrap();
+        assert_eq!((cache.num_rounds(), cache.num_distinct_rounds()), (4, 1));
+        let cached =
+            DetectorErrorModel::from_gadget_with_rounds(gadget, &experiment.code, &experiment.noise, &mut cache).unwrap();
+        let full = DetectorErrorModel::from_gadget(gadget, &experiment.code, &experiment.noise).unwrap();
+        assert_eq!(cached, full);
+
+        let mut other = RoundCache::new(&Circuit::new(1), Vec::new()).unwrap();
+        assert!(
+            DetectorErrorModel::from_gadget_with_rounds(gadget, &experiment.code, &experiment.noise, &mut other).is_err()
+        );
+    }
+
+    #[test]
+    fn test_hypergraph_manipulation() {
+        let error = |probability, detectors: Vec<usize>, observables: Vec<usize>| DemError {
+            probability,
+            detectors,
+            observables,
+        };
+        let dem = DetectorErrorModel {
+            num_detectors: 4,
+            num_observables: 2,
+            errors: vec![
+                error(0.1, vec![0, 2], vec![0]),
+                error(0.1, vec![0, 1, 3], vec![1]),
+                error(0.2, vec![2], vec![]),
+            ],
+        };
+        assert!(!dem.is_graphlike());
+        assert_eq!(dem.errors_touching(0).count(), 2);
+        assert_eq!(dem.filter(|e| e.detectors.len() <= 2).errors.len(), 2);
+        assert!((dem.rescaled(10.0).errors[2].probability - 1.0).abs() < 1e-12);
+
+        // Keep detectors 0 and 1 with observable 0: the first mechanism and
+        // the third collapse to [0] / nothing; the second becomes [0, 1]
+        let sector = dem.project(&[0, 1], &[0]);
+        assert_eq!(sector.num_detectors, 2);
+        assert_eq!(sector.errors.len(), 2);
+        assert!(sector.errors.iter().any(|e| e.detectors == vec![0] && e.observables == vec![0]));
+        assert!(sector.is_graphlike());
+    }
+}
@@ -0,0 +1,205 @@
+//! Stim `.dem` text format.
+//!
+//! Supported instructions: `error(p)` with `D`/`L` targets (and `^`
+//! separators, whose components are XOR-ed together), `detector`,
+//! `logical_observable`, `shift_detectors` and nested `repeat N { ... }`
+//! blocks. Detector coordinates are accepted and ignored.
+
+use crate::dem::model::{DemError, DetectorErrorModel};
+
+pub fn import_dem(text: &str) -> Result<DetectorErrorModel, String> {
+    let lines: Vec<(usize, &str)> = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or("").trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .collect();
+
+    let mut dem = DetectorErrorModel::default();
+    let mut offset = 0;
+    let consumed = parse_block(&lines, &mut dem, &mut offset)?;
+    if consumed != lines.len() {
+        return Err(format!("Line {}: unmatched '}}'", lines[consumed].0));
+    }
+    Ok(dem)
+}
+
+/// Writes `error(p) D.. L..` lines, preceded by declarations so that
+/// trailing detectors and observables without errors survive a round trip.
+pub fn export_dem(dem: &DetectorErrorModel) -> String {
+    let mut text = String::new();
+    for error in &dem.errors {
+        text.push_str(&format!("error({})", error.probability));
+        for detector in &error.detectors {
+            text.push_str(&format!(" D{}", detector));
+        }
+        for observable in &error.observables {
+            text.push_str(&format!(" L{}", observable));
+        }
+        text.push('\n');
+    }
+    if dem.num_detectors > 0 {
+        text.push_str(&format!("detector D{}\n", dem.num_detectors - 1));
+    }
+    if dem.num_observables > 0 {
+        text.push_str(&format!("logical_observable L{}\n", dem.num_observables - 1));
+    }
+    text
+}
+
+/// Parses instructions until the end of input or a closing brace; returns
+/// the number of lines consumed (not counting that brace).
+fn parse_block(lines: &[(usize, &str)], dem: &mut DetectorErrorModel, offset: &mut usize) -> Result<usize, String> {
+    let mut index = 0;
+    while index < lines.len() {
+        let (number, line) = lines[index];
+        if line == "}" {
+            return Ok(index);
+        }
+        let err = |message: String| format!("Line {}: {}", number, message);
+        let (name, argument, targets) = split_instruction(line).map_err(err)?;
+
+        match name {
+            "error" => {
+                let probability: f64 = argument
+                    .ok_or("error needs a probability")
+                    .and_then(|a| a.parse().map_err(|_| "invalid probability"))
+                    .map_err(|e| err(e.to_string()))?;
+                if !(0.0..=1.0).contains(&probability) {
+                    return Err(err(format!("probability {} out of range", probability)));
+                }
+                let mut detectors = Vec::new();
+                let mut observables = Vec::new();
+                for target in targets.iter().filter(|t| **t != "^") {
+                    if let Some(index) = target.strip_prefix('D') {
+                        toggle(&mut detectors, parse_index(index).map_err(err)? + *offset);
+                    } else if let Some(index) = target.strip_prefix('L') {
+                        toggle(&mut observables, parse_index(index).map_err(err)?);
+                    } else {
+                        return Err(err(format!("unknown target '{}'", target)));
+                    }
+                }
+                detectors.sort_unstable();
+                observables.sort_unstable();
+                if let Some(&max) = detectors.last() {
+                    dem.num_detectors = dem.num_detectors.max(max + 1);
+                }
+                if let Some(&max) = observables.last() {
+                    dem.num_observables = dem.num_observables.max(max + 1);
+                }
+                dem.errors.push(DemError {
+                    probability,
+                    detectors,
+                    observables,
+                });
+            }
+            "detector" => {
+                for target in &targets {
+                    let index = target.strip_prefix('D').ok_or_else(|| err(format!("bad detector '{}'", target)))?;
+                    dem.num_detectors = dem.num_detectors.max(parse_index(index).map_err(err)? + *offset + 1);
+                }
+            }
+            "logical_observable" => {
+                for target in &targets {
+                    let index = target
+                        .strip_prefix('L')
+                        .ok_or_else(|| err(format!("bad observable '{}'", target)))?;
+                    dem.num_observables = dem.num_observables.max(parse_index(index).map_err(err)? + 1);
+                }
+            }
+            "shift_detectors" => {
+                let shift = targets.first().ok_or_else(|| err("shift_detectors needs a count".to_string()))?;
+                *offset += parse_index(shift).map_err(err)?;
+            }
+            "repeat" => {
+                let count = targets.first().ok_or_else(|| err("repeat needs a count".to_string()))?;
+                let count = parse_index(count).map_err(err)?;
+                if targets.get(1) != Some(&"{") {
+                    return Err(err("expected '{' after repeat count".to_string()));
+                }
+                let body = &lines[index + 1..];
+                let mut length = 0;
+                for _ in 0..count.max(1) {
+                    let mut scratch = dem.clone();
+                    let mut scratch_offset = *offset;
+                    length = parse_block(body, &mut scratch, &mut scratch_offset)?;
+                    if count > 0 {
+                        *dem = scratch;
+                        *offset = scratch_offset;
+                    }
+                }
+                if length == body.len() {
+                    return Err(err("repeat block is not closed".to_string()));
+                }
+                index += length + 1;
+            }
+            other => return Err(err(format!("unsupported instruction '{}'", other))),
+        }
+        index += 1;
+    }
+    Ok(index)
+}
+
+/// `name(args) targets...` into its parts.
+fn split_instruction(line: &str) -> Result<(&str, Option<&str>, Vec<&str>), String> {
+    let head_end = line.find(|c: char| c.is_whitespace() || c == '(').unwrap_or(line.len());
+    let name = &line[..head_end];
+    let mut rest = &line[head_end..];
+    let mut argument = None;
+    if let Some(stripped) = rest.strip_prefix('(') {
+        let close = stripped.find(')').ok_or("missing ')'")?;
+        argument = Some(stripped[..close].trim());
+        rest = &stripped[close + 1..];
+    }
+    Ok((name, argument, rest.split_whitespace().collect()))
+}
+
+fn parse_index(text: &str) -> Result<usize, String> {
+    text.parse().map_err(|_| format!("invalid index '{}'", text))
+}
+
+fn toggle(indices: &mut Vec<usize>, index: usize) {
+    match indices.iter().position(|&i| i == index) {
+        Some(position) => {
+            indices.remove(position);
+        }
+        None => indices.push(index),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_stim_dem() {
+        let text = "\
+            # from stim
+            error(0.125) D0
+            error(0.25) D0 D1 ^ D1 D2 L0
+            detector(1, 0) D2
+            repeat 2 {
+                error(0.5) D0 D3
+                shift_detectors 3
+            }
+            logical_observable L1
+        ";
+        let dem = import_dem(text).unwrap();
+        assert_eq!(dem.errors.len(), 4);
+        assert_eq!(dem.errors[1].detectors, vec![0, 2]);
+        assert_eq!(dem.errors[1].observables, vec![0]);
+        assert_eq!(dem.errors[3].detectors, vec![3, 6]);
+        assert_eq!(dem.num_detectors, 7);
+        assert_eq!(dem.num_observables, 2);
+    }
+
+    #[test]
+    fn test_round_trip_and_errors() {
+        let dem = import_dem("error(0.1) D1 L0\ndetector D4\n").unwrap();
+        assert_eq!(import_dem(&export_dem(&dem)).unwrap(), dem);
+
+        assert!(import_dem("error(2) D0").is_err());
+        assert!(import_dem("repeat 2 {\nerror(0.1) D0\n").is_err());
+        assert!(import_dem("mystery D0").is_err());
+    }
+}
@@ -0,0 +1,117 @@
+//! Gate aliases for the importers.
+//!
+//! Toolchains spell the same gate differently (`cnot`, `CX`, `sx`, `v`,
+//! `u1(pi/2)`). An alias maps a gate name, case-insensitively, to a
+//! sequence of supported gates applied to the same operands and with the
+//! same parameter, so `u1(pi/2)` becomes `rz(pi/2)` and from there `s`.
+//! Targets use the QASM spelling; the Stim importer translates them.
+
+use std::collections::BTreeMap;
+
+/// Gate names the importers understand without an alias.
+pub const SUPPORTED_GATES: [&str; 12] = ["h", "x", "y", "z", "s", "sdg", "id", "cx", "cz", "swap", "rz", "rx"];
+
+const BUILTIN: [(&str, &[&str]); 18] = [
+    ("cnot", &["cx"]),
+    ("zcx", &["cx"]),
+    ("zcz", &["cz"]),
+    ("i", &["id"]),
+    ("sdag", &["sdg"]),
+    ("s_dag", &["sdg"]),
+    ("si", &["sdg"]),
+    ("sqrt_z", &["s"]),
+    ("sqrt_z_dag", &["sdg"]),
+    ("sx", &["h", "s", "h"]),
+    ("sxdg", &["h", "sdg", "h"]),
+    ("v", &["h", "s", "h"]),
+    ("vdg", &["h", "sdg", "h"]),
+    ("sqrt_x", &["h", "s", "h"]),
+    ("sqrt_x_dag", &["h", "sdg", "h"]),
+    ("phase", &["rz"]),
+    ("p", &["rz"]),
+    ("u1", &["rz"]),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GateAliases {
+    aliases: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for GateAliases {
+    /// The built-in aliases.
+    fn default() -> Self {
+        let aliases = BUILTIN
+            .iter()
+            .map(|(name, targets)| (name.to_string(), targets.iter().map(|t| t.to_string()).collect()))
+            .collect();
+        Self { aliases }
+    }
+}
+
+impl GateAliases {
+    /// No aliases at all, not even the built-in ones.
+    pub fn none() -> Self {
+        Self { aliases: BTreeMap::new() }
+    }
+
+    /// Adds (or replaces) an alias. Supported gate names cannot be
+    /// redefined, and every target must be a supported gate.
+    pub fn add(&mut self, name: &str, targets: &[&str]) -> Result<(), String> {
+        let name = name.trim().to_lowercase();
+        if name.is_empty() {
+            return Err("An alias needs a name".to_string());
+        }
+        if SUPPORTED_GATES.contains(&name.as_str()) {
+            return Err(format!("'{}' is a supported gate and cannot be aliased", name));
+        }
+        if targets.is_empty() {
+            return Err(format!("Alias '{}' needs at least one target gate", name));
+        }
+        let targets = targets.iter().map(|t| t.trim().to_lowercase()).collect::<Vec<_>>();
+        if let Some(unknown) = targets.iter().find(|t| !SUPPORTED_GATES.contains(&t.as_str())) {
+            return Err(format!("Alias '{}' targets unsupported gate '{}'", name, unknown));
+        }
+        self.aliases.insert(name, targets);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.aliases.remove(&name.to_lowercase()).is_some()
+    }
+
+    /// The supported gates `name` stands for, if it is an alias.
+    pub fn resolve(&self, name: &str) -> Option<&[String]> {
+        self.aliases.get(&name.to_lowercase()).map(Vec::as_slice)
+    }
+
+    /// `name` itself when it is not an alias, its expansion otherwise.
+    pub(crate) fn expand(&self, name: &str) -> Vec<String> {
+        match self.resolve(name) {
+            Some(targets) => targets.to_vec(),
+            None => vec![name.to_string()],
+        }
+    }
+
+    /// Alias names, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.aliases.keys().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_and_custom_aliases() {
+        let mut aliases = GateAliases::default();
+        assert_eq!(aliases.resolve("CNOT").unwrap(), ["cx"]);
+        assert_eq!(aliases.expand("h"), vec!["h"]);
+        assert!(aliases.add("cx", &["cz"]).is_err());
+        assert!(aliases.add("t", &["tdg"]).is_err());
+        aliases.add("XX_Flip", &["x", "x"]).unwrap();
+        assert_eq!(aliases.resolve("xx_flip").unwrap(), ["x", "x"]);
+        assert!(aliases.remove("xx_flip"));
+        assert!(GateAliases::none().resolve("cnot").is_none());
+    }
+}
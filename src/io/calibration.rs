@@ -0,0 +1,174 @@
+//! Noise models from hardware calibration dumps.
+//!
+//! Reads the backend properties JSON Qiskit and IBM devices export:
+//! `qubits` holds one list of `{name, value, unit}` entries per qubit (T1,
+//! T2, readout_error, ...), and `gates` one entry per calibrated gate with
+//! its qubits and `gate_error` / `gate_length` parameters. `NoiseModel` has
+//! one rate per location kind, so per-gate and per-qubit values are
+//! aggregated (mean or worst case). Conversions:
+//!
+//! - a gate error r is an average infidelity; the depolarizing channel
+//!   with that infidelity on d = 2^k dimensions has p = r (d + 1) / d;
+//! - readout errors become `measurement_flip`;
+//! - an idle step of length t under T1/T2 is Pauli-twirled into
+//!   p_x = p_y = (1 - e^(-t/T1)) / 4 and p_z = (1 - e^(-t/T2)) / 2 - p_x,
+//!   whose total becomes `idle_depolarizing`.
+//!
+//! Virtual gates (`rz`, `u1`, `p`) and non-unitary entries (`measure`,
+//! `reset`, `delay`) are skipped.
+
+use crate::noise::model::NoiseModel;
+use serde_json::Value;
+
+const SKIPPED_GATES: [&str; 6] = ["rz", "u1", "p", "measure", "reset", "delay"];
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Aggregation {
+    #[default]
+    Mean,
+    /// The largest rate, for pessimistic estimates
+    Worst,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CalibrationOptions {
+    pub aggregation: Aggregation,
+    /// Length of one idle step in nanoseconds; defaults to the mean
+    /// single-qubit gate length of the dump
+    pub idle_duration_ns: Option<f64>,
+}
+
+pub fn import_calibration(json_str: &str, options: &CalibrationOptions) -> Result<NoiseModel, String> {
+    let root: Value = serde_json::from_str(json_str).map_err(|e| format!("Failed to parse calibration: {}", e))?;
+
+    let mut single_errors = Vec::new();
+    let mut two_errors = Vec::new();
+    let mut single_lengths = Vec::new();
+    for gate in root["gates"].as_array().ok_or("Calibration has no 'gates' list")? {
+        let name = gate["gate"].as_str().ok_or("Calibrated gate without a 'gate' name")?;
+        if SKIPPED_GATES.contains(&name.to_lowercase().as_str()) {
+            continue;
+        }
+        let arity = gate["qubits"].as_array().map_or(0, Vec::len);
+        let error = parameter(&gate["parameters"], "gate_error")?;
+        match (arity, error) {
+            (1, Some((error, _))) => single_errors.push(1.5 * error),
+            (2, Some((error, _))) => two_errors.push(1.25 * error),
+            _ => {}
+        }
+        if arity == 1 {
+            if let Some((length, unit)) = parameter(&gate["parameters"], "gate_length")? {
+                single_lengths.push(length * nanoseconds(unit.as_deref().unwrap_or("ns"))?);
+            }
+        }
+    }
+
+    let mut readout = Vec::new();
+    let mut idle = Vec::new();
+    let step = match options.idle_duration_ns {
+        Some(step) => Some(step),
+        None => (!single_lengths.is_empty()).then(|| mean(&single_lengths)),
+    };
+    for properties in root["qubits"].as_array().ok_or("Calibration has no 'qubits' list")? {
+        if let Some((error, _)) = parameter(properties, "readout_error")? {
+            readout.push(error);
+        }
+        let t1 = parameter(properties, "T1")?;
+        let t2 = parameter(properties, "T2")?;
+        if let (Some(step), Some((t1, t1_unit)), Some((t2, t2_unit))) = (step, t1, t2) {
+            let t1 = t1 * nanoseconds(t1_unit.as_deref().unwrap_or("us"))?;
+            let t2 = t2 * nanoseconds(t2_unit.as_deref().unwrap_or("us"))?;
+            if t1 <= 0.0 || t2 <= 0.0 {
+                return Err("T1 and T2 must be positive".to_string());
+            }
+            let damping = (1.0 - (-step / t1).exp()) / 4.0;
+            let dephasing = (1.0 - (-step / t2).exp()) / 2.0;
+            // p_x + p_y + p_z; T2 <= 2 T1 keeps p_z non-negative
+            idle.push(damping + dephasing.max(damping));
+        }
+    }
+
+    let aggregate = |values: &[f64]| match options.aggregation {
+        _ if values.is_empty() => 0.0,
+        Aggregation::Mean => mean(values),
+        Aggregation::Worst => values.iter().copied().fold(0.0, f64::max),
+    };
+    let noise = NoiseModel {
+        single_qubit_depolarizing: aggregate(&single_errors).min(1.0),
+        two_qubit_depolarizing: aggregate(&two_errors).min(1.0),
+        idle_depolarizing: aggregate(&idle).min(1.0),
+        measurement_flip: aggregate(&readout),
+        ..NoiseModel::default()
+    };
+    noise.validate()?;
+    Ok(noise)
+}
+
+/// Value and unit of the entry called `name` in a `{name, value, unit}`
+/// list; None when absent.
+fn parameter(list: &Value, name: &str) -> Result<Option<(f64, Option<String>)>, String> {
+    let Some(entries) = list.as_array() else {
+        return Ok(None);
+    };
+    let Some(entry) = entries.iter().find(|e| e["name"].as_str() == Some(name)) else {
+        return Ok(None);
+    };
+    let value = entry["value"]
+        .as_f64()
+        .ok_or_else(|| format!("Calibration entry '{}' has no numeric value", name))?;
+    Ok(Some((value, entry["unit"].as_str().map(str::to_string))))
+}
+
+fn nanoseconds(unit: &str) -> Result<f64, String> {
+    match unit {
+        "s" => Ok(1e9),
+        "ms" => Ok(1e6),
+        "us" | "µs" => Ok(1e3),
+        "ns" => Ok(1.0),
+        other => Err(format!("Unknown time unit '{}'", other)),
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DUMP: &str = r#"{
+        "qubits": [
+            [{"name": "T1", "value": 100.0, "unit": "us"}, {"name": "T2", "value": 80.0, "unit": "us"},
+             {"name": "readout_error", "value": 0.02}],
+            [{"name": "T1", "value": 50.0, "unit": "us"}, {"name": "T2", "value": 40.0, "unit": "us"},
+             {"name": "readout_error", "value": 0.04}]
+        ],
+        "gates": [
+            {"gate": "sx", "qubits": [0], "parameters": [{"name": "gate_error", "value": 0.0002},
+                                                        {"name": "gate_length", "value": 35.5, "unit": "ns"}]},
+            {"gate": "sx", "qubits": [1], "parameters": [{"name": "gate_error", "value": 0.0004},
+                                                        {"name": "gate_length", "value": 35.5, "unit": "ns"}]},
+            {"gate": "rz", "qubits": [0], "parameters": [{"name": "gate_error", "value": 0.0}]},
+            {"gate": "cx", "qubits": [0, 1], "parameters": [{"name": "gate_error", "value": 0.008}]}
+        ]
+    }"#;
+
+    #[test]
+    fn test_import_calibration() {
+        let noise = import_calibration(DUMP, &CalibrationOptions::default()).unwrap();
+        assert!((noise.single_qubit_depolarizing - 1.5 * 0.0003).abs() < 1e-12);
+        assert!((noise.two_qubit_depolarizing - 0.01).abs() < 1e-12);
+        assert!((noise.measurement_flip - 0.03).abs() < 1e-12);
+        assert!(noise.idle_depolarizing > 0.0 && noise.idle_depolarizing < 1e-3);
+
+        let worst = CalibrationOptions {
+            aggregation: Aggregation::Worst,
+            idle_duration_ns: Some(0.0),
+        };
+        let noise = import_calibration(DUMP, &worst).unwrap();
+        assert!((noise.measurement_flip - 0.04).abs() < 1e-12);
+        assert_eq!(noise.idle_depolarizing, 0.0);
+        assert!(import_calibration(r#"{"gates": []}"#, &worst).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+//! Golden-file checks for exporter output.
+//!
+//! `normalize` drops differences that carry no meaning: line endings,
+//! trailing whitespace and blank-line runs, plus optionally comment lines
+//! and line order. `check_golden` compares normalized output with a stored
+//! file and returns a line diff on mismatch. With the environment variable
+//! `UPDATE_GOLDEN` set, the file is rewritten instead, to accept an
+//! intended format change.
+
+use std::fs;
+use std::path::Path;
+
+/// Set to any value to rewrite golden files rather than compare.
+pub const UPDATE_GOLDEN_VAR: &str = "UPDATE_GOLDEN";
+
+/// Unchanged lines shown around each difference in `line_diff`.
+const CONTEXT: usize = 2;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GoldenOptions {
+    /// Compare lines in sorted order, for formats whose line order is not
+    /// meaningful
+    pub sort_lines: bool,
+    /// Drop lines starting (after indentation) with any of these, e.g.
+    /// comments carrying a version or a date
+    pub ignore_prefixes: Vec<String>,
+}
+
+pub fn normalize(text: &str, options: &GoldenOptions) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        let content = line.trim_start();
+        if options.ignore_prefixes.iter().any(|prefix| content.starts_with(prefix.as_str())) {
+            continue;
+        }
+        if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+    if options.sort_lines {
+        lines.sort_unstable();
+    }
+    let mut normalized = lines.join("\n");
+    normalized.push('\n');
+    normalized
+}
+
+/// Line diff of `actual` against `expected`: `-` lines only in expected,
+/// `+` lines only in actual, with a little context and `...` for skipped
+/// runs. Empty when the texts agree.
+pub fn line_diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    // Longest common subsequence lengths of every pair of suffixes
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut edits: Vec<(char, &str)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            edits.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] > lcs[i + 1][j]) {
+            edits.push(('+', new[j]));
+            j += 1;
+        } else {
+            edits.push(('-', old[i]));
+            i += 1;
+        }
+    }
+
+    let changed: Vec<usize> = (0..edits.len()).filter(|&k| edits[k].0 != ' ').collect();
+    let mut diff = String::new();
+    let mut next_unshown = 0;
+    for (k, &(kind, line)) in edits.iter().enumerate() {
+        if !changed.iter().any(|&c| c.abs_diff(k) <= CONTEXT) {
+            continue;
+        }
+        if k > next_unshown {
+            diff.push_str("...\n");
+        }
+        diff.push_str(&format!("{} {}\n", kind, line));
+        next_unshown = k + 1;
+    }
+    if !diff.is_empty() && next_unshown < edits.len() {
+        diff.push_str("...\n");
+    }
+    diff
+}
+
+/// Compares `actual` with the golden file at `path`, both normalized.
+pub fn check_golden(path: impl AsRef<Path>, actual: &str, options: &GoldenOptions) -> Result<(), String> {
+    let path = path.as_ref();
+    let actual = normalize(actual, options);
+    if std::env::var_os(UPDATE_GOLDEN_VAR).is_some() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        return fs::write(path, &actual).map_err(|e| format!("Failed to write {}: {}", path.display(), e));
+    }
+    let expected = fs::read_to_string(path).map_err(|e| {
+        format!(
+            "Failed to read golden file {} ({}); set {} to create it",
+            path.display(),
+            e,
+            UPDATE_GOLDEN_VAR
+        )
+    })?;
+    let expected = normalize(&expected, options);
+    if expected == actual {
+        return Ok(());
+    }
+    Err(format!(
+        "Output differs from golden file {} (set {} to accept):\n{}",
+        path.display(),
+        UPDATE_GOLDEN_VAR,
+        line_diff(&expected, &actual)
+    ))
+}
+
+/// `check_golden`, panicking with the diff on mismatch.
+pub fn assert_golden(path: impl AsRef<Path>, actual: &str, options: &GoldenOptions) {
+    if let Err(message) = check_golden(path, actual, options) {
+        panic!("{}", message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_and_diff() {
+        let options = GoldenOptions {
+            sort_lines: false,
+            ignore_prefixes: vec!["//".to_string()],
+        };
+        let text = "// generated today\r\nOPENQASM 2.0;  \r\n\r\n\r\nh q[0];\n\n";
+        assert_eq!(normalize(text, &options), "OPENQASM 2.0;\n\nh q[0];\n");
+
+        let sorted = GoldenOptions {
+            sort_lines: true,
+            ..GoldenOptions::default()
+        };
+        assert_eq!(normalize("b\na\n", &sorted), normalize("a\nb", &sorted));
+
+        let expected = "a\nb\nc\nd\ne\nf\ng\n";
+        let actual = "a\nb\nc\nd\nE\nf\ng\n";
+        assert_eq!(line_diff(expected, expected), "");
+        assert_eq!(line_diff(expected, actual), "...\n  c\n  d\n- e\n+ E\n  f\n  g\n");
+    }
+}
@@ -1,4 +1,6 @@
+use crate::io::options::ImportOptions;
 use crate::physics::circuit::Circuit;
+use crate::physics::simulator::Snapshot;
 use serde_json;
 
 pub fn export_json(circuit: &Circuit) -> Result<String, String> {
@@ -7,14 +9,66 @@ pub fn export_json(circuit: &Circuit) -> Result<String, String> {
 }
 
 pub fn import_json(json_str: &str) -> Result<Circuit, String> {
-    serde_json::from_str(json_str)
-        .map_err(|e| format!("Failed to parse JSON: {}", e))
+    let circuit: Circuit = serde_json::from_str(json_str)
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    circuit.validate_regions()?;
+    circuit.validate_noise_overrides()?;
+    circuit.validate_moments()?;
+    circuit.validate_registers()?;
+    circuit.validate_barriers()?;
+    circuit.validate_measurements()?;
+    Ok(circuit)
+}
+
+/// In strict mode everything in the input must reappear when the circuit is
+/// serialized again, so unknown fields are reported instead of ignored.
+pub fn import_json_with(json_str: &str, options: &ImportOptions) -> Result<Circuit, String> {
+    let circuit = import_json(json_str)?;
+    if options.strict_roundtrip {
+        let input: serde_json::Value =
+            serde_json::from_str(json_str).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+        let output = serde_json::to_value(&circuit).map_err(|e| format!("Failed to serialize circuit: {}", e))?;
+        if let Some(path) = dropped(&input, &output, "") {
+            return Err(format!("JSON field '{}' would be dropped", path));
+        }
+    }
+    Ok(circuit)
+}
+
+/// Path of the first part of `input` missing from `output`. Empty arrays and
+/// nulls count as absent.
+fn dropped(input: &serde_json::Value, output: &serde_json::Value, path: &str) -> Option<String> {
+    use serde_json::Value;
+    match (input, output) {
+        (Value::Object(fields), Value::Object(kept)) => fields.iter().find_map(|(key, value)| {
+            let path = format!("{}/{}", path, key);
+            match (value, kept.get(key)) {
+                (Value::Null, None) => None,
+                (Value::Array(items), None) if items.is_empty() => None,
+                (_, None) => Some(path),
+                (_, Some(kept)) => dropped(value, kept, &path),
+            }
+        }),
+        (Value::Array(items), Value::Array(kept)) if items.len() == kept.len() => items
+            .iter()
+            .zip(kept)
+            .enumerate()
+            .find_map(|(i, (item, kept))| dropped(item, kept, &format!("{}/{}", path, i))),
+        _ if input == output => None,
+        _ => Some(path.to_string()),
+    }
+}
+
+/// Simulator timeline, including any classical bits recorded per snapshot.
+pub fn export_timeline_json(timeline: &[Snapshot]) -> Result<String, String> {
+    serde_json::to_string_pretty(timeline)
+        .map_err(|e| format!("Failed to serialize timeline to JSON: {}", e))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::physics::circuit::{Circuit, Gate, SingleGate, TwoGate};
+    use crate::physics::circuit::{Circuit, Gate, GateNoise, SingleGate, TwoGate};
 
     #[test]
     fn test_json_roundtrip() {
@@ -31,6 +85,7 @@ mod tests {
                 target: 1,
             }))
             .unwrap();
+        circuit.set_noise_override(1, GateNoise::Depolarizing(0.05)).unwrap();
 
         let json = export_json(&circuit).unwrap();
         let imported = import_json(&json).unwrap();
@@ -38,6 +93,17 @@ mod tests {
         assert_eq!(circuit.num_qubits, imported.num_qubits);
         assert_eq!(circuit.gates.len(), imported.gates.len());
         assert_eq!(circuit.gates, imported.gates);
+        assert_eq!(imported.noise_override(1), Some(&GateNoise::Depolarizing(0.05)));
+    }
+
+    #[test]
+    fn test_import_rejects_bad_positions() {
+        let region = r#"{"num_qubits": 1, "gates": [], "regions": [{"name": "r", "start": 0, "end": 2}]}"#;
+        assert!(import_json(region).unwrap_err().contains("out of bounds"));
+        let missing = r#"{"num_qubits": 1, "gates": [], "noise_overrides": [{"gate": 5, "noise": {"Depolarizing": 0.1}}]}"#;
+        assert!(import_json(missing).unwrap_err().contains("missing gate 5"));
+        let rate = r#"{"num_qubits": 1, "gates": [{"Single": {"qubit": 0, "gate": "H"}}], "noise_overrides": [{"gate": 0, "noise": {"Depolarizing": 7.0}}]}"#;
+        assert!(import_json(rate).unwrap_err().contains("must be in [0, 1]"));
     }
 }
 
@@ -1,21 +1,41 @@
+use crate::io::options::ExportOptions;
 use crate::physics::circuit::{Circuit, Gate, SingleGate, TwoGate};
 
 pub fn export_latex(circuit: &Circuit) -> String {
+    export_latex_with(circuit, &ExportOptions::default())
+}
+
+/// `export_latex` with wires in `options.wire_order`, each labelled with
+/// its qubit index in `options.index_base`.
+pub fn export_latex_with(circuit: &Circuit, options: &ExportOptions) -> String {
     let mut latex = String::from("\\documentclass{article}\n");
     latex.push_str("\\usepackage{qcircuit}\n");
     latex.push_str("\\begin{document}\n");
     latex.push_str("\\begin{equation*}\n");
     latex.push_str("\\Qcircuit @C=1em @R=.7em {\n");
 
-    // Group gates by time step(assuming sequential gates for now)
-    // TODO: A more sophisticated version(e.g. analyze gate dependencies)
-    let mut gates_by_time: Vec<Vec<&Gate>> = Vec::new();
-    for gate in &circuit.gates {
-        gates_by_time.push(vec![gate]);
+    // One column per moment
+    let gates_by_time: Vec<Vec<&Gate>> = (0..circuit.depth()).map(|time| circuit.gates_at_time(time)).collect();
+
+    let wires = options.wire_order(circuit.num_qubits);
+    let mut row_of = vec![0; circuit.num_qubits];
+    for (row, &qubit) in wires.iter().enumerate() {
+        row_of[qubit] = row;
+    }
+
+    // Barriers hang off the top wire, after the column before them
+    let mut barrier_after = vec![false; gates_by_time.len() + 1];
+    for &barrier in &circuit.barriers {
+        barrier_after[circuit.moment_of(barrier).unwrap_or(gates_by_time.len())] = true;
     }
+    let barrier = format!(" \\barrier{{{}}}", circuit.num_qubits.saturating_sub(1));
 
-    for qubit in 0..circuit.num_qubits {
-        let mut line = String::new();
+    for (row, &qubit) in wires.iter().enumerate() {
+        let mut line = format!("\\lstick{{q_{{{}}}}}", options.qubit_index(qubit));
+        if row == 0 && barrier_after[0] {
+            line.push_str(&barrier);
+        }
+        line.push_str(" & ");
         
         for (time, gates_at_time) in gates_by_time.iter().enumerate() {
             let gate_on_qubit: Option<&Gate> = gates_at_time
@@ -33,12 +53,8 @@ pub fn export_latex(circuit: &Circuit) -> String {
                             TwoGate::CNOT { control, target } => {
                                 if *control == qubit {
                                     line.push_str("\\ctrl{");
-                                    let target_idx = if *target > qubit {
-                                        *target - qubit
-                                    } else {
-                                        qubit - *target
-                                    };
-                                    line.push_str(&target_idx.to_string());
+                                    let offset = row_of[*target] as isize - row_of[qubit] as isize;
+                                    line.push_str(&offset.to_string());
                                     line.push('}');
                                 } else if *target == qubit {
                                     line.push_str("\\targ");
@@ -71,6 +87,9 @@ pub fn export_latex(circuit: &Circuit) -> String {
             } else {
                 line.push_str("\\qw");
             }
+            if row == 0 && barrier_after[time + 1] {
+                line.push_str(&barrier);
+            }
             
             if time < gates_by_time.len() - 1 {
                 line.push_str(" & ");
@@ -103,6 +122,11 @@ fn format_single_gate_latex(gate: SingleGate) -> String {
 /// Export a circuit to LaTeX format using a simpler tikz based representation
 /// This is a more readable format that doesn't require qcircuit
 pub fn export_latex_simple(circuit: &Circuit) -> String {
+    export_latex_simple_with(circuit, &ExportOptions::default())
+}
+
+/// `export_latex_simple` with qubit indices in `options.index_base`.
+pub fn export_latex_simple_with(circuit: &Circuit, options: &ExportOptions) -> String {
     let mut latex = String::from("\\documentclass{article}\n");
     latex.push_str("\\usepackage{tikz}\n");
     latex.push_str("\\begin{document}\n");
@@ -110,6 +134,7 @@ pub fn export_latex_simple(circuit: &Circuit) -> String {
     latex.push_str("\\begin{verbatim}\n");
     
     for (i, gate) in circuit.gates.iter().enumerate() {
+        let gate = gate.map_qubits(|q| options.qubit_index(q));
         latex.push_str(&format!("Gate {}: {}\n", i, gate));
     }
     
@@ -121,6 +146,8 @@ pub fn export_latex_simple(circuit: &Circuit) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::io::options::QubitOrder;
+    use crate::physics::pauli::PauliString;
 
     #[test]
     fn test_latex_export() {
@@ -141,6 +168,35 @@ mod tests {
         let latex = export_latex(&circuit);
         assert!(latex.contains("qcircuit"));
         assert!(latex.contains("\\gate{H}"));
+
+        // Gates sharing a moment share a column
+        circuit.add_gate_at(0, Gate::Single { qubit: 1, gate: SingleGate::S }).unwrap();
+        let latex = export_latex(&circuit);
+        assert!(latex.contains("\\lstick{q_{1}} & \\gate{S} & \\targ"), "{}", latex);
+
+        circuit.barriers = vec![2];
+        let latex = export_latex(&circuit);
+        assert!(latex.contains("\\lstick{q_{0}} & \\gate{H} \\barrier{1} & \\ctrl{1}"), "{}", latex);
+    }
+
+    #[test]
+    fn test_latex_export_notation() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+
+        let options = ExportOptions {
+            wire_order: QubitOrder::Qubit0Last,
+            index_base: 1,
+            ..ExportOptions::default()
+        };
+        let latex = export_latex_with(&circuit, &options);
+        // Qubit 1 is drawn on top, so the control points up
+        assert!(latex.contains("\\lstick{q_{2}} & \\targ \\\\\n\\lstick{q_{1}} & \\ctrl{-1}"));
+        assert!(export_latex_simple_with(&circuit, &options).contains("Gate 0: CNOT(1, 2)"));
+
+        let pauli = PauliString::from_str("XIZ", 3).unwrap();
+        assert_eq!(ExportOptions::default().format_pauli(&pauli), "+XIZ");
+        assert_eq!(ExportOptions::qiskit().format_pauli(&pauli), "+ZIX");
     }
 }
 
@@ -1,8 +1,27 @@
+pub mod aliases;
+#[cfg(feature = "analysis")]
+pub mod calibration;
+pub mod golden;
 pub mod json;
 pub mod qasm;
 pub mod latex;
+pub mod options;
+pub mod project;
+#[cfg(feature = "analysis")]
+pub mod samples;
+pub mod stim;
 
-pub use json::{export_json, import_json};
-pub use qasm::{export_qasm, import_qasm};
-pub use latex::{export_latex, export_latex_simple};
-
+pub use aliases::GateAliases;
+#[cfg(feature = "analysis")]
+pub use calibration::{import_calibration, Aggregation, CalibrationOptions};
+pub use golden::{assert_golden, check_golden, line_diff, normalize, GoldenOptions};
+pub use json::{export_json, export_timeline_json, import_json, import_json_with};
+pub use options::{ExportOptions, ImportOptions, QubitOrder};
+pub use project::{export_project, import_project, Project, PROJECT_FORMAT_VERSION};
+#[cfg(feature = "analysis")]
+pub use project::ProjectScenario;
+pub use qasm::{export_qasm, import_qasm, import_qasm_with, import_qasm_with_report, NonCliffordContent, SymbolicRotation};
+pub use latex::{export_latex, export_latex_simple, export_latex_simple_with, export_latex_with};
+#[cfg(feature = "analysis")]
+pub use samples::{export_csr_npz, export_fault_detector_npz, export_npy, export_samples_csv, export_samples_npz};
+pub use stim::{export_stim, import_stim, import_stim_with};
@@ -0,0 +1,132 @@
+//! Options shared by the circuit importers and exporters.
+
+use crate::io::aliases::GateAliases;
+use crate::physics::circuit::{GateNoise, NoiseOverride, QubitRegister};
+use crate::physics::pauli::{PauliOrder, PauliString};
+
+/// `strict_roundtrip` makes an import fail instead of silently dropping
+/// anything it does not represent: unknown annotations, ignored
+/// statements, or JSON fields outside the circuit schema. `aliases` maps
+/// vendor gate names to supported gates (the built-in table by default).
+///
+/// Everything a `Circuit` holds (gates, moments, regions, registers, noise
+/// overrides, barriers and measurements) survives a JSON -> QASM -> Stim
+/// round trip. Detectors, observables and noise schedules belong to
+/// `codes::experiments` and `NoiseModel` rather than to a circuit, and
+/// free-form metadata has no place in one, so strict imports reject them,
+/// as they do QASM classical bits that would be renumbered.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportOptions {
+    pub strict_roundtrip: bool,
+    pub aliases: GateAliases,
+}
+
+impl ImportOptions {
+    pub fn strict() -> Self {
+        Self {
+            strict_roundtrip: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// Which end qubit 0 sits at when wires are listed.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum QubitOrder {
+    /// Qubit 0 on the top wire
+    #[default]
+    Qubit0First,
+    /// Qubit 0 on the bottom wire
+    Qubit0Last,
+}
+
+/// Notation of exported diagrams and Pauli strings. `wire_order` orders
+/// the wires of a diagram, `index_base` offsets printed qubit indices and
+/// `pauli_order` is the tensor order of Pauli string letters. QASM and Stim
+/// fix their own conventions (0-based, qubit 0 first) and ignore these.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExportOptions {
+    pub wire_order: QubitOrder,
+    pub index_base: usize,
+    pub pauli_order: PauliOrder,
+}
+
+impl ExportOptions {
+    /// Qiskit's conventions: 0-based, qubit 0 on the top wire but
+    /// rightmost in Pauli labels.
+    pub fn qiskit() -> Self {
+        Self {
+            pauli_order: PauliOrder::Qubit0Rightmost,
+            ..Self::default()
+        }
+    }
+
+    /// `qubit` as printed.
+    pub fn qubit_index(&self, qubit: usize) -> usize {
+        qubit + self.index_base
+    }
+
+    /// Qubits of an `num_qubits`-qubit register in wire order.
+    pub fn wire_order(&self, num_qubits: usize) -> Vec<usize> {
+        match self.wire_order {
+            QubitOrder::Qubit0First => (0..num_qubits).collect(),
+            QubitOrder::Qubit0Last => (0..num_qubits).rev().collect(),
+        }
+    }
+
+    /// `pauli` as a sign (`+`, `-`, `+i`, `-i`) followed by one letter per
+    /// qubit in `pauli_order`.
+    pub fn format_pauli(&self, pauli: &PauliString) -> String {
+        pauli.to_string_in(self.pauli_order)
+    }
+}
+
+/// Parses a `@region <start> <end> <name>` annotation body (the text after
+/// the comment marker), shared by the QASM and Stim formats.
+pub(crate) fn parse_region_annotation(body: &str) -> Result<(usize, usize, String), String> {
+    parse_named_span("@region", ["start", "end"], body)
+}
+
+/// `@register <start> <size> <name>` annotation for a qubit register.
+pub(crate) fn register_annotation(register: &QubitRegister) -> String {
+    format!("@register {} {} {}", register.start, register.size, register.name)
+}
+
+/// Parses a `@register <start> <size> <name>` annotation body.
+pub(crate) fn parse_register_annotation(body: &str) -> Result<QubitRegister, String> {
+    let (start, size, name) = parse_named_span("@register", ["start", "size"], body)?;
+    Ok(QubitRegister { name, start, size })
+}
+
+fn parse_named_span(kind: &str, numbers: [&str; 2], body: &str) -> Result<(usize, usize, String), String> {
+    let mut parts = body.splitn(3, ' ');
+    let mut bound = |what: &str| {
+        parts
+            .next()
+            .and_then(|p| p.parse::<usize>().ok())
+            .ok_or_else(|| format!("{} needs a numeric {}", kind, what))
+    };
+    let first = bound(numbers[0])?;
+    let second = bound(numbers[1])?;
+    let name = parts
+        .next()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .ok_or_else(|| format!("{} needs a name", kind))?;
+    Ok((first, second, name.to_string()))
+}
+
+/// `@noise <gate> <noise as JSON>` annotation (without the comment marker)
+/// for a noise override, shared by the QASM and Stim formats.
+pub(crate) fn noise_annotation(entry: &NoiseOverride) -> String {
+    let noise = serde_json::to_string(&entry.noise).expect("gate noise serializes");
+    format!("@noise {} {}", entry.gate, noise)
+}
+
+/// Parses a `@noise <gate> <json>` annotation body.
+pub(crate) fn parse_noise_annotation(body: &str) -> Result<(usize, GateNoise), String> {
+    let (gate, noise) = body.trim().split_once(' ').ok_or("@noise needs a gate and a noise channel")?;
+    let gate = gate.parse().map_err(|_| format!("@noise needs a numeric gate, got '{}'", gate))?;
+    let noise = serde_json::from_str(noise).map_err(|e| format!("Invalid @noise channel: {}", e))?;
+    Ok((gate, noise))
+}
@@ -0,0 +1,188 @@
+//! Project files: a whole study in one JSON document.
+//!
+//! A project holds named circuits plus the shared noise model, named codes
+//! and named scenario trees that go with them. Everything but the circuits
+//! is stored as raw JSON, so builds without the `analysis` feature (the
+//! WASM visualizer) load a project and save it back without losing the
+//! parts they cannot interpret; with `analysis`, typed accessors convert
+//! them. Maps are keyed by name and serialized in name order.
+
+use crate::physics::circuit::Circuit;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[cfg(feature = "analysis")]
+use crate::analysis::scenario::{ScenarioRecord, ScenarioTree};
+#[cfg(feature = "analysis")]
+use crate::codes::StabilizerCode;
+#[cfg(feature = "analysis")]
+use crate::noise::model::NoiseModel;
+
+/// Newest format version this build reads and the one it writes.
+pub const PROJECT_FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub circuits: BTreeMap<String, Circuit>,
+    /// A `NoiseModel`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise: Option<Value>,
+    /// `StabilizerCode`s
+    #[serde(default)]
+    pub codes: BTreeMap<String, Value>,
+    /// `ProjectScenario`s
+    #[serde(default)]
+    pub scenarios: BTreeMap<String, Value>,
+}
+
+/// A scenario tree as stored in a project: the circuit it explores, by
+/// name, and its exported nodes.
+#[cfg(feature = "analysis")]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectScenario {
+    pub circuit: String,
+    pub records: Vec<ScenarioRecord>,
+}
+
+impl Project {
+    pub fn new(name: &str) -> Self {
+        Self {
+            version: PROJECT_FORMAT_VERSION,
+            name: name.to_string(),
+            circuits: BTreeMap::new(),
+            noise: None,
+            codes: BTreeMap::new(),
+            scenarios: BTreeMap::new(),
+        }
+    }
+
+    /// Adds or replaces a circuit.
+    pub fn add_circuit(&mut self, name: &str, circuit: Circuit) -> Result<(), String> {
+        if name.trim().is_empty() {
+            return Err("A project circuit needs a name".to_string());
+        }
+        self.circuits.insert(name.to_string(), circuit);
+        Ok(())
+    }
+
+    pub fn circuit(&self, name: &str) -> Option<&Circuit> {
+        self.circuits.get(name)
+    }
+
+    pub fn circuit_names(&self) -> impl Iterator<Item = &str> {
+        self.circuits.keys().map(String::as_str)
+    }
+}
+
+#[cfg(feature = "analysis")]
+impl Project {
+    pub fn noise_model(&self) -> Result<Option<NoiseModel>, String> {
+        self.noise
+            .as_ref()
+            .map(|value| serde_json::from_value(value.clone()).map_err(|e| format!("Invalid project noise model: {}", e)))
+            .transpose()
+    }
+
+    pub fn set_noise_model(&mut self, noise: &NoiseModel) -> Result<(), String> {
+        self.noise = Some(to_value(noise)?);
+        Ok(())
+    }
+
+    pub fn code(&self, name: &str) -> Result<StabilizerCode, String> {
+        let value = self.codes.get(name).ok_or_else(|| format!("No code '{}' in project", name))?;
+        serde_json::from_value(value.clone()).map_err(|e| format!("Invalid project code '{}': {}", name, e))
+    }
+
+    pub fn add_code(&mut self, name: &str, code: &StabilizerCode) -> Result<(), String> {
+        self.codes.insert(name.to_string(), to_value(code)?);
+        Ok(())
+    }
+
+    /// Rebuilds the named scenario tree on its circuit.
+    pub fn scenario(&self, name: &str) -> Result<ScenarioTree, String> {
+        let value = self
+            .scenarios
+            .get(name)
+            .ok_or_else(|| format!("No scenario '{}' in project", name))?;
+        let stored: ProjectScenario =
+            serde_json::from_value(value.clone()).map_err(|e| format!("Invalid project scenario '{}': {}", name, e))?;
+        let circuit = self
+            .circuit(&stored.circuit)
+            .ok_or_else(|| format!("Scenario '{}' explores unknown circuit '{}'", name, stored.circuit))?;
+        ScenarioTree::from_records(circuit.clone(), &stored.records)
+    }
+
+    /// Stores `tree`, which must explore the project circuit `circuit`.
+    pub fn add_scenario(&mut self, name: &str, circuit: &str, tree: &ScenarioTree) -> Result<(), String> {
+        if !self.circuits.contains_key(circuit) {
+            return Err(format!("No circuit '{}' in project", circuit));
+        }
+        let stored = ProjectScenario {
+            circuit: circuit.to_string(),
+            records: tree.export(),
+        };
+        self.scenarios.insert(name.to_string(), to_value(&stored)?);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "analysis")]
+fn to_value<T: Serialize>(value: &T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|e| format!("Failed to serialize project entry: {}", e))
+}
+
+pub fn export_project(project: &Project) -> Result<String, String> {
+    serde_json::to_string_pretty(project).map_err(|e| format!("Failed to serialize project to JSON: {}", e))
+}
+
+pub fn import_project(json_str: &str) -> Result<Project, String> {
+    let project: Project = serde_json::from_str(json_str).map_err(|e| format!("Failed to parse project: {}", e))?;
+    if project.version > PROJECT_FORMAT_VERSION {
+        return Err(format!(
+            "Project format version {} is newer than the supported version {}",
+            project.version, PROJECT_FORMAT_VERSION
+        ));
+    }
+    Ok(project)
+}
+
+#[cfg(all(test, feature = "analysis"))]
+mod tests {
+    use super::*;
+    use crate::analysis::scenario::ScenarioAction;
+    use crate::physics::circuit::{Gate, SingleGate};
+    use crate::physics::pauli::SinglePauli;
+
+    #[test]
+    fn test_project_roundtrip() {
+        let mut idle = Circuit::new(3);
+        for qubit in 0..3 {
+            idle.add_gate(Gate::Single { qubit, gate: SingleGate::I }).unwrap();
+        }
+        let mut tree = ScenarioTree::new(idle.clone());
+        let x0 = tree
+            .branch(ScenarioTree::ROOT, ScenarioAction::InjectError { qubit: 0, pauli: SinglePauli::X }, "X0")
+            .unwrap();
+        tree.branch(x0, ScenarioAction::RunToEnd, "finish").unwrap();
+
+        let mut project = Project::new("repetition study");
+        project.add_circuit("idle", idle).unwrap();
+        project.set_noise_model(&NoiseModel::code_capacity(0.01)).unwrap();
+        project.add_code("rep3", &StabilizerCode::repetition(3)).unwrap();
+        project.add_scenario("single flip", "idle", &tree).unwrap();
+        assert!(project.add_scenario("orphan", "missing", &tree).is_err());
+
+        let loaded = import_project(&export_project(&project).unwrap()).unwrap();
+        assert_eq!(loaded, project);
+        assert_eq!(loaded.noise_model().unwrap(), Some(NoiseModel::code_capacity(0.01)));
+        assert_eq!(loaded.code("rep3").unwrap(), StabilizerCode::repetition(3));
+        assert_eq!(loaded.scenario("single flip").unwrap().export(), tree.export());
+
+        let future = export_project(&project).unwrap().replacen("\"version\": 1", "\"version\": 2", 1);
+        assert!(import_project(&future).is_err());
+    }
+}
@@ -1,15 +1,102 @@
 //! OpenQASM 2.0 format
+//!
+//! Identity gates are written as `id`, regions as `// @region <start>
+//! <end> <name>` comments, registers as `// @register <start> <size>
+//! <name>`, moments as `// @moments <first gates>` and noise overrides as
+//! `// @noise <gate> <json>` comments so that they survive a round trip.
+//! Measurements are written as `measure q[i] -> c[k];` into one `c`
+//! register, bit k being the k-th readout; an X-basis readout is preceded
+//! by a `// @basis x` comment. Strict imports reject other classical bit
+//! layouts, which the circuit cannot keep.
+//!
+//! `rz(θ)` and `rx(θ)` are imported exactly when θ is a multiple of π/2
+//! (up to global phase: `rz(π/2)` is `s`, `rx(θ)` is `h rz(θ) h`). Other
+//! angles cannot be simulated; `import_qasm_with_report` keeps them as
+//! symbolic rotations with an identity placeholder in the circuit.
 
-use crate::physics::circuit::{Circuit, Gate, SingleGate, TwoGate};
+use crate::io::options::{
+    noise_annotation, parse_noise_annotation, parse_region_annotation, parse_register_annotation, register_annotation,
+    ImportOptions,
+};
+use crate::physics::circuit::{Circuit, Gate, MeasurementBasis, SingleGate, TwoGate};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::f64::consts::FRAC_PI_2;
+
+/// A rotation by a non-Clifford angle, kept as written.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SymbolicRotation {
+    /// Index of the identity placeholder in the imported circuit
+    pub gate: usize,
+    pub qubit: usize,
+    /// `rz` or `rx`
+    pub axis: String,
+    /// Angle expression from the source
+    pub angle: String,
+    pub radians: f64,
+}
+
+/// Non-Clifford content dropped from an imported circuit.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NonCliffordContent {
+    pub rotations: Vec<SymbolicRotation>,
+}
+
+impl NonCliffordContent {
+    pub fn is_empty(&self) -> bool {
+        self.rotations.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        if self.is_empty() {
+            return "Circuit is Clifford".to_string();
+        }
+        let listed: Vec<String> = self
+            .rotations
+            .iter()
+            .map(|r| format!("{}({}) q[{}] at gate {}", r.axis, r.angle, r.qubit, r.gate))
+            .collect();
+        format!("{} non-Clifford rotation(s): {}", listed.len(), listed.join(", "))
+    }
+}
 
 pub fn export_qasm(circuit: &Circuit) -> String {
     let mut qasm = String::from("OPENQASM 2.0;\n");
     qasm.push_str("include \"qelib1.inc\";\n");
     qasm.push_str(&format!("qreg q[{}];\n", circuit.num_qubits));
+    if !circuit.measurements.is_empty() {
+        qasm.push_str(&format!("creg c[{}];\n", circuit.measurements.len()));
+    }
+    for region in &circuit.regions {
+        qasm.push_str(&format!("// @region {} {} {}\n", region.start, region.end, region.name));
+    }
+    for register in &circuit.registers {
+        qasm.push_str(&format!("// {}\n", register_annotation(register)));
+    }
+    if !circuit.moments.is_empty() {
+        let starts: Vec<String> = circuit.moments.iter().map(|start| start.to_string()).collect();
+        qasm.push_str(&format!("// @moments {}\n", starts.join(" ")));
+    }
+    for entry in &circuit.noise_overrides {
+        qasm.push_str(&format!("// {}\n", noise_annotation(entry)));
+    }
     qasm.push('\n');
 
-    for gate in &circuit.gates {
+    let mut bit = 0;
+    let mut measure = |qasm: &mut String, position: usize| {
+        for m in circuit.measurements_at(position) {
+            if m.basis == MeasurementBasis::X {
+                qasm.push_str("// @basis x\n");
+            }
+            qasm.push_str(&format!("measure q[{}] -> c[{}];\n", m.qubit, bit));
+            bit += 1;
+        }
+    };
+    for (index, gate) in circuit.gates.iter().enumerate() {
+        if circuit.has_barrier_before(index) {
+            qasm.push_str("barrier q;\n");
+        }
+        measure(&mut qasm, index);
         match gate {
             Gate::Single { qubit, gate } => {
                 let gate_name = match gate {
@@ -19,7 +106,7 @@ pub fn export_qasm(circuit: &Circuit) -> String {
                     SingleGate::X => "x",
                     SingleGate::Y => "y",
                     SingleGate::Z => "z",
-                    SingleGate::I => continue, // Identity gates are not included in QASM
+                    SingleGate::I => "id",
                 };
                 qasm.push_str(&format!("{} q[{}];\n", gate_name, qubit));
             }
@@ -36,20 +123,91 @@ pub fn export_qasm(circuit: &Circuit) -> String {
             },
         }
     }
+    if circuit.has_barrier_before(circuit.gates.len()) {
+        qasm.push_str("barrier q;\n");
+    }
+    measure(&mut qasm, circuit.gates.len());
 
     qasm
 }
 
 pub fn import_qasm(qasm_str: &str) -> Result<Circuit, String> {
+    import_qasm_with(qasm_str, &ImportOptions::default())
+}
+
+/// Imports a circuit, failing on rotations by non-Clifford angles.
+pub fn import_qasm_with(qasm_str: &str, options: &ImportOptions) -> Result<Circuit, String> {
+    let (circuit, non_clifford) = import_qasm_with_report(qasm_str, options)?;
+    if !non_clifford.is_empty() {
+        return Err(non_clifford.summary());
+    }
+    Ok(circuit)
+}
+
+/// Imports a circuit, keeping rotations by non-Clifford angles as symbolic
+/// entries with an identity gate in their place.
+pub fn import_qasm_with_report(
+    qasm_str: &str,
+    options: &ImportOptions,
+) -> Result<(Circuit, NonCliffordContent), String> {
+    let mut non_clifford = NonCliffordContent::default();
     let mut circuit = Circuit::new(0);
     let mut num_qubits = 0;
     let mut qubit_map: HashMap<String, usize> = HashMap::new();
+    let mut regions = Vec::new();
+    let mut registers = Vec::new();
+    let mut moments = Vec::new();
+    let mut noise_overrides = Vec::new();
+    // Classical registers as (name, size)
+    let mut cregs: Vec<(String, usize)> = Vec::new();
+    // Basis of the next measurement, from a `// @basis` annotation
+    let mut basis = MeasurementBasis::Z;
 
     for line in qasm_str.lines() {
         let line = line.trim();
-        
+
+        if let Some(annotation) = line.strip_prefix("// @") {
+            match (annotation.strip_prefix("region "), annotation) {
+                (Some(body), _) => regions.push(parse_region_annotation(body)?),
+                (None, _) if annotation.starts_with("register ") => {
+                    registers.push(parse_register_annotation(&annotation["register ".len()..])?);
+                }
+                (None, _) if annotation.starts_with("moments ") => {
+                    moments = annotation["moments ".len()..]
+                        .split_whitespace()
+                        .map(|start| start.parse().map_err(|_| format!("@moments needs gate indices, got '{}'", start)))
+                        .collect::<Result<_, String>>()?;
+                }
+                (None, _) if annotation.starts_with("noise ") => {
+                    noise_overrides.push(parse_noise_annotation(&annotation["noise ".len()..])?);
+                }
+                (None, "basis x") => basis = MeasurementBasis::X,
+                (None, "basis z") => basis = MeasurementBasis::Z,
+                (None, _) if options.strict_roundtrip => {
+                    return Err(format!("Unknown annotation would be dropped: {}", line));
+                }
+                (None, _) => {}
+            }
+            continue;
+        }
+
         // Skip comments and empty lines
-        if line.is_empty() || line.starts_with("//") || line.starts_with("OPENQASM") || line.starts_with("include") {
+        if line.is_empty()
+            || line.starts_with("//")
+            || line.starts_with("OPENQASM")
+            || line.starts_with("include")
+        {
+            continue;
+        }
+
+        if let Some(declaration) = line.strip_prefix("creg ") {
+            let declaration = declaration.trim_end_matches(';').replace(' ', "");
+            let (name, size) = declaration
+                .strip_suffix(']')
+                .and_then(|d| d.split_once('['))
+                .and_then(|(name, size)| Some((name.to_string(), size.parse::<usize>().ok()?)))
+                .ok_or_else(|| format!("Could not parse classical register: {}", line))?;
+            cregs.push((name, size));
             continue;
         }
 
@@ -76,74 +234,165 @@ pub fn import_qasm(qasm_str: &str) -> Result<Circuit, String> {
         }
 
         // Parse gate operations
-        if line.ends_with(';') {
-            let gate_line = &line[..line.len() - 1]; // Remove semicolon
+        if let Some(gate_line) = line.strip_suffix(';') {
+            // Parameters may contain spaces: rz(pi / 2) q[0];
+            let (gate_line, parameter) = match (gate_line.find('('), gate_line.rfind(')')) {
+                (Some(open), Some(close)) if open < close => (
+                    format!("{}{}", &gate_line[..open], &gate_line[close + 1..]),
+                    Some(gate_line[open + 1..close].trim().to_string()),
+                ),
+                _ => (gate_line.to_string(), None),
+            };
             let parts: Vec<&str> = gate_line.split_whitespace().collect();
             
             if parts.is_empty() {
                 continue;
             }
 
-            let gate_name = parts[0].to_lowercase();
+            let name = parts[0].to_lowercase();
+            // Barriers span the whole circuit, so one on some qubits is
+            // widened, which strict mode reports
+            if name == "barrier" {
+                let mut covered = Vec::new();
+                for target in parts[1..].concat().split(',').filter(|t| !t.is_empty()) {
+                    if target.contains('[') {
+                        covered.push(parse_qubit_index(target, &qubit_map)?);
+                    } else {
+                        covered.extend(0..num_qubits);
+                    }
+                }
+                covered.sort_unstable();
+                covered.dedup();
+                if options.strict_roundtrip && covered.len() < num_qubits {
+                    return Err(format!("Barrier on some qubits would be widened to all: {}", line));
+                }
+                circuit.add_barrier();
+                continue;
+            }
+            // measure q[i] -> c[k]; a bare register measures every qubit
+            if name == "measure" {
+                let targets = parts[1..].concat();
+                let (source, bit) = targets.split_once("->").unwrap_or((&targets, ""));
+                if source.is_empty() {
+                    return Err(format!("Measurement needs a qubit: {}", line));
+                }
+                let measured: Vec<usize> = if source.contains('[') {
+                    vec![parse_qubit_index(source, &qubit_map)?]
+                } else {
+                    (0..num_qubits).collect()
+                };
+                if options.strict_roundtrip {
+                    let next = circuit.measurements.len();
+                    let (register, index) = match bit.split_once('[') {
+                        Some((register, index)) => (register, index.trim_end_matches(']').parse::<usize>().ok()),
+                        None => (bit, Some(0)),
+                    };
+                    if cregs.len() != 1 || cregs[0].0 != register || index != Some(next) {
+                        return Err(format!("Classical bit would be renumbered to c[{}]: {}", next, line));
+                    }
+                }
+                for qubit in measured {
+                    circuit.add_measurement(qubit, basis)?;
+                }
+                basis = MeasurementBasis::Z;
+                continue;
+            }
             let qubits: Vec<&str> = if parts.len() > 1 {
                 parts[1].split(',').collect()
             } else {
                 vec![]
             };
 
-            match gate_name.as_str() {
-                "h" | "x" | "y" | "z" | "s" | "sdg" => {
-                    if qubits.len() != 1 {
-                        return Err(format!("Single-qubit gate {} requires exactly one qubit", gate_name));
+            for gate_name in options.aliases.expand(&name) {
+                match gate_name.as_str() {
+                    "rz" | "rx" => {
+                        if qubits.len() != 1 {
+                            return Err(format!("Rotation {} requires exactly one qubit", gate_name));
+                        }
+                        let qubit = parse_qubit_index(qubits[0].trim(), &qubit_map)?;
+                        let angle = parameter.clone().ok_or_else(|| format!("Rotation {} requires an angle", gate_name))?;
+                        let radians = parse_angle(&angle)?;
+                        let quarter_turns = radians / FRAC_PI_2;
+                        if (quarter_turns - quarter_turns.round()).abs() > 1e-9 {
+                            non_clifford.rotations.push(SymbolicRotation {
+                                gate: circuit.gates.len(),
+                                qubit,
+                                axis: gate_name.clone(),
+                                angle,
+                                radians,
+                            });
+                            circuit.add_gate(Gate::Single { qubit, gate: SingleGate::I })?;
+                            continue;
+                        }
+                        let z_rotation = match (quarter_turns.round() as i64).rem_euclid(4) {
+                            0 => SingleGate::I,
+                            1 => SingleGate::S,
+                            2 => SingleGate::Z,
+                            _ => SingleGate::Sdg,
+                        };
+                        let gates = match (gate_name.as_str(), z_rotation) {
+                            ("rx", SingleGate::Z) => vec![SingleGate::X],
+                            ("rx", SingleGate::S) | ("rx", SingleGate::Sdg) => vec![SingleGate::H, z_rotation, SingleGate::H],
+                            _ => vec![z_rotation],
+                        };
+                        for gate in gates {
+                            circuit.add_gate(Gate::Single { qubit, gate })?;
+                        }
                     }
-                    let qubit_str = qubits[0].trim();
-                    let qubit = parse_qubit_index(qubit_str, &qubit_map)?;
+                    "h" | "x" | "y" | "z" | "s" | "sdg" | "id" => {
+                        if qubits.len() != 1 {
+                            return Err(format!("Single-qubit gate {} requires exactly one qubit", gate_name));
+                        }
+                        let qubit_str = qubits[0].trim();
+                        let qubit = parse_qubit_index(qubit_str, &qubit_map)?;
                     
-                    let gate = match gate_name.as_str() {
-                        "h" => SingleGate::H,
-                        "x" => SingleGate::X,
-                        "y" => SingleGate::Y,
-                        "z" => SingleGate::Z,
-                        "s" => SingleGate::S,
-                        "sdg" => SingleGate::Sdg,
-                        _ => return Err(format!("Unknown single-qubit gate: {}", gate_name)),
-                    };
+                        let gate = match gate_name.as_str() {
+                            "h" => SingleGate::H,
+                            "x" => SingleGate::X,
+                            "y" => SingleGate::Y,
+                            "z" => SingleGate::Z,
+                            "s" => SingleGate::S,
+                            "sdg" => SingleGate::Sdg,
+                            "id" => SingleGate::I,
+                            _ => return Err(format!("Unknown single-qubit gate: {}", gate_name)),
+                        };
                     
-                    circuit.add_gate(Gate::Single { qubit, gate })
-                        .map_err(|e| format!("Failed to add gate: {}", e))?;
-                }
-                "cx" => {
-                    if qubits.len() != 2 {
-                        return Err("CNOT gate requires exactly two qubits".to_string());
+                        circuit.add_gate(Gate::Single { qubit, gate })
+                            .map_err(|e| format!("Failed to add gate: {}", e))?;
                     }
-                    let control = parse_qubit_index(qubits[0].trim(), &qubit_map)?;
-                    let target = parse_qubit_index(qubits[1].trim(), &qubit_map)?;
+                    "cx" => {
+                        if qubits.len() != 2 {
+                            return Err("CNOT gate requires exactly two qubits".to_string());
+                        }
+                        let control = parse_qubit_index(qubits[0].trim(), &qubit_map)?;
+                        let target = parse_qubit_index(qubits[1].trim(), &qubit_map)?;
                     
-                    circuit.add_gate(Gate::Two(TwoGate::CNOT { control, target }))
-                        .map_err(|e| format!("Failed to add gate: {}", e))?;
-                }
-                "cz" => {
-                    if qubits.len() != 2 {
-                        return Err("CZ gate requires exactly two qubits".to_string());
+                        circuit.add_gate(Gate::Two(TwoGate::CNOT { control, target }))
+                            .map_err(|e| format!("Failed to add gate: {}", e))?;
                     }
-                    let control = parse_qubit_index(qubits[0].trim(), &qubit_map)?;
-                    let target = parse_qubit_index(qubits[1].trim(), &qubit_map)?;
+                    "cz" => {
+                        if qubits.len() != 2 {
+                            return Err("CZ gate requires exactly two qubits".to_string());
+                        }
+                        let control = parse_qubit_index(qubits[0].trim(), &qubit_map)?;
+                        let target = parse_qubit_index(qubits[1].trim(), &qubit_map)?;
                     
-                    circuit.add_gate(Gate::Two(TwoGate::CZ { control, target }))
-                        .map_err(|e| format!("Failed to add gate: {}", e))?;
-                }
-                "swap" => {
-                    if qubits.len() != 2 {
-                        return Err("SWAP gate requires exactly two qubits".to_string());
+                        circuit.add_gate(Gate::Two(TwoGate::CZ { control, target }))
+                            .map_err(|e| format!("Failed to add gate: {}", e))?;
                     }
-                    let qubit1 = parse_qubit_index(qubits[0].trim(), &qubit_map)?;
-                    let qubit2 = parse_qubit_index(qubits[1].trim(), &qubit_map)?;
+                    "swap" => {
+                        if qubits.len() != 2 {
+                            return Err("SWAP gate requires exactly two qubits".to_string());
+                        }
+                        let qubit1 = parse_qubit_index(qubits[0].trim(), &qubit_map)?;
+                        let qubit2 = parse_qubit_index(qubits[1].trim(), &qubit_map)?;
                     
-                    circuit.add_gate(Gate::Two(TwoGate::SWAP { qubit1, qubit2 }))
-                        .map_err(|e| format!("Failed to add gate: {}", e))?;
-                }
-                _ => {
-                    return Err(format!("Unsupported gate: {}", gate_name));
+                        circuit.add_gate(Gate::Two(TwoGate::SWAP { qubit1, qubit2 }))
+                            .map_err(|e| format!("Failed to add gate: {}", e))?;
+                    }
+                    _ => {
+                        return Err(format!("Unsupported gate: {}", gate_name));
+                    }
                 }
             }
         }
@@ -152,8 +401,89 @@ pub fn import_qasm(qasm_str: &str) -> Result<Circuit, String> {
     if num_qubits == 0 {
         return Err("No qubit register found in QASM file".to_string());
     }
+    if options.strict_roundtrip && cregs.iter().map(|(_, size)| size).sum::<usize>() != circuit.measurements.len() {
+        return Err(format!(
+            "{} classical bits declared for {} measurements would be dropped",
+            cregs.iter().map(|(_, size)| size).sum::<usize>(),
+            circuit.measurements.len()
+        ));
+    }
+    if !moments.is_empty() {
+        circuit.moments = moments;
+        circuit.validate_moments()?;
+    }
+    circuit.registers = registers;
+    circuit.validate_registers()?;
+    for (start, end, name) in regions {
+        circuit.add_region(&name, start, end)?;
+    }
+    for (gate, noise) in noise_overrides {
+        circuit.set_noise_override(gate, noise)?;
+    }
 
-    Ok(circuit)
+    Ok((circuit, non_clifford))
+}
+
+/// Evaluates an angle expression built from numbers, `pi`, `+ - * /` and
+/// parentheses.
+fn parse_angle(expression: &str) -> Result<f64, String> {
+    let tokens: Vec<char> = expression.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut position = 0;
+    let value = angle_sum(&tokens, &mut position)?;
+    if position != tokens.len() {
+        return Err(format!("Could not parse angle: {}", expression));
+    }
+    Ok(value)
+}
+
+fn angle_sum(tokens: &[char], position: &mut usize) -> Result<f64, String> {
+    let mut value = angle_product(tokens, position)?;
+    while let Some(&op) = tokens.get(*position).filter(|c| **c == '+' || **c == '-') {
+        *position += 1;
+        let rhs = angle_product(tokens, position)?;
+        value = if op == '+' { value + rhs } else { value - rhs };
+    }
+    Ok(value)
+}
+
+fn angle_product(tokens: &[char], position: &mut usize) -> Result<f64, String> {
+    let mut value = angle_factor(tokens, position)?;
+    while let Some(&op) = tokens.get(*position).filter(|c| **c == '*' || **c == '/') {
+        *position += 1;
+        let rhs = angle_factor(tokens, position)?;
+        value = if op == '*' { value * rhs } else { value / rhs };
+    }
+    Ok(value)
+}
+
+fn angle_factor(tokens: &[char], position: &mut usize) -> Result<f64, String> {
+    match tokens.get(*position) {
+        Some('-') => {
+            *position += 1;
+            Ok(-angle_factor(tokens, position)?)
+        }
+        Some('(') => {
+            *position += 1;
+            let value = angle_sum(tokens, position)?;
+            if tokens.get(*position) != Some(&')') {
+                return Err("Unbalanced parentheses in angle".to_string());
+            }
+            *position += 1;
+            Ok(value)
+        }
+        Some(_) => {
+            let start = *position;
+            while tokens.get(*position).is_some_and(|c| c.is_alphanumeric() || *c == '.') {
+                *position += 1;
+            }
+            let word: String = tokens[start..*position].iter().collect();
+            match word.as_str() {
+                "pi" | "π" => Ok(std::f64::consts::PI),
+                _ => word.parse().map_err(|_| format!("Could not parse angle term: '{}'", word)),
+            }
+        }
+        None => Err("Angle expression ended early".to_string()),
+    }
 }
 
 fn parse_qubit_index(qubit_str: &str, qubit_map: &HashMap<String, usize>) -> Result<usize, String> {
@@ -211,6 +541,30 @@ cx q[0],q[1];
         assert_eq!(circuit.gates.len(), 2);
     }
 
+    #[test]
+    fn test_vendor_gate_aliases() {
+        let qasm = "qreg q[2];\nCNOT q[0],q[1];\nsx q[1];\nu1(pi/2) q[0];\nmygate q[1];\n";
+        assert!(import_qasm(qasm).unwrap_err().contains("mygate"));
+
+        let mut options = ImportOptions::default();
+        options.aliases.add("mygate", &["z"]).unwrap();
+        let circuit = import_qasm_with(qasm, &options).unwrap();
+        let singles = |gates: &[Gate]| {
+            gates
+                .iter()
+                .filter_map(|g| match g {
+                    Gate::Single { gate, .. } => Some(*gate),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(circuit.gates[0], Gate::Two(TwoGate::CNOT { control: 0, target: 1 }));
+        assert_eq!(
+            singles(&circuit.gates),
+            vec![SingleGate::H, SingleGate::S, SingleGate::H, SingleGate::S, SingleGate::Z]
+        );
+    }
+
     #[test]
     fn test_qasm_roundtrip() {
         let mut circuit = Circuit::new(3);
@@ -239,11 +593,65 @@ cx q[0],q[1];
             }))
             .unwrap();
 
+        circuit.barriers = vec![2, 4];
+
         let qasm = export_qasm(&circuit);
+        assert!(qasm.contains("s q[1];\nbarrier q;\ncx q[0],q[2];"));
         let imported = import_qasm(&qasm).unwrap();
 
         assert_eq!(circuit.num_qubits, imported.num_qubits);
         assert_eq!(circuit.gates.len(), imported.gates.len());
+        assert_eq!(imported.barriers, vec![2, 4]);
+
+        let partial = "qreg q[3];\nh q[0];\nbarrier q[0], q[1];\nh q[2];\n";
+        assert_eq!(import_qasm(partial).unwrap().barriers, vec![1]);
+        let strict = ImportOptions::strict();
+        assert!(import_qasm_with(partial, &strict).unwrap_err().contains("widened"));
+        assert!(import_qasm_with("qreg q[2];\nbarrier q[1],q[0];\n", &strict).is_ok());
+        assert!(import_qasm_with("qreg q[2];\nbarrier q;\n", &strict).is_ok());
+    }
+
+    #[test]
+    fn test_measurement_roundtrip() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        circuit.add_measurement(1, MeasurementBasis::Z).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        circuit.add_measurement(0, MeasurementBasis::X).unwrap();
+
+        let qasm = export_qasm(&circuit);
+        assert!(qasm.contains("creg c[2];"));
+        assert!(qasm.contains("cx q[0],q[1];\nmeasure q[1] -> c[0];\nh q[0];"));
+        assert_eq!(import_qasm_with(&qasm, &ImportOptions::strict()).unwrap(), circuit);
+
+        assert_eq!(import_qasm("qreg q[2];\nmeasure q[1]->c[0];\n").unwrap().measurements[0].qubit, 1);
+        let all = import_qasm("qreg q[2];\ncreg c[2];\nmeasure q -> c;\n").unwrap();
+        assert_eq!(all.measurements_at(0).len(), 2);
+    }
+
+    #[test]
+    fn test_clifford_angle_rotations() {
+        let qasm = "OPENQASM 2.0;\nqreg q[2];\nrz(pi/2) q[0];\nrz(-pi / 2) q[1];\nrx(pi) q[0];\nrx(3*pi/2) q[1];\nrz(2*pi) q[0];\n";
+        let circuit = import_qasm(qasm).unwrap();
+        let gates: Vec<SingleGate> = circuit
+            .gates
+            .iter()
+            .map(|g| match g {
+                Gate::Single { gate, .. } => *gate,
+                _ => unreachable!(),
+            })
+            .collect();
+        use SingleGate::*;
+        assert_eq!(gates, vec![S, Sdg, X, H, Sdg, H, I]);
+
+        let qasm = "OPENQASM 2.0;\nqreg q[1];\nh q[0];\nrz(pi/4) q[0];\n";
+        assert!(import_qasm(qasm).is_err());
+        let (circuit, content) = import_qasm_with_report(qasm, &ImportOptions::default()).unwrap();
+        assert_eq!(circuit.gates[1], Gate::Single { qubit: 0, gate: SingleGate::I });
+        assert_eq!(content.rotations.len(), 1);
+        assert_eq!(content.rotations[0].gate, 1);
+        assert_eq!(content.rotations[0].angle, "pi/4");
+        assert!(content.summary().contains("rz(pi/4) q[0]"));
     }
 }
 
@@ -0,0 +1,232 @@
+//! Export of sampled detection events and observable flips.
+//!
+//! Matrices have one row per shot. NumPy files use dtype uint8 in C order;
+//! the `.npz` archive stores `detectors.npy` and `observables.npy`
+//! uncompressed, which `numpy.load` reads directly.
+//!
+//! Sparse 0/1 matrices, such as which detectors each fault flips, are
+//! written in the compressed sparse row layout of `scipy.sparse.save_npz`,
+//! so `scipy.sparse.load_npz` reads them without a dense intermediate.
+
+use crate::dem::DetectorErrorModel;
+use crate::noise::sampler::ShotRecord;
+
+/// CSV with a `d0,d1,...,o0,o1,...` header and one 0/1 row per shot.
+pub fn export_samples_csv(shots: &[ShotRecord]) -> String {
+    let (detectors, observables) = shot_widths(shots);
+    let header: Vec<String> = (0..detectors)
+        .map(|i| format!("d{}", i))
+        .chain((0..observables).map(|i| format!("o{}", i)))
+        .collect();
+
+    let mut csv = header.join(",");
+    csv.push('\n');
+    for shot in shots {
+        let row: Vec<&str> = shot
+            .detectors
+            .iter()
+            .chain(&shot.observables)
+            .map(|&bit| if bit { "1" } else { "0" })
+            .collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// `.npy` (format 1.0) encoding of a 0/1 matrix. Rows must have equal length.
+pub fn export_npy(rows: &[Vec<bool>]) -> Result<Vec<u8>, String> {
+    let columns = rows.first().map_or(0, |row| row.len());
+    if let Some(row) = rows.iter().find(|row| row.len() != columns) {
+        return Err(format!("Ragged matrix: expected {} columns, found {}", columns, row.len()));
+    }
+
+    let mut bytes = npy_header("|u1", &format!("({}, {})", rows.len(), columns));
+    bytes.extend(rows.iter().flatten().map(|&bit| bit as u8));
+    Ok(bytes)
+}
+
+/// Scipy CSR `.npz` of a 0/1 matrix given by the column indices of the set
+/// entries in each row. Indices are stored as int64, entries as uint8.
+pub fn export_csr_npz(rows: &[Vec<usize>], num_columns: usize) -> Result<Vec<u8>, String> {
+    let mut indptr = vec![0i64];
+    let mut indices = Vec::new();
+    for (r, row) in rows.iter().enumerate() {
+        if let Some(&column) = row.iter().find(|&&column| column >= num_columns) {
+            return Err(format!("Row {} has column {} but the matrix has {} columns", r, column, num_columns));
+        }
+        indices.extend(row.iter().map(|&column| column as i64));
+        indptr.push(indices.len() as i64);
+    }
+
+    let mut data = npy_header("|u1", &format!("({},)", indices.len()));
+    data.resize(data.len() + indices.len(), 1);
+    let mut format = npy_header("<U3", "()");
+    for c in "csr".chars() {
+        format.extend_from_slice(&(c as u32).to_le_bytes());
+    }
+    Ok(stored_zip(&[
+        ("indices.npy", npy_i64(&indices)),
+        ("indptr.npy", npy_i64(&indptr)),
+        ("format.npy", format),
+        ("shape.npy", npy_i64(&[rows.len() as i64, num_columns as i64])),
+        ("data.npy", data),
+    ]))
+}
+
+/// The detectors each error of `dem` flips, as a CSR matrix with one row
+/// per error (in `dem.errors` order) and one column per detector.
+pub fn export_fault_detector_npz(dem: &DetectorErrorModel) -> Result<Vec<u8>, String> {
+    let rows: Vec<Vec<usize>> = dem.errors.iter().map(|error| error.detectors.clone()).collect();
+    export_csr_npz(&rows, dem.num_detectors)
+}
+
+/// Magic, version and padded header of a `.npy` file.
+fn npy_header(descr: &str, shape: &str) -> Vec<u8> {
+    let mut header = format!("{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}", descr, shape);
+    // Magic (6) + version (2) + length (2) + header must be a multiple of 64
+    let padding = (64 - (10 + header.len() + 1) % 64) % 64;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+
+    let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    bytes
+}
+
+fn npy_i64(values: &[i64]) -> Vec<u8> {
+    let mut bytes = npy_header("<i8", &format!("({},)", values.len()));
+    for value in values {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    bytes
+}
+
+/// `.npz` archive with `detectors` and `observables` matrices.
+pub fn export_samples_npz(shots: &[ShotRecord]) -> Result<Vec<u8>, String> {
+    let detectors: Vec<Vec<bool>> = shots.iter().map(|shot| shot.detectors.clone()).collect();
+    let observables: Vec<Vec<bool>> = shots.iter().map(|shot| shot.observables.clone()).collect();
+    Ok(stored_zip(&[
+        ("detectors.npy", export_npy(&detectors)?),
+        ("observables.npy", export_npy(&observables)?),
+    ]))
+}
+
+fn shot_widths(shots: &[ShotRecord]) -> (usize, usize) {
+    shots
+        .first()
+        .map_or((0, 0), |shot| (shot.detectors.len(), shot.observables.len()))
+}
+
+/// Minimal zip writer: stored (uncompressed) entries, no timestamps.
+fn stored_zip(files: &[(&str, Vec<u8>)]) -> Vec<u8> {
+    let mut archive = Vec::new();
+    let mut directory = Vec::new();
+
+    for (name, data) in files {
+        let offset = archive.len() as u32;
+        let crc = crc32(data);
+        let size = data.len() as u32;
+
+        archive.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        archive.extend_from_slice(&[20, 0, 0, 0, 0, 0, 0, 0, 0x21, 0]);
+        archive.extend_from_slice(&crc.to_le_bytes());
+        archive.extend_from_slice(&size.to_le_bytes());
+        archive.extend_from_slice(&size.to_le_bytes());
+        archive.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        archive.extend_from_slice(&0u16.to_le_bytes());
+        archive.extend_from_slice(name.as_bytes());
+        archive.extend_from_slice(data);
+
+        directory.extend_from_slice(&0x02014b50u32.to_le_bytes());
+        directory.extend_from_slice(&[20, 0, 20, 0, 0, 0, 0, 0, 0, 0, 0x21, 0]);
+        directory.extend_from_slice(&crc.to_le_bytes());
+        directory.extend_from_slice(&size.to_le_bytes());
+        directory.extend_from_slice(&size.to_le_bytes());
+        directory.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        // Extra field, comment, disk number, internal and external attributes
+        directory.extend_from_slice(&[0; 12]);
+        directory.extend_from_slice(&offset.to_le_bytes());
+        directory.extend_from_slice(name.as_bytes());
+    }
+
+    let directory_offset = archive.len() as u32;
+    let count = files.len() as u16;
+    archive.extend_from_slice(&directory);
+    archive.extend_from_slice(&0x06054b50u32.to_le_bytes());
+    archive.extend_from_slice(&[0, 0, 0, 0]);
+    archive.extend_from_slice(&count.to_le_bytes());
+    archive.extend_from_slice(&count.to_le_bytes());
+    archive.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+    archive.extend_from_slice(&directory_offset.to_le_bytes());
+    archive.extend_from_slice(&0u16.to_le_bytes());
+    archive
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB88320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::pauli::PauliString;
+
+    fn shot(detectors: Vec<bool>, observables: Vec<bool>) -> ShotRecord {
+        ShotRecord {
+            faults: Vec::new(),
+            detectors,
+            observables,
+            heralds: Vec::new(),
+            residual: PauliString::new(1),
+        }
+    }
+
+    #[test]
+    fn test_csv() {
+        let shots = vec![shot(vec![true, false], vec![false]), shot(vec![false, false], vec![true])];
+        assert_eq!(export_samples_csv(&shots), "d0,d1,o0\n1,0,0\n0,0,1\n");
+    }
+
+    #[test]
+    fn test_npy_layout() {
+        let bytes = export_npy(&[vec![true, false, true], vec![false, true, false]]).unwrap();
+        assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+        assert!(header.contains("'shape': (2, 3)"));
+        assert_eq!(&bytes[10 + header_len..], &[1, 0, 1, 0, 1, 0]);
+        assert!(export_npy(&[vec![true], vec![]]).is_err());
+    }
+
+    #[test]
+    fn test_npz_archive() {
+        assert_eq!(crc32(b"123456789"), 0xCBF43926);
+        let archive = export_samples_npz(&[shot(vec![true], vec![false])]).unwrap();
+        assert_eq!(&archive[..4], b"PK\x03\x04");
+        // End of central directory lists both entries
+        let end = archive.len() - 22;
+        assert_eq!(&archive[end..end + 4], b"PK\x05\x06");
+        assert_eq!(archive[end + 10], 2);
+    }
+
+    #[test]
+    fn test_csr_npz() {
+        let archive = export_csr_npz(&[vec![0, 2], vec![], vec![1]], 3).unwrap();
+        let end = archive.len() - 22;
+        assert_eq!(archive[end + 10], 5);
+        // Stored entries hold the arrays verbatim
+        let indptr = npy_i64(&[0, 2, 2, 3]);
+        assert!(archive.windows(indptr.len()).any(|window| window == indptr.as_slice()));
+        assert!(export_csr_npz(&[vec![3]], 3).is_err());
+    }
+}
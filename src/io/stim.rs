@@ -0,0 +1,273 @@
+//! Stim circuit text format (Clifford gates only).
+//!
+//! Gates map to `H`, `S`, `S_DAG`, `X`, `Y`, `Z`, `I`, `CX`, `CZ` and
+//! `SWAP`, one instruction per gate. Stim has no qubit count, so it is kept
+//! in a `# @num_qubits <n>` annotation; regions use `# @region <start> <end>
+//! <name>`, registers `# @register <start> <size> <name>` and noise
+//! overrides `# @noise <gate> <json>` as in the QASM format, and barriers a
+//! `# @barrier` line.
+//! Measurements are `M` (Z basis) and `MX`. Circuits with moments end each
+//! moment with `TICK`. On import, instructions with several targets expand
+//! into one gate or measurement per target (or target pair); if there are
+//! `TICK`s, the gates between two of them form a moment, split where gates
+//! share a qubit or a measurement comes between them.
+
+use crate::io::options::{
+    noise_annotation, parse_noise_annotation, parse_region_annotation, parse_register_annotation, register_annotation,
+    ImportOptions,
+};
+use crate::physics::circuit::{Circuit, Gate, Measure, MeasurementBasis, SingleGate, TwoGate};
+
+pub fn export_stim(circuit: &Circuit) -> String {
+    let mut stim = format!("# @num_qubits {}\n", circuit.num_qubits);
+    for region in &circuit.regions {
+        stim.push_str(&format!("# @region {} {} {}\n", region.start, region.end, region.name));
+    }
+    for register in &circuit.registers {
+        stim.push_str(&format!("# {}\n", register_annotation(register)));
+    }
+    for entry in &circuit.noise_overrides {
+        stim.push_str(&format!("# {}\n", noise_annotation(entry)));
+    }
+    let measure = |stim: &mut String, position: usize| {
+        for m in circuit.measurements_at(position) {
+            let name = match m.basis {
+                MeasurementBasis::Z => "M",
+                MeasurementBasis::X => "MX",
+            };
+            stim.push_str(&format!("{} {}\n", name, m.qubit));
+        }
+    };
+    for (index, gate) in circuit.gates.iter().enumerate() {
+        if circuit.has_barrier_before(index) {
+            stim.push_str("# @barrier\n");
+        }
+        measure(&mut stim, index);
+        let line = match gate {
+            Gate::Single { qubit, gate } => {
+                let name = match gate {
+                    SingleGate::H => "H",
+                    SingleGate::S => "S",
+                    SingleGate::Sdg => "S_DAG",
+                    SingleGate::X => "X",
+                    SingleGate::Y => "Y",
+                    SingleGate::Z => "Z",
+                    SingleGate::I => "I",
+                };
+                format!("{} {}", name, qubit)
+            }
+            Gate::Two(TwoGate::CNOT { control, target }) => format!("CX {} {}", control, target),
+            Gate::Two(TwoGate::CZ { control, target }) => format!("CZ {} {}", control, target),
+            Gate::Two(TwoGate::SWAP { qubit1, qubit2 }) => format!("SWAP {} {}", qubit1, qubit2),
+        };
+        stim.push_str(&line);
+        stim.push('\n');
+        if !circuit.moments.is_empty() && circuit.moment_of(index + 1) != circuit.moment_of(index) {
+            stim.push_str("TICK\n");
+        }
+    }
+    if circuit.has_barrier_before(circuit.gates.len()) {
+        stim.push_str("# @barrier\n");
+    }
+    measure(&mut stim, circuit.gates.len());
+    stim
+}
+
+/// Stim spelling of a (QASM-named) alias target.
+fn stim_name(name: &str) -> String {
+    match name {
+        "sdg" => "S_DAG".to_string(),
+        "id" => "I".to_string(),
+        other => other.to_uppercase(),
+    }
+}
+
+pub fn import_stim(stim: &str) -> Result<Circuit, String> {
+    import_stim_with(stim, &ImportOptions::default())
+}
+
+pub fn import_stim_with(stim: &str, options: &ImportOptions) -> Result<Circuit, String> {
+    let mut declared_qubits = None;
+    let mut regions = Vec::new();
+    let mut registers = Vec::new();
+    let mut noise_overrides = Vec::new();
+    let mut gates = Vec::new();
+    let mut ticks = Vec::new();
+    let mut barriers: Vec<usize> = Vec::new();
+    let mut measurements = Vec::new();
+
+    for (index, line) in stim.lines().enumerate() {
+        let err = |message: String| format!("Line {}: {}", index + 1, message);
+        let line = line.trim();
+        if let Some(annotation) = line.strip_prefix("# @") {
+            if let Some(count) = annotation.strip_prefix("num_qubits ") {
+                let count = count.trim().parse().map_err(|_| err(format!("invalid qubit count '{}'", count)))?;
+                declared_qubits = Some(count);
+            } else if annotation.trim() == "barrier" {
+                if barriers.last() != Some(&gates.len()) {
+                    barriers.push(gates.len());
+                }
+            } else if let Some(body) = annotation.strip_prefix("region ") {
+                regions.push(parse_region_annotation(body).map_err(err)?);
+            } else if let Some(body) = annotation.strip_prefix("register ") {
+                registers.push(parse_register_annotation(body).map_err(err)?);
+            } else if let Some(body) = annotation.strip_prefix("noise ") {
+                noise_overrides.push(parse_noise_annotation(body).map_err(err)?);
+            } else if options.strict_roundtrip {
+                return Err(err(format!("unknown annotation would be dropped: {}", line)));
+            }
+            continue;
+        }
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let name = parts.next().unwrap_or_default().to_uppercase();
+        let targets = parts
+            .map(|t| t.parse::<usize>().map_err(|_| err(format!("invalid target '{}'", t))))
+            .collect::<Result<Vec<usize>, String>>()?;
+        for name in options.aliases.expand(&name).iter().map(|n| stim_name(n)) {
+            let single = match name.as_str() {
+                "H" => Some(SingleGate::H),
+                "S" => Some(SingleGate::S),
+                "S_DAG" => Some(SingleGate::Sdg),
+                "X" => Some(SingleGate::X),
+                "Y" => Some(SingleGate::Y),
+                "Z" => Some(SingleGate::Z),
+                "I" => Some(SingleGate::I),
+                _ => None,
+            };
+            if let Some(gate) = single {
+                gates.extend(targets.iter().map(|&qubit| Gate::Single { qubit, gate }));
+                continue;
+            }
+            let two = |a: usize, b: usize| match name.as_str() {
+                "CX" | "CNOT" => Some(TwoGate::CNOT { control: a, target: b }),
+                "CZ" => Some(TwoGate::CZ { control: a, target: b }),
+                "SWAP" => Some(TwoGate::SWAP { qubit1: a, qubit2: b }),
+                _ => None,
+            };
+            match name.as_str() {
+                "CX" | "CNOT" | "CZ" | "SWAP" => {
+                    if targets.len() % 2 != 0 {
+                        return Err(err(format!("{} needs an even number of targets", name)));
+                    }
+                    gates.extend(targets.chunks(2).filter_map(|pair| two(pair[0], pair[1])).map(Gate::Two));
+                }
+                "M" | "MZ" | "MX" => {
+                    let basis = if name == "MX" { MeasurementBasis::X } else { MeasurementBasis::Z };
+                    measurements.extend(targets.iter().map(|&qubit| Measure {
+                        position: gates.len(),
+                        qubit,
+                        basis,
+                    }));
+                }
+                "TICK" => ticks.push(gates.len()),
+                other => return Err(err(format!("unsupported instruction '{}'", other))),
+            }
+        }
+    }
+
+    let used = gates
+        .iter()
+        .flat_map(|g| g.qubits())
+        .chain(measurements.iter().map(|m| m.qubit))
+        .max()
+        .map_or(0, |q| q + 1);
+    let num_qubits = declared_qubits.unwrap_or(used);
+    if num_qubits < used {
+        return Err(format!("Circuit declares {} qubits but uses {}", num_qubits, used));
+    }
+    let mut circuit = Circuit::new(num_qubits);
+    for gate in gates {
+        circuit.add_gate(gate)?;
+    }
+    if !ticks.is_empty() {
+        ticks.extend(measurements.iter().map(|m| m.position));
+        ticks.sort_unstable();
+        circuit.moments = moments_between_ticks(&circuit.gates, &ticks);
+    }
+    circuit.barriers = barriers;
+    circuit.validate_barriers()?;
+    circuit.measurements = measurements;
+    circuit.validate_measurements()?;
+    circuit.registers = registers;
+    circuit.validate_registers()?;
+    for (start, end, name) in regions {
+        circuit.add_region(&name, start, end)?;
+    }
+    for (gate, noise) in noise_overrides {
+        circuit.set_noise_override(gate, noise)?;
+    }
+    Ok(circuit)
+}
+
+/// Moment starts for `gates` with a `TICK` before each index in `ticks`:
+/// a moment ends at every `TICK` and before a gate reusing one of its
+/// qubits.
+fn moments_between_ticks(gates: &[Gate], ticks: &[usize]) -> Vec<usize> {
+    let mut moments = Vec::new();
+    let mut busy: Vec<usize> = Vec::new();
+    for (index, gate) in gates.iter().enumerate() {
+        let qubits = gate.qubits();
+        if index == 0 || ticks.binary_search(&index).is_ok() || qubits.iter().any(|q| busy.contains(q)) {
+            moments.push(index);
+            busy.clear();
+        }
+        busy.extend(qubits);
+    }
+    moments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_stim() {
+        let circuit = import_stim("H 0 1\nTICK\nCX 0 2 1 3  # two pairs\nS_DAG 3\n").unwrap();
+        assert_eq!(circuit.num_qubits, 4);
+        assert_eq!(circuit.gates.len(), 5);
+        assert_eq!(circuit.gates[3], Gate::Two(TwoGate::CNOT { control: 1, target: 3 }));
+
+        assert_eq!(circuit.moments, vec![0, 2, 4]);
+        assert_eq!(import_stim(&export_stim(&circuit)).unwrap(), circuit);
+        assert!(export_stim(&circuit).ends_with("S_DAG 3\nTICK\n"));
+        assert!(import_stim("H 0\nH 1\n").unwrap().moments.is_empty());
+
+        let mut fenced = circuit.clone();
+        fenced.barriers = vec![2, 5];
+        let stim = export_stim(&fenced);
+        assert!(stim.contains("TICK\n# @barrier\nCX 0 2"));
+        assert_eq!(import_stim_with(&stim, &ImportOptions::strict()).unwrap(), fenced);
+        assert!(import_stim("MY 0\n").is_err());
+        assert!(import_stim("CX 0\n").is_err());
+
+        let aliased = import_stim("SQRT_X 0\nZCX 0 1\n").unwrap();
+        assert_eq!(aliased.gates.len(), 4);
+        assert_eq!(aliased.gates[1], Gate::Single { qubit: 0, gate: SingleGate::S });
+        let options = ImportOptions {
+            aliases: crate::io::aliases::GateAliases::none(),
+            ..ImportOptions::default()
+        };
+        assert!(import_stim_with("SQRT_X 0\n", &options).is_err());
+    }
+
+    #[test]
+    fn test_measurement_roundtrip() {
+        let mut circuit = import_stim("CX 0 1\nTICK\nH 0\nTICK\n").unwrap();
+        circuit.measurements = vec![
+            Measure { position: 1, qubit: 1, basis: MeasurementBasis::Z },
+            Measure { position: 2, qubit: 0, basis: MeasurementBasis::X },
+        ];
+        let stim = export_stim(&circuit);
+        assert!(stim.contains("TICK\nM 1\nH 0\nTICK\nMX 0\n"));
+        assert_eq!(import_stim_with(&stim, &ImportOptions::strict()).unwrap(), circuit);
+
+        let split = import_stim("H 0\nM 0\nH 1\nTICK\n").unwrap();
+        assert_eq!(split.moments, vec![0, 1]);
+        assert_eq!(split.measurements.len(), 1);
+    }
+}
@@ -1,5 +1,39 @@
-pub mod physics;
+//! Pauli error propagation through Clifford circuits.
+//!
+//! The core (`physics`, `memory`, `perf`, `shared`) always builds; `physics`,
+//! `perf` and the basic `memory` accounting come from the
+//! `quantum-error-analyzer-core` crate and are re-exported under the same
+//! paths, so depending on the core alone gives the same API. Larger parts sit
+//! behind cargo features, all on by default: `io` for file formats,
+//! `analysis` for codes, noise, DEMs, transforms and analyses, and
+//! `decoders` for decoding and the `Analyzer` facade bundling them all.
+//! The WASM build enables `io`, plus `decoders` for its decoding demo.
+//!
+//! Output order never depends on hash-map iteration: every list an analysis
+//! or exporter returns has a documented order, so identical inputs (and
+//! seeds) give byte-identical reports.
+
+#[cfg(feature = "io")]
 pub mod io;
+#[cfg(feature = "analysis")]
+pub mod analysis;
+#[cfg(feature = "analysis")]
+pub mod transform;
+#[cfg(feature = "analysis")]
+pub mod codes;
+#[cfg(feature = "analysis")]
+pub mod noise;
+#[cfg(feature = "decoders")]
+pub mod decoding;
+#[cfg(feature = "analysis")]
+pub mod dem;
+#[cfg(feature = "decoders")]
+pub mod analyzer;
+pub mod memory;
+pub mod shared;
 
+pub use quantum_error_analyzer_core::{perf, physics};
 pub use physics::*;
-
+pub use shared::{Shareable, Shared};
+#[cfg(feature = "decoders")]
+pub use analyzer::{Analyzer, AnalyzerOptions};
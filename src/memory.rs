@@ -0,0 +1,55 @@
+//! Memory estimates for DEMs and shot records. The accounting itself, the
+//! limits and the estimates for circuits and simulators are re-exported
+//! from the core crate.
+
+pub use quantum_error_analyzer_core::memory::*;
+
+#[cfg(feature = "analysis")]
+use crate::analysis::fault_sweep::Fault;
+#[cfg(feature = "analysis")]
+use crate::dem::model::{DemError, DetectorErrorModel};
+#[cfg(feature = "analysis")]
+use crate::noise::model::Herald;
+#[cfg(feature = "analysis")]
+use crate::noise::sampler::ShotRecord;
+#[cfg(feature = "analysis")]
+use std::mem::size_of;
+
+#[cfg(feature = "analysis")]
+impl ApproximateSize for DemError {
+    fn approximate_bytes(&self) -> usize {
+        size_of::<DemError>() + (self.detectors.len() + self.observables.len()) * size_of::<usize>()
+    }
+}
+
+#[cfg(feature = "analysis")]
+impl ApproximateSize for DetectorErrorModel {
+    fn approximate_bytes(&self) -> usize {
+        size_of::<DetectorErrorModel>() + self.errors.approximate_bytes()
+    }
+}
+
+#[cfg(feature = "analysis")]
+impl ApproximateSize for ShotRecord {
+    fn approximate_bytes(&self) -> usize {
+        size_of::<ShotRecord>()
+            + self.faults.len() * size_of::<Fault>()
+            + self.detectors.len()
+            + self.observables.len()
+            + self.heralds.len() * size_of::<Herald>()
+            + pauli_heap_bytes(self.residual.num_qubits())
+    }
+}
+
+#[cfg(feature = "analysis")]
+/// Projected size of `shots` fault-free shot records.
+pub fn shot_records_bytes(shots: usize, detectors: usize, observables: usize, code_qubits: usize) -> usize {
+    shots * (size_of::<ShotRecord>() + detectors + observables + pauli_heap_bytes(code_qubits))
+}
+
+#[cfg(feature = "analysis")]
+/// Projected size of `mechanisms` unmerged DEM errors, each flipping about
+/// two detectors.
+pub fn dem_bytes(mechanisms: usize) -> usize {
+    size_of::<DetectorErrorModel>() + mechanisms * (size_of::<DemError>() + 2 * size_of::<usize>())
+}
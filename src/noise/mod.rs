@@ -0,0 +1,13 @@
+pub mod model;
+pub mod monte_carlo;
+pub mod sampler;
+pub mod stochastic_clifford;
+
+pub use model::{
+    BurstFootprint, BurstNoise, Herald, HeraldedNoise, NoiseModel, NoiseSample, NoiseSegment, ScheduleSpan,
+};
+pub use monte_carlo::{run_monte_carlo, MonteCarloResult};
+pub use sampler::{sample_shot, sample_shots, sample_shots_within, ShotRecord};
+pub use stochastic_clifford::{
+    decompose_rotation, run_stochastic_clifford, CliffordApproximation, CliffordTerm, StochasticCliffordResult,
+};
@@ -0,0 +1,627 @@
+use crate::analysis::fault_sweep::Fault;
+use crate::physics::circuit::Circuit;
+use crate::physics::pauli::{PauliString, SinglePauli};
+use rand_core::Rng;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+const PAULIS: [SinglePauli; 4] = [SinglePauli::I, SinglePauli::X, SinglePauli::Y, SinglePauli::Z];
+
+/// Circuit-level depolarizing noise. Each probability is the chance that a
+/// uniformly random non-identity Pauli strikes the listed location.
+///
+/// `data_depolarizing` and `measurement_flip` are not tied to gates: they
+/// act on the code block and on the gadget's readouts, so they are applied
+/// by the gadget-level consumers (shot sampling, Monte Carlo, DEMs).
+///
+/// A circuit can replace the gate channel after single gate instances with
+/// a `GateNoise` override, which every consumer honours.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NoiseModel {
+    /// After every single-qubit gate, on its qubit
+    pub single_qubit_depolarizing: f64,
+    /// After every two-qubit gate, one of the 15 two-qubit Paulis
+    pub two_qubit_depolarizing: f64,
+    /// On every qubit left idle by a moment
+    pub idle_depolarizing: f64,
+    /// On every code qubit once, at the end of the gadget
+    #[serde(default)]
+    pub data_depolarizing: f64,
+    /// Classical flip of every gadget measurement outcome
+    #[serde(default)]
+    pub measurement_flip: f64,
+    /// Temporally correlated events on top of the independent noise
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bursts: Vec<BurstNoise>,
+    /// Detected events (e.g. leakage) that set a herald bit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub heralded: Vec<HeraldedNoise>,
+    /// Piecewise changes of the gate-tied rates over the circuit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedule: Vec<NoiseSegment>,
+}
+
+/// Gates over which the gate-tied rates (single-qubit, two-qubit and idle
+/// depolarizing) are multiplied by `scale`, e.g. for drift or duty-cycle
+/// heating. Where segments overlap the last one listed wins; gates outside
+/// every segment keep the base rates.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NoiseSegment {
+    pub span: ScheduleSpan,
+    pub scale: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScheduleSpan {
+    /// Gates `start..end`, i.e. moments of the sequential circuit
+    Gates { start: usize, end: usize },
+    /// The gates of a named circuit region, e.g. one syndrome round
+    Region(String),
+}
+
+impl NoiseSegment {
+    pub fn gates(start: usize, end: usize, scale: f64) -> Self {
+        Self {
+            span: ScheduleSpan::Gates { start, end },
+            scale,
+        }
+    }
+
+    pub fn region(name: &str, scale: f64) -> Self {
+        Self {
+            span: ScheduleSpan::Region(name.to_string()),
+            scale,
+        }
+    }
+}
+
+impl ScheduleSpan {
+    /// The gate range covered in `circuit`.
+    fn range(&self, circuit: &Circuit) -> Result<Range<usize>, String> {
+        match self {
+            ScheduleSpan::Gates { start, end } => Ok(*start..*end),
+            ScheduleSpan::Region(name) => circuit
+                .region(name)
+                .map(|region| region.start..region.end)
+                .ok_or_else(|| format!("Schedule refers to unknown region '{}'", name)),
+        }
+    }
+}
+
+/// After every gate, each operand in `qubits` (all qubits if empty) is
+/// replaced by the maximally mixed state with probability `probability`,
+/// and the event is heralded.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HeraldedNoise {
+    pub probability: f64,
+    #[serde(default)]
+    pub qubits: Vec<usize>,
+}
+
+/// Classical record of a heralded event.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Herald {
+    pub after_gate: usize,
+    pub qubit: usize,
+}
+
+/// Faults of one shot together with the heralds they raised.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NoiseSample {
+    pub faults: Vec<Fault>,
+    pub heralds: Vec<Herald>,
+}
+
+/// Which qubits a burst hits.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BurstFootprint {
+    /// Qubits within this index distance of a uniformly chosen centre
+    Radius(usize),
+    /// One of these neighbourhoods, chosen uniformly
+    Neighborhoods(Vec<Vec<usize>>),
+}
+
+/// A cosmic-ray-like event: starts at any time step with probability
+/// `rate`, then for `duration` steps depolarizes every footprint qubit with
+/// probability `strength` per step.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BurstNoise {
+    pub rate: f64,
+    pub duration: usize,
+    pub strength: f64,
+    pub footprint: BurstFootprint,
+}
+
+impl BurstNoise {
+    fn sample_footprint<R: Rng + ?Sized>(&self, num_qubits: usize, rng: &mut R) -> Vec<usize> {
+        match &self.footprint {
+            BurstFootprint::Radius(radius) => {
+                let centre = below(rng, num_qubits);
+                (centre.saturating_sub(*radius)..=(centre + radius).min(num_qubits - 1)).collect()
+            }
+            BurstFootprint::Neighborhoods(neighborhoods) => {
+                let chosen = &neighborhoods[below(rng, neighborhoods.len())];
+                chosen.iter().copied().filter(|&q| q < num_qubits).collect()
+            }
+        }
+    }
+}
+
+impl NoiseModel {
+    /// Names accepted by `preset`.
+    pub const PRESETS: [&'static str; 3] = ["code_capacity", "phenomenological", "circuit_level"];
+
+    /// The same depolarizing strength at every location.
+    pub fn uniform(p: f64) -> Self {
+        Self {
+            single_qubit_depolarizing: p,
+            two_qubit_depolarizing: p,
+            idle_depolarizing: p,
+            ..Self::default()
+        }
+    }
+
+    /// Data errors only; the gadget itself and its readouts are perfect.
+    pub fn code_capacity(p: f64) -> Self {
+        Self {
+            data_depolarizing: p,
+            ..Self::default()
+        }
+    }
+
+    /// Data errors plus faulty measurement outcomes, with perfect gates.
+    pub fn phenomenological(p: f64) -> Self {
+        Self {
+            data_depolarizing: p,
+            measurement_flip: p,
+            ..Self::default()
+        }
+    }
+
+    /// Every gate, idle location and measurement is faulty with strength `p`.
+    pub fn circuit_level(p: f64) -> Self {
+        Self {
+            measurement_flip: p,
+            ..Self::uniform(p)
+        }
+    }
+
+    /// One of `PRESETS` at strength `p`.
+    pub fn preset(name: &str, p: f64) -> Result<Self, String> {
+        let noise = match name {
+            "code_capacity" => Self::code_capacity(p),
+            "phenomenological" => Self::phenomenological(p),
+            "circuit_level" => Self::circuit_level(p),
+            other => {
+                return Err(format!(
+                    "Unknown noise preset '{}', expected one of {}",
+                    other,
+                    Self::PRESETS.join(", ")
+                ))
+            }
+        };
+        noise.validate()?;
+        Ok(noise)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for (name, p) in [
+            ("single_qubit_depolarizing", self.single_qubit_depolarizing),
+            ("two_qubit_depolarizing", self.two_qubit_depolarizing),
+            ("idle_depolarizing", self.idle_depolarizing),
+            ("data_depolarizing", self.data_depolarizing),
+            ("measurement_flip", self.measurement_flip),
+        ]
+        .into_iter()
+        .chain(self.bursts.iter().flat_map(|b| [("burst rate", b.rate), ("burst strength", b.strength)]))
+        .chain(self.heralded.iter().map(|h| ("heralded probability", h.probability)))
+        {
+            if !(0.0..=1.0).contains(&p) {
+                return Err(format!("{} must be a probability, got {}", name, p));
+            }
+        }
+        for burst in &self.bursts {
+            if burst.duration == 0 {
+                return Err("Burst duration must be at least one time step".to_string());
+            }
+            if let BurstFootprint::Neighborhoods(neighborhoods) = &burst.footprint {
+                if neighborhoods.is_empty() {
+                    return Err("Burst footprint needs at least one neighbourhood".to_string());
+                }
+            }
+        }
+        for segment in &self.schedule {
+            if !segment.scale.is_finite() || segment.scale < 0.0 {
+                return Err(format!("Schedule scale must be non-negative, got {}", segment.scale));
+            }
+            if let ScheduleSpan::Gates { start, end } = segment.span {
+                if start > end {
+                    return Err(format!("Schedule segment {}..{} is reversed", start, end));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `validate`, plus checks against `circuit`: its noise overrides must
+    /// be valid, schedule regions exist, gate spans fit, and scaled rates
+    /// stay probabilities.
+    pub fn validate_for(&self, circuit: &Circuit) -> Result<(), String> {
+        self.validate()?;
+        circuit.validate_noise_overrides()?;
+        let base = self
+            .single_qubit_depolarizing
+            .max(self.two_qubit_depolarizing)
+            .max(self.idle_depolarizing);
+        for segment in &self.schedule {
+            let range = segment.span.range(circuit)?;
+            if range.end > circuit.gates.len() {
+                return Err(format!(
+                    "Schedule segment {}..{} is out of bounds for a circuit with {} gates",
+                    range.start,
+                    range.end,
+                    circuit.gates.len()
+                ));
+            }
+            if base * segment.scale > 1.0 {
+                return Err(format!("Schedule scale {} pushes a rate above 1", segment.scale));
+            }
+        }
+        Ok(())
+    }
+
+    /// Factor the schedule applies to the gate-tied rates of every gate.
+    /// Segments that do not resolve in `circuit` are ignored.
+    pub fn scales(&self, circuit: &Circuit) -> Vec<f64> {
+        let mut scales = vec![1.0; circuit.gates.len()];
+        for segment in &self.schedule {
+            if let Ok(range) = segment.span.range(circuit) {
+                let end = range.end.min(scales.len());
+                let start = range.start.min(end);
+                scales[start..end].fill(segment.scale);
+            }
+        }
+        scales
+    }
+
+    /// Draws one shot of faults, ordered by gate index.
+    pub fn sample_faults<R: Rng + ?Sized>(&self, circuit: &Circuit, rng: &mut R) -> Vec<Fault> {
+        self.sample(circuit, rng).faults
+    }
+
+    /// Draws one shot of faults and heralds, ordered by gate index.
+    pub fn sample<R: Rng + ?Sized>(&self, circuit: &Circuit, rng: &mut R) -> NoiseSample {
+        let mut faults = Vec::new();
+        let mut heralds = Vec::new();
+        // (steps left, qubits, strength) of bursts in progress
+        let mut active: Vec<(usize, Vec<usize>, f64)> = Vec::new();
+        let scales = self.scales(circuit);
+        // Noise on a qubit after its final readout is dropped
+        let retired = circuit.retirement_positions();
+        for (after_gate, gate) in circuit.gates.iter().enumerate() {
+            let scale = scales[after_gate];
+            let qubits = gate.qubits();
+            if let Some(noise) = circuit.noise_override(after_gate) {
+                let draw = uniform(rng);
+                let mut total = 0.0;
+                for (paulis, p) in noise.terms(qubits.len()) {
+                    total += p;
+                    if draw < total {
+                        for (&qubit, pauli) in qubits.iter().zip(paulis) {
+                            if pauli != SinglePauli::I {
+                                faults.push(Fault { after_gate, qubit, pauli });
+                            }
+                        }
+                        break;
+                    }
+                }
+            } else {
+                match *qubits.as_slice() {
+                    [qubit] if uniform(rng) < scale * self.single_qubit_depolarizing => {
+                        let pauli = PAULIS[1 + below(rng, 3)];
+                        faults.push(Fault { after_gate, qubit, pauli });
+                    }
+                    [first, second] if uniform(rng) < scale * self.two_qubit_depolarizing => {
+                        let pair = 1 + below(rng, 15);
+                        for (qubit, pauli) in [(first, PAULIS[pair / 4]), (second, PAULIS[pair % 4])] {
+                            if pauli != SinglePauli::I {
+                                faults.push(Fault { after_gate, qubit, pauli });
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            for qubit in circuit.idle_qubits_after_gate(after_gate) {
+                if after_gate < retired[qubit] && uniform(rng) < scale * self.idle_depolarizing {
+                    let pauli = PAULIS[1 + below(rng, 3)];
+                    faults.push(Fault { after_gate, qubit, pauli });
+                }
+            }
+
+            for burst in &self.bursts {
+                if uniform(rng) < burst.rate {
+                    let qubits = burst.sample_footprint(circuit.num_qubits, rng);
+                    active.push((burst.duration, qubits, burst.strength));
+                }
+            }
+            for (steps_left, qubits, strength) in &mut active {
+                for &qubit in qubits.iter() {
+                    if after_gate < retired[qubit] && uniform(rng) < *strength {
+                        let pauli = PAULIS[1 + below(rng, 3)];
+                        faults.push(Fault { after_gate, qubit, pauli });
+                    }
+                }
+                *steps_left -= 1;
+            }
+            active.retain(|(steps_left, _, _)| *steps_left > 0);
+
+            for heralded in &self.heralded {
+                for qubit in gate.qubits() {
+                    if !heralded.qubits.is_empty() && !heralded.qubits.contains(&qubit) {
+                        continue;
+                    }
+                    if uniform(rng) < heralded.probability {
+                        heralds.push(Herald { after_gate, qubit });
+                        let pauli = PAULIS[below(rng, 4)];
+                        if pauli != SinglePauli::I {
+                            faults.push(Fault { after_gate, qubit, pauli });
+                        }
+                    }
+                }
+            }
+        }
+        NoiseSample { faults, heralds }
+    }
+
+    /// Every independent gate and idle error mechanism of `circuit` (one
+    /// Pauli at one location) with its probability; mechanisms that cannot
+    /// fire are left out. Bursts and heralded noise are not included, nor
+    /// idling of a qubit after its final readout.
+    pub(crate) fn mechanisms(&self, circuit: &Circuit) -> Vec<(Vec<Fault>, f64)> {
+        let scales = self.scales(circuit);
+        let retired = circuit.retirement_positions();
+        (0..circuit.gates.len())
+            .flat_map(|after_gate| self.scaled_mechanisms_at(circuit, after_gate, scales[after_gate], &retired))
+            .collect()
+    }
+
+    /// The mechanisms of `mechanisms` located right after gate `after_gate`.
+    pub(crate) fn mechanisms_at(&self, circuit: &Circuit, after_gate: usize) -> Vec<(Vec<Fault>, f64)> {
+        let scale = self.scales(circuit)[after_gate];
+        self.scaled_mechanisms_at(circuit, after_gate, scale, &circuit.retirement_positions())
+    }
+
+    fn scaled_mechanisms_at(
+        &self,
+        circuit: &Circuit,
+        after_gate: usize,
+        scale: f64,
+        retired: &[usize],
+    ) -> Vec<(Vec<Fault>, f64)> {
+        let mut mechanisms = Vec::new();
+        let mut add = |faults: Vec<Fault>, probability: f64| {
+            if probability > 0.0 {
+                mechanisms.push((faults, probability));
+            }
+        };
+        let single = |qubit, pauli| Fault { after_gate, qubit, pauli };
+        let qubits = circuit.gates[after_gate].qubits();
+        if let Some(noise) = circuit.noise_override(after_gate) {
+            for (paulis, p) in noise.terms(qubits.len()) {
+                let faults = qubits
+                    .iter()
+                    .zip(paulis)
+                    .filter(|&(_, pauli)| pauli != SinglePauli::I)
+                    .map(|(&qubit, pauli)| single(qubit, pauli))
+                    .collect();
+                add(faults, p);
+            }
+        } else {
+            match *qubits.as_slice() {
+                [qubit] => {
+                    for &pauli in &PAULIS[1..] {
+                        add(vec![single(qubit, pauli)], scale * self.single_qubit_depolarizing / 3.0);
+                    }
+                }
+                [first, second] => {
+                    for pair in 1..16 {
+                        let faults = [(first, PAULIS[pair / 4]), (second, PAULIS[pair % 4])]
+                            .into_iter()
+                            .filter(|&(_, pauli)| pauli != SinglePauli::I)
+                            .map(|(qubit, pauli)| single(qubit, pauli))
+                            .collect();
+                        add(faults, scale * self.two_qubit_depolarizing / 15.0);
+                    }
+                }
+                _ => {}
+            }
+        }
+        for qubit in circuit.idle_qubits_after_gate(after_gate) {
+            if after_gate >= retired[qubit] {
+                continue;
+            }
+            for &pauli in &PAULIS[1..] {
+                add(vec![single(qubit, pauli)], scale * self.idle_depolarizing / 3.0);
+            }
+        }
+        mechanisms
+    }
+
+    /// Draws the end-of-gadget data error on a block of `num_qubits`.
+    pub fn sample_data_error<R: Rng + ?Sized>(&self, num_qubits: usize, rng: &mut R) -> PauliString {
+        let mut error = PauliString::new(num_qubits);
+        if self.data_depolarizing > 0.0 {
+            for qubit in 0..num_qubits {
+                if uniform(rng) < self.data_depolarizing {
+                    error.set_pauli(qubit, PAULIS[1 + below(rng, 3)]);
+                }
+            }
+        }
+        error
+    }
+
+    /// Draws which of `count` measurement outcomes are flipped.
+    pub fn sample_measurement_flips<R: Rng + ?Sized>(&self, count: usize, rng: &mut R) -> Vec<bool> {
+        if self.measurement_flip == 0.0 {
+            return vec![false; count];
+        }
+        (0..count).map(|_| uniform(rng) < self.measurement_flip).collect()
+    }
+}
+
+/// Uniform sample from [0, 1) with 53 bits of precision.
+pub(crate) fn uniform<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Uniform sample from 0..n.
+pub(crate) fn below<R: Rng + ?Sized>(rng: &mut R, n: usize) -> usize {
+    ((rng.next_u64() as u128 * n as u128) >> 64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{Gate, GateNoise, SingleGate, TwoGate};
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_sample_faults() {
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        let mut rng = SmallRng::seed_from_u64(2213);
+
+        assert!(NoiseModel::default().sample_faults(&circuit, &mut rng).is_empty());
+
+        // Certain noise: H, CNOT (1 or 2 faults) and 2 + 1 idle faults
+        let faults = NoiseModel::uniform(1.0).sample_faults(&circuit, &mut rng);
+        assert!(faults.len() == 5 || faults.len() == 6);
+        assert!(faults.iter().all(|f| f.pauli != SinglePauli::I));
+        assert!(NoiseModel::uniform(1.5).validate().is_err());
+    }
+
+    #[test]
+    fn test_presets() {
+        let capacity = NoiseModel::preset("code_capacity", 0.1).unwrap();
+        assert_eq!(capacity, NoiseModel::code_capacity(0.1));
+        assert_eq!(capacity.single_qubit_depolarizing, 0.0);
+        assert_eq!(capacity.measurement_flip, 0.0);
+
+        let phenomenological = NoiseModel::preset("phenomenological", 0.1).unwrap();
+        assert_eq!(phenomenological.measurement_flip, 0.1);
+        assert_eq!(phenomenological.two_qubit_depolarizing, 0.0);
+
+        let circuit_level = NoiseModel::preset("circuit_level", 0.1).unwrap();
+        assert_eq!(circuit_level.two_qubit_depolarizing, 0.1);
+        assert_eq!(circuit_level.data_depolarizing, 0.0);
+
+        assert!(NoiseModel::preset("biased", 0.1).is_err());
+        assert!(NoiseModel::preset("code_capacity", 2.0).is_err());
+
+        let mut rng = SmallRng::seed_from_u64(2223);
+        let error = NoiseModel::code_capacity(1.0).sample_data_error(3, &mut rng);
+        assert!((0..3).all(|q| error.get_pauli(q) != SinglePauli::I));
+        assert_eq!(NoiseModel::phenomenological(1.0).sample_measurement_flips(2, &mut rng), vec![true, true]);
+    }
+
+    #[test]
+    fn test_schedule_scales_gate_rates() {
+        let mut circuit = Circuit::new(1);
+        for _ in 0..4 {
+            circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        }
+        circuit.add_region("hot", 2, 4).unwrap();
+        let noise = NoiseModel {
+            single_qubit_depolarizing: 0.1,
+            schedule: vec![NoiseSegment::gates(0, 3, 0.0), NoiseSegment::region("hot", 10.0)],
+            ..NoiseModel::default()
+        };
+        noise.validate_for(&circuit).unwrap();
+        assert_eq!(noise.scales(&circuit), vec![0.0, 0.0, 10.0, 10.0]);
+
+        // Certain faults after gates 2 and 3, none before
+        let mut rng = SmallRng::seed_from_u64(2261);
+        let faults = noise.sample_faults(&circuit, &mut rng);
+        assert_eq!(faults.iter().map(|f| f.after_gate).collect::<Vec<_>>(), vec![2, 3]);
+        let mechanisms = noise.mechanisms(&circuit);
+        assert_eq!(mechanisms.len(), 6);
+        assert!(mechanisms.iter().all(|(_, p)| (p - 1.0 / 3.0).abs() < 1e-12));
+
+        let too_hot = NoiseModel {
+            schedule: vec![NoiseSegment::region("hot", 20.0)],
+            ..noise.clone()
+        };
+        assert!(too_hot.validate_for(&circuit).is_err());
+        let missing = NoiseModel {
+            schedule: vec![NoiseSegment::region("cold", 1.0)],
+            ..noise
+        };
+        assert!(missing.validate().is_ok());
+        assert!(missing.validate_for(&circuit).is_err());
+    }
+
+    #[test]
+    fn test_gate_noise_override() {
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 1, target: 2 })).unwrap();
+        let bad_coupler = GateNoise::Pauli(vec![(vec![SinglePauli::X, SinglePauli::I], 1.0)]);
+        circuit.set_noise_override(1, bad_coupler).unwrap();
+
+        let noise = NoiseModel::default();
+        noise.validate_for(&circuit).unwrap();
+        let mut rng = SmallRng::seed_from_u64(2270);
+        let faults = noise.sample_faults(&circuit, &mut rng);
+        assert_eq!(faults, vec![Fault { after_gate: 1, qubit: 1, pauli: SinglePauli::X }]);
+        assert_eq!(noise.mechanisms(&circuit).len(), 1);
+
+        // The override replaces the model's rate on gate 1 only
+        circuit.set_noise_override(1, GateNoise::Depolarizing(0.0)).unwrap();
+        let noisy = NoiseModel {
+            two_qubit_depolarizing: 0.15,
+            ..NoiseModel::default()
+        };
+        assert_eq!(noisy.mechanisms(&circuit).len(), 15);
+        assert!(circuit.set_noise_override(1, GateNoise::Depolarizing(1.5)).is_err());
+        assert!(circuit.set_noise_override(2, GateNoise::Depolarizing(0.1)).is_err());
+    }
+
+    #[test]
+    fn test_burst_hits_neighbourhood_for_its_duration() {
+        let mut circuit = Circuit::new(6);
+        for _ in 0..5 {
+            circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::I }).unwrap();
+        }
+        let burst = BurstNoise {
+            rate: 1.0,
+            duration: 2,
+            strength: 1.0,
+            footprint: BurstFootprint::Neighborhoods(vec![vec![3, 4]]),
+        };
+        let noise = NoiseModel {
+            bursts: vec![burst],
+            ..NoiseModel::default()
+        };
+        noise.validate().unwrap();
+
+        let mut rng = SmallRng::seed_from_u64(2214);
+        let faults = noise.sample_faults(&circuit, &mut rng);
+        // A new burst starts every step, so qubits 3 and 4 are hit by one
+        // burst at step 0 and by two overlapping bursts afterwards
+        assert_eq!(faults.len(), 2 + 4 * 4);
+        assert!(faults.iter().all(|f| f.qubit == 3 || f.qubit == 4));
+
+        let radius = BurstNoise {
+            rate: 1.0,
+            duration: 1,
+            strength: 1.0,
+            footprint: BurstFootprint::Radius(1),
+        };
+        let qubits = radius.sample_footprint(6, &mut rng);
+        assert!((2..=3).contains(&qubits.len()));
+        assert!(qubits.windows(2).all(|w| w[1] == w[0] + 1));
+    }
+}
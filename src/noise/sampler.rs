@@ -0,0 +1,138 @@
+//! Per-shot result records.
+//!
+//! A shot runs the gadget under the noise model and records what an
+//! experiment would see: detection events (the gadget's measurement flips
+//! followed by the code syndrome of the residual, as read out by a final
+//! perfect round), logical observable flips, and any heralds raised. Data
+//! and measurement noise of the model land on the residual and on the
+//! gadget's detectors respectively; `faults` only lists circuit faults.
+
+use crate::analysis::fault_sweep::{propagate_faults, Fault};
+use crate::codes::{Gadget, StabilizerCode};
+use crate::memory::{shot_records_bytes, MemoryLimits};
+use crate::noise::model::{Herald, NoiseModel};
+use crate::physics::pauli::PauliString;
+use rand_core::Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShotRecord {
+    pub faults: Vec<Fault>,
+    pub detectors: Vec<bool>,
+    /// See `StabilizerCode::observable_flips`
+    pub observables: Vec<bool>,
+    pub heralds: Vec<Herald>,
+    /// Error left on the code block, indexed by code qubit
+    pub residual: PauliString,
+}
+
+impl ShotRecord {
+    pub fn is_heralded(&self) -> bool {
+        !self.heralds.is_empty()
+    }
+}
+
+pub fn sample_shot<R: Rng + ?Sized>(
+    gadget: &Gadget,
+    code: &StabilizerCode,
+    noise: &NoiseModel,
+    rng: &mut R,
+) -> ShotRecord {
+    let sample = noise.sample(&gadget.circuit, rng);
+    let error = propagate_faults(&gadget.circuit, &sample.faults);
+    let residual = gadget
+        .data_error(&error)
+        .multiply(&noise.sample_data_error(code.num_qubits, rng));
+
+    let readout_flips = noise.sample_measurement_flips(gadget.measurements().len(), rng);
+    let mut detectors: Vec<bool> = gadget
+        .measurement_flips(&error)
+        .into_iter()
+        .zip(readout_flips)
+        .map(|(flip, readout)| flip ^ readout)
+        .collect();
+    detectors.extend(code.syndrome(&residual));
+    ShotRecord {
+        faults: sample.faults,
+        detectors,
+        observables: code.observable_flips(&residual),
+        heralds: sample.heralds,
+        residual,
+    }
+}
+
+pub fn sample_shots<R: Rng + ?Sized>(
+    gadget: &Gadget,
+    code: &StabilizerCode,
+    noise: &NoiseModel,
+    shots: usize,
+    rng: &mut R,
+) -> Result<Vec<ShotRecord>, String> {
+    sample_shots_within(gadget, code, noise, shots, &MemoryLimits::default(), rng)
+}
+
+/// `sample_shots`, failing before sampling when the records would exceed
+/// `limits`.
+pub fn sample_shots_within<R: Rng + ?Sized>(
+    gadget: &Gadget,
+    code: &StabilizerCode,
+    noise: &NoiseModel,
+    shots: usize,
+    limits: &MemoryLimits,
+    rng: &mut R,
+) -> Result<Vec<ShotRecord>, String> {
+    noise.validate_for(&gadget.circuit)?;
+    if gadget.data_qubits.len() != code.num_qubits {
+        return Err(format!(
+            "Gadget has {} data qubits but code '{}' has {}",
+            gadget.data_qubits.len(),
+            code.name,
+            code.num_qubits
+        ));
+    }
+    let bytes = shot_records_bytes(
+        shots,
+        gadget.measurements().len() + code.stabilizers.len(),
+        2 * code.num_logical_qubits(),
+        code.num_qubits,
+    );
+    limits.check(&format!("{} shot records", shots), bytes)?;
+    Ok((0..shots).map(|_| sample_shot(gadget, code, noise, rng)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::noise::model::HeraldedNoise;
+    use crate::physics::circuit::{Circuit, Gate, SingleGate};
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_heralds_are_recorded() {
+        let mut circuit = Circuit::new(3);
+        for qubit in 0..3 {
+            circuit.add_gate(Gate::Single { qubit, gate: SingleGate::I }).unwrap();
+        }
+        let gadget = Gadget::new(circuit, vec![0, 1, 2]).unwrap();
+        let code = StabilizerCode::repetition(3);
+        let noise = NoiseModel {
+            heralded: vec![HeraldedNoise {
+                probability: 0.5,
+                qubits: vec![2],
+            }],
+            ..NoiseModel::default()
+        };
+
+        let mut rng = SmallRng::seed_from_u64(2215);
+        let shots = sample_shots(&gadget, &code, &noise, 200, &mut rng).unwrap();
+        let heralded = shots.iter().filter(|shot| shot.is_heralded()).count();
+        assert!(heralded > 50 && heralded < 150);
+        for shot in &shots {
+            assert_eq!(shot.detectors.len(), 2);
+            assert!(shot.heralds.iter().all(|h| *h == Herald { after_gate: 2, qubit: 2 }));
+            // Errors only ever come with a herald
+            assert!(shot.is_heralded() || shot.faults.is_empty());
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Approximate error analysis of circuits with a few non-Clifford rotations.
+//!
+//! Each symbolic rotation left by `import_qasm_with_report` is replaced, shot
+//! by shot, by a Clifford drawn from a decomposition of its channel:
+//!
+//! - `QuasiProbability`: Rz(θ) = a·I + b·Z + c·S (S† for θ < 0) as channels,
+//!   with a = (1 + cos θ − |sin θ|)/2, b = (1 − cos θ − |sin θ|)/2 and
+//!   c = |sin θ|. Terms are drawn with probability |q|/γ, γ = Σ|q|, and each
+//!   shot is weighted by the product of sign(q)·γ; the estimate is unbiased
+//!   but its variance grows as γ² per rotation.
+//! - `PauliTwirl`: the twirled channel cos²(θ/2)·I + sin²(θ/2)·Z. It is a
+//!   proper mixture, but it ignores the coherent part of the rotation.
+//!
+//! `rx` rotations use the same terms conjugated by H, so their S terms are
+//! inserted as `h s h`. Every sampled instance is then analysed like
+//! `run_monte_carlo`: faults are drawn on the instance, propagated, decoded
+//! with the lookup table and checked for a logical flip.
+
+use crate::analysis::fault_sweep::propagate_faults;
+use crate::codes::{Gadget, StabilizerCode};
+use crate::io::qasm::{NonCliffordContent, SymbolicRotation};
+use crate::noise::model::{uniform, NoiseModel};
+use crate::physics::circuit::{Circuit, Gate, Measure, SingleGate};
+use rand_core::Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CliffordApproximation {
+    QuasiProbability,
+    PauliTwirl,
+}
+
+/// One Clifford term of a rotation's decomposition.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CliffordTerm {
+    /// Phase gate the term applies (`I`, `Z`, `S` or `Sdg`) before the
+    /// rotation axis is taken into account
+    pub gate: SingleGate,
+    /// Quasi-probability; negative terms flip the sign of a shot's weight
+    pub weight: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StochasticCliffordResult {
+    pub shots: usize,
+    /// Σ of signed shot weights over failing shots
+    pub weighted_failures: f64,
+    /// Σ of squared shot weights over failing shots
+    pub weighted_failures_squared: f64,
+    /// Product of the rotations' γ factors (1 for `PauliTwirl`)
+    pub sampling_overhead: f64,
+}
+
+impl StochasticCliffordResult {
+    pub fn logical_error_rate(&self) -> f64 {
+        if self.shots == 0 {
+            0.0
+        } else {
+            self.weighted_failures / self.shots as f64
+        }
+    }
+
+    /// Standard error of `logical_error_rate`.
+    pub fn standard_error(&self) -> f64 {
+        if self.shots < 2 {
+            return 0.0;
+        }
+        let n = self.shots as f64;
+        let mean = self.logical_error_rate();
+        ((self.weighted_failures_squared / n - mean * mean).max(0.0) / (n - 1.0)).sqrt()
+    }
+}
+
+/// Clifford terms of a rotation by `radians` about Z.
+pub fn decompose_rotation(radians: f64, approximation: CliffordApproximation) -> Vec<CliffordTerm> {
+    let term = |gate, weight| CliffordTerm { gate, weight };
+    match approximation {
+        CliffordApproximation::QuasiProbability => {
+            let (sin, cos) = radians.sin_cos();
+            let phase = if sin >= 0.0 { SingleGate::S } else { SingleGate::Sdg };
+            vec![
+                term(SingleGate::I, (1.0 + cos - sin.abs()) / 2.0),
+                term(SingleGate::Z, (1.0 - cos - sin.abs()) / 2.0),
+                term(phase, sin.abs()),
+            ]
+        }
+        CliffordApproximation::PauliTwirl => {
+            let sin = (radians / 2.0).sin();
+            vec![term(SingleGate::I, 1.0 - sin * sin), term(SingleGate::Z, sin * sin)]
+        }
+    }
+}
+
+pub fn run_stochastic_clifford<R: Rng + ?Sized>(
+    gadget: &Gadget,
+    code: &StabilizerCode,
+    content: &NonCliffordContent,
+    noise: &NoiseModel,
+    approximation: CliffordApproximation,
+    shots: usize,
+    rng: &mut R,
+) -> Result<StochasticCliffordResult, String> {
+    noise.validate_for(&gadget.circuit)?;
+    if gadget.data_qubits.len() != code.num_qubits {
+        return Err(format!(
+            "Gadget has {} data qubits but code '{}' has {}",
+            gadget.data_qubits.len(),
+            code.name,
+            code.num_qubits
+        ));
+    }
+    for rotation in &content.rotations {
+        let placeholder = Gate::Single { qubit: rotation.qubit, gate: SingleGate::I };
+        if gadget.circuit.gates.get(rotation.gate) != Some(&placeholder) {
+            return Err(format!(
+                "Gate {} is not the identity placeholder of {}({}) on qubit {}",
+                rotation.gate, rotation.axis, rotation.angle, rotation.qubit
+            ));
+        }
+    }
+
+    let decompositions: Vec<Vec<CliffordTerm>> = content
+        .rotations
+        .iter()
+        .map(|rotation| decompose_rotation(rotation.radians, approximation))
+        .collect();
+    let gammas: Vec<f64> = decompositions
+        .iter()
+        .map(|terms| terms.iter().map(|t| t.weight.abs()).sum())
+        .collect();
+    let table = code.lookup_table();
+    let mut result = StochasticCliffordResult {
+        shots,
+        weighted_failures: 0.0,
+        weighted_failures_squared: 0.0,
+        sampling_overhead: gammas.iter().product(),
+    };
+
+    for _ in 0..shots {
+        let mut weight = 1.0;
+        let mut chosen = Vec::with_capacity(decompositions.len());
+        for (terms, gamma) in decompositions.iter().zip(&gammas) {
+            let mut draw = uniform(rng) * gamma;
+            let term = terms
+                .iter()
+                .find(|t| {
+                    draw -= t.weight.abs();
+                    draw < 0.0
+                })
+                .unwrap_or(&terms[terms.len() - 1]);
+            weight *= term.weight.signum() * gamma;
+            chosen.push(term.gate);
+        }
+        let instance = Gadget::new(
+            clifford_instance(&gadget.circuit, &content.rotations, &chosen)?,
+            gadget.data_qubits.clone(),
+        )?;
+
+        let faults = noise.sample_faults(&instance.circuit, rng);
+        let data_error = noise.sample_data_error(code.num_qubits, rng);
+        let residual = instance
+            .data_error(&propagate_faults(&instance.circuit, &faults))
+            .multiply(&data_error);
+        let net = residual.multiply(&table[&code.syndrome(&residual)]);
+        if code.is_logical_error(&net) {
+            result.weighted_failures += weight;
+            result.weighted_failures_squared += weight * weight;
+        }
+    }
+    Ok(result)
+}
+
+/// The circuit with each placeholder replaced by its chosen term. Readouts
+/// keep their place relative to the surrounding gates.
+fn clifford_instance(
+    circuit: &Circuit,
+    rotations: &[SymbolicRotation],
+    chosen: &[SingleGate],
+) -> Result<Circuit, String> {
+    let mut replacements = vec![None; circuit.gates.len()];
+    for (rotation, &gate) in rotations.iter().zip(chosen) {
+        replacements[rotation.gate] = Some((rotation, gate));
+    }
+    let mut instance = Circuit::new(circuit.num_qubits);
+    let mut new_position = Vec::with_capacity(circuit.gates.len() + 1);
+    for (gate, replacement) in circuit.gates.iter().zip(replacements) {
+        new_position.push(instance.gates.len());
+        let Some((rotation, term)) = replacement else {
+            instance.add_gate(gate.clone())?;
+            continue;
+        };
+        let qubit = rotation.qubit;
+        let gates = match (rotation.axis.as_str(), term) {
+            ("rx", SingleGate::Z) => vec![SingleGate::X],
+            ("rx", SingleGate::S) | ("rx", SingleGate::Sdg) => vec![SingleGate::H, term, SingleGate::H],
+            _ => vec![term],
+        };
+        for gate in gates {
+            instance.add_gate(Gate::Single { qubit, gate })?;
+        }
+    }
+    new_position.push(instance.gates.len());
+    instance.measurements = circuit
+        .measurements
+        .iter()
+        .map(|m| Measure { position: new_position[m.position], ..*m })
+        .collect();
+    Ok(instance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::options::ImportOptions;
+    use crate::io::qasm::import_qasm_with_report;
+    use crate::noise::monte_carlo::run_monte_carlo;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_decomposition_weights() {
+        for theta in [0.0, 0.3, -1.1, FRAC_PI_2, 2.5] {
+            for approximation in [CliffordApproximation::QuasiProbability, CliffordApproximation::PauliTwirl] {
+                let terms = decompose_rotation(theta, approximation);
+                let total: f64 = terms.iter().map(|t| t.weight).sum();
+                assert!((total - 1.0).abs() < 1e-12);
+            }
+        }
+        // Clifford angles decompose into a single term
+        let s = decompose_rotation(FRAC_PI_2, CliffordApproximation::QuasiProbability);
+        assert!((s[2].weight - 1.0).abs() < 1e-12 && s[0].weight.abs() < 1e-12 && s[1].weight.abs() < 1e-12);
+        // Small angles need negative weights
+        let t = decompose_rotation(0.3, CliffordApproximation::QuasiProbability);
+        assert!(t[1].weight < 0.0);
+    }
+
+    #[test]
+    fn test_clifford_angle_matches_exact_circuit() {
+        // Repetition memory with a phase rotation on qubit 0: at θ = π/2 the
+        // only term is S, so the estimate must match the explicit circuit
+        let qasm = "OPENQASM 2.0;\nqreg q[3];\nid q[0];\nrz(pi/3) q[0];\nid q[0];\n";
+        let (circuit, mut content) = import_qasm_with_report(qasm, &ImportOptions::default()).unwrap();
+        content.rotations[0].radians = FRAC_PI_2;
+        let gadget = Gadget::new(circuit.clone(), vec![0, 1, 2]).unwrap();
+        let code = StabilizerCode::repetition(3);
+        let noise = NoiseModel {
+            single_qubit_depolarizing: 0.2,
+            ..NoiseModel::default()
+        };
+
+        let mut rng = SmallRng::seed_from_u64(2234);
+        let approx = run_stochastic_clifford(
+            &gadget,
+            &code,
+            &content,
+            &noise,
+            CliffordApproximation::QuasiProbability,
+            4000,
+            &mut rng,
+        )
+        .unwrap();
+        assert!((approx.sampling_overhead - 1.0).abs() < 1e-12);
+
+        let mut exact_circuit = circuit;
+        exact_circuit.gates[1] = Gate::Single { qubit: 0, gate: SingleGate::S };
+        let exact_gadget = Gadget::new(exact_circuit, vec![0, 1, 2]).unwrap();
+        let exact = run_monte_carlo(&exact_gadget, &code, &noise, 4000, &mut rng).unwrap();
+        assert!((approx.logical_error_rate() - exact.logical_error_rate()).abs() < 0.04);
+
+        let mut bad = content.clone();
+        bad.rotations[0].gate = 0;
+        bad.rotations[0].qubit = 1;
+        assert!(run_stochastic_clifford(&gadget, &code, &bad, &noise, CliffordApproximation::PauliTwirl, 1, &mut rng).is_err());
+    }
+}
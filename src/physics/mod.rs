@@ -1,10 +0,0 @@
-pub mod pauli;
-pub mod circuit;
-pub mod propagation;
-pub mod simulator;
-
-pub use pauli::*;
-pub use circuit::*;
-pub use propagation::*;
-pub use simulator::*;
-
@@ -0,0 +1,63 @@
+//! Read-only handles for sharing analysis inputs across threads.
+//!
+//! Circuits, tableaux, codes, noise models, gadgets and detector error
+//! models are plain data: no interior mutability, and every analysis takes
+//! them by shared reference. `Shareable` asserts at compile time that they
+//! are `Send + Sync`, and `share` wraps one in an `Arc`, whose clones are a
+//! pointer copy, so a server or a rayon pool can hand the same model to
+//! every worker without cloning or locking it.
+
+#[cfg(feature = "analysis")]
+use crate::codes::{Gadget, StabilizerCode};
+#[cfg(feature = "analysis")]
+use crate::dem::model::DetectorErrorModel;
+#[cfg(feature = "analysis")]
+use crate::noise::model::NoiseModel;
+use crate::physics::circuit::Circuit;
+use crate::physics::tableau::Tableau;
+use std::sync::Arc;
+
+/// A cheap, immutable, thread-safe handle.
+pub type Shared<T> = Arc<T>;
+
+/// Implemented only for types that are safe to share read-only across
+/// threads; adding a non-`Sync` field to one of them fails to compile.
+pub trait Shareable: Send + Sync + Sized + 'static {
+    fn share(self) -> Shared<Self> {
+        Arc::new(self)
+    }
+}
+
+impl Shareable for Circuit {}
+impl Shareable for Tableau {}
+
+#[cfg(feature = "analysis")]
+impl Shareable for StabilizerCode {}
+#[cfg(feature = "analysis")]
+impl Shareable for Gadget {}
+#[cfg(feature = "analysis")]
+impl Shareable for NoiseModel {}
+#[cfg(feature = "analysis")]
+impl Shareable for DetectorErrorModel {}
+
+#[cfg(all(test, feature = "analysis"))]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_shared_across_threads() {
+        let code = StabilizerCode::steane().share();
+        let distances: Vec<Option<usize>> = (0..4)
+            .map(|_| {
+                let code = Shared::clone(&code);
+                thread::spawn(move || code.distance())
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .collect();
+        assert_eq!(distances, vec![Some(3); 4]);
+        assert_eq!(Arc::strong_count(&code), 1);
+    }
+}
@@ -0,0 +1,166 @@
+//! Peephole reduction of two-qubit gates.
+//!
+//! CNOT, CZ and SWAP are self-inverse, so two identical instances cancel
+//! whenever every gate between them commutes with the pair. Commutation
+//! follows `Gate::commutes_with` (e.g. CNOTs sharing a control or a target,
+//! diagonal gates on a control, X on a target). Barriers stop commutation,
+//! as they do for `Circuit::schedule`, and so does a measurement of either
+//! qubit of the pair.
+
+use crate::physics::circuit::{Circuit, Gate, TwoGate};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReductionReport {
+    pub two_qubit_gates_before: usize,
+    pub two_qubit_gates_after: usize,
+    pub cancelled_pairs: usize,
+}
+
+/// Cancels pairs of identical two-qubit gates that can be commuted next to
+/// each other. Returns the reduced circuit and before/after gate counts.
+pub fn reduce_two_qubit_gates(circuit: &Circuit) -> (Circuit, ReductionReport) {
+    let gates = &circuit.gates;
+    let mut removed = vec![false; gates.len()];
+    let mut cancelled_pairs = 0;
+
+    // Each cancellation can expose a new adjacent pair, so iterate to a fixed point
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in 0..gates.len() {
+            if removed[i] || !matches!(gates[i], Gate::Two(_)) {
+                continue;
+            }
+            for j in i + 1..gates.len() {
+                let qubits = gates[i].qubits();
+                if circuit.has_barrier_before(j)
+                    || circuit.measurements_at(j).iter().any(|m| qubits.contains(&m.qubit))
+                {
+                    break;
+                }
+                if removed[j] {
+                    continue;
+                }
+                if same_operation(&gates[i], &gates[j]) {
+                    removed[i] = true;
+                    removed[j] = true;
+                    cancelled_pairs += 1;
+                    changed = true;
+                    break;
+                }
+                if !gates[i].commutes_with(&gates[j]) {
+                    break;
+                }
+            }
+        }
+    }
+
+    let reduced = circuit.without_gates(&removed);
+
+    let report = ReductionReport {
+        two_qubit_gates_before: circuit.two_qubit_gate_count(),
+        two_qubit_gates_after: reduced.two_qubit_gate_count(),
+        cancelled_pairs,
+    };
+    (reduced, report)
+}
+
+/// CZ and SWAP are symmetric in their operands; CNOT is not.
+fn same_operation(a: &Gate, b: &Gate) -> bool {
+    match (a, b) {
+        (Gate::Two(TwoGate::CZ { control: c1, target: t1 }), Gate::Two(TwoGate::CZ { control: c2, target: t2 })) => {
+            (c1, t1) == (c2, t2) || (c1, t1) == (t2, c2)
+        }
+        (Gate::Two(TwoGate::SWAP { qubit1: a1, qubit2: a2 }), Gate::Two(TwoGate::SWAP { qubit1: b1, qubit2: b2 })) => {
+            (a1, a2) == (b1, b2) || (a1, a2) == (b2, b1)
+        }
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{MeasurementBasis, SingleGate};
+
+    fn cnot(control: usize, target: usize) -> Gate {
+        Gate::Two(TwoGate::CNOT { control, target })
+    }
+
+    #[test]
+    fn test_cancel_through_commuting_gates() {
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(cnot(0, 1)).unwrap();
+        circuit.add_gate(cnot(0, 2)).unwrap();
+        circuit
+            .add_gate(Gate::Single {
+                qubit: 0,
+                gate: SingleGate::S,
+            })
+            .unwrap();
+        circuit.add_gate(cnot(0, 1)).unwrap();
+
+        let (reduced, report) = reduce_two_qubit_gates(&circuit);
+        assert_eq!(report.two_qubit_gates_before, 3);
+        assert_eq!(report.two_qubit_gates_after, 1);
+        assert_eq!(report.cancelled_pairs, 1);
+        assert_eq!(reduced.gates.len(), 2);
+        assert_eq!(reduced.gates[0], cnot(0, 2));
+    }
+
+    #[test]
+    fn test_blocked_by_non_commuting_gate() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(cnot(0, 1)).unwrap();
+        circuit
+            .add_gate(Gate::Single {
+                qubit: 0,
+                gate: SingleGate::H,
+            })
+            .unwrap();
+        circuit.add_gate(cnot(0, 1)).unwrap();
+
+        let (reduced, report) = reduce_two_qubit_gates(&circuit);
+        assert_eq!(reduced, circuit);
+        assert_eq!(report.cancelled_pairs, 0);
+    }
+
+    #[test]
+    fn test_blocked_by_barrier() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(cnot(0, 1)).unwrap();
+        circuit.add_barrier();
+        circuit.add_gate(cnot(0, 1)).unwrap();
+
+        let (reduced, report) = reduce_two_qubit_gates(&circuit);
+        assert_eq!(reduced, circuit);
+        assert_eq!(report.cancelled_pairs, 0);
+    }
+
+    #[test]
+    fn test_blocked_by_measurement() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(cnot(0, 1)).unwrap();
+        circuit.add_measurement(1, MeasurementBasis::Z).unwrap();
+        circuit.add_gate(cnot(0, 1)).unwrap();
+
+        let (reduced, report) = reduce_two_qubit_gates(&circuit);
+        assert_eq!(reduced, circuit);
+        assert_eq!(report.cancelled_pairs, 0);
+    }
+
+    #[test]
+    fn test_nested_cancellation() {
+        // CNOT(0,1) CZ(1,2) CZ(2,1) CNOT(0,1): the inner CZ pair cancels first
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(cnot(0, 1)).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CZ { control: 1, target: 2 })).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CZ { control: 2, target: 1 })).unwrap();
+        circuit.add_gate(cnot(0, 1)).unwrap();
+
+        let (reduced, report) = reduce_two_qubit_gates(&circuit);
+        assert!(reduced.gates.is_empty());
+        assert_eq!(report.cancelled_pairs, 2);
+    }
+}
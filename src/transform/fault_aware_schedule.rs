@@ -0,0 +1,198 @@
+//! Experimental fault-aware gate ordering.
+//!
+//! Within each region (e.g. one syndrome-extraction round), consecutive
+//! gates that pairwise commute may be applied in any order without changing
+//! the circuit. The order does change how a fault in the middle of the
+//! block spreads, so every permutation of each block is scored with a
+//! single-fault sweep and the one minimising the worst-case output weight
+//! on the data qubits is kept. Blocks end at moment, barrier and
+//! measurement boundaries, and noise overrides follow their gates.
+
+use crate::analysis::fault_sweep::{restricted_weight, single_fault_sweep};
+use crate::physics::circuit::{Circuit, Gate};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Blocks larger than this are left in their original order (8! orderings).
+pub const MAX_BLOCK_SIZE: usize = 8;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionSchedule {
+    pub name: String,
+    pub worst_weight_before: usize,
+    pub worst_weight_after: usize,
+    pub reordered: bool,
+}
+
+/// Reorders commuting gates inside every region to minimise the worst-case
+/// weight (restricted to `data_qubits`) of any single fault's output.
+pub fn schedule_regions_for_faults(circuit: &Circuit, data_qubits: &[usize]) -> (Circuit, Vec<RegionSchedule>) {
+    let mut current = circuit.clone();
+    let mut reports = Vec::new();
+
+    for region in &circuit.regions {
+        let worst_weight_before = score(&current, data_qubits).0;
+
+        let blocks = commuting_blocks(&current.gates[region.start..region.end])
+            .into_iter()
+            .flat_map(|block| reorderable_parts(&current, (region.start + block.start)..(region.start + block.end)))
+            .collect::<Vec<_>>();
+        for block in blocks {
+            if block.len() < 2 || block.len() > MAX_BLOCK_SIZE {
+                continue;
+            }
+
+            let original = current.clone();
+            let reordered = |order: &[usize]| {
+                original
+                    .with_reordered_gates(block.start, order)
+                    .expect("block lies in the circuit")
+            };
+            let mut best_score = score(&current, data_qubits);
+            let mut best_order: Vec<usize> = (0..block.len()).collect();
+
+            let mut order = best_order.clone();
+            while next_permutation(&mut order) {
+                let candidate = score(&reordered(&order), data_qubits);
+                if candidate < best_score {
+                    best_score = candidate;
+                    best_order = order.clone();
+                }
+            }
+            current = reordered(&best_order);
+        }
+
+        let worst_weight_after = score(&current, data_qubits).0;
+        reports.push(RegionSchedule {
+            name: region.name.clone(),
+            worst_weight_before,
+            worst_weight_after,
+            reordered: current.gates[region.start..region.end] != circuit.gates[region.start..region.end],
+        });
+    }
+
+    (current, reports)
+}
+
+/// (worst-case data weight, number of faults reaching it); lower is better.
+fn score(circuit: &Circuit, data_qubits: &[usize]) -> (usize, usize) {
+    let weights: Vec<usize> = single_fault_sweep(circuit)
+        .iter()
+        .map(|outcome| restricted_weight(&outcome.output, data_qubits))
+        .collect();
+    let worst = weights.iter().copied().max().unwrap_or(0);
+    (worst, weights.iter().filter(|&&w| w == worst).count())
+}
+
+/// Splits gates into maximal runs of consecutive, pairwise commuting gates.
+fn commuting_blocks(gates: &[Gate]) -> Vec<Range<usize>> {
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    for end in 0..gates.len() {
+        if !gates[start..end].iter().all(|g| g.commutes_with(&gates[end])) {
+            blocks.push(start..end);
+            start = end;
+        }
+    }
+    if start < gates.len() {
+        blocks.push(start..gates.len());
+    }
+    blocks
+}
+
+/// Splits `block` where `Circuit::can_reorder` forbids moving gates across.
+fn reorderable_parts(circuit: &Circuit, block: Range<usize>) -> Vec<Range<usize>> {
+    let mut parts = Vec::new();
+    let mut start = block.start;
+    for position in block.start + 1..block.end {
+        if !circuit.can_reorder(position - 1..position + 1) {
+            parts.push(start..position);
+            start = position;
+        }
+    }
+    parts.push(start..block.end);
+    parts
+}
+
+/// Advances to the next lexicographic permutation; false after the last.
+fn next_permutation(order: &mut [usize]) -> bool {
+    if order.len() < 2 {
+        return false;
+    }
+    let mut i = order.len() - 1;
+    while i > 0 && order[i - 1] >= order[i] {
+        i -= 1;
+    }
+    if i == 0 {
+        return false;
+    }
+    let mut j = order.len() - 1;
+    while order[j] <= order[i - 1] {
+        j -= 1;
+    }
+    order.swap(i - 1, j);
+    order[i..].reverse();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::equivalence::circuits_equivalent;
+    use crate::physics::circuit::{GateNoise, Measure, MeasurementBasis, TwoGate};
+
+    #[test]
+    fn test_next_permutation() {
+        let mut order = vec![0, 1, 2];
+        let mut count = 1;
+        while next_permutation(&mut order) {
+            count += 1;
+        }
+        assert_eq!(count, 6);
+        assert_eq!(order, vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn test_commuting_blocks() {
+        let cnot = |control, target| Gate::Two(TwoGate::CNOT { control, target });
+        let gates = vec![cnot(0, 3), cnot(1, 3), cnot(3, 2), cnot(0, 2)];
+        assert_eq!(commuting_blocks(&gates), vec![0..2, 2..4]);
+    }
+
+    #[test]
+    fn test_blocks_end_at_readouts() {
+        let mut circuit = Circuit::new(5);
+        for data in [2, 3, 0, 1] {
+            circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 4, target: data })).unwrap();
+        }
+        circuit.add_region("round", 0, 4).unwrap();
+        circuit.measurements = vec![Measure { position: 2, qubit: 2, basis: MeasurementBasis::Z }];
+        assert_eq!(reorderable_parts(&circuit, 0..4), vec![0..2, 2..4]);
+        circuit.measurements.clear();
+        circuit.moments = vec![0, 1, 2, 3];
+        assert_eq!(reorderable_parts(&circuit, 0..4).len(), 4);
+    }
+
+    #[test]
+    fn test_reordering_reduces_hook_error_weight() {
+        // Weight-4 X check with ancilla 4 as CNOT control. An ancilla fault
+        // spreads to every data qubit coupled after it, so the qubits we
+        // care about (0 and 1) should be coupled first.
+        let mut circuit = Circuit::new(5);
+        for data in [2, 3, 0, 1] {
+            circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 4, target: data })).unwrap();
+        }
+        circuit.add_region("round", 0, 4).unwrap();
+        circuit.set_noise_override(2, GateNoise::Depolarizing(0.1)).unwrap();
+
+        let (scheduled, reports) = schedule_regions_for_faults(&circuit, &[0, 1]);
+        assert!(circuits_equivalent(&circuit, &scheduled));
+        assert_eq!(reports[0].worst_weight_before, 2);
+        assert_eq!(reports[0].worst_weight_after, 1);
+        assert!(reports[0].reordered);
+        let moved = scheduled.gates.iter().position(|g| *g == circuit.gates[2]).unwrap();
+        assert_ne!(moved, 2);
+        assert_eq!(scheduled.noise_overrides.len(), 1);
+        assert!(scheduled.noise_override(moved).is_some());
+    }
+}
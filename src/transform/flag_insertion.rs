@@ -0,0 +1,197 @@
+//! Flag-qubit insertion suggestions.
+//!
+//! A single fault on a syndrome ancilla can spread to several data qubits
+//! (a "hook" error). For every ancilla responsible for such a fault, a fresh
+//! flag qubit is coupled to it right after its first and right before its
+//! last data interaction, so that a fault in between leaves a detectable
+//! error on the flag (Chao-Reichardt style flags).
+//!
+//! Faults striking the ancilla after its first data interaction but before
+//! the first flag coupling can hit both couplings and cancel on the flag.
+//! They spread to all but one coupled data qubit, which for a measured
+//! stabilizer is a single-qubit error in disguise; they are still listed in
+//! `FlagReport::undetected_after` so the caller can decide.
+//!
+//! State preparation and measurement of the flag are not part of the
+//! circuit; `FlagBasis` records the basis the flag must be measured in.
+
+use crate::analysis::fault_sweep::{restricted_weight, single_fault_sweep, Fault};
+use crate::physics::circuit::{Circuit, Gate, TwoGate};
+use crate::physics::pauli::PauliString;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FlagBasis {
+    /// Flag prepared in |0> and measured in Z: detects X errors
+    Z,
+    /// Flag prepared in |+> and measured in X: detects Z errors
+    X,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlagInsertion {
+    pub ancilla: usize,
+    pub flag_qubit: usize,
+    pub basis: FlagBasis,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlagReport {
+    /// Faults of the input circuit whose data weight exceeds the limit
+    pub dangerous_faults: Vec<Fault>,
+    pub insertions: Vec<FlagInsertion>,
+    /// Faults of the flagged circuit that still exceed the limit without
+    /// tripping any flag
+    pub undetected_after: Vec<Fault>,
+}
+
+/// Proposes flag qubits for every ancilla whose single faults leave more
+/// than `max_weight` errors on `data_qubits`. Flags are appended after the
+/// existing qubits; the modified circuit is returned with the report.
+pub fn suggest_flags(circuit: &Circuit, data_qubits: &[usize], max_weight: usize) -> (Circuit, FlagReport) {
+    let dangerous_faults: Vec<Fault> = single_fault_sweep(circuit)
+        .into_iter()
+        .filter(|outcome| restricted_weight(&outcome.output, data_qubits) > max_weight)
+        .map(|outcome| outcome.fault)
+        .collect();
+
+    let ancillas: BTreeSet<usize> = dangerous_faults
+        .iter()
+        .map(|fault| fault.qubit)
+        .filter(|qubit| !data_qubits.contains(qubit))
+        .collect();
+
+    let mut flagged = circuit.clone();
+    let mut insertions = Vec::new();
+    let mut new_gates = Vec::new();
+
+    for ancilla in ancillas {
+        let interactions: Vec<usize> = circuit
+            .gates
+            .iter()
+            .enumerate()
+            .filter(|(_, gate)| {
+                let qubits = gate.qubits();
+                qubits.len() == 2 && qubits.contains(&ancilla) && qubits.iter().any(|q| data_qubits.contains(q))
+            })
+            .map(|(index, _)| index)
+            .collect();
+        if interactions.len() < 2 {
+            continue;
+        }
+
+        // An ancilla used as CNOT target collects (and spreads back) Z errors
+        let as_target = interactions
+            .iter()
+            .filter(|&&i| matches!(circuit.gates[i], Gate::Two(TwoGate::CNOT { target, .. }) if target == ancilla))
+            .count();
+        let basis = if 2 * as_target > interactions.len() {
+            FlagBasis::X
+        } else {
+            FlagBasis::Z
+        };
+
+        let flag_qubit = flagged.num_qubits;
+        flagged.num_qubits += 1;
+        let coupling = match basis {
+            FlagBasis::Z => Gate::Two(TwoGate::CNOT {
+                control: ancilla,
+                target: flag_qubit,
+            }),
+            FlagBasis::X => Gate::Two(TwoGate::CNOT {
+                control: flag_qubit,
+                target: ancilla,
+            }),
+        };
+        new_gates.push((interactions[0] + 1, coupling.clone()));
+        new_gates.push((*interactions.last().unwrap(), coupling));
+        insertions.push(FlagInsertion {
+            ancilla,
+            flag_qubit,
+            basis,
+        });
+    }
+
+    let flagged = flagged.with_insertions(new_gates);
+    let undetected_after = single_fault_sweep(&flagged)
+        .into_iter()
+        .filter(|outcome| restricted_weight(&outcome.output, data_qubits) > max_weight)
+        .filter(|outcome| !insertions.iter().any(|flag| trips(&outcome.output, flag)))
+        .map(|outcome| outcome.fault)
+        .collect();
+
+    let report = FlagReport {
+        dangerous_faults,
+        insertions,
+        undetected_after,
+    };
+    (flagged, report)
+}
+
+fn trips(error: &PauliString, flag: &FlagInsertion) -> bool {
+    match flag.basis {
+        FlagBasis::Z => error.x_bits()[flag.flag_qubit],
+        FlagBasis::X => error.z_bits()[flag.flag_qubit],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weight_four_check(ancilla_is_control: bool) -> Circuit {
+        let mut circuit = Circuit::new(5);
+        for data in 0..4 {
+            let gate = if ancilla_is_control {
+                TwoGate::CNOT { control: 4, target: data }
+            } else {
+                TwoGate::CNOT { control: data, target: 4 }
+            };
+            circuit.add_gate(Gate::Two(gate)).unwrap();
+        }
+        circuit
+    }
+
+    #[test]
+    fn test_x_check_gets_z_basis_flag() {
+        let circuit = weight_four_check(true);
+        let (flagged, report) = suggest_flags(&circuit, &[0, 1, 2, 3], 1);
+
+        assert!(!report.dangerous_faults.is_empty());
+        assert_eq!(
+            report.insertions,
+            vec![FlagInsertion {
+                ancilla: 4,
+                flag_qubit: 5,
+                basis: FlagBasis::Z
+            }]
+        );
+        assert!(report.undetected_after.is_empty());
+        assert_eq!(flagged.num_qubits, 6);
+        assert_eq!(flagged.gates.len(), 6);
+        assert_eq!(flagged.gates[1], Gate::Two(TwoGate::CNOT { control: 4, target: 5 }));
+        assert_eq!(flagged.gates[4], Gate::Two(TwoGate::CNOT { control: 4, target: 5 }));
+    }
+
+    #[test]
+    fn test_z_check_gets_x_basis_flag() {
+        let circuit = weight_four_check(false);
+        let (_, report) = suggest_flags(&circuit, &[0, 1, 2, 3], 1);
+        assert_eq!(report.insertions[0].basis, FlagBasis::X);
+        // Z faults right after the first interaction toggle the flag twice
+        assert!(!report.undetected_after.is_empty());
+        assert!(report.undetected_after.iter().all(|fault| fault.after_gate == 0 && fault.qubit == 4));
+    }
+
+    #[test]
+    fn test_safe_circuit_is_unchanged() {
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 2 })).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 1, target: 2 })).unwrap();
+
+        let (flagged, report) = suggest_flags(&circuit, &[0, 1], 1);
+        assert!(report.dangerous_faults.is_empty());
+        assert_eq!(flagged, circuit);
+    }
+}
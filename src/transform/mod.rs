@@ -0,0 +1,13 @@
+pub mod cnot_reduction;
+pub mod fault_aware_schedule;
+pub mod flag_insertion;
+pub mod mutation;
+pub mod resynthesis;
+pub mod swap_elision;
+
+pub use cnot_reduction::{reduce_two_qubit_gates, ReductionReport};
+pub use fault_aware_schedule::{schedule_regions_for_faults, RegionSchedule};
+pub use flag_insertion::{suggest_flags, FlagBasis, FlagInsertion, FlagReport};
+pub use mutation::{mutations, random_mutations, sensitivity, Mutation, SensitivityReport};
+pub use resynthesis::{resynthesize_regions, RegionResynthesis};
+pub use swap_elision::{elide_swaps, SwapElision};
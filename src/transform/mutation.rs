@@ -0,0 +1,225 @@
+//! Circuit mutations for robustness studies.
+//!
+//! A mutation is a small perturbation of a design: exchanging two adjacent
+//! commuting gates (a different but equivalent schedule), dropping a gate,
+//! or flipping a CNOT's direction. `sensitivity` re-runs an analysis on
+//! every mutant and reports which kinds of change move the metric.
+
+use crate::noise::model::below;
+use crate::physics::circuit::{Circuit, Gate, TwoGate};
+use rand_core::Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mutation {
+    /// Exchange gates `index` and `index + 1`, which commute and share a
+    /// moment with no barrier or measurement between them
+    SwapCommuting { index: usize },
+    DropGate { index: usize },
+    /// Exchange control and target of the CNOT at `index`
+    FlipCnot { index: usize },
+}
+
+impl Mutation {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Mutation::SwapCommuting { .. } => "swap commuting",
+            Mutation::DropGate { .. } => "drop gate",
+            Mutation::FlipCnot { .. } => "flip cnot",
+        }
+    }
+
+    /// The mutated circuit. Regions keep their gates and noise overrides
+    /// follow them; a dropped gate shrinks the regions containing it.
+    pub fn apply(&self, circuit: &Circuit) -> Result<Circuit, String> {
+        let gates = &circuit.gates;
+        match *self {
+            Mutation::SwapCommuting { index } => {
+                if index + 1 >= gates.len() || !gates[index].commutes_with(&gates[index + 1]) {
+                    return Err(format!("Gates {} and {} cannot be exchanged", index, index + 1));
+                }
+                circuit.with_reordered_gates(index, &[1, 0])
+            }
+            Mutation::DropGate { index } => {
+                if index >= gates.len() {
+                    return Err(format!("No gate {}", index));
+                }
+                let mut removed = vec![false; gates.len()];
+                removed[index] = true;
+                Ok(circuit.without_gates(&removed))
+            }
+            Mutation::FlipCnot { index } => match gates.get(index) {
+                Some(Gate::Two(TwoGate::CNOT { control, target })) => {
+                    let mut mutant = circuit.clone();
+                    mutant.gates[index] = Gate::Two(TwoGate::CNOT {
+                        control: *target,
+                        target: *control,
+                    });
+                    Ok(mutant)
+                }
+                _ => Err(format!("Gate {} is not a CNOT", index)),
+            },
+        }
+    }
+}
+
+/// Every applicable mutation, by gate index.
+pub fn mutations(circuit: &Circuit) -> Vec<Mutation> {
+    let gates = &circuit.gates;
+    let mut all = Vec::new();
+    for index in 0..gates.len() {
+        if index + 1 < gates.len()
+            && gates[index] != gates[index + 1]
+            && gates[index].commutes_with(&gates[index + 1])
+            && circuit.can_reorder(index..index + 2)
+        {
+            all.push(Mutation::SwapCommuting { index });
+        }
+        all.push(Mutation::DropGate { index });
+        if let Gate::Two(TwoGate::CNOT { .. }) = gates[index] {
+            all.push(Mutation::FlipCnot { index });
+        }
+    }
+    all
+}
+
+/// `count` mutations drawn uniformly (with replacement) from `mutations`.
+pub fn random_mutations<R: Rng + ?Sized>(circuit: &Circuit, count: usize, rng: &mut R) -> Vec<Mutation> {
+    let all = mutations(circuit);
+    if all.is_empty() {
+        return Vec::new();
+    }
+    (0..count).map(|_| all[below(rng, all.len())]).collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MutantResult<T> {
+    pub mutation: Mutation,
+    /// Err when the analysis failed on the mutant
+    pub result: Result<T, String>,
+    /// The result differs from the baseline
+    pub changed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KindSensitivity {
+    pub kind: String,
+    pub mutants: usize,
+    pub changed: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SensitivityReport<T> {
+    pub baseline: T,
+    /// In the order the mutations were given
+    pub mutants: Vec<MutantResult<T>>,
+    /// One row per mutation kind present, in first-seen order
+    pub by_kind: Vec<KindSensitivity>,
+}
+
+/// Runs `analysis` on `circuit` and on each mutant.
+pub fn sensitivity<T, F>(circuit: &Circuit, mutations: &[Mutation], mut analysis: F) -> Result<SensitivityReport<T>, String>
+where
+    T: PartialEq,
+    F: FnMut(&Circuit) -> Result<T, String>,
+{
+    let baseline = analysis(circuit)?;
+    let mut mutants = Vec::with_capacity(mutations.len());
+    let mut by_kind: Vec<KindSensitivity> = Vec::new();
+    for &mutation in mutations {
+        let result = mutation.apply(circuit).and_then(|mutant| analysis(&mutant));
+        let changed = result.as_ref().map_or(true, |value| *value != baseline);
+        let position = match by_kind.iter().position(|row| row.kind == mutation.kind()) {
+            Some(position) => position,
+            None => {
+                by_kind.push(KindSensitivity {
+                    kind: mutation.kind().to_string(),
+                    mutants: 0,
+                    changed: 0,
+                });
+                by_kind.len() - 1
+            }
+        };
+        by_kind[position].mutants += 1;
+        by_kind[position].changed += usize::from(changed);
+        mutants.push(MutantResult {
+            mutation,
+            result,
+            changed,
+        });
+    }
+    Ok(SensitivityReport {
+        baseline,
+        mutants,
+        by_kind,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::equivalence::circuits_equivalent;
+    use crate::physics::circuit::{GateNoise, Measure, MeasurementBasis, SingleGate};
+
+    #[test]
+    fn test_mutations_and_sensitivity() {
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 2 })).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 1, gate: SingleGate::H }).unwrap();
+
+        let all = mutations(&circuit);
+        assert_eq!(
+            all,
+            vec![
+                Mutation::SwapCommuting { index: 0 },
+                Mutation::DropGate { index: 0 },
+                Mutation::FlipCnot { index: 0 },
+                Mutation::SwapCommuting { index: 1 },
+                Mutation::DropGate { index: 1 },
+                Mutation::FlipCnot { index: 1 },
+                Mutation::DropGate { index: 2 },
+            ]
+        );
+        assert!(Mutation::FlipCnot { index: 2 }.apply(&circuit).is_err());
+
+        // Commuting swaps never change the implemented Clifford
+        let report = sensitivity(&circuit, &all, |c| Ok(circuits_equivalent(c, &circuit))).unwrap();
+        assert!(report.baseline);
+        let swaps = report.by_kind.iter().find(|row| row.kind == "swap commuting").unwrap();
+        assert_eq!((swaps.mutants, swaps.changed), (2, 0));
+        let drops = report.by_kind.iter().find(|row| row.kind == "drop gate").unwrap();
+        assert_eq!(drops.changed, 3);
+    }
+
+    #[test]
+    fn test_noise_override_follows_swapped_gate() {
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 2, gate: SingleGate::H }).unwrap();
+        circuit.set_noise_override(0, GateNoise::Depolarizing(0.2)).unwrap();
+
+        let mutant = Mutation::SwapCommuting { index: 0 }.apply(&circuit).unwrap();
+        assert_eq!(mutant.gates[1], circuit.gates[0]);
+        assert_eq!(mutant.noise_override(0), None);
+        assert_eq!(mutant.noise_override(1), Some(&GateNoise::Depolarizing(0.2)));
+    }
+
+    #[test]
+    fn test_swaps_stay_inside_moments() {
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 1, gate: SingleGate::H }).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 2, gate: SingleGate::H }).unwrap();
+        circuit.moments = vec![0, 2];
+        let swaps: Vec<Mutation> = mutations(&circuit).into_iter().filter(|m| m.kind() == "swap commuting").collect();
+        assert_eq!(swaps, vec![Mutation::SwapCommuting { index: 0 }]);
+        assert!(Mutation::SwapCommuting { index: 1 }.apply(&circuit).is_err());
+        Mutation::SwapCommuting { index: 0 }.apply(&circuit).unwrap().validate_moments().unwrap();
+
+        // No gate moves across a readout
+        circuit.moments.clear();
+        circuit.measurements = vec![Measure { position: 1, qubit: 0, basis: MeasurementBasis::Z }];
+        assert!(Mutation::SwapCommuting { index: 0 }.apply(&circuit).is_err());
+    }
+}
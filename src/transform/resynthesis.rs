@@ -0,0 +1,281 @@
+//! Per-region Clifford resynthesis.
+//!
+//! Each labelled region is compiled to a tableau over the qubits it touches,
+//! resynthesized, and swapped in only when the result is verified equivalent
+//! and strictly shorter. Gates outside regions are never moved across region
+//! boundaries.
+
+use crate::analysis::equivalence::circuits_equivalent;
+use crate::physics::circuit::{Circuit, Gate, NoiseOverride, Region, SingleGate};
+use crate::physics::tableau::Tableau;
+use crate::transform::cnot_reduction::reduce_two_qubit_gates;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegionResynthesis {
+    pub name: String,
+    pub gates_before: usize,
+    pub gates_after: usize,
+    pub two_qubit_gates_before: usize,
+    pub two_qubit_gates_after: usize,
+    pub replaced: bool,
+}
+
+/// Resynthesizes every region of the circuit independently. Regions must
+/// not overlap. A region holding a barrier or measurement strictly inside
+/// it keeps its gates. Everything else about the circuit is kept: noise
+/// overrides, moments, barriers and measurements follow the gates outside
+/// replaced regions, and new gates get moments of their own.
+pub fn resynthesize_regions(circuit: &Circuit) -> Result<(Circuit, Vec<RegionResynthesis>), String> {
+    let mut order: Vec<usize> = (0..circuit.regions.len()).collect();
+    order.sort_by_key(|&i| (circuit.regions[i].start, circuit.regions[i].end));
+    for pair in order.windows(2) {
+        let (first, second) = (&circuit.regions[pair[0]], &circuit.regions[pair[1]]);
+        if first.end > second.start {
+            return Err(format!("Regions '{}' and '{}' overlap", first.name, second.name));
+        }
+    }
+
+    let mut reports = vec![None; circuit.regions.len()];
+    // replacements[start] = (end, gates) for each region that is replaced
+    let mut replacements: Vec<Option<(usize, Vec<Gate>)>> = vec![None; circuit.gates.len()];
+    for &index in &order {
+        let region = &circuit.regions[index];
+        let pinned = circuit
+            .barriers
+            .iter()
+            .copied()
+            .chain(circuit.measurements.iter().map(|m| m.position))
+            .any(|position| region.start < position && position < region.end);
+        let original = &circuit.gates[region.start..region.end];
+        let (gates, report) = resynthesize_block(circuit.num_qubits, region, original, !pinned)?;
+        if report.replaced {
+            replacements[region.start] = Some((region.end, gates));
+        }
+        reports[index] = Some(report);
+    }
+
+    // new_position[p] is where old position p ends up; inside replaced
+    // regions only the bounds are meaningful
+    let mut result = circuit.clone();
+    result.gates = Vec::with_capacity(circuit.gates.len());
+    let mut new_position = vec![0; circuit.gates.len() + 1];
+    let mut replaced = vec![false; circuit.gates.len()];
+    let mut moments = Vec::new();
+    let mut after_replacement = false;
+    let mut index = 0;
+    while index < circuit.gates.len() {
+        new_position[index] = result.gates.len();
+        if let Some((end, gates)) = replacements[index].take() {
+            for gate in gates {
+                moments.push(result.gates.len());
+                result.gates.push(gate);
+            }
+            replaced[index..end].fill(true);
+            after_replacement = true;
+            index = end;
+            continue;
+        }
+        if after_replacement || circuit.moments.binary_search(&index).is_ok() {
+            moments.push(result.gates.len());
+        }
+        after_replacement = false;
+        result.gates.push(circuit.gates[index].clone());
+        index += 1;
+    }
+    new_position[circuit.gates.len()] = result.gates.len();
+
+    for region in &mut result.regions {
+        region.start = new_position[region.start];
+        region.end = new_position[region.end];
+    }
+    result.noise_overrides = circuit
+        .noise_overrides
+        .iter()
+        .filter(|entry| !replaced[entry.gate])
+        .map(|entry| NoiseOverride {
+            gate: new_position[entry.gate],
+            noise: entry.noise.clone(),
+        })
+        .collect();
+    if !circuit.moments.is_empty() {
+        result.moments = moments;
+    }
+    for barrier in &mut result.barriers {
+        *barrier = new_position[*barrier];
+    }
+    for m in &mut result.measurements {
+        m.position = new_position[m.position];
+    }
+
+    Ok((result, reports.into_iter().map(|report| report.unwrap()).collect()))
+}
+
+fn resynthesize_block(
+    num_qubits: usize,
+    region: &Region,
+    original: &[Gate],
+    may_replace: bool,
+) -> Result<(Vec<Gate>, RegionResynthesis), String> {
+    // Work on the touched qubits only, so the tableau stays small
+    let mut touched: Vec<usize> = original.iter().flat_map(|gate| gate.qubits()).collect();
+    touched.sort_unstable();
+    touched.dedup();
+    let mut to_local = vec![usize::MAX; num_qubits];
+    for (local, &global) in touched.iter().enumerate() {
+        to_local[global] = local;
+    }
+
+    let mut local = Circuit::new(touched.len());
+    local.gates = original.iter().map(|gate| gate.map_qubits(|q| to_local[q])).collect();
+
+    let synthesized = Tableau::from_circuit(&local).to_circuit();
+    let (mut candidate, _) = reduce_two_qubit_gates(&synthesized);
+    candidate = cancel_single_qubit_pairs(&candidate);
+
+    if !circuits_equivalent(&local, &candidate) {
+        return Err(format!("Resynthesis of region '{}' failed verification", region.name));
+    }
+
+    let improves = may_replace
+        && (candidate.depth(), candidate.two_qubit_gate_count()) < (local.depth(), local.two_qubit_gate_count());
+    let chosen = if improves { &candidate } else { &local };
+    let gates: Vec<Gate> = chosen.gates.iter().map(|gate| gate.map_qubits(|q| touched[q])).collect();
+
+    let report = RegionResynthesis {
+        name: region.name.clone(),
+        gates_before: local.depth(),
+        gates_after: chosen.depth(),
+        two_qubit_gates_before: local.two_qubit_gate_count(),
+        two_qubit_gates_after: chosen.two_qubit_gate_count(),
+        replaced: improves,
+    };
+    Ok((gates, report))
+}
+
+/// Removes adjacent single-qubit gate pairs that multiply to the identity
+/// (H·H, S·S†, Pauli·Pauli) on the same qubit.
+fn cancel_single_qubit_pairs(circuit: &Circuit) -> Circuit {
+    let mut removed = vec![false; circuit.gates.len()];
+    // Index of the last surviving gate touching each qubit
+    let mut last_on_qubit: Vec<Option<usize>> = vec![None; circuit.num_qubits];
+
+    for (index, gate) in circuit.gates.iter().enumerate() {
+        if let Gate::Single { qubit, gate: current } = gate {
+            if let Some(previous) = last_on_qubit[*qubit] {
+                if let Gate::Single { gate: earlier, .. } = circuit.gates[previous] {
+                    if is_inverse_pair(earlier, *current) {
+                        removed[previous] = true;
+                        removed[index] = true;
+                        last_on_qubit[*qubit] = (0..previous)
+                            .rev()
+                            .find(|&i| !removed[i] && circuit.gates[i].qubits().contains(qubit));
+                        continue;
+                    }
+                }
+            }
+        }
+        for qubit in gate.qubits() {
+            last_on_qubit[qubit] = Some(index);
+        }
+    }
+    circuit.without_gates(&removed)
+}
+
+fn is_inverse_pair(a: SingleGate, b: SingleGate) -> bool {
+    matches!(
+        (a, b),
+        (SingleGate::S, SingleGate::Sdg) | (SingleGate::Sdg, SingleGate::S)
+    ) || (a == b && !matches!(a, SingleGate::S | SingleGate::Sdg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{GateNoise, MeasurementBasis, TwoGate};
+
+    fn single(qubit: usize, gate: SingleGate) -> Gate {
+        Gate::Single { qubit, gate }
+    }
+
+    #[test]
+    fn test_region_is_shortened_and_verified() {
+        // H S S H on qubit 1 is X; the surrounding CNOTs lie outside the region
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        for gate in [SingleGate::H, SingleGate::S, SingleGate::S, SingleGate::H] {
+            circuit.add_gate(single(1, gate)).unwrap();
+        }
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        circuit.add_region("middle", 1, 5).unwrap();
+
+        let (result, reports) = resynthesize_regions(&circuit).unwrap();
+        assert!(reports[0].replaced);
+        assert!(reports[0].gates_after < 4);
+        assert!(circuits_equivalent(&circuit, &result));
+
+        let region = result.region("middle").unwrap();
+        assert_eq!(region.start, 1);
+        assert_eq!(region.end, 1 + reports[0].gates_after);
+        assert_eq!(result.gates[0], circuit.gates[0]);
+        assert_eq!(result.gates.last(), circuit.gates.last());
+    }
+
+    #[test]
+    fn test_circuit_metadata_is_kept() {
+        let mut circuit = Circuit::new(0);
+        circuit.add_register("q", 3).unwrap();
+        circuit.add_gate(single(0, SingleGate::H)).unwrap();
+        circuit.add_gate_at(0, single(2, SingleGate::X)).unwrap();
+        for gate in [SingleGate::H, SingleGate::S, SingleGate::S, SingleGate::H] {
+            circuit.add_gate(single(1, gate)).unwrap();
+        }
+        circuit.add_barrier();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        circuit.add_measurement(1, MeasurementBasis::Z).unwrap();
+        circuit.add_region("middle", 2, 6).unwrap();
+        circuit.set_noise_override(6, GateNoise::Depolarizing(0.1)).unwrap();
+        circuit.set_noise_override(3, GateNoise::Depolarizing(0.2)).unwrap();
+
+        let (result, reports) = resynthesize_regions(&circuit).unwrap();
+        assert!(reports[0].replaced);
+        let end = result.region("middle").unwrap().end;
+        assert_eq!(result.registers, circuit.registers);
+        assert_eq!(result.barriers, vec![end]);
+        assert_eq!(result.measurements[0].position, end + 1);
+        assert_eq!(result.noise_overrides.len(), 1);
+        assert_eq!(result.noise_override(end), Some(&GateNoise::Depolarizing(0.1)));
+        assert_eq!(result.gates_at_time(0).len(), 2);
+        result.validate_moments().unwrap();
+
+        // A barrier inside the region pins its gates
+        let mut pinned = circuit.clone();
+        pinned.barriers = vec![4, 6];
+        let (result, reports) = resynthesize_regions(&pinned).unwrap();
+        assert!(!reports[0].replaced);
+        assert_eq!(result, pinned);
+    }
+
+    #[test]
+    fn test_optimal_region_is_kept() {
+        let mut circuit = Circuit::new(2);
+        circuit.add_gate(single(0, SingleGate::H)).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+        circuit.add_region("bell", 0, 2).unwrap();
+
+        let (result, reports) = resynthesize_regions(&circuit).unwrap();
+        assert!(!reports[0].replaced);
+        assert_eq!(result, circuit);
+    }
+
+    #[test]
+    fn test_overlapping_regions_rejected() {
+        let mut circuit = Circuit::new(1);
+        for _ in 0..3 {
+            circuit.add_gate(single(0, SingleGate::H)).unwrap();
+        }
+        circuit.add_region("a", 0, 2).unwrap();
+        circuit.add_region("b", 1, 3).unwrap();
+        assert!(resynthesize_regions(&circuit).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+//! SWAP elision.
+//!
+//! Routed circuits move states around with explicit SWAPs. Eliding them
+//! and renaming the qubits of every later gate leaves an equivalent
+//! SWAP-free circuit in the "logical wire" frame, followed by a final
+//! permutation: wire `final_layout[q]` ends up on physical qubit `q`.
+//! Measurements are renamed the same way and regions keep covering the
+//! same (remaining) gates. The simulator shows
+//! the same logical frame without rewriting the circuit, see
+//! `physics::frame`.
+
+use crate::physics::circuit::{Circuit, Gate, Measure, TwoGate};
+use crate::physics::frame::{to_logical, to_physical};
+use crate::physics::pauli::PauliString;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapElision {
+    pub circuit: Circuit,
+    /// Logical wire held by each physical qubit at the end
+    pub final_layout: Vec<usize>,
+    pub swaps_removed: usize,
+}
+
+impl SwapElision {
+    /// Moves an error on the logical wires at the end of the elided circuit
+    /// onto the physical qubits of the original one.
+    pub fn physical_error(&self, logical: &PauliString) -> PauliString {
+        to_physical(logical, &self.final_layout)
+    }
+
+    /// The inverse of `physical_error`.
+    pub fn logical_error(&self, physical: &PauliString) -> PauliString {
+        to_logical(physical, &self.final_layout)
+    }
+}
+
+pub fn elide_swaps(circuit: &Circuit) -> SwapElision {
+    let mut layout: Vec<usize> = (0..circuit.num_qubits).collect();
+    let mut removed = vec![false; circuit.gates.len()];
+    let mut relabeled = circuit.clone();
+    relabeled.measurements.clear();
+    let mut measurements = circuit.measurements.iter().peekable();
+    for (index, gate) in circuit.gates.iter().enumerate() {
+        while let Some(m) = measurements.next_if(|m| m.position <= index) {
+            relabeled.measurements.push(Measure { qubit: layout[m.qubit], ..*m });
+        }
+        match gate {
+            Gate::Two(TwoGate::SWAP { qubit1, qubit2 }) => {
+                layout.swap(*qubit1, *qubit2);
+                removed[index] = true;
+            }
+            _ => relabeled.gates[index] = gate.map_qubits(|q| layout[q]),
+        }
+    }
+    for m in measurements {
+        relabeled.measurements.push(Measure { qubit: layout[m.qubit], ..*m });
+    }
+    SwapElision {
+        circuit: relabeled.without_gates(&removed),
+        final_layout: layout,
+        swaps_removed: removed.iter().filter(|&&r| r).count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::circuit::{MeasurementBasis, SingleGate};
+    use crate::physics::pauli::SinglePauli;
+    use crate::physics::propagation::apply_gate;
+
+    #[test]
+    fn test_elided_circuit_matches_routed_one() {
+        let mut circuit = Circuit::new(3);
+        circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::SWAP { qubit1: 0, qubit2: 1 })).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 1, target: 2 })).unwrap();
+        circuit.add_gate(Gate::Two(TwoGate::SWAP { qubit1: 1, qubit2: 2 })).unwrap();
+        circuit.add_gate(Gate::Single { qubit: 2, gate: SingleGate::S }).unwrap();
+        circuit.add_region("tail", 2, 5).unwrap();
+        circuit.add_measurement(2, MeasurementBasis::Z).unwrap();
+
+        let elision = elide_swaps(&circuit);
+        assert_eq!(elision.swaps_removed, 2);
+        assert_eq!(elision.final_layout, vec![1, 2, 0]);
+        assert_eq!(elision.circuit.gates[1], Gate::Two(TwoGate::CNOT { control: 0, target: 2 }));
+        assert_eq!(elision.circuit.gates[2], Gate::Single { qubit: 0, gate: SingleGate::S });
+        assert_eq!(elision.circuit.region("tail").map(|r| (r.start, r.end)), Some((1, 3)));
+        assert_eq!(
+            elision.circuit.measurements,
+            vec![Measure { position: 3, qubit: 0, basis: MeasurementBasis::Z }]
+        );
+
+        for (qubit, pauli) in [(0, SinglePauli::X), (1, SinglePauli::Z), (2, SinglePauli::Y)] {
+            let mut routed = PauliString::new(3);
+            routed.set_pauli(qubit, pauli);
+            let mut logical = routed.clone();
+            circuit.gates.iter().for_each(|g| apply_gate(&mut routed, g));
+            elision.circuit.gates.iter().for_each(|g| apply_gate(&mut logical, g));
+            assert_eq!(elision.physical_error(&logical), routed);
+            assert_eq!(elision.logical_error(&routed), logical);
+        }
+    }
+}
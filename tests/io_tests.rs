@@ -3,7 +3,8 @@
 //! Tests import/export using sample files from test_data directory.
 
 use quantum_error_analyzer::io;
-use quantum_error_analyzer::physics::circuit::{Circuit, Gate, SingleGate, TwoGate};
+use quantum_error_analyzer::physics::circuit::{Circuit, Gate, GateNoise, MeasurementBasis, SingleGate, TwoGate};
+use quantum_error_analyzer::physics::pauli::SinglePauli;
 use std::fs;
 use std::path::PathBuf;
 
@@ -325,3 +326,63 @@ fn test_single_gate_circuit() {
     assert_eq!(circuit.gates.len(), imported.gates.len());
 }
 
+
+#[test]
+fn test_annotations_survive_json_qasm_stim_roundtrip() {
+    let mut circuit = Circuit::new(0);
+    circuit.add_register("data", 2).unwrap();
+    circuit.add_register("ancilla", 2).unwrap();
+    circuit.add_gate(Gate::Single { qubit: 0, gate: SingleGate::H }).unwrap();
+    circuit.add_gate(Gate::Single { qubit: 3, gate: SingleGate::I }).unwrap();
+    circuit.add_barrier();
+    circuit.add_gate(Gate::Two(TwoGate::CNOT { control: 0, target: 1 })).unwrap();
+    circuit.add_gate(Gate::Single { qubit: 1, gate: SingleGate::Sdg }).unwrap();
+    circuit.add_measurement(1, MeasurementBasis::Z).unwrap();
+    circuit.add_measurement(3, MeasurementBasis::X).unwrap();
+    circuit.add_region("encoder", 0, 3).unwrap();
+    circuit.add_region("syndrome round 1", 3, 4).unwrap();
+    circuit.set_noise_override(2, GateNoise::Depolarizing(0.015)).unwrap();
+    circuit
+        .set_noise_override(3, GateNoise::Pauli(vec![(vec![SinglePauli::Z], 0.1)]))
+        .unwrap();
+    circuit.moments = vec![0, 2, 3];
+    circuit.validate_moments().unwrap();
+
+    let strict = io::ImportOptions::strict();
+    let from_json = io::import_json_with(&io::export_json(&circuit).unwrap(), &strict).unwrap();
+    let from_qasm = io::import_qasm_with(&io::export_qasm(&from_json), &strict).unwrap();
+    let from_stim = io::import_stim_with(&io::export_stim(&from_qasm), &strict).unwrap();
+    assert_eq!(from_stim, circuit);
+}
+
+#[test]
+fn test_strict_roundtrip_rejects_dropped_content() {
+    let strict = io::ImportOptions::strict();
+    let json = r#"{"num_qubits": 1, "gates": [], "metadata": {"author": "x"}}"#;
+    assert!(io::import_json(json).is_ok());
+    assert!(io::import_json_with(json, &strict).is_err());
+
+    let qasm = "OPENQASM 2.0;\nqreg q[1];\n// @detector 0\nh q[0];\n";
+    assert!(io::import_qasm(qasm).is_ok());
+    assert!(io::import_qasm_with(qasm, &strict).is_err());
+
+    // Classical bits the circuit cannot number the same way
+    let renumbered = "qreg q[2];\ncreg c[2];\nmeasure q[0] -> c[1];\nmeasure q[1] -> c[0];\n";
+    assert!(io::import_qasm(renumbered).is_ok());
+    assert!(io::import_qasm_with(renumbered, &strict).is_err());
+    let unused = "qreg q[1];\ncreg c[2];\nmeasure q[0] -> c[0];\n";
+    assert!(io::import_qasm_with(unused, &strict).is_err());
+
+    let stim = "H 0\nM 0\n# @observable 0\n";
+    assert!(io::import_stim(stim).is_ok());
+    assert!(io::import_stim_with(stim, &strict).is_err());
+}
+
+#[test]
+fn test_exporters_match_golden_files() {
+    let circuit = io::import_json(&load_test_file("bell_state.json")).unwrap();
+    let options = io::GoldenOptions::default();
+    io::assert_golden(test_data_path("golden/bell_state.qasm"), &io::export_qasm(&circuit), &options);
+    io::assert_golden(test_data_path("golden/bell_state.tex"), &io::export_latex(&circuit), &options);
+    io::assert_golden(test_data_path("golden/bell_state.stim"), &io::export_stim(&circuit), &options);
+}
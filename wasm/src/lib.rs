@@ -1,9 +1,25 @@
 use wasm_bindgen::prelude::*;
 
-use quantum_error_analyzer::physics::circuit::{Circuit, Gate, SingleGate, TwoGate};
+use quantum_error_analyzer::physics::circuit::{Circuit, Gate, GateNoise, MeasurementBasis, SingleGate, TwoGate};
+use quantum_error_analyzer::physics::frame::ErrorFrame;
 use quantum_error_analyzer::physics::pauli::{PauliString, SinglePauli, Phase};
 use quantum_error_analyzer::physics::simulator::Simulator;
+use quantum_error_analyzer::physics::timeline::TimelineRetention;
 use quantum_error_analyzer::io;
+use quantum_error_analyzer::memory::{ApproximateSize, MemoryLimits};
+
+#[cfg(feature = "demo")]
+use quantum_error_analyzer::analysis::single_fault_sweep;
+#[cfg(feature = "demo")]
+use quantum_error_analyzer::codes::{Gadget, StabilizerCode};
+#[cfg(feature = "demo")]
+use quantum_error_analyzer::decoding::{verify_decoder, Decoder, LookupDecoder, Syndrome, UnionFindDecoder};
+#[cfg(feature = "demo")]
+use quantum_error_analyzer::dem::{export_dem, DetectorErrorModel};
+#[cfg(feature = "demo")]
+use quantum_error_analyzer::noise::{sample_shots, NoiseModel};
+#[cfg(feature = "demo")]
+use serde::Serialize;
 
 #[wasm_bindgen]
 #[derive(Clone)]
@@ -51,6 +67,28 @@ impl WasmCircuit {
         self.circuit.add_gate(Gate::Two(TwoGate::SWAP { qubit1, qubit2 }))
     }
 
+    #[wasm_bindgen]
+    pub fn add_barrier(&mut self) {
+        self.circuit.add_barrier();
+    }
+
+    #[wasm_bindgen]
+    pub fn add_measurement(&mut self, qubit: usize, basis: String) -> Result<(), String> {
+        let basis = match basis.as_str() {
+            "X" => MeasurementBasis::X,
+            "Z" => MeasurementBasis::Z,
+            _ => return Err(format!("Unknown measurement basis: {}", basis)),
+        };
+        self.circuit.add_measurement(qubit, basis)
+    }
+
+    /// Depolarizing with probability `p` after gate `gate`, in place of the
+    /// noise model's rate for that gate.
+    #[wasm_bindgen]
+    pub fn set_gate_noise(&mut self, gate: usize, p: f64) -> Result<(), String> {
+        self.circuit.set_noise_override(gate, GateNoise::Depolarizing(p))
+    }
+
     #[wasm_bindgen]
     pub fn num_qubits(&self) -> usize {
         self.circuit.num_qubits
@@ -61,6 +99,52 @@ impl WasmCircuit {
         self.circuit.depth()
     }
 
+    /// Packs the gates into parallel moments, as early as possible.
+    #[wasm_bindgen]
+    pub fn schedule(&mut self) {
+        self.circuit = self.circuit.schedule();
+    }
+
+    #[wasm_bindgen]
+    pub fn idle_qubits_at(&self, time: usize) -> Vec<usize> {
+        self.circuit.idle_qubits_at(time)
+    }
+
+    /// Idle qubits of every time slot up to the last occupied one, when
+    /// gate i is drawn in slot `slots[i]`. The occupied slots are laid out
+    /// as moments and read back with `idle_qubits_at`, so the renderer
+    /// greys the same wires the idle-noise model treats as idle.
+    #[wasm_bindgen]
+    pub fn idle_wires(&self, slots: Vec<usize>) -> Result<JsValue, String> {
+        if slots.len() != self.circuit.gates.len() {
+            return Err(format!("Got {} slots for {} gates", slots.len(), self.circuit.gates.len()));
+        }
+        let mut order: Vec<usize> = (0..slots.len()).collect();
+        order.sort_by_key(|&gate| slots[gate]);
+        let mut layout = Circuit::new(self.circuit.num_qubits);
+        for &gate in &order {
+            layout.add_gate(self.circuit.gates[gate].clone())?;
+        }
+        layout.moments = (0..order.len())
+            .filter(|&i| i == 0 || slots[order[i - 1]] != slots[order[i]])
+            .collect();
+        layout.validate_moments()?;
+
+        let depth = slots.iter().max().map_or(0, |&slot| slot + 1);
+        let mut moment = 0;
+        let idle: Vec<Vec<usize>> = (0..depth)
+            .map(|slot| {
+                if slots.contains(&slot) {
+                    moment += 1;
+                    layout.idle_qubits_at(moment - 1)
+                } else {
+                    (0..layout.num_qubits).collect()
+                }
+            })
+            .collect();
+        Ok(serde_wasm_bindgen::to_value(&idle).unwrap())
+    }
+
     #[wasm_bindgen]
     pub fn get_gates(&self) -> JsValue {
         serde_wasm_bindgen::to_value(&self.circuit.gates).unwrap()
@@ -154,6 +238,7 @@ impl WasmPauliString {
     }
 
     #[wasm_bindgen]
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         let mut result = String::new();
         for qubit in 0..self.pauli.num_qubits() {
@@ -163,9 +248,25 @@ impl WasmPauliString {
     }
 }
 
+#[cfg(feature = "demo")]
+#[wasm_bindgen]
+impl WasmPauliString {
+    /// The product `self * other`.
+    #[wasm_bindgen]
+    pub fn multiply(&self, other: &WasmPauliString) -> Result<WasmPauliString, String> {
+        if self.pauli.num_qubits() != other.pauli.num_qubits() {
+            return Err("Pauli strings have different qubit counts".to_string());
+        }
+        Ok(WasmPauliString {
+            pauli: self.pauli.multiply(&other.pauli),
+        })
+    }
+}
+
 #[wasm_bindgen]
 pub struct WasmSimulator {
     simulator: Simulator,
+    frame: ErrorFrame,
 }
 
 #[wasm_bindgen]
@@ -174,6 +275,16 @@ impl WasmSimulator {
     pub fn new(circuit: &WasmCircuit) -> WasmSimulator {
         WasmSimulator {
             simulator: Simulator::new(circuit.circuit.clone()),
+            frame: ErrorFrame::default(),
+        }
+    }
+
+    /// Independent copy at the current time that shares the circuit.
+    #[wasm_bindgen]
+    pub fn fork(&self) -> WasmSimulator {
+        WasmSimulator {
+            simulator: self.simulator.fork(),
+            frame: self.frame,
         }
     }
 
@@ -204,15 +315,31 @@ impl WasmSimulator {
         self.simulator.reset();
     }
 
+    /// The rule behind the last step for tooltips: `summary` holds the
+    /// sentence, the other fields the per-qubit changes. Null at time 0.
+    #[wasm_bindgen]
+    pub fn explain_last_step(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.simulator.explain_last_step()).unwrap()
+    }
+
     #[wasm_bindgen]
     pub fn current_time(&self) -> usize {
         self.simulator.current_time()
     }
 
+    /// `frame` is "physical" (default) or "logical"; the logical view
+    /// follows wires through SWAPs. Applies to the error pattern, the
+    /// timeline and its export.
+    #[wasm_bindgen]
+    pub fn set_error_frame(&mut self, frame: String) -> Result<(), String> {
+        self.frame = ErrorFrame::parse(&frame)?;
+        Ok(())
+    }
+
     #[wasm_bindgen]
     pub fn get_error_pattern(&self) -> WasmPauliString {
         WasmPauliString {
-            pauli: self.simulator.error_pattern().clone(),
+            pauli: self.simulator.error_pattern_in(self.frame).into_owned(),
         }
     }
 
@@ -221,6 +348,52 @@ impl WasmSimulator {
         self.simulator.run();
     }
 
+    /// `mode` is "keep_all", "spill" or "drop". Browsers have no temporary
+    /// files, so "spill" keeps everything in memory there.
+    #[wasm_bindgen]
+    pub fn set_timeline_retention(&mut self, mode: String, resident: usize) -> Result<(), String> {
+        let retention = match mode.as_str() {
+            "keep_all" => TimelineRetention::KeepAll,
+            "spill" => TimelineRetention::Spill { resident },
+            "drop" => TimelineRetention::Drop { resident },
+            _ => return Err(format!("Unknown timeline retention '{}'", mode)),
+        };
+        self.simulator.set_timeline_retention(retention)
+    }
+
+    /// Runs to the end, or fails without stepping when the timeline would
+    /// take more than `max_bytes`.
+    #[wasm_bindgen]
+    pub fn run_within(&mut self, max_bytes: usize) -> Result<(), String> {
+        self.simulator.run_within(&MemoryLimits::new(max_bytes))
+    }
+
+    #[wasm_bindgen]
+    pub fn memory_bytes(&self) -> usize {
+        self.simulator.approximate_bytes()
+    }
+
+    #[wasm_bindgen]
+    pub fn record_classical_bits(&mut self, bits: Vec<u8>) {
+        let bits: Vec<bool> = bits.iter().map(|&bit| bit != 0).collect();
+        self.simulator.record_classical_bits(&bits);
+    }
+
+    #[wasm_bindgen]
+    pub fn classical_bits(&self) -> Vec<u8> {
+        self.simulator.classical_bits().iter().map(|&bit| bit as u8).collect()
+    }
+
+    #[wasm_bindgen]
+    pub fn measurement_flips(&self) -> Vec<u8> {
+        self.simulator.measurement_flips().iter().map(|&flip| flip as u8).collect()
+    }
+
+    #[wasm_bindgen]
+    pub fn export_timeline_json(&self) -> Result<String, String> {
+        io::export_timeline_json(&self.simulator.timeline_in(self.frame))
+    }
+
     #[wasm_bindgen]
     pub fn get_timeline(&self) -> JsValue {
         use serde::{Serialize, Deserialize};
@@ -229,9 +402,11 @@ impl WasmSimulator {
             time: usize,
             error_pattern: String,
             gate_applied: Option<usize>,
+            classical_bits: Vec<bool>,
+            measurement_flips: Vec<bool>,
         }
         
-        let timeline: Vec<SnapshotData> = self.simulator.timeline()
+        let timeline: Vec<SnapshotData> = self.simulator.timeline_in(self.frame)
             .iter()
             .map(|snapshot| {
                 let mut pattern = String::new();
@@ -247,6 +422,8 @@ impl WasmSimulator {
                     time: snapshot.time,
                     error_pattern: pattern,
                     gate_applied: snapshot.gate_applied,
+                    classical_bits: snapshot.classical_bits.clone(),
+                    measurement_flips: snapshot.measurement_flips.clone(),
                 }
             })
             .collect();
@@ -255,6 +432,395 @@ impl WasmSimulator {
     }
 }
 
+/// A project file: named circuits, plus noise, codes and scenarios that
+/// are carried along unchanged.
+#[wasm_bindgen]
+pub struct WasmProject {
+    project: io::Project,
+}
+
+#[wasm_bindgen]
+impl WasmProject {
+    #[wasm_bindgen(constructor)]
+    pub fn new(name: String) -> WasmProject {
+        WasmProject {
+            project: io::Project::new(&name),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn import_json(json_str: &str) -> Result<WasmProject, String> {
+        let project = io::import_project(json_str)?;
+        Ok(WasmProject { project })
+    }
+
+    #[wasm_bindgen]
+    pub fn export_json(&self) -> Result<String, String> {
+        io::export_project(&self.project)
+    }
+
+    #[wasm_bindgen]
+    pub fn name(&self) -> String {
+        self.project.name.clone()
+    }
+
+    #[wasm_bindgen]
+    pub fn circuit_names(&self) -> Vec<String> {
+        self.project.circuit_names().map(str::to_string).collect()
+    }
+
+    #[wasm_bindgen]
+    pub fn get_circuit(&self, name: &str) -> Result<WasmCircuit, String> {
+        let circuit = self
+            .project
+            .circuit(name)
+            .ok_or_else(|| format!("No circuit '{}' in project", name))?;
+        Ok(WasmCircuit {
+            circuit: circuit.clone(),
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn set_circuit(&mut self, name: &str, circuit: &WasmCircuit) -> Result<(), String> {
+        self.project.add_circuit(name, circuit.circuit.clone())
+    }
+}
+
+/// A stabilizer code for the decoding demo: inject an error, read its
+/// syndrome, decode it and check the result.
+#[cfg(feature = "demo")]
+#[wasm_bindgen]
+pub struct WasmCode {
+    code: StabilizerCode,
+}
+
+#[cfg(feature = "demo")]
+#[wasm_bindgen]
+impl WasmCode {
+    #[wasm_bindgen]
+    pub fn repetition(distance: usize) -> Result<WasmCode, String> {
+        check_distance("Repetition", distance)?;
+        Ok(WasmCode {
+            code: StabilizerCode::repetition(distance),
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn phase_flip_repetition(distance: usize) -> Result<WasmCode, String> {
+        check_distance("Repetition", distance)?;
+        Ok(WasmCode {
+            code: StabilizerCode::phase_flip_repetition(distance),
+        })
+    }
+
+    /// Rotated surface code on a d x d grid, qubit `row * d + col`.
+    #[wasm_bindgen]
+    pub fn rotated_surface(distance: usize) -> Result<WasmCode, String> {
+        check_distance("Surface", distance)?;
+        Ok(WasmCode {
+            code: StabilizerCode::rotated_surface(distance),
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn steane() -> WasmCode {
+        WasmCode {
+            code: StabilizerCode::steane(),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn name(&self) -> String {
+        self.code.name.clone()
+    }
+
+    #[wasm_bindgen]
+    pub fn num_qubits(&self) -> usize {
+        self.code.num_qubits
+    }
+
+    #[wasm_bindgen]
+    pub fn stabilizers(&self) -> Vec<String> {
+        self.code.stabilizers.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// One 0/1 entry per stabilizer.
+    #[wasm_bindgen]
+    pub fn syndrome(&self, error: &WasmPauliString) -> Result<Vec<u8>, String> {
+        self.check_size(error)?;
+        Ok(self.code.syndrome(&error.pauli).into_iter().map(u8::from).collect())
+    }
+
+    /// Whether `error` is undetectable but changes the logical state.
+    #[wasm_bindgen]
+    pub fn is_logical_error(&self, error: &WasmPauliString) -> Result<bool, String> {
+        self.check_size(error)?;
+        Ok(self.code.is_logical_error(&error.pauli))
+    }
+
+    /// Whether `pauli` acts trivially on the code space.
+    #[wasm_bindgen]
+    pub fn in_stabilizer_group(&self, pauli: &WasmPauliString) -> Result<bool, String> {
+        self.check_size(pauli)?;
+        Ok(self.code.in_stabilizer_group(&pauli.pauli))
+    }
+
+    /// The detector error model of `circuit` in Stim's text format. The
+    /// first `num_qubits` circuit qubits hold the code block; `noise_json`
+    /// is a serialized `NoiseModel`.
+    #[wasm_bindgen]
+    pub fn detector_error_model(&self, circuit: &WasmCircuit, noise_json: &str) -> Result<String, String> {
+        let noise: NoiseModel =
+            serde_json::from_str(noise_json).map_err(|e| format!("Invalid noise model: {}", e))?;
+        let gadget = Gadget::new(circuit.circuit.clone(), (0..self.code.num_qubits).collect())?;
+        let dem = DetectorErrorModel::from_gadget(&gadget, &self.code, &noise)?;
+        Ok(export_dem(&dem))
+    }
+
+    fn check_size(&self, pauli: &WasmPauliString) -> Result<(), String> {
+        if pauli.pauli.num_qubits() != self.code.num_qubits {
+            return Err(format!(
+                "Expected a {}-qubit Pauli string, got {} qubits",
+                self.code.num_qubits,
+                pauli.pauli.num_qubits()
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "demo")]
+fn check_distance(family: &str, distance: usize) -> Result<(), String> {
+    if distance < 2 {
+        return Err(format!("{} code needs distance >= 2", family));
+    }
+    Ok(())
+}
+
+/// Decoding of a code's syndrome by lookup table or union-find.
+#[cfg(feature = "demo")]
+#[wasm_bindgen]
+pub struct WasmDecoder {
+    decoder: Box<dyn Decoder>,
+}
+
+#[cfg(feature = "demo")]
+#[wasm_bindgen]
+impl WasmDecoder {
+    #[wasm_bindgen]
+    pub fn lookup(code: &WasmCode) -> WasmDecoder {
+        WasmDecoder {
+            decoder: Box::new(LookupDecoder::new(&code.code)),
+        }
+    }
+
+    /// Union-find decoding; scales to surface codes whose lookup table
+    /// would be too large. Fails for non-CSS codes and for codes where a
+    /// qubit flips more than two checks of one type, such as Steane.
+    #[wasm_bindgen]
+    pub fn union_find(code: &WasmCode) -> Result<WasmDecoder, String> {
+        Ok(WasmDecoder {
+            decoder: Box::new(UnionFindDecoder::new(&code.code)?),
+        })
+    }
+
+    /// Correction for a syndrome of 0/1 entries, one per stabilizer.
+    #[wasm_bindgen]
+    pub fn decode(&mut self, syndrome: Vec<u8>) -> Result<WasmPauliString, String> {
+        let bits: Vec<bool> = syndrome.iter().map(|&bit| bit != 0).collect();
+        let correction = self.decoder.decode(&Syndrome::from_bools(&bits))?;
+        Ok(WasmPauliString {
+            pauli: correction.to_pauli(),
+        })
+    }
+}
+
+/// A long analysis of one circuit that reports partial results to
+/// subscribed JS callbacks as it goes. The first `code.num_qubits()`
+/// circuit qubits hold the code block. Calls run synchronously, so a page
+/// that wants to repaint between updates runs one batch per animation
+/// frame and lets the running totals accumulate across calls.
+#[cfg(feature = "demo")]
+#[wasm_bindgen]
+pub struct WasmLiveAnalysis {
+    gadget: Gadget,
+    code: StabilizerCode,
+    noise: NoiseModel,
+    decoder: LookupDecoder,
+    rng: SplitMix64,
+    subscribers: Vec<(usize, js_sys::Function)>,
+    next_subscription: usize,
+    shots: usize,
+    failures: usize,
+}
+
+/// Payload passed to subscribers, tagged by `kind`.
+#[cfg(feature = "demo")]
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum LiveUpdate {
+    /// Running logical error estimate over all shots so far
+    LogicalError { shots: usize, failures: usize, rate: f64 },
+    /// Single faults inside one circuit region
+    Sweep {
+        region: String,
+        index: usize,
+        regions: usize,
+        faults: usize,
+        detected: usize,
+        logical_errors: usize,
+    },
+}
+
+#[cfg(feature = "demo")]
+#[wasm_bindgen]
+impl WasmLiveAnalysis {
+    #[wasm_bindgen(constructor)]
+    pub fn new(circuit: &WasmCircuit, code: &WasmCode, noise_json: &str, seed: u64) -> Result<WasmLiveAnalysis, String> {
+        let noise: NoiseModel =
+            serde_json::from_str(noise_json).map_err(|e| format!("Invalid noise model: {}", e))?;
+        let gadget = Gadget::new(circuit.circuit.clone(), (0..code.code.num_qubits).collect())?;
+        noise.validate_for(&gadget.circuit)?;
+        Ok(WasmLiveAnalysis {
+            gadget,
+            decoder: LookupDecoder::new(&code.code),
+            code: code.code.clone(),
+            noise,
+            rng: SplitMix64(seed),
+            subscribers: Vec::new(),
+            next_subscription: 0,
+            shots: 0,
+            failures: 0,
+        })
+    }
+
+    /// Registers `callback` for every update; returns an id for
+    /// `unsubscribe`.
+    #[wasm_bindgen]
+    pub fn subscribe(&mut self, callback: js_sys::Function) -> usize {
+        let id = self.next_subscription;
+        self.next_subscription += 1;
+        self.subscribers.push((id, callback));
+        id
+    }
+
+    #[wasm_bindgen]
+    pub fn unsubscribe(&mut self, id: usize) -> bool {
+        let before = self.subscribers.len();
+        self.subscribers.retain(|(subscription, _)| *subscription != id);
+        self.subscribers.len() < before
+    }
+
+    /// Samples and decodes `shots` more shots, emitting the running
+    /// estimate after every `batch` of them.
+    #[wasm_bindgen]
+    pub fn run_shots(&mut self, shots: usize, batch: usize) -> Result<(), String> {
+        if batch == 0 {
+            return Err("Batch size must be positive".to_string());
+        }
+        let mut remaining = shots;
+        while remaining > 0 {
+            let size = remaining.min(batch);
+            let records = sample_shots(&self.gadget, &self.code, &self.noise, size, &mut self.rng)?;
+            let report = verify_decoder(&mut self.decoder, &self.code, &records)?;
+            self.shots += size;
+            self.failures += report.failures.len();
+            remaining -= size;
+            self.emit(&LiveUpdate::LogicalError {
+                shots: self.shots,
+                failures: self.failures,
+                rate: self.failures as f64 / self.shots as f64,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Sweeps every single fault, emitting one update per circuit region
+    /// (the whole circuit when it has none).
+    #[wasm_bindgen]
+    pub fn sweep_regions(&self) -> Result<(), String> {
+        let circuit = &self.gadget.circuit;
+        let regions: Vec<(String, usize, usize)> = if circuit.regions.is_empty() {
+            vec![("circuit".to_string(), 0, circuit.gates.len())]
+        } else {
+            circuit.regions.iter().map(|r| (r.name.clone(), r.start, r.end)).collect()
+        };
+        let outcomes = single_fault_sweep(circuit);
+        for (index, (region, start, end)) in regions.iter().enumerate() {
+            let (mut faults, mut detected, mut logical_errors) = (0, 0, 0);
+            for outcome in outcomes.iter().filter(|o| (*start..*end).contains(&o.fault.after_gate)) {
+                let error = self.gadget.data_error(&outcome.output);
+                faults += 1;
+                if self.code.syndrome(&error).contains(&true) {
+                    detected += 1;
+                } else if self.code.is_logical_error(&error) {
+                    logical_errors += 1;
+                }
+            }
+            self.emit(&LiveUpdate::Sweep {
+                region: region.clone(),
+                index,
+                regions: regions.len(),
+                faults,
+                detected,
+                logical_errors,
+            })?;
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn reset(&mut self) {
+        self.shots = 0;
+        self.failures = 0;
+    }
+
+    fn emit(&self, update: &LiveUpdate) -> Result<(), String> {
+        let value = serde_wasm_bindgen::to_value(update).map_err(|e| e.to_string())?;
+        for (_, callback) in &self.subscribers {
+            callback
+                .call1(&JsValue::NULL, &value)
+                .map_err(|e| format!("Subscriber failed: {:?}", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// SplitMix64, a small seedable generator for in-browser sampling.
+#[cfg(feature = "demo")]
+struct SplitMix64(u64);
+
+#[cfg(feature = "demo")]
+impl rand_core::TryRng for SplitMix64 {
+    type Error = core::convert::Infallible;
+
+    fn try_next_u32(&mut self) -> Result<u32, Self::Error> {
+        Ok((self.try_next_u64()? >> 32) as u32)
+    }
+
+    fn try_next_u64(&mut self) -> Result<u64, Self::Error> {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        Ok(z ^ (z >> 31))
+    }
+
+    fn try_fill_bytes(&mut self, dst: &mut [u8]) -> Result<(), Self::Error> {
+        for chunk in dst.chunks_mut(8) {
+            let bytes = self.try_next_u64()?.to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+        Ok(())
+    }
+}
+
+/// The conjugation rules of every gate kind, computed from the simulator.
+#[wasm_bindgen]
+pub fn conjugation_rules() -> JsValue {
+    serde_wasm_bindgen::to_value(&quantum_error_analyzer::physics::rule_tables()).unwrap()
+}
+
 #[wasm_bindgen(start)]
 pub fn init() {
     console_error_panic_hook::set_once();
@@ -14,6 +14,25 @@ export function getCircuitDepth() {
     return depth;
 }
 
+// Idle qubits of each time slot, computed in Rust from the gate layout
+// (one array per slot up to the last occupied one)
+export function getIdleWires() {
+    if (!circuit) {
+        return [];
+    }
+    const gates = circuit.get_gates();
+    const slots = gates.map((_, idx) => gateTimePositions.get(idx));
+    if (slots.some(slot => slot === undefined)) {
+        return [];
+    }
+    try {
+        return circuit.idle_wires(Uint32Array.from(slots));
+    } catch (e) {
+        console.warn('Could not compute idle wires:', e);
+        return [];
+    }
+}
+
 export function calculateGateTimeSlots(gates) {
     const timeSlots = [];
     const qubitLastTime = new Map();
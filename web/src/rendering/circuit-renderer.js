@@ -1,7 +1,7 @@
 // Main circuit Konva rendering
 import Konva from 'konva';
 import { circuit, simulator, selectedGate, currentTime, initialError, gateTimePositions, previousCircuitDepth, setPreviousCircuitDepth, lastPlacedGateTime, setLastPlacedGateTime, pendingTwoQubitGate } from '../state.js';
-import { getCircuitDepth } from '../components/time-scheduler.js';
+import { getCircuitDepth, getIdleWires } from '../components/time-scheduler.js';
 import { renderGate } from './gate-renderer.js';
 import { renderError } from './error-renderer.js';
 import { handleCircuitClick, handleCircuitRightClick } from '../events/circuit-handlers.js';
@@ -55,6 +55,26 @@ export function updateTimeSeparators() {
     staticLayer.batchDraw();
 }
 
+// Grey the wire segments of qubits left idle in each occupied time slot
+function renderIdleWires(spacing, qubitSpacing, startX, visibleQubitStart, visibleQubitEnd) {
+    getIdleWires().forEach((idleQubits, t) => {
+        const x = startX + t * spacing;
+        idleQubits
+            .filter(q => q >= visibleQubitStart && q < visibleQubitEnd)
+            .forEach(q => {
+                const y = 40 + q * qubitSpacing;
+                staticLayer.add(new Konva.Line({
+                    name: 'idle-wire',
+                    points: [x - spacing / 2, y, x + spacing / 2, y],
+                    stroke: '#eee',
+                    strokeWidth: 2,
+                    dash: [6, 4],
+                    listening: false,
+                }));
+            });
+    });
+}
+
 export function renderCircuit() {
     const view = document.getElementById('circuit-view');
     if (!view || !circuit) return;
@@ -513,6 +533,9 @@ export function renderCircuit() {
         });
     }
     
+    // Idle wires need every gate's time slot, assigned above
+    renderIdleWires(spacing, qubitSpacing, startX, visibleQubitStart, visibleQubitEnd);
+
     // Update separators (on static layer)
     updateTimeSeparators();
     
@@ -1,6 +1,6 @@
 // Simulation controls UI
 import { circuit, currentTime, gateTimePositions, simulator, pendingTwoQubitGate, selectedGate } from '../state.js';
-import { getCircuitDepth } from '../components/time-scheduler.js';
+import { getCircuitDepth, getIdleWires } from '../components/time-scheduler.js';
 import { stepToTime } from '../components/simulator.js';
 import { reset } from '../components/simulator.js';
 import { placeGate, placeGateAtNextTimeSlot } from '../components/gate-manager.js';
@@ -15,6 +15,7 @@ export function setupSimulationControls() {
         <div class="step-buttons">
             <button id="step-back-btn" class="step-btn">← Step Back</button>
             <span class="time-display-text">Time: <span id="current-time-display">0</span> / <span id="max-time-display">0</span></span>
+            <span class="time-display-text">Idle qubits: <span id="idle-qubits-display">0</span></span>
             <button id="step-forward-btn" class="step-btn">Step Forward →</button>
         </div>
     `;
@@ -23,12 +24,16 @@ export function setupSimulationControls() {
     const stepForwardBtn = document.getElementById('step-forward-btn');
     const currentTimeDisplay = document.getElementById('current-time-display');
     const maxTimeDisplay = document.getElementById('max-time-display');
+    const idleQubitsDisplay = document.getElementById('idle-qubits-display');
     
     const updateStepControls = () => {
         if (circuit) {
             const maxTime = getCircuitDepth();
             maxTimeDisplay.textContent = maxTime;
             currentTimeDisplay.textContent = currentTime;
+            // Idle qubits in the slot the next step applies
+            const idle = getIdleWires()[currentTime];
+            idleQubitsDisplay.textContent = idle ? idle.length : 0;
             stepBackBtn.disabled = currentTime <= 0;
             stepForwardBtn.disabled = currentTime >= maxTime;
         }